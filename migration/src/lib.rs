@@ -1,12 +1,40 @@
 pub use sea_orm_migration::prelude::*;
 
 mod m20260128_000001_init;
+mod m20261016_000001_aggregate_extrema_times;
+mod m20261016_000002_sensor_align_timestamps;
+mod m20261016_000003_sensor_thresholds;
+mod m20261016_000004_maintenance_windows;
+mod m20261016_000005_sync_status_check;
+mod m20261016_000006_sync_runs;
+mod m20261016_000007_readings_ingested_at;
+mod m20261016_000008_sensor_alarming_paused;
+mod m20261016_000009_alarm_event_location_ids;
+mod m20261016_000010_event_affected_locations;
+mod m20261016_000011_readings_raw_time;
+mod m20261016_000012_aggregate_percentiles;
+mod m20261016_000013_sensor_type_patterns;
 
 pub struct Migrator;
 
 #[async_trait::async_trait]
 impl MigratorTrait for Migrator {
     fn migrations() -> Vec<Box<dyn MigrationTrait>> {
-        vec![Box::new(m20260128_000001_init::Migration)]
+        vec![
+            Box::new(m20260128_000001_init::Migration),
+            Box::new(m20261016_000001_aggregate_extrema_times::Migration),
+            Box::new(m20261016_000002_sensor_align_timestamps::Migration),
+            Box::new(m20261016_000003_sensor_thresholds::Migration),
+            Box::new(m20261016_000004_maintenance_windows::Migration),
+            Box::new(m20261016_000005_sync_status_check::Migration),
+            Box::new(m20261016_000006_sync_runs::Migration),
+            Box::new(m20261016_000007_readings_ingested_at::Migration),
+            Box::new(m20261016_000008_sensor_alarming_paused::Migration),
+            Box::new(m20261016_000009_alarm_event_location_ids::Migration),
+            Box::new(m20261016_000010_event_affected_locations::Migration),
+            Box::new(m20261016_000011_readings_raw_time::Migration),
+            Box::new(m20261016_000012_aggregate_percentiles::Migration),
+            Box::new(m20261016_000013_sensor_type_patterns::Migration),
+        ]
     }
 }
@@ -598,7 +598,8 @@ impl MigrationTrait for Migration {
 
         // NOTE: Continuous aggregates start empty (WITH NO DATA) and are populated by
         // the refresh policies as data arrives. For a fresh deployment this is correct.
-        // If restoring historical data, manually run:
+        // If restoring historical data, set REFRESH_AGGREGATES_ON_START=true or
+        // manually run:
         //   CALL refresh_continuous_aggregate('readings_hourly', NULL, NULL);
         //   CALL refresh_continuous_aggregate('readings_daily', NULL, NULL);
         //   etc.
@@ -0,0 +1,42 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // When false, the sync worker stores raw timestamps instead of rounding
+        // them to the 10-minute grid
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Sensors::Table)
+                    .add_column(
+                        ColumnDef::new(Sensors::AlignTimestamps)
+                            .boolean()
+                            .not_null()
+                            .default(true),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Sensors::Table)
+                    .drop_column(Sensors::AlignTimestamps)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Sensors {
+    Table,
+    AlignTimestamps,
+}
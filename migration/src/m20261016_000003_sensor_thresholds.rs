@@ -0,0 +1,77 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Alarm limits configured in viewLinc, replaced on every readings sync
+        manager
+            .create_table(
+                Table::create()
+                    .table(SensorThresholds::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(SensorThresholds::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key()
+                            .extra("DEFAULT gen_random_uuid()"),
+                    )
+                    .col(ColumnDef::new(SensorThresholds::SensorId).uuid().not_null())
+                    .col(ColumnDef::new(SensorThresholds::Kind).string_len(16).not_null())
+                    .col(ColumnDef::new(SensorThresholds::Value).double().not_null())
+                    .col(ColumnDef::new(SensorThresholds::Severity).small_integer())
+                    .col(ColumnDef::new(SensorThresholds::Description).text())
+                    .col(
+                        ColumnDef::new(SensorThresholds::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .extra("DEFAULT NOW()"),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_sensor_thresholds_sensor")
+                            .from(SensorThresholds::Table, SensorThresholds::SensorId)
+                            .to(Sensors::Table, Sensors::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_sensor_thresholds_sensor")
+                    .table(SensorThresholds::Table)
+                    .col(SensorThresholds::SensorId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(SensorThresholds::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum SensorThresholds {
+    Table,
+    Id,
+    SensorId,
+    Kind,
+    Value,
+    Severity,
+    Description,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Sensors {
+    Table,
+    Id,
+}
@@ -0,0 +1,72 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Planned maintenance periods during which alarm notifications are held back
+        manager
+            .create_table(
+                Table::create()
+                    .table(MaintenanceWindows::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(MaintenanceWindows::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key()
+                            .extra("DEFAULT gen_random_uuid()"),
+                    )
+                    .col(
+                        ColumnDef::new(MaintenanceWindows::StartsAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(MaintenanceWindows::EndsAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(MaintenanceWindows::Reason).text())
+                    .col(
+                        ColumnDef::new(MaintenanceWindows::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .extra("DEFAULT NOW()"),
+                    )
+                    .check(
+                        Expr::col(MaintenanceWindows::EndsAt)
+                            .gt(Expr::col(MaintenanceWindows::StartsAt)),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_maintenance_windows_ends_at")
+                    .table(MaintenanceWindows::Table)
+                    .col(MaintenanceWindows::EndsAt)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(MaintenanceWindows::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum MaintenanceWindows {
+    Table,
+    Id,
+    StartsAt,
+    EndsAt,
+    Reason,
+    CreatedAt,
+}
@@ -0,0 +1,38 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        // Unknown statuses predate the typed enum; re-sync those sensors
+        db.execute_unprepared(
+            r"UPDATE sync_state SET sync_status = 'pending'
+              WHERE sync_status NOT IN ('pending', 'success', 'error')",
+        )
+        .await?;
+
+        // Must match entity::sync_state::SyncStatus
+        db.execute_unprepared(
+            r"ALTER TABLE sync_state ADD CONSTRAINT chk_sync_state_status
+              CHECK (sync_status IN ('pending', 'success', 'error'))",
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "ALTER TABLE sync_state DROP CONSTRAINT IF EXISTS chk_sync_state_status",
+            )
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,81 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // One row per scheduler iteration; pruned by SYNC_RUNS_RETENTION_DAYS
+        manager
+            .create_table(
+                Table::create()
+                    .table(SyncRuns::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(SyncRuns::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key()
+                            .extra("DEFAULT gen_random_uuid()"),
+                    )
+                    .col(ColumnDef::new(SyncRuns::Task).string_len(32).not_null())
+                    .col(
+                        ColumnDef::new(SyncRuns::StartedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(SyncRuns::FinishedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(SyncRuns::Status).string_len(32).not_null())
+                    .col(ColumnDef::new(SyncRuns::RecordsAffected).big_integer())
+                    .col(ColumnDef::new(SyncRuns::Error).text())
+                    // Must match entity::sync_runs::SyncRunStatus
+                    .check(Expr::col(SyncRuns::Status).is_in(["success", "error"]))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_sync_runs_task_started_at")
+                    .table(SyncRuns::Table)
+                    .col(SyncRuns::Task)
+                    .col(SyncRuns::StartedAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_sync_runs_started_at")
+                    .table(SyncRuns::Table)
+                    .col(SyncRuns::StartedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(SyncRuns::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum SyncRuns {
+    Table,
+    Id,
+    Task,
+    StartedAt,
+    FinishedAt,
+    Status,
+    RecordsAffected,
+    Error,
+}
@@ -0,0 +1,38 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Set by the sync worker on insert, for `as_of` snapshot queries.
+        // Nullable without a default: existing rows have no known ingest time,
+        // and adding a nullable column leaves compressed chunks untouched
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Readings::Table)
+                    .add_column(ColumnDef::new(Readings::IngestedAt).timestamp_with_time_zone())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Readings::Table)
+                    .drop_column(Readings::IngestedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Readings {
+    Table,
+    IngestedAt,
+}
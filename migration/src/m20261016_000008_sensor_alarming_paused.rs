@@ -0,0 +1,42 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Mirrors the viewLinc location `pause` flag: a paused sensor raises
+        // no alarms
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Sensors::Table)
+                    .add_column(
+                        ColumnDef::new(Sensors::AlarmingPaused)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Sensors::Table)
+                    .drop_column(Sensors::AlarmingPaused)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Sensors {
+    Table,
+    AlarmingPaused,
+}
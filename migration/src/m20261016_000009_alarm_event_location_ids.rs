@@ -0,0 +1,62 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Vaisala location IDs as received, so alarms and events that arrive
+        // before their sensor is discovered can be linked later. Nullable:
+        // rows synced before this migration have no known locations
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Alarms::Table)
+                    .add_column(ColumnDef::new(Alarms::LocationIds).json_binary())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Events::Table)
+                    .add_column(ColumnDef::new(Events::LocationId).integer())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Events::Table)
+                    .drop_column(Events::LocationId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Alarms::Table)
+                    .drop_column(Alarms::LocationIds)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Alarms {
+    Table,
+    LocationIds,
+}
+
+#[derive(DeriveIden)]
+enum Events {
+    Table,
+    LocationId,
+}
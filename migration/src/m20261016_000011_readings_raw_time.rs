@@ -0,0 +1,38 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Unrounded sample time, set by the sync worker when `time` is aligned
+        // to the 10-minute grid. Existing rows can't be recovered and stay null;
+        // adding a nullable column leaves compressed chunks untouched
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Readings::Table)
+                    .add_column(ColumnDef::new(Readings::RawTime).timestamp_with_time_zone())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Readings::Table)
+                    .drop_column(Readings::RawTime)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Readings {
+    Table,
+    RawTime,
+}
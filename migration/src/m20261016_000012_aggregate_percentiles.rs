@@ -0,0 +1,75 @@
+use sea_orm_migration::prelude::*;
+
+/// Percentile continuous aggregates with their bucket width and refresh policy
+/// offsets (view, bucket, start_offset, end_offset/schedule_interval)
+const VIEWS: [(&str, &str, &str, &str); 4] = [
+    ("readings_hourly_percentiles", "1 hour", "3 hours", "1 hour"),
+    ("readings_daily_percentiles", "1 day", "3 days", "1 day"),
+    ("readings_weekly_percentiles", "1 week", "3 weeks", "1 week"),
+    ("readings_monthly_percentiles", "1 month", "3 months", "1 month"),
+];
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        // Separate views rather than new columns on readings_hourly etc.:
+        // rebuilding those would empty them, and buckets whose raw chunks
+        // retention has dropped could never be refilled. Percentiles use the
+        // exact `percentile_cont` ordered-set aggregate rather than the
+        // toolkit's `percentile_agg`: the `timescale/timescaledb` images we
+        // deploy and test against don't ship `timescaledb_toolkit`.
+        for (view, bucket, start_offset, interval) in VIEWS {
+            db.execute_unprepared(&format!(
+                r"
+                CREATE MATERIALIZED VIEW {view}
+                WITH (timescaledb.continuous) AS
+                SELECT
+                    time_bucket('{bucket}', time) AS bucket,
+                    sensor_id,
+                    percentile_cont(0.5) WITHIN GROUP (ORDER BY value) AS p50_value,
+                    percentile_cont(0.95) WITHIN GROUP (ORDER BY value) AS p95_value
+                FROM readings
+                GROUP BY time_bucket('{bucket}', time), sensor_id
+                WITH NO DATA
+                "
+            ))
+            .await?;
+
+            db.execute_unprepared(&format!(
+                r"SELECT add_continuous_aggregate_policy('{view}',
+                    start_offset => INTERVAL '{start_offset}',
+                    end_offset => INTERVAL '{interval}',
+                    schedule_interval => INTERVAL '{interval}')"
+            ))
+            .await?;
+        }
+
+        // NOTE: The views start empty and only cover readings still stored
+        // raw. Refreshes cannot run inside the migration transaction, so
+        // backfill with REFRESH_AGGREGATES_ON_START=true or manually:
+        //   CALL refresh_continuous_aggregate('readings_hourly_percentiles', NULL, NULL);
+        //   etc.
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        for (view, ..) in VIEWS {
+            db.execute_unprepared(&format!(
+                "SELECT remove_continuous_aggregate_policy('{view}', if_exists => true)"
+            ))
+            .await?;
+            db.execute_unprepared(&format!("DROP MATERIALIZED VIEW IF EXISTS {view}"))
+                .await?;
+        }
+
+        Ok(())
+    }
+}
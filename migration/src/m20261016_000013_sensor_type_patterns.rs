@@ -0,0 +1,77 @@
+use sea_orm_migration::prelude::*;
+
+/// Patterns `derive_sensor_type` hardcoded before this table, in match order:
+/// (sensor_type, keywords, priority)
+const SEED: [(&str, &[&str], i32); 8] = [
+    ("Depth", &["depth", "Depth"], 80),
+    ("CDOM", &["cdom", "CDOM"], 70),
+    ("Turbidity", &["turb", "Turb"], 60),
+    ("Battery", &["batt", "Batt"], 50),
+    ("DO_Temperature", &["DOdegC", "DOTdegC"], 40),
+    ("Dissolved_O2", &["DOuM"], 30),
+    ("Conductivity", &["Condu", "condu"], 20),
+    ("Cond_Temperature", &["CondT"], 10),
+];
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Sensor name keywords mapped to a sensor type during discovery. Higher
+        // priorities are tried first; keywords match case-sensitively
+        manager
+            .create_table(
+                Table::create()
+                    .table(SensorTypePatterns::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(SensorTypePatterns::Keyword)
+                            .string_len(64)
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(SensorTypePatterns::SensorType)
+                            .string_len(64)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(SensorTypePatterns::Priority)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        let mut insert = Query::insert();
+        insert.into_table(SensorTypePatterns::Table).columns([
+            SensorTypePatterns::Keyword,
+            SensorTypePatterns::SensorType,
+            SensorTypePatterns::Priority,
+        ]);
+        for (sensor_type, keywords, priority) in SEED {
+            for keyword in keywords {
+                insert.values_panic([(*keyword).into(), sensor_type.into(), priority.into()]);
+            }
+        }
+        manager.exec_stmt(insert).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(SensorTypePatterns::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum SensorTypePatterns {
+    Table,
+    Keyword,
+    SensorType,
+    Priority,
+}
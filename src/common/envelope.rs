@@ -0,0 +1,48 @@
+//! Opt-in `{data, meta}` envelope for list endpoints.
+//!
+//! List endpoints return a bare JSON array by default. With `envelope=true`
+//! the array is wrapped as `{"data": [...], "meta": {"count", "generated_at"}}`.
+
+use axum::{
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+/// Query parameter for list endpoints without other filters
+#[derive(Debug, Default, Deserialize, IntoParams)]
+pub struct EnvelopeQuery {
+    /// Wrap the array in `{data, meta}` (default: false)
+    #[serde(default)]
+    pub envelope: bool,
+}
+
+/// Metadata returned alongside enveloped lists
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ListMeta {
+    /// Number of items in `data`
+    pub count: usize,
+    /// When the response was generated
+    pub generated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+struct Envelope<T> {
+    data: Vec<T>,
+    meta: ListMeta,
+}
+
+/// Respond with `items` as a bare array, or wrapped when `envelope` is set.
+pub fn list_response<T: Serialize>(items: Vec<T>, envelope: bool) -> Response {
+    if !envelope {
+        return Json(items).into_response();
+    }
+
+    let meta = ListMeta {
+        count: items.len(),
+        generated_at: Utc::now(),
+    };
+    Json(Envelope { data: items, meta }).into_response()
+}
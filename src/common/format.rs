@@ -0,0 +1,228 @@
+//! Response format negotiation for endpoints that support JSON, CSV and NDJSON.
+//!
+//! Precedence:
+//! 1. An explicit `format` query parameter always wins, including `format=json`.
+//! 2. Otherwise the `Accept` header picks the supported media type with the
+//!    highest q-value (ties go to the one listed first; `q=0` means "not
+//!    acceptable"). `*/*` and `application/*` count as JSON.
+//! 3. Otherwise JSON.
+//!
+//! CSV exports write their records through one [`CsvLines`] per stream, which
+//! quotes fields per RFC 4180.
+//! Bulk exports can be gzipped by the endpoint itself with `compress=gzip`
+//! (see [`compress_export`]).
+
+use async_compression::tokio::bufread::GzipEncoder;
+use axum::body::Body;
+use axum::http::{header, HeaderMap, HeaderValue};
+use axum::response::Response;
+use futures::TryStreamExt;
+use serde::Deserialize;
+use std::cell::RefCell;
+use std::io::Write;
+use tokio_util::io::{ReaderStream, StreamReader};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::error::{AppError, AppResult};
+
+/// Media types we can produce, with the format name they map to
+const MEDIA_TYPES: [(&str, &str); 5] = [
+    ("application/json", "json"),
+    ("text/csv", "csv"),
+    ("application/x-ndjson", "ndjson"),
+    ("application/*", "json"),
+    ("*/*", "json"),
+];
+
+/// Pick the response format from the `format` query parameter and `Accept` header.
+pub fn negotiate_format(query_format: Option<&str>, headers: &HeaderMap) -> String {
+    if let Some(format) = query_format {
+        return format.to_lowercase();
+    }
+
+    headers
+        .get(header::ACCEPT)
+        .and_then(|accept| accept.to_str().ok())
+        .and_then(format_from_accept)
+        .unwrap_or("json")
+        .to_string()
+}
+
+/// Parse an `Accept` header value, returning the preferred supported format.
+///
+/// Returns `None` when no listed media type is supported.
+pub fn format_from_accept(accept: &str) -> Option<&'static str> {
+    let mut best: Option<(&'static str, f32)> = None;
+
+    for entry in accept.split(',') {
+        let mut parts = entry.split(';');
+        let media_type = parts.next().unwrap_or("").trim().to_ascii_lowercase();
+        let q = parts
+            .filter_map(|param| param.trim().strip_prefix("q="))
+            .find_map(|q| q.trim().parse::<f32>().ok())
+            .unwrap_or(1.0);
+
+        let Some(&(_, format)) = MEDIA_TYPES.iter().find(|(mt, _)| *mt == media_type) else {
+            continue;
+        };
+        if q <= 0.0 {
+            continue;
+        }
+        if best.is_none_or(|(_, best_q)| q > best_q) {
+            best = Some((format, q));
+        }
+    }
+
+    best.map(|(format, _)| format)
+}
+
+/// Columns of a bulk export in order: by name, then UUID for equal names.
+///
+/// Handlers already query sensors in this order; sorting again here keeps CSV
+/// and NDJSON output byte-identical for identical queries regardless of how
+/// the slice was assembled. `key` gives a column's name and UUID.
+pub fn ordered_columns<T: Clone>(columns: &[T], key: impl Fn(&T) -> (&str, Uuid)) -> Vec<T> {
+    let mut columns = columns.to_vec();
+    columns.sort_by(|a, b| key(a).cmp(&key(b)));
+    columns
+}
+
+/// Rows a CSV export buffers before sending them as one body chunk
+const CSV_CHUNK_ROWS: usize = 256;
+
+/// Output buffer of a [`CsvLines`], drained while the writer keeps it
+#[derive(Default)]
+struct CsvBuffer(RefCell<Vec<u8>>);
+
+impl Write for CsvBuffer {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.get_mut().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// CSV writer for one streamed export.
+///
+/// Records are quoted where needed and buffered until [`CsvLines::take`]
+/// hands them out as one chunk.
+pub struct CsvLines {
+    writer: csv::Writer<CsvBuffer>,
+    rows: usize,
+}
+
+impl Default for CsvLines {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CsvLines {
+    pub fn new() -> Self {
+        Self {
+            // Otherwise a record wider or narrower than the first is dropped
+            writer: csv::WriterBuilder::new()
+                .flexible(true)
+                .from_writer(CsvBuffer::default()),
+            rows: 0,
+        }
+    }
+
+    /// Buffer one record
+    pub fn write<I, T>(&mut self, fields: I)
+    where
+        I: IntoIterator<Item = T>,
+        T: AsRef<[u8]>,
+    {
+        // Writing to memory cannot fail
+        let _ = self.writer.write_record(fields);
+        self.rows += 1;
+    }
+
+    /// Whether enough rows are buffered to send a chunk
+    pub fn is_full(&self) -> bool {
+        self.rows >= CSV_CHUNK_ROWS
+    }
+
+    /// The buffered records, each with a trailing newline, leaving none behind
+    pub fn take(&mut self) -> String {
+        let _ = self.writer.flush();
+        self.rows = 0;
+        String::from_utf8(self.writer.get_ref().0.take()).unwrap_or_default()
+    }
+}
+
+/// Compression a bulk export applies itself, regardless of `Accept-Encoding`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Compression {
+    /// gzip, downloaded as `<name>.gz`
+    Gzip,
+}
+
+/// Reject `compress` for JSON, which only `Accept-Encoding` compresses.
+///
+/// # Errors
+///
+/// Returns `BadRequest` when `compress` is set and `format` is not a bulk format.
+pub fn check_compress(compress: Option<Compression>, format: &str) -> AppResult<()> {
+    if compress.is_some() && format != "csv" && format != "ndjson" {
+        return Err(AppError::BadRequest(
+            "compress is only supported for csv and ndjson formats".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Download name for a bulk export, e.g. `Station_1-readings.csv`.
+///
+/// Characters other than ASCII alphanumerics, `-`, `_` and `.` become `_`, so
+/// the name is safe in a `Content-Disposition` header.
+pub fn export_filename(station: &str, kind: &str, format: &str) -> String {
+    let station: String = station
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    format!("{station}-{kind}.{format}")
+}
+
+/// Apply `compress` to a streamed export named `filename`.
+///
+/// Gzip wraps the body stream in an encoder as it is produced and sets
+/// `Content-Encoding: gzip` (which `CompressionLayer` leaves alone) and an
+/// attachment name ending in `.gz`. Without `compress`, `response` is returned
+/// unchanged.
+///
+/// # Errors
+///
+/// Returns `Internal` if `filename` is not a valid header value.
+pub fn compress_export(
+    response: Response,
+    compress: Option<Compression>,
+    filename: &str,
+) -> AppResult<Response> {
+    let Some(Compression::Gzip) = compress else {
+        return Ok(response);
+    };
+
+    let disposition = HeaderValue::from_str(&format!("attachment; filename=\"{filename}.gz\""))
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+    let (mut parts, body) = response.into_parts();
+    let headers = &mut parts.headers;
+    headers.insert(header::CONTENT_ENCODING, HeaderValue::from_static("gzip"));
+    headers.insert(header::CONTENT_DISPOSITION, disposition);
+
+    let reader = StreamReader::new(body.into_data_stream().map_err(std::io::Error::other));
+    let encoded = ReaderStream::new(GzipEncoder::new(reader));
+    Ok(Response::from_parts(parts, Body::from_stream(encoded)))
+}
@@ -1,3 +1,10 @@
+pub mod envelope;
+pub mod format;
+pub mod pagination;
+pub mod round;
 pub mod state;
+pub mod time;
 
-pub use state::{AppState, CachedResponse};
+pub use state::{
+    build_response_cache, AppState, BulkLimiter, CachedCheck, CachedResponse, SyncPassRecord,
+};
@@ -0,0 +1,42 @@
+use chrono::{DateTime, Utc};
+
+use crate::error::{AppError, AppResult};
+
+/// Highest page number accepted by offset-paginated endpoints
+pub const MAX_PAGE: i32 = 1_000_000;
+
+/// Trim a keyset page and compute its continuation cursor.
+///
+/// `rows` should be fetched with `LIMIT limit + 1` so that an extra row
+/// signals more data. Returns at most `limit` rows, plus the timestamp of the
+/// last returned row as the cursor when more data exists (`None` otherwise).
+pub fn keyset_page<T>(
+    mut rows: Vec<T>,
+    limit: usize,
+    time_of: impl Fn(&T) -> DateTime<Utc>,
+) -> (Vec<T>, Option<DateTime<Utc>>) {
+    if rows.len() <= limit {
+        return (rows, None);
+    }
+
+    rows.truncate(limit);
+    let cursor = rows.last().map(time_of);
+    (rows, cursor)
+}
+
+/// Compute the row offset for a 1-indexed page.
+///
+/// Rejects pages below 1 or above [`MAX_PAGE`]; the multiplication is done in
+/// `u64` so it cannot overflow for any accepted input.
+pub fn page_offset(page: i32, page_size: i32) -> AppResult<u64> {
+    if !(1..=MAX_PAGE).contains(&page) {
+        return Err(AppError::BadRequest(format!(
+            "page must be between 1 and {MAX_PAGE}"
+        )));
+    }
+
+    let page_size = u64::try_from(page_size.max(0)).unwrap_or(0);
+    (page as u64 - 1)
+        .checked_mul(page_size)
+        .ok_or_else(|| AppError::BadRequest("page is out of range".to_string()))
+}
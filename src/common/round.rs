@@ -0,0 +1,62 @@
+//! Optional rounding of JSON values to each sensor's `decimal_places`, and
+//! plain decimal formatting of values for CSV.
+//!
+//! Values stay JSON numbers; only float noise beyond the sensor's configured
+//! precision is removed.
+
+/// f64 carries about 15 significant decimal digits; more places change nothing
+const MAX_DECIMAL_PLACES: i16 = 15;
+
+/// Round `value` half away from zero to `decimal_places`.
+///
+/// `None` returns the value unchanged. Negative places are treated as zero,
+/// and a negative value that rounds to zero yields `0.0` rather than `-0.0`.
+pub fn round_to(value: f64, decimal_places: Option<i16>) -> f64 {
+    let Some(places) = decimal_places else {
+        return value;
+    };
+
+    let factor = 10f64.powi(i32::from(places.clamp(0, MAX_DECIMAL_PLACES)));
+    let scaled = value * factor;
+    if !scaled.is_finite() {
+        return value;
+    }
+
+    let rounded = scaled.round() / factor;
+    if rounded == 0.0 { 0.0 } else { rounded }
+}
+
+/// Places for averaged values: one more than the sensor's, since averaging
+/// adds precision. `None` stays `None` (left exact).
+pub fn average_decimal_places(decimal_places: Option<i16>) -> Option<i16> {
+    decimal_places.map(|places| places.max(0).saturating_add(1))
+}
+
+/// Round every present value in place with [`round_to`].
+pub fn round_values(values: &mut [Option<f64>], decimal_places: Option<i16>) {
+    if decimal_places.is_none() {
+        return;
+    }
+    for value in values.iter_mut().flatten() {
+        *value = round_to(*value, decimal_places);
+    }
+}
+
+/// Format `value` for CSV in plain decimal notation, never with an exponent.
+///
+/// With `decimal_places`, the value is rounded with [`round_to`] and written
+/// with exactly that many places; otherwise the shortest representation that
+/// round-trips is used. Non-finite values yield an empty string (a CSV null).
+pub fn format_decimal(value: f64, decimal_places: Option<i16>) -> String {
+    if !value.is_finite() {
+        return String::new();
+    }
+    match decimal_places {
+        Some(places) => {
+            let places = usize::try_from(places.clamp(0, MAX_DECIMAL_PLACES)).unwrap_or(0);
+            format!("{:.*}", places, round_to(value, decimal_places))
+        }
+        // f64's Display never switches to scientific notation
+        None => value.to_string(),
+    }
+}
@@ -1,10 +1,19 @@
+use axum::http::StatusCode;
 use chrono::{DateTime, Utc};
+use metrics_exporter_prometheus::PrometheusHandle;
+use moka::Expiry;
 use moka::future::Cache;
 use sea_orm::DatabaseConnection;
-use std::sync::Arc;
-use std::time::Duration;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+use std::future::Future;
+use tokio::sync::{Mutex, OwnedSemaphorePermit, Semaphore};
+use tokio_util::sync::CancellationToken;
 
 use crate::config::Config;
+use crate::error::{AppError, AppResult};
+use crate::services::device_events::{self, DeviceEventSender};
+use crate::services::{metrics, InFlightRequests};
 use crate::vaisala::VaisalaClient;
 
 /// Cached response with metadata for freshness checking
@@ -12,37 +21,249 @@ use crate::vaisala::VaisalaClient;
 pub struct CachedResponse {
     pub data: Arc<Vec<u8>>,
     pub max_time: Option<DateTime<Utc>>,
+    /// Whether the query had an end time (selects the TTL)
+    pub bounded: bool,
+    /// Per-route TTL override; takes precedence over the bounded/unbounded TTL
+    pub ttl: Option<Duration>,
 }
 
 /// Cache for API responses. Key is request params, value is serialized response + metadata.
 /// Weighted by byte size to enforce memory limit.
 pub type ResponseCache = Cache<String, CachedResponse>;
 
+/// Per-entry expiry: an entry's own TTL override wins, otherwise unbounded
+/// queries get a shorter TTL than bounded ones.
+pub struct ResponseExpiry {
+    pub bounded_ttl: Duration,
+    pub unbounded_ttl: Duration,
+}
+
+impl ResponseExpiry {
+    fn ttl(&self, value: &CachedResponse) -> Duration {
+        if let Some(ttl) = value.ttl {
+            ttl
+        } else if value.bounded {
+            self.bounded_ttl
+        } else {
+            self.unbounded_ttl
+        }
+    }
+}
+
+impl Expiry<String, CachedResponse> for ResponseExpiry {
+    fn expire_after_create(
+        &self,
+        _key: &String,
+        value: &CachedResponse,
+        _created_at: Instant,
+    ) -> Option<Duration> {
+        Some(self.ttl(value))
+    }
+
+    fn expire_after_update(
+        &self,
+        _key: &String,
+        value: &CachedResponse,
+        _updated_at: Instant,
+        _duration_until_expiry: Option<Duration>,
+    ) -> Option<Duration> {
+        Some(self.ttl(value))
+    }
+}
+
+/// Build the response cache, weighted by byte size, with per-entry TTLs.
+pub fn build_response_cache(
+    max_bytes: u64,
+    bounded_ttl: Duration,
+    unbounded_ttl: Duration,
+) -> ResponseCache {
+    Cache::builder()
+        .weigher(|_key: &String, value: &CachedResponse| -> u32 {
+            // Weight is the size in bytes (capped at u32::MAX)
+            value.data.len().try_into().unwrap_or(u32::MAX)
+        })
+        .max_capacity(max_bytes)
+        .expire_after(ResponseExpiry {
+            bounded_ttl,
+            unbounded_ttl,
+        })
+        // Required by `invalidate_entries_if` (prefix invalidation after sync)
+        .support_invalidation_closures()
+        .build()
+}
+
+/// Limits concurrent bulk (CSV/NDJSON) responses across all endpoints.
+///
+/// Protects the database from distributed DDoS attacks. Clones share one pool
+/// of `BULK_CONCURRENT_LIMIT` permits. Requests estimated at or below
+/// `BULK_BYPASS_POINTS` skip the pool (see [`BulkLimiter::acquire_sized`]).
+#[derive(Debug, Clone)]
+pub struct BulkLimiter {
+    permits: Arc<Semaphore>,
+    bypass_points: u64,
+}
+
+impl BulkLimiter {
+    pub fn new(limit: usize) -> Self {
+        Self {
+            permits: Arc::new(Semaphore::new(limit)),
+            bypass_points: 0,
+        }
+    }
+
+    /// Let requests estimated at or below `points` skip the permit pool (0 disables).
+    pub fn with_bypass_points(mut self, points: u64) -> Self {
+        self.bypass_points = points;
+        self
+    }
+
+    /// Claim a permit for a bulk `format`; JSON requests need none.
+    ///
+    /// Hold the returned permit until the response has been produced.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ServiceUnavailable` when all permits are in use.
+    pub fn acquire(&self, format: &str) -> AppResult<Option<OwnedSemaphorePermit>> {
+        if format != "csv" && format != "ndjson" {
+            return Ok(None);
+        }
+
+        match self.permits.clone().try_acquire_owned() {
+            Ok(permit) => Ok(Some(permit)),
+            Err(_) => {
+                tracing::warn!(
+                    format = %format,
+                    status = StatusCode::SERVICE_UNAVAILABLE.as_u16(),
+                    "bulk_request_rejected"
+                );
+                Err(AppError::ServiceUnavailable(
+                    "Too many concurrent bulk requests. Please try again later.".to_string(),
+                ))
+            }
+        }
+    }
+
+    /// Like [`acquire`](Self::acquire), but small requests take no permit.
+    ///
+    /// `estimated_points` is the caller's estimate of rows returned (sensors
+    /// times range); a small pull then never waits behind large exports.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ServiceUnavailable` when a permit is needed and all are in use.
+    pub fn acquire_sized(
+        &self,
+        format: &str,
+        estimated_points: u64,
+    ) -> AppResult<Option<OwnedSemaphorePermit>> {
+        if self.bypass_points > 0 && estimated_points <= self.bypass_points {
+            return Ok(None);
+        }
+        self.acquire(format)
+    }
+}
+
+/// Last result of a dependency check, reused by health probes for a while.
+///
+/// Clones share the result. Concurrent callers wait for the one check in
+/// progress rather than starting their own.
+#[derive(Debug, Clone, Default)]
+pub struct CachedCheck {
+    last: Arc<Mutex<Option<(Instant, bool)>>>,
+}
+
+impl CachedCheck {
+    /// The last result if younger than `ttl`, otherwise the result of `check`.
+    pub async fn get_or_check(&self, ttl: Duration, check: impl Future<Output = bool>) -> bool {
+        let mut last = self.last.lock().await;
+        if let Some((checked_at, ok)) = *last
+            && checked_at.elapsed() < ttl
+        {
+            return ok;
+        }
+
+        let ok = check.await;
+        *last = Some((Instant::now(), ok));
+        ok
+    }
+}
+
+/// Summary of the most recent readings sync pass
+#[derive(Debug, Clone)]
+pub struct SyncPassRecord {
+    pub started_at: DateTime<Utc>,
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub sensors: usize,
+    pub points_inserted: u64,
+    pub backfill_points: u64,
+    pub duration_ms: u64,
+    pub full_sync: bool,
+}
+
 #[derive(Clone)]
 pub struct AppState {
+    /// Primary connection (sync writes)
     pub db: DatabaseConnection,
+    /// Connection for read-only API queries: the replica if configured, else the primary
+    pub read_db: DatabaseConnection,
     pub config: Arc<Config>,
     pub vaisala_client: Arc<VaisalaClient>,
     pub response_cache: ResponseCache,
+    /// Shared by every endpoint that streams CSV/NDJSON
+    pub bulk_limiter: BulkLimiter,
+    pub last_sync_pass: Arc<RwLock<Option<SyncPassRecord>>>,
+    /// Process start, for uptime reporting
+    pub started_at: Instant,
+    /// Device status changes published by the sync, read by the SSE stream
+    pub device_events: DeviceEventSender,
+    /// Requests still being served, reported on shutdown
+    pub in_flight: InFlightRequests,
+    /// Vaisala reachability as last seen by `/healthz`
+    pub vaisala_health: CachedCheck,
+    /// Renders the process-wide Prometheus metrics for `/metrics`
+    pub metrics: PrometheusHandle,
+    /// Cancelled when the server stops accepting connections; ends
+    /// long-lived streams that would otherwise hold up a graceful shutdown
+    pub shutdown: CancellationToken,
 }
 
 impl AppState {
     pub fn new(db: DatabaseConnection, config: Config, vaisala_client: VaisalaClient) -> Self {
         // Cache weighted by byte size, not entry count
-        let cache: ResponseCache = Cache::builder()
-            .weigher(|_key: &String, value: &CachedResponse| -> u32 {
-                // Weight is the size in bytes (capped at u32::MAX)
-                value.data.len().try_into().unwrap_or(u32::MAX)
-            })
-            .max_capacity(config.cache_max_bytes)
-            .time_to_live(Duration::from_secs(config.cache_ttl_seconds))
-            .build();
+        let cache = build_response_cache(
+            config.cache_max_bytes,
+            Duration::from_secs(config.cache_ttl_seconds),
+            Duration::from_secs(config.cache_unbounded_ttl_seconds),
+        );
+
+        let bulk_limiter = BulkLimiter::new(config.bulk_concurrent_limit)
+            .with_bypass_points(config.bulk_bypass_points);
 
         Self {
+            read_db: db.clone(),
             db,
             config: Arc::new(config),
             vaisala_client: Arc::new(vaisala_client),
             response_cache: cache,
+            bulk_limiter,
+            last_sync_pass: Arc::new(RwLock::new(None)),
+            started_at: Instant::now(),
+            device_events: device_events::channel(),
+            in_flight: InFlightRequests::new(),
+            vaisala_health: CachedCheck::default(),
+            metrics: metrics::handle(),
+            shutdown: CancellationToken::new(),
         }
     }
+
+    /// Serve read-only API queries from a replica connection.
+    ///
+    /// Replicas lag the primary, so freshness checks on unbounded queries may
+    /// briefly miss readings the sync worker has just written.
+    pub fn with_read_replica(mut self, read_db: DatabaseConnection) -> Self {
+        self.read_db = read_db;
+        self
+    }
 }
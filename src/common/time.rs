@@ -0,0 +1,250 @@
+use chrono::{DateTime, Duration, DurationRound, Utc};
+use serde::ser::{SerializeSeq, Serializer};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::ops::Deref;
+use utoipa::ToSchema;
+
+use crate::error::{AppError, AppResult};
+
+/// Granularity used to anchor relative windows to "now".
+///
+/// Rounding keeps the resolved range (and therefore the cache key) stable for
+/// a minute, so repeated "last 24h" requests share a cache entry.
+const WINDOW_ANCHOR_SECS: i64 = 60;
+
+/// Parse a relative duration such as `30m`, `24h`, `7d` or `2w`.
+///
+/// # Errors
+///
+/// Returns `AppError::BadRequest` if the value is not a positive integer
+/// followed by one of `m`, `h`, `d`, `w`.
+pub fn parse_relative_duration(value: &str) -> AppResult<Duration> {
+    let value = value.trim();
+    let invalid = || {
+        AppError::BadRequest(format!(
+            "Invalid relative duration: {value}. Expected e.g. 30m, 24h, 7d, 2w"
+        ))
+    };
+
+    let (split, _) = value.char_indices().last().ok_or_else(invalid)?;
+    let (amount, unit) = value.split_at(split);
+    let amount: i64 = amount.parse().map_err(|_| invalid())?;
+    if amount <= 0 {
+        return Err(invalid());
+    }
+
+    let duration = match unit {
+        "m" => Duration::try_minutes(amount),
+        "h" => Duration::try_hours(amount),
+        "d" => Duration::try_days(amount),
+        "w" => Duration::try_weeks(amount),
+        _ => None,
+    };
+
+    duration.ok_or_else(invalid)
+}
+
+/// Resolve a relative `window` into an absolute `(start, end)` range ending now.
+///
+/// `now` is rounded down to the minute so the range is stable across
+/// requests made within the same minute.
+///
+/// # Errors
+///
+/// Returns `AppError::BadRequest` if the window cannot be parsed.
+pub fn resolve_window(window: &str) -> AppResult<(DateTime<Utc>, DateTime<Utc>)> {
+    let duration = parse_relative_duration(window)?;
+    Ok(window_ending_now(duration))
+}
+
+/// `(start, end)` range of length `duration` ending now, anchored like
+/// [`resolve_window`].
+pub fn window_ending_now(duration: Duration) -> (DateTime<Utc>, DateTime<Utc>) {
+    let now = Utc::now();
+    let end = now
+        .duration_trunc(Duration::seconds(WINDOW_ANCHOR_SECS))
+        .unwrap_or(now);
+    (end - duration, end)
+}
+
+/// Reject a time range whose end is not after its start.
+///
+/// Either bound may be omitted (pass `None`), in which case there is nothing
+/// to check. Required bounds can be passed directly.
+///
+/// # Errors
+///
+/// Returns `AppError::BadRequest` if both bounds are given and `end <= start`.
+pub fn validate_range(
+    start: impl Into<Option<DateTime<Utc>>>,
+    end: impl Into<Option<DateTime<Utc>>>,
+) -> AppResult<()> {
+    match (start.into(), end.into()) {
+        (Some(start), Some(end)) if end <= start => Err(AppError::BadRequest(
+            "end time must be after start time".to_string(),
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Recommended data source for charting a range of the given length.
+///
+/// Keeps a single-sensor chart under ~3000 points: raw 10-minute readings up
+/// to 14 days, hourly aggregates up to 120 days, daily up to a year, weekly
+/// beyond (same thresholds as the dashboard).
+pub fn resolution_hint(span: Duration) -> &'static str {
+    if span <= Duration::days(14) {
+        "raw"
+    } else if span <= Duration::days(120) {
+        "hourly"
+    } else if span <= Duration::days(365) {
+        "daily"
+    } else {
+        "weekly"
+    }
+}
+
+/// Canonical aggregate resolution for a user-supplied name.
+///
+/// Matching is case-insensitive, and the aliases `1h`, `1d` and `1w` map to
+/// `hourly`, `daily` and `weekly`. Returns `None` for unknown resolutions.
+pub fn normalize_resolution(value: &str) -> Option<&'static str> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "hourly" | "1h" => Some("hourly"),
+        "daily" | "1d" => Some("daily"),
+        "weekly" | "1w" => Some("weekly"),
+        "monthly" => Some("monthly"),
+        _ => None,
+    }
+}
+
+/// Parse an RFC 3339 timestamp or Unix epoch seconds (integer or fractional).
+///
+/// Returns `None` for anything else, including epochs out of chrono's range.
+pub fn parse_timestamp(value: &str) -> Option<DateTime<Utc>> {
+    let value = value.trim();
+    if let Ok(t) = DateTime::parse_from_rfc3339(value) {
+        return Some(t.with_timezone(&Utc));
+    }
+    if let Ok(secs) = value.parse::<i64>() {
+        return DateTime::from_timestamp(secs, 0);
+    }
+    value.parse::<f64>().ok().and_then(epoch_from_float)
+}
+
+fn epoch_from_float(secs: f64) -> Option<DateTime<Utc>> {
+    if !secs.is_finite() {
+        return None;
+    }
+    let whole = secs.floor();
+    // Saturating casts; out-of-range values are rejected by `from_timestamp`
+    let nanos = ((secs - whole) * 1e9).round().min(999_999_999.0) as u32;
+    DateTime::from_timestamp(whole as i64, nanos)
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawTimestamp {
+    Int(i64),
+    Float(f64),
+    Text(String),
+}
+
+/// Deserialize a timestamp given as RFC 3339 or Unix epoch seconds.
+///
+/// Query strings carry every value as text, so `start=1700000000` and
+/// `start=2023-11-14T22:13:20Z` both arrive as strings and parse to the same
+/// instant. Use with `#[serde(deserialize_with = "time::deserialize_timestamp")]`.
+pub fn deserialize_timestamp<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<DateTime<Utc>, D::Error> {
+    let parsed = match RawTimestamp::deserialize(deserializer)? {
+        RawTimestamp::Int(secs) => DateTime::from_timestamp(secs, 0),
+        RawTimestamp::Float(secs) => epoch_from_float(secs),
+        RawTimestamp::Text(text) => parse_timestamp(&text),
+    };
+    parsed.ok_or_else(|| {
+        serde::de::Error::custom("invalid timestamp: expected RFC 3339 or Unix epoch seconds")
+    })
+}
+
+/// [`deserialize_timestamp`] for optional fields; pair with `#[serde(default)]`.
+pub fn deserialize_optional_timestamp<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<DateTime<Utc>>, D::Error> {
+    deserialize_timestamp(deserializer).map(Some)
+}
+
+/// Serialization of timestamp arrays in JSON responses
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum TimeFormat {
+    /// RFC 3339 strings, e.g. `2025-01-01T00:10:00Z`
+    #[default]
+    Iso,
+    /// Unix epoch seconds (integers)
+    Epoch,
+}
+
+impl TimeFormat {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Iso => "iso",
+            Self::Epoch => "epoch",
+        }
+    }
+}
+
+/// Element of a [`TimeArray`]: a timestamp or a missing one.
+pub trait TimeValue {
+    fn time(&self) -> Option<&DateTime<Utc>>;
+}
+
+impl TimeValue for DateTime<Utc> {
+    fn time(&self) -> Option<&DateTime<Utc>> {
+        Some(self)
+    }
+}
+
+impl TimeValue for Option<DateTime<Utc>> {
+    fn time(&self) -> Option<&DateTime<Utc>> {
+        self.as_ref()
+    }
+}
+
+/// Timestamp array serialized according to a [`TimeFormat`].
+///
+/// Derefs to the underlying slice, so it can be indexed like a `Vec`.
+#[derive(Debug, Clone, Default)]
+pub struct TimeArray<T> {
+    pub values: Vec<T>,
+    pub format: TimeFormat,
+}
+
+impl<T> TimeArray<T> {
+    pub fn new(values: Vec<T>, format: TimeFormat) -> Self {
+        Self { values, format }
+    }
+}
+
+impl<T> Deref for TimeArray<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        &self.values
+    }
+}
+
+impl<T: TimeValue> Serialize for TimeArray<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(self.values.len()))?;
+        for value in &self.values {
+            match (value.time(), self.format) {
+                (None, _) => seq.serialize_element(&None::<i64>)?,
+                (Some(t), TimeFormat::Iso) => seq.serialize_element(t)?,
+                (Some(t), TimeFormat::Epoch) => seq.serialize_element(&t.timestamp())?,
+            }
+        }
+        seq.end()
+    }
+}
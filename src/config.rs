@@ -1,5 +1,10 @@
 use std::env;
 
+use crate::common::time::{normalize_resolution, parse_relative_duration};
+
+/// Aggregate resolutions served when `ENABLED_RESOLUTIONS` is unset
+pub const ALL_RESOLUTIONS: [&str; 4] = ["hourly", "daily", "weekly", "monthly"];
+
 #[derive(Debug, Clone)]
 pub enum Deployment {
     Local,
@@ -24,14 +29,22 @@ impl std::str::FromStr for Deployment {
 
 #[derive(Debug, Clone)]
 pub struct Config {
-    // Database
+    // Database (optional read replica for API queries)
     pub database_url: String,
+    pub database_replica_url: Option<String>,
+    /// Fill empty continuous aggregates from existing readings at startup
+    pub refresh_aggregates_on_start: bool,
 
     // Vaisala API
     pub vaisala_base_url: String,
     pub vaisala_bearer_token: String,
     pub vaisala_skip_tls_verify: bool,
     pub vaisala_max_history_days: i64,
+    /// Retries of a request answered 429, 502, 503 or 504 before giving up
+    pub vaisala_max_retries: u32,
+    /// First retry delay; doubles on each retry, plus jitter. A `Retry-After`
+    /// header takes precedence
+    pub vaisala_retry_base_ms: u64,
 
     // Sync settings
     pub sync_readings_interval_seconds: u64,
@@ -40,10 +53,47 @@ pub struct Config {
     pub sync_events_interval_seconds: u64,
     pub sync_retry_max: u32,
     pub sync_retry_delay_seconds: u64,
+    pub sync_logged_overrides_realtime: bool,
+    pub sync_stale_after_seconds: i64,
+    pub sync_gap_threshold_seconds: i64,
+    /// Days of sync run history to keep (0 keeps everything)
+    pub sync_runs_retention_days: i64,
+    pub sync_sanity_min: f64,
+    pub sync_sanity_max: f64,
+    pub sync_sanity_use_units_range: bool,
+    /// Sensor types (or names) discovery creates; empty allows all
+    pub sync_sensor_type_allowlist: Vec<String>,
+    /// Sensor types (or names) discovery skips; wins over the allowlist
+    pub sync_sensor_type_denylist: Vec<String>,
+    /// Sample interval assumed for sensors without one (seconds)
+    pub default_sample_interval_sec: i64,
 
     // API settings
     pub api_host: String,
     pub api_port: u16,
+    pub api_default_page_size: u64,
+    pub api_max_page_size: u64,
+    pub cors_max_age_seconds: u64,
+    pub admin_token: Option<String>,
+    pub compression_level: Option<i32>,
+    /// Aggregate resolutions the API accepts (canonical names)
+    pub enabled_resolutions: Vec<String>,
+    /// Stream JSON readings responses estimated above this size instead of
+    /// buffering and caching them (0 disables)
+    pub json_stream_threshold_bytes: usize,
+    /// Maximum estimated raw readings per station readings request (0 disables)
+    pub readings_point_budget: u64,
+    /// Maximum sensors per station readings/aggregates request (0 disables)
+    pub max_sensors_per_request: usize,
+    /// Window ending now applied to station readings requests without
+    /// `start`, `end` or `window` (`None` returns all history)
+    pub default_readings_window: Option<chrono::Duration>,
+    /// Wait after a shutdown signal before refusing new connections, so load
+    /// balancers can stop routing here
+    pub shutdown_drain_seconds: u64,
+    /// Time in-flight requests get to finish once the server stops accepting
+    /// connections; the rest are dropped (0 waits indefinitely)
+    pub shutdown_timeout_seconds: u64,
 
     // Rate limiting
     pub disable_rate_limiting: bool,
@@ -52,10 +102,17 @@ pub struct Config {
     pub rate_limit_data_per_second: u64,
     pub rate_limit_data_burst: u32,
     pub bulk_concurrent_limit: usize,
+    /// Bulk requests estimated at or below this many points skip the bulk limit
+    pub bulk_bypass_points: u64,
+    pub per_client_concurrent_limit: usize,
 
-    // Caching
+    // Caching (TTLs for bounded vs unbounded queries)
     pub cache_ttl_seconds: u64,
+    pub cache_unbounded_ttl_seconds: u64,
     pub cache_max_bytes: u64,
+    /// Per-route TTL overrides for station latest/rolling stats and aggregates
+    pub cache_latest_ttl_seconds: u64,
+    pub cache_aggregates_ttl_seconds: u64,
 
     // Application metadata
     pub deployment: Deployment,
@@ -82,6 +139,13 @@ impl Config {
                     "postgresql://{user}:{password}@{host}:{port}/{name}"
                 ))
             }).map_err(|_| ConfigError::Missing("DATABASE_URL or DB_USER/DB_PASSWORD/DB_HOST/DB_NAME"))?,
+            database_replica_url: env::var("DATABASE_REPLICA_URL")
+                .ok()
+                .filter(|url| !url.is_empty()),
+            refresh_aggregates_on_start: env::var("REFRESH_AGGREGATES_ON_START")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .unwrap_or(false),
 
             // Vaisala API
             vaisala_base_url: env::var("VAISALA_BASE_URL")
@@ -96,6 +160,14 @@ impl Config {
                 .unwrap_or_else(|_| "90".to_string())
                 .parse()
                 .unwrap_or(90),
+            vaisala_max_retries: env::var("VAISALA_MAX_RETRIES")
+                .unwrap_or_else(|_| "3".to_string())
+                .parse()
+                .unwrap_or(3),
+            vaisala_retry_base_ms: env::var("VAISALA_RETRY_BASE_MS")
+                .unwrap_or_else(|_| "500".to_string())
+                .parse()
+                .unwrap_or(500),
 
             // Sync settings
             sync_readings_interval_seconds: env::var("SYNC_READINGS_INTERVAL_SECONDS")
@@ -122,6 +194,54 @@ impl Config {
                 .unwrap_or_else(|_| "60".to_string())
                 .parse()
                 .unwrap_or(60),
+            // When true, a logged reading replaces a realtime one at the same timestamp
+            sync_logged_overrides_realtime: env::var("SYNC_LOGGED_OVERRIDES_REALTIME")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .unwrap_or(false),
+            // Sensors without data for this long are listed as problematic
+            sync_stale_after_seconds: env::var("SYNC_STALE_AFTER_SECONDS")
+                .unwrap_or_else(|_| "10800".to_string())
+                .parse()
+                .unwrap_or(10800),
+            // Gaps longer than this are backfilled after incremental syncs (0 disables)
+            sync_gap_threshold_seconds: env::var("SYNC_GAP_THRESHOLD_SECONDS")
+                .unwrap_or_else(|_| "3600".to_string())
+                .parse()
+                .unwrap_or(3600),
+            sync_runs_retention_days: env::var("SYNC_RUNS_RETENTION_DAYS")
+                .unwrap_or_else(|_| "30".to_string())
+                .parse()
+                .unwrap_or(30),
+            // Values outside [min, max] are dropped; the defaults only
+            // catch sentinels such as 1e38
+            sync_sanity_min: env::var("SYNC_SANITY_MIN")
+                .unwrap_or_else(|_| "-1e30".to_string())
+                .parse()
+                .unwrap_or(-1e30),
+            sync_sanity_max: env::var("SYNC_SANITY_MAX")
+                .unwrap_or_else(|_| "1e30".to_string())
+                .parse()
+                .unwrap_or(1e30),
+            // Narrow the range further to each sensor's viewLinc units_min/units_max
+            sync_sanity_use_units_range: env::var("SYNC_SANITY_USE_UNITS_RANGE")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .unwrap_or(false),
+            // Comma-separated, case-insensitive; matched against the derived
+            // sensor type or the location name
+            sync_sensor_type_allowlist: parse_list(
+                &env::var("SYNC_SENSOR_TYPE_ALLOWLIST").unwrap_or_default(),
+            ),
+            sync_sensor_type_denylist: parse_list(
+                &env::var("SYNC_SENSOR_TYPE_DENYLIST").unwrap_or_default(),
+            ),
+            default_sample_interval_sec: env::var("DEFAULT_SAMPLE_INTERVAL_SEC")
+                .unwrap_or_else(|_| "600".to_string())
+                .parse()
+                .ok()
+                .filter(|&sec: &i64| sec > 0)
+                .unwrap_or(600), // 10 minutes, the viewLinc default
 
             // API settings
             api_host: env::var("API_HOST").unwrap_or_else(|_| "0.0.0.0".to_string()),
@@ -129,6 +249,56 @@ impl Config {
                 .unwrap_or_else(|_| "3000".to_string())
                 .parse()
                 .unwrap_or(3000),
+            api_default_page_size: env::var("API_DEFAULT_PAGE_SIZE")
+                .unwrap_or_else(|_| "1000".to_string())
+                .parse()
+                .unwrap_or(1000),
+            api_max_page_size: env::var("API_MAX_PAGE_SIZE")
+                .unwrap_or_else(|_| "10000".to_string())
+                .parse()
+                .unwrap_or(10000),
+            cors_max_age_seconds: env::var("CORS_MAX_AGE_SECONDS")
+                .unwrap_or_else(|_| "3600".to_string())
+                .parse()
+                .unwrap_or(3600),
+            // Bearer token for /api/admin; admin routes are disabled when unset
+            admin_token: env::var("ADMIN_TOKEN")
+                .ok()
+                .filter(|token| !token.is_empty()),
+            // Brotli/gzip quality; unset uses each algorithm's default
+            compression_level: env::var("COMPRESSION_LEVEL")
+                .ok()
+                .and_then(|level| level.parse().ok()),
+            // Comma-separated; unknown names are ignored, empty means all
+            enabled_resolutions: parse_resolutions(
+                &env::var("ENABLED_RESOLUTIONS").unwrap_or_default(),
+            ),
+            json_stream_threshold_bytes: env::var("JSON_STREAM_THRESHOLD_BYTES")
+                .unwrap_or_else(|_| "33554432".to_string())
+                .parse()
+                .unwrap_or(33_554_432), // 32MB default
+            // Estimated points = sum over sensors of range / sample interval;
+            // the default is about a year of 10-minute data for 100 sensors
+            readings_point_budget: env::var("READINGS_POINT_BUDGET")
+                .unwrap_or_else(|_| "5000000".to_string())
+                .parse()
+                .unwrap_or(5_000_000),
+            max_sensors_per_request: env::var("MAX_SENSORS_PER_REQUEST")
+                .unwrap_or_else(|_| "50".to_string())
+                .parse()
+                .unwrap_or(50),
+            // Relative duration such as 7d; empty disables, invalid falls back to 7d
+            default_readings_window: parse_default_window(
+                &env::var("DEFAULT_READINGS_WINDOW").unwrap_or_else(|_| "7d".to_string()),
+            ),
+            shutdown_drain_seconds: env::var("SHUTDOWN_DRAIN_SECONDS")
+                .unwrap_or_else(|_| "0".to_string())
+                .parse()
+                .unwrap_or(0),
+            shutdown_timeout_seconds: env::var("SHUTDOWN_TIMEOUT_SECONDS")
+                .unwrap_or_else(|_| "30".to_string())
+                .parse()
+                .unwrap_or(30),
 
             // Rate limiting
             // With response caching, rate limits primarily prevent bandwidth abuse
@@ -154,19 +324,39 @@ impl Config {
                 .parse()
                 .unwrap_or(300),
             bulk_concurrent_limit: env::var("BULK_CONCURRENT_LIMIT")
-                .unwrap_or_else(|_| "10".to_string())
+                .unwrap_or_else(|_| "5".to_string())
                 .parse()
-                .unwrap_or(10),
+                .unwrap_or(5),
+            bulk_bypass_points: env::var("BULK_BYPASS_POINTS")
+                .unwrap_or_else(|_| "10000".to_string())
+                .parse()
+                .unwrap_or(10_000), // 0 disables
+            per_client_concurrent_limit: env::var("PER_CLIENT_CONCURRENT_LIMIT")
+                .unwrap_or_else(|_| "4".to_string())
+                .parse()
+                .unwrap_or(4), // 0 disables
 
             // Caching
             cache_ttl_seconds: env::var("CACHE_TTL_SECONDS")
                 .unwrap_or_else(|_| "300".to_string())
                 .parse()
                 .unwrap_or(300), // 5 minutes default
+            cache_unbounded_ttl_seconds: env::var("CACHE_UNBOUNDED_TTL_SECONDS")
+                .unwrap_or_else(|_| "30".to_string())
+                .parse()
+                .unwrap_or(30),
             cache_max_bytes: env::var("CACHE_MAX_BYTES")
                 .unwrap_or_else(|_| "209715200".to_string())
                 .parse()
                 .unwrap_or(209_715_200), // 200MB default
+            cache_latest_ttl_seconds: env::var("CACHE_LATEST_TTL_SECONDS")
+                .unwrap_or_else(|_| "10".to_string())
+                .parse()
+                .unwrap_or(10),
+            cache_aggregates_ttl_seconds: env::var("CACHE_AGGREGATES_TTL_SECONDS")
+                .unwrap_or_else(|_| "3600".to_string())
+                .parse()
+                .unwrap_or(3600), // 1 hour default
 
             // Application metadata
             deployment: env::var("DEPLOYMENT")
@@ -180,6 +370,51 @@ impl Config {
     pub fn bind_address(&self) -> String {
         format!("{}:{}", self.api_host, self.api_port)
     }
+
+    /// Whether the canonical aggregate `resolution` may be queried
+    #[must_use]
+    pub fn resolution_enabled(&self, resolution: &str) -> bool {
+        self.enabled_resolutions.iter().any(|r| r == resolution)
+    }
+}
+
+/// Parse a comma-separated resolution list into canonical names.
+///
+/// Names are matched like the aggregates path parameter (case-insensitive,
+/// with aliases). Unknown names are dropped; a list with no valid names
+/// enables every resolution.
+pub fn parse_resolutions(value: &str) -> Vec<String> {
+    let mut resolutions: Vec<String> = Vec::new();
+    for name in value.split(',').filter_map(normalize_resolution) {
+        if !resolutions.iter().any(|r| r == name) {
+            resolutions.push(name.to_string());
+        }
+    }
+    if resolutions.is_empty() {
+        return ALL_RESOLUTIONS.iter().map(ToString::to_string).collect();
+    }
+    resolutions
+}
+
+/// Split a comma-separated list, trimming entries and dropping empty ones.
+pub fn parse_list(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(ToString::to_string)
+        .collect()
+}
+
+/// Parse `DEFAULT_READINGS_WINDOW` as a relative duration such as `7d`.
+///
+/// An empty value disables the default window; an invalid one falls back to
+/// 7 days rather than silently serving all history.
+pub fn parse_default_window(value: &str) -> Option<chrono::Duration> {
+    if value.trim().is_empty() {
+        return None;
+    }
+    Some(parse_relative_duration(value).unwrap_or_else(|_| chrono::Duration::days(7)))
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -27,6 +27,9 @@ pub struct Model {
     #[sea_orm(column_type = "JsonBinary")]
     pub ack_comments: Option<serde_json::Value>,
     pub ack_action_taken: Option<String>,
+    /// Vaisala location IDs the alarm applies to (JSON array)
+    #[sea_orm(column_type = "JsonBinary")]
+    pub location_ids: Option<serde_json::Value>,
     pub created_at: Option<DateTimeWithTimeZone>,
     pub updated_at: Option<DateTimeWithTimeZone>,
 }
@@ -18,6 +18,11 @@ pub struct Model {
     pub device_id: Option<i32>,
     pub channel_id: Option<i32>,
     pub host_id: Option<i32>,
+    /// Vaisala location ID the event refers to
+    pub location_id: Option<i32>,
+    /// All Vaisala location IDs the event affects (JSON array)
+    #[sea_orm(column_type = "JsonBinary")]
+    pub affected_location_ids: Option<serde_json::Value>,
     #[sea_orm(column_type = "JsonBinary")]
     pub extra_fields: Option<serde_json::Value>,
 }
@@ -3,8 +3,12 @@ pub mod alarms;
 pub mod calibrations;
 pub mod device_status;
 pub mod events;
+pub mod maintenance_windows;
 pub mod readings;
+pub mod sensor_thresholds;
+pub mod sensor_type_patterns;
 pub mod sensors;
 pub mod stations;
+pub mod sync_runs;
 pub mod sync_state;
 pub mod zones;
@@ -10,6 +10,12 @@ pub struct Model {
     pub time: DateTimeWithTimeZone,
     pub value: f64,
     pub logged: Option<bool>,
+    /// When sync inserted (or last replaced) the row; null for rows ingested
+    /// before this was tracked
+    pub ingested_at: Option<DateTimeWithTimeZone>,
+    /// Sample time as reported by Vaisala, before alignment to the 10-minute
+    /// grid; null for rows synced before this was tracked
+    pub raw_time: Option<DateTimeWithTimeZone>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -0,0 +1,19 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Sensor name keyword mapped to a sensor type during location discovery
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "sensor_type_patterns")]
+pub struct Model {
+    /// Case-sensitive substring of the sensor name
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub keyword: String,
+    pub sensor_type: String,
+    /// Higher priorities are tried first
+    pub priority: i32,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
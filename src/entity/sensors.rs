@@ -21,11 +21,27 @@ pub struct Model {
     pub channel_id: Option<i32>,
     pub sample_interval_sec: Option<i32>,
     pub is_active: Option<bool>,
+    pub align_timestamps: bool,
+    pub alarming_paused: bool,
     pub created_at: Option<DateTimeWithTimeZone>,
     pub updated_at: Option<DateTimeWithTimeZone>,
     pub discovered_at: Option<DateTimeWithTimeZone>,
 }
 
+impl Model {
+    /// Sample interval in seconds, or `default_sec` (`DEFAULT_SAMPLE_INTERVAL_SEC`)
+    /// when Vaisala reported none.
+    ///
+    /// Use this wherever an interval is needed instead of reading
+    /// `sample_interval_sec` directly.
+    pub fn effective_interval(&self, default_sec: i64) -> i64 {
+        self.sample_interval_sec
+            .map(i64::from)
+            .filter(|&sec| sec > 0)
+            .unwrap_or(default_sec)
+    }
+}
+
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
     #[sea_orm(
@@ -46,6 +62,8 @@ pub enum Relation {
     Events,
     #[sea_orm(has_many = "super::alarm_locations::Entity")]
     AlarmLocations,
+    #[sea_orm(has_many = "super::sensor_thresholds::Entity")]
+    Thresholds,
 }
 
 impl Related<super::stations::Entity> for Entity {
@@ -72,6 +90,12 @@ impl Related<super::calibrations::Entity> for Entity {
     }
 }
 
+impl Related<super::sensor_thresholds::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Thresholds.def()
+    }
+}
+
 impl Related<super::sync_state::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::SyncState.def()
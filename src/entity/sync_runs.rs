@@ -0,0 +1,38 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Outcome of one scheduler iteration, stored as lowercase text
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, Hash, EnumIter, DeriveActiveEnum, Serialize, Deserialize,
+    ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::N(32))")]
+#[serde(rename_all = "lowercase")]
+pub enum SyncRunStatus {
+    #[sea_orm(string_value = "success")]
+    Success,
+    /// Failed after all retries
+    #[sea_orm(string_value = "error")]
+    Error,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "sync_runs")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+    /// Scheduler task, one of `sync::history::SYNC_TASKS`
+    pub task: String,
+    pub started_at: DateTimeWithTimeZone,
+    pub finished_at: DateTimeWithTimeZone,
+    pub status: SyncRunStatus,
+    /// Rows written, for tasks that report it
+    pub records_affected: Option<i64>,
+    pub error: Option<String>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
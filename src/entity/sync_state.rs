@@ -1,5 +1,23 @@
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Outcome of the last readings sync for a sensor, stored as lowercase text
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, Hash, EnumIter, DeriveActiveEnum, Serialize, Deserialize,
+    ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::N(32))")]
+#[serde(rename_all = "lowercase")]
+pub enum SyncStatus {
+    /// Discovered but not synced yet
+    #[sea_orm(string_value = "pending")]
+    Pending,
+    #[sea_orm(string_value = "success")]
+    Success,
+    #[sea_orm(string_value = "error")]
+    Error,
+}
 
 #[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
 #[sea_orm(table_name = "sync_state")]
@@ -8,7 +26,7 @@ pub struct Model {
     pub sensor_id: Uuid,
     pub last_data_time: Option<DateTimeWithTimeZone>,
     pub last_sync_attempt: Option<DateTimeWithTimeZone>,
-    pub sync_status: Option<String>,
+    pub sync_status: Option<SyncStatus>,
     pub error_message: Option<String>,
     pub retry_count: Option<i32>,
     pub last_full_sync: Option<DateTimeWithTimeZone>,
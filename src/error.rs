@@ -25,8 +25,16 @@ pub enum AppError {
     #[error("Service unavailable: {0}")]
     ServiceUnavailable(String),
 
-    #[error("Not found: {0}")]
-    NotFound(String),
+    /// `resource` is a lowercase kind (`zone`, `station`, ...) and `id` the
+    /// identifier as given by the client
+    #[error("Not found: {resource} {id}")]
+    NotFound { resource: &'static str, id: String },
+
+    #[error("Unauthorized: {0}")]
+    Unauthorized(String),
+
+    #[error("Method not allowed: {0}")]
+    MethodNotAllowed(String),
 }
 
 impl IntoResponse for AppError {
@@ -59,15 +67,43 @@ impl IntoResponse for AppError {
                 )
             }
             Self::ServiceUnavailable(msg) => (StatusCode::SERVICE_UNAVAILABLE, msg.clone()),
-            Self::NotFound(msg) => (StatusCode::NOT_FOUND, msg.clone()),
+            Self::NotFound { resource, .. } => {
+                (StatusCode::NOT_FOUND, format!("{} not found", resource_label(resource)))
+            }
+            Self::Unauthorized(msg) => (StatusCode::UNAUTHORIZED, msg.clone()),
+            Self::MethodNotAllowed(msg) => (StatusCode::METHOD_NOT_ALLOWED, msg.clone()),
         };
 
-        let body = Json(json!({
+        let mut body = json!({
             "error": error_message,
-        }));
+        });
+        // Lets clients tell which part of a nested route was missing
+        if let Self::NotFound { resource, id } = &self {
+            body["resource"] = json!(resource);
+            body["id"] = json!(id);
+        }
+
+        (status, Json(body)).into_response()
+    }
+}
 
-        (status, body).into_response()
+impl AppError {
+    /// A missing `resource` (e.g. `"station"`) looked up by `id`
+    pub fn not_found(resource: &'static str, id: impl Into<String>) -> Self {
+        Self::NotFound {
+            resource,
+            id: id.into(),
+        }
     }
 }
 
+/// `sync_pass` -> `Sync pass`
+fn resource_label(resource: &str) -> String {
+    let label = resource.replace('_', " ");
+    let mut chars = label.chars();
+    chars.next().map_or_else(String::new, |first| {
+        first.to_uppercase().chain(chars).collect()
+    })
+}
+
 pub type AppResult<T> = Result<T, AppError>;
@@ -1,12 +1,16 @@
 use sea_orm::Database;
 use sea_orm_migration::MigratorTrait;
+use std::future::IntoFuture;
+use std::time::Duration;
 use tokio::net::TcpListener;
 use tokio::signal;
+use tokio::sync::watch;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 use river_db::common::AppState;
 use river_db::config::Config;
 use river_db::routes;
+use river_db::services::timescale;
 use river_db::sync;
 use river_db::vaisala::VaisalaClient;
 
@@ -42,19 +46,51 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     migration::Migrator::up(&db, None).await?;
     tracing::info!("Migrations completed");
 
+    // Warn early if TimescaleDB features are missing
+    timescale::check_setup(&db).await;
+
+    // Backfill empty continuous aggregates (e.g. after a restore) without
+    // delaying startup
+    if config.refresh_aggregates_on_start {
+        tokio::spawn(timescale::refresh_empty_aggregates(db.clone()));
+    }
+
     // Create Vaisala client
     let vaisala_client = VaisalaClient::new(&config);
     tracing::info!("Vaisala client initialized");
 
     // Create application state
-    let state = AppState::new(db, config.clone(), vaisala_client);
+    let mut state = AppState::new(db, config.clone(), vaisala_client);
+
+    // Optional read replica for API queries (fail-fast if configured but unreachable)
+    if let Some(replica_url) = &config.database_replica_url {
+        tracing::info!("Connecting to read replica...");
+        let replica = Database::connect(replica_url).await?;
+        state = state.with_read_replica(replica);
+        tracing::info!("Read replica connection established");
+    }
 
-    // Spawn background sync tasks (fire-and-forget, non-blocking)
+    // Spawn background sync tasks (non-blocking, aborted on shutdown)
     tracing::info!("Spawning background sync tasks...");
-    tokio::spawn(sync::scheduler::run_readings_sync(state.clone()));
-    tokio::spawn(sync::scheduler::run_device_status_sync(state.clone()));
-    tokio::spawn(sync::scheduler::run_alarms_sync(state.clone()));
-    tokio::spawn(sync::scheduler::run_events_sync(state.clone()));
+    let sync_tasks = [
+        tokio::spawn(sync::scheduler::run_readings_sync(state.clone())),
+        tokio::spawn(sync::scheduler::run_device_status_sync(state.clone())),
+        tokio::spawn(sync::scheduler::run_alarms_sync(state.clone())),
+        tokio::spawn(sync::scheduler::run_events_sync(state.clone())),
+    ];
+
+    // Drain recorded histogram samples even when /metrics is never scraped
+    let metrics = state.metrics.clone();
+    tokio::spawn(async move {
+        let mut upkeep = tokio::time::interval(Duration::from_secs(5));
+        loop {
+            upkeep.tick().await;
+            metrics.run_upkeep();
+        }
+    });
+
+    let in_flight = state.in_flight.clone();
+    let shutdown = state.shutdown.clone();
 
     // Build router
     let app = routes::build_router(state);
@@ -63,11 +99,57 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let addr = config.bind_address();
     tracing::info!(address = %addr, "Starting server");
     let listener = TcpListener::bind(&addr).await?;
-    axum::serve(listener, app)
-        .with_graceful_shutdown(shutdown_signal())
-        .await?;
 
-    tracing::info!("Server shut down gracefully");
+    // Flipped once the server stops accepting connections, starting the timeout
+    let (stopping_tx, mut stopping_rx) = watch::channel(false);
+    let drain = Duration::from_secs(config.shutdown_drain_seconds);
+    let draining = in_flight.clone();
+    let server = axum::serve(listener, app).with_graceful_shutdown(async move {
+        shutdown_signal().await;
+
+        // Each sensor's readings are stored in one transaction, so an aborted
+        // pass rolls back cleanly and resumes from sync_state on the next start
+        for task in &sync_tasks {
+            task.abort();
+        }
+
+        if !drain.is_zero() {
+            tracing::info!(drain_secs = drain.as_secs(), "Draining before shutdown");
+            tokio::time::sleep(drain).await;
+        }
+
+        tracing::info!(
+            in_flight = draining.count(),
+            "Refusing new connections, waiting for in-flight requests"
+        );
+        // Device status streams never finish on their own
+        shutdown.cancel();
+        let _ = stopping_tx.send(true);
+    });
+
+    // Bound the wait so a long export can't hold up a deploy
+    let timeout = Duration::from_secs(config.shutdown_timeout_seconds);
+    let deadline = async move {
+        if stopping_rx.wait_for(|stopping| *stopping).await.is_err() || timeout.is_zero() {
+            std::future::pending::<()>().await;
+        }
+        tokio::time::sleep(timeout).await;
+    };
+
+    tokio::select! {
+        result = server.into_future() => {
+            result?;
+            tracing::info!("Server shut down gracefully");
+        }
+        () = deadline => {
+            tracing::warn!(
+                in_flight = in_flight.count(),
+                timeout_secs = timeout.as_secs(),
+                "Shutdown timeout elapsed, dropping in-flight requests"
+            );
+        }
+    }
+
     Ok(())
 }
 
@@ -0,0 +1,315 @@
+use axum::{
+    extract::{Request, State},
+    http::{header, StatusCode},
+    middleware::Next,
+    response::Response,
+    Json,
+};
+use chrono::Utc;
+use sea_orm::{EntityTrait, Set};
+use std::sync::Arc;
+use subtle::ConstantTimeEq;
+use uuid::Uuid;
+
+use crate::common::{time, AppState};
+use crate::entity::maintenance_windows;
+use crate::error::{AppError, AppResult};
+use crate::services::{diagnostics, maintenance, retention};
+use crate::sync::relink;
+
+use super::types::{
+    DiagnosticsResponse, DropChunksRequest, DropChunksResponse, HypertableDiagnostics,
+    MaintenanceWindowRequest, MaintenanceWindowResponse, RelinkResponse, RetentionPolicyRequest,
+    RetentionPolicyResponse,
+};
+
+/// Middleware requiring `Authorization: Bearer <ADMIN_TOKEN>`.
+///
+/// The token is compared in constant time, so response timing does not leak
+/// how much of a guess matched. Use with `axum::middleware::from_fn_with_state`.
+pub async fn require_admin_token(
+    State(token): State<Arc<str>>,
+    req: Request,
+    next: Next,
+) -> AppResult<Response> {
+    let authorized = req
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .is_some_and(|given| bool::from(given.as_bytes().ct_eq(token.as_bytes())));
+
+    if !authorized {
+        return Err(AppError::Unauthorized("Invalid or missing admin token".to_string()));
+    }
+
+    Ok(next.run(req).await)
+}
+
+fn window_response(window: maintenance_windows::Model) -> MaintenanceWindowResponse {
+    MaintenanceWindowResponse {
+        active: maintenance::covers(&window, Utc::now()),
+        id: window.id,
+        start: window.starts_at.with_timezone(&Utc),
+        end: window.ends_at.with_timezone(&Utc),
+        reason: window.reason,
+    }
+}
+
+/// Get the current maintenance window
+///
+/// Returns the window in effect now, or else the next scheduled one. Returns
+/// null when none is active or upcoming.
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/maintenance",
+    responses(
+        (status = 200, description = "Maintenance window retrieved successfully", body = Option<MaintenanceWindowResponse>),
+        (status = 401, description = "Invalid or missing admin token"),
+    ),
+    tag = "admin"
+)]
+pub async fn get_maintenance_window(
+    State(state): State<AppState>,
+) -> AppResult<Json<Option<MaintenanceWindowResponse>>> {
+    let window = maintenance::current_or_next_window(&state.db, Utc::now()).await?;
+
+    Ok(Json(window.map(window_response)))
+}
+
+/// Schedule a maintenance window
+///
+/// Alarms keep syncing while the window is in effect. The window is recorded
+/// for alarm notifications to honour; none are sent yet.
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/maintenance",
+    request_body = MaintenanceWindowRequest,
+    responses(
+        (status = 201, description = "Maintenance window scheduled", body = MaintenanceWindowResponse),
+        (status = 400, description = "End is not after start"),
+        (status = 401, description = "Invalid or missing admin token"),
+    ),
+    tag = "admin"
+)]
+pub async fn set_maintenance_window(
+    State(state): State<AppState>,
+    Json(body): Json<MaintenanceWindowRequest>,
+) -> AppResult<(StatusCode, Json<MaintenanceWindowResponse>)> {
+    time::validate_range(body.start, body.end)?;
+
+    let window = maintenance_windows::Model {
+        id: Uuid::new_v4(),
+        starts_at: body.start.into(),
+        ends_at: body.end.into(),
+        reason: body.reason,
+        created_at: Some(Utc::now().into()),
+    };
+
+    maintenance_windows::Entity::insert(maintenance_windows::ActiveModel {
+        id: Set(window.id),
+        starts_at: Set(window.starts_at),
+        ends_at: Set(window.ends_at),
+        reason: Set(window.reason.clone()),
+        created_at: Set(window.created_at),
+    })
+    .exec_without_returning(&state.db)
+    .await?;
+
+    tracing::info!(start = %body.start, end = %body.end, "Maintenance window scheduled");
+
+    Ok((StatusCode::CREATED, Json(window_response(window))))
+}
+
+fn policy_response(policy: retention::RetentionPolicy) -> RetentionPolicyResponse {
+    RetentionPolicyResponse {
+        job_id: policy.job_id,
+        drop_after: policy.drop_after,
+        schedule_interval: policy.schedule_interval,
+        next_start: policy.next_start,
+    }
+}
+
+/// Reject destructive requests that were not explicitly confirmed
+fn require_confirmation(confirm: bool, action: &str) -> AppResult<()> {
+    if !confirm {
+        return Err(AppError::BadRequest(format!(
+            "{action} permanently deletes raw readings; set \"confirm\": true to proceed"
+        )));
+    }
+    Ok(())
+}
+
+/// Get the raw readings retention policy
+///
+/// Returns null when readings are kept forever.
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/retention",
+    responses(
+        (status = 200, description = "Retention policy retrieved successfully", body = Option<RetentionPolicyResponse>),
+        (status = 401, description = "Invalid or missing admin token"),
+        (status = 503, description = "TimescaleDB not available"),
+    ),
+    tag = "admin"
+)]
+pub async fn get_retention_policy(
+    State(state): State<AppState>,
+) -> AppResult<Json<Option<RetentionPolicyResponse>>> {
+    let policy = retention::current_policy(&state.db).await?;
+
+    Ok(Json(policy.map(policy_response)))
+}
+
+/// Set the raw readings retention policy
+///
+/// Replaces any existing policy. TimescaleDB then periodically drops raw
+/// readings chunks older than `older_than`; hourly to monthly aggregates are
+/// kept. The age must exceed 124 days (the widest aggregate refresh window)
+/// and VAISALA_MAX_HISTORY_DAYS.
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/retention",
+    request_body = RetentionPolicyRequest,
+    responses(
+        (status = 200, description = "Retention policy set", body = RetentionPolicyResponse),
+        (status = 400, description = "Invalid or too short interval, or not confirmed"),
+        (status = 401, description = "Invalid or missing admin token"),
+        (status = 503, description = "TimescaleDB not available"),
+    ),
+    tag = "admin"
+)]
+pub async fn set_retention_policy(
+    State(state): State<AppState>,
+    Json(body): Json<RetentionPolicyRequest>,
+) -> AppResult<Json<RetentionPolicyResponse>> {
+    let interval = retention::validate(&body.older_than, state.config.vaisala_max_history_days)?;
+    require_confirmation(body.confirm, "A retention policy")?;
+
+    let policy = retention::set_policy(&state.db, interval)
+        .await?
+        .ok_or_else(|| AppError::Internal("retention policy not found after adding it".to_string()))?;
+
+    Ok(Json(policy_response(policy)))
+}
+
+/// Remove the raw readings retention policy
+#[utoipa::path(
+    delete,
+    path = "/api/v1/admin/retention",
+    responses(
+        (status = 204, description = "Retention policy removed"),
+        (status = 401, description = "Invalid or missing admin token"),
+        (status = 404, description = "No retention policy"),
+        (status = 503, description = "TimescaleDB not available"),
+    ),
+    tag = "admin"
+)]
+pub async fn delete_retention_policy(State(state): State<AppState>) -> AppResult<StatusCode> {
+    if !retention::remove_policy(&state.db).await? {
+        return Err(AppError::not_found("retention_policy", "readings"));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Drop old raw readings now
+///
+/// Drops readings chunks entirely older than `older_than` once, with the
+/// same minimum age as the retention policy. Aggregates are kept.
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/retention/drop",
+    request_body = DropChunksRequest,
+    responses(
+        (status = 200, description = "Chunks dropped", body = DropChunksResponse),
+        (status = 400, description = "Invalid or too short interval, or not confirmed"),
+        (status = 401, description = "Invalid or missing admin token"),
+        (status = 503, description = "TimescaleDB not available"),
+    ),
+    tag = "admin"
+)]
+pub async fn drop_old_chunks(
+    State(state): State<AppState>,
+    Json(body): Json<DropChunksRequest>,
+) -> AppResult<Json<DropChunksResponse>> {
+    let interval = retention::validate(&body.older_than, state.config.vaisala_max_history_days)?;
+    require_confirmation(body.confirm, "Dropping chunks")?;
+
+    let dropped_chunks = retention::drop_chunks(&state.db, interval).await?;
+
+    Ok(Json(DropChunksResponse {
+        older_than: interval.to_string(),
+        dropped_chunks,
+    }))
+}
+
+/// Link orphaned alarms and events to sensors
+///
+/// Alarms and events synced before their sensor was discovered are stored
+/// without a sensor or station. Resolves their Vaisala location IDs against
+/// the current sensors and fills in the missing links, including alarm to
+/// sensor rows. Safe to repeat.
+///
+/// Location IDs are only stored for alarms and events synced since they were
+/// tracked; older orphans cannot be linked and are reported separately.
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/relink",
+    responses(
+        (status = 200, description = "Orphans relinked", body = RelinkResponse),
+        (status = 401, description = "Invalid or missing admin token"),
+    ),
+    tag = "admin"
+)]
+pub async fn relink_orphans(State(state): State<AppState>) -> AppResult<Json<RelinkResponse>> {
+    let counts = relink::relink_orphans(&state.db).await?;
+
+    Ok(Json(RelinkResponse {
+        alarms: counts.alarms,
+        alarm_locations: counts.alarm_locations,
+        events: counts.events,
+        alarms_without_locations: counts.alarms_without_locations,
+        events_without_location: counts.events_without_location,
+    }))
+}
+
+/// Get database storage diagnostics
+///
+/// Reports the database size and, per hypertable, its size, chunk count,
+/// compression savings and estimated rows, for capacity planning. When
+/// TimescaleDB's size functions are unavailable only the database size is
+/// returned, with `timescaledb_available: false`.
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/diagnostics",
+    responses(
+        (status = 200, description = "Diagnostics retrieved", body = DiagnosticsResponse),
+        (status = 401, description = "Invalid or missing admin token"),
+    ),
+    tag = "admin"
+)]
+pub async fn get_diagnostics(
+    State(state): State<AppState>,
+) -> AppResult<Json<DiagnosticsResponse>> {
+    let database_bytes = diagnostics::database_size(&state.read_db).await?;
+    let stats = diagnostics::hypertable_stats(&state.read_db).await?;
+
+    Ok(Json(DiagnosticsResponse {
+        database_bytes,
+        timescaledb_available: stats.is_some(),
+        hypertables: stats
+            .unwrap_or_default()
+            .into_iter()
+            .map(|h| HypertableDiagnostics {
+                compression_ratio: h.compression_ratio(),
+                name: h.name,
+                total_bytes: h.total_bytes,
+                chunk_count: h.chunk_count,
+                compressed_chunk_count: h.compressed_chunk_count,
+                before_compression_bytes: h.before_compression_bytes,
+                after_compression_bytes: h.after_compression_bytes,
+                row_estimate: h.row_estimate,
+            })
+            .collect(),
+    }))
+}
@@ -0,0 +1,20 @@
+mod handlers;
+mod types;
+
+pub use handlers::{
+    delete_retention_policy, drop_old_chunks, get_diagnostics, get_maintenance_window,
+    get_retention_policy, relink_orphans, require_admin_token, set_maintenance_window,
+    set_retention_policy,
+};
+pub use types::{
+    DiagnosticsResponse, DropChunksRequest, DropChunksResponse, HypertableDiagnostics,
+    MaintenanceWindowRequest, MaintenanceWindowResponse, RelinkResponse, RetentionPolicyRequest,
+    RetentionPolicyResponse,
+};
+
+// Re-export utoipa path structs for OpenAPI documentation
+pub use handlers::{
+    __path_delete_retention_policy, __path_drop_old_chunks, __path_get_diagnostics,
+    __path_get_maintenance_window, __path_get_retention_policy, __path_relink_orphans,
+    __path_set_maintenance_window, __path_set_retention_policy,
+};
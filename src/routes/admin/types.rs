@@ -0,0 +1,115 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Body for scheduling a maintenance window
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct MaintenanceWindowRequest {
+    /// Start of the window (ISO 8601)
+    pub start: DateTime<Utc>,
+    /// End of the window, exclusive (ISO 8601)
+    pub end: DateTime<Utc>,
+    /// Free-text note, e.g. the planned work
+    pub reason: Option<String>,
+}
+
+/// A scheduled maintenance window
+#[derive(Debug, Serialize, ToSchema)]
+pub struct MaintenanceWindowResponse {
+    pub id: Uuid,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub reason: Option<String>,
+    /// Whether the window is in effect now
+    pub active: bool,
+}
+
+/// Body for setting the raw readings retention policy
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct RetentionPolicyRequest {
+    /// Drop readings older than this, e.g. `400 days` or `2 years`
+    /// (units: days, weeks, months, years)
+    pub older_than: String,
+    /// Must be true: readings dropped by the policy cannot be recovered
+    #[serde(default)]
+    pub confirm: bool,
+}
+
+/// The retention policy on raw readings
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RetentionPolicyResponse {
+    /// TimescaleDB background job ID
+    pub job_id: i32,
+    /// Age past which readings chunks are dropped, as stored by TimescaleDB
+    pub drop_after: Option<String>,
+    /// How often the policy runs
+    pub schedule_interval: Option<String>,
+    /// Next scheduled run
+    pub next_start: Option<DateTime<Utc>>,
+}
+
+/// Body for dropping old readings once
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct DropChunksRequest {
+    /// Drop readings chunks entirely older than this, e.g. `400 days`
+    pub older_than: String,
+    /// Must be true: dropped readings cannot be recovered
+    #[serde(default)]
+    pub confirm: bool,
+}
+
+/// Result of a one-off chunk drop
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DropChunksResponse {
+    /// Normalized age, e.g. `400 days`
+    pub older_than: String,
+    /// Names of the dropped chunks
+    pub dropped_chunks: Vec<String>,
+}
+
+/// Records linked by a relink pass
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RelinkResponse {
+    /// Alarms that gained a station or sensor links
+    pub alarms: u64,
+    /// Alarm to sensor links created
+    pub alarm_locations: u64,
+    /// Events linked to a sensor and station
+    pub events: u64,
+    /// Orphaned alarms synced before location IDs were stored, left unlinked.
+    /// Those still active in Vaisala can be linked after the next alarm sync
+    pub alarms_without_locations: u64,
+    /// Orphaned events synced before location IDs were stored; these cannot
+    /// be linked
+    pub events_without_location: u64,
+}
+
+/// Storage statistics of one TimescaleDB hypertable
+#[derive(Debug, Serialize, ToSchema)]
+pub struct HypertableDiagnostics {
+    pub name: String,
+    /// Total size including indexes, TOAST and compressed chunks
+    pub total_bytes: Option<i64>,
+    pub chunk_count: i64,
+    pub compressed_chunk_count: i64,
+    /// Size of the compressed chunks before compression (null if compression is off)
+    pub before_compression_bytes: Option<i64>,
+    /// Size of the compressed chunks after compression (null if compression is off)
+    pub after_compression_bytes: Option<i64>,
+    /// `before_compression_bytes / after_compression_bytes`
+    pub compression_ratio: Option<f64>,
+    /// Approximate row count from table statistics
+    pub row_estimate: Option<i64>,
+}
+
+/// Database storage overview
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DiagnosticsResponse {
+    /// Size of the whole database
+    pub database_bytes: i64,
+    /// Whether TimescaleDB size functions were available
+    pub timescaledb_available: bool,
+    /// Hypertables, largest first (empty when TimescaleDB is unavailable)
+    pub hypertables: Vec<HypertableDiagnostics>,
+}
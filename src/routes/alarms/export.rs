@@ -0,0 +1,232 @@
+//! CSV and NDJSON exports of alarms and events.
+
+use axum::{
+    http::{header, HeaderValue},
+    response::Response,
+};
+use sea_orm::{
+    ColumnTrait, Condition, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder, QuerySelect,
+    Select,
+};
+use std::collections::HashMap;
+use tokio::sync::{mpsc, OwnedSemaphorePermit};
+use tokio_stream::wrappers::ReceiverStream;
+use uuid::Uuid;
+
+use crate::common::format::CsvLines;
+use crate::entity::{events, stations};
+use crate::error::{AppError, AppResult};
+
+use super::handlers::event_response;
+use super::types::{AlarmSummary, EventResponse};
+
+/// Alarm CSV header row
+const ALARM_COLUMNS: [&str; 8] = [
+    "id",
+    "severity",
+    "description",
+    "when_on",
+    "when_off",
+    "status",
+    "station",
+    "duration",
+];
+
+/// Event CSV header row
+const EVENT_COLUMNS: [&str; 6] = ["time", "num", "category", "message", "user", "station"];
+
+/// Events fetched per query while streaming an export
+const EVENT_EXPORT_PAGE_SIZE: u64 = 1000;
+
+type Line = Result<String, std::io::Error>;
+
+/// Station names by ID, for the `station` column
+pub async fn station_names(db: &DatabaseConnection) -> AppResult<HashMap<Uuid, String>> {
+    Ok(stations::Entity::find()
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|s| (s.id, s.name))
+        .collect())
+}
+
+fn station_name(names: &HashMap<Uuid, String>, id: Option<Uuid>) -> &str {
+    id.and_then(|id| names.get(&id)).map_or("", String::as_str)
+}
+
+fn stream_response(rx: mpsc::Receiver<Line>, content_type: &'static str) -> AppResult<Response> {
+    Response::builder()
+        .header(header::CONTENT_TYPE, HeaderValue::from_static(content_type))
+        .body(axum::body::Body::from_stream(ReceiverStream::new(rx)))
+        .map_err(|e| AppError::Internal(e.to_string()))
+}
+
+fn content_type(format: &str) -> &'static str {
+    if format == "csv" {
+        "text/csv"
+    } else {
+        "application/x-ndjson"
+    }
+}
+
+/// Chunk to send after adding `alarm`, if any: CSV rows are batched in
+/// `csv`, NDJSON objects go out one line at a time
+fn alarm_line(
+    format: &str,
+    csv: &mut CsvLines,
+    alarm: &AlarmSummary,
+    names: &HashMap<Uuid, String>,
+) -> Option<String> {
+    if format == "csv" {
+        csv.write([
+            alarm.id.to_string(),
+            alarm.severity.to_string(),
+            alarm.description.clone(),
+            alarm.when_on.to_rfc3339(),
+            alarm.when_off.map(|t| t.to_rfc3339()).unwrap_or_default(),
+            alarm.status.to_string(),
+            station_name(names, alarm.station_id).to_string(),
+            alarm.duration.clone(),
+        ]);
+        csv.is_full().then(|| csv.take())
+    } else {
+        Some(format!("{}\n", serde_json::to_string(alarm).unwrap_or_default()))
+    }
+}
+
+/// Chunk to send after adding `event`, like [`alarm_line`]
+fn event_line(
+    format: &str,
+    csv: &mut CsvLines,
+    event: &EventResponse,
+    names: &HashMap<Uuid, String>,
+) -> Option<String> {
+    if format == "csv" {
+        csv.write([
+            event.time.to_rfc3339(),
+            event.vaisala_event_num.to_string(),
+            event.category.clone(),
+            event.message.clone(),
+            event.user_name.clone().unwrap_or_default(),
+            station_name(names, event.station_id).to_string(),
+        ]);
+        csv.is_full().then(|| csv.take())
+    } else {
+        Some(format!("{}\n", serde_json::to_string(event).unwrap_or_default()))
+    }
+}
+
+/// Stream `alarms` as CSV or NDJSON (`format`), holding the bulk `permit`
+/// until the last row is sent.
+pub fn alarms_response(
+    format: &str,
+    alarms: Vec<AlarmSummary>,
+    names: HashMap<Uuid, String>,
+    permit: Option<OwnedSemaphorePermit>,
+) -> AppResult<Response> {
+    let (tx, rx) = mpsc::channel::<Line>(100);
+    let format = format.to_string();
+
+    tokio::spawn(async move {
+        let _permit = permit;
+        let mut csv = CsvLines::new();
+        if format == "csv" {
+            csv.write(ALARM_COLUMNS);
+        }
+        for alarm in &alarms {
+            if let Some(line) = alarm_line(&format, &mut csv, alarm, &names)
+                && tx.send(Ok(line)).await.is_err()
+            {
+                return;
+            }
+        }
+        if format == "csv" {
+            let _ = tx.send(Ok(csv.take())).await;
+        }
+    });
+
+    stream_response(rx, content_type(&format))
+}
+
+/// Events of `select` after `cursor` (the `(time, vaisala_event_num)` of the
+/// last event already sent), newest first, at most `EVENT_EXPORT_PAGE_SIZE`.
+///
+/// Seeking past the cursor instead of using an offset keeps pages stable while
+/// the events sync inserts newer rows, and each page reads only its own rows.
+async fn events_page(
+    db: &DatabaseConnection,
+    select: &Select<events::Entity>,
+    cursor: Option<(chrono::DateTime<chrono::FixedOffset>, i32)>,
+) -> Result<Vec<events::Model>, sea_orm::DbErr> {
+    let mut page = select.clone();
+    if let Some((time, num)) = cursor {
+        page = page.filter(
+            Condition::any().add(events::Column::Time.lt(time)).add(
+                Condition::all()
+                    .add(events::Column::Time.eq(time))
+                    .add(events::Column::VaisalaEventNum.gt(num)),
+            ),
+        );
+    }
+    page.order_by_desc(events::Column::Time)
+        .order_by_asc(events::Column::VaisalaEventNum)
+        .limit(EVENT_EXPORT_PAGE_SIZE)
+        .all(db)
+        .await
+}
+
+/// Stream every event matched by `select` as CSV or NDJSON (`format`),
+/// newest first.
+///
+/// Rows are fetched a page at a time, so exports of long ranges are not
+/// buffered. Events synced after the export started are not included. A
+/// database error mid-stream aborts the response.
+pub fn events_response(
+    format: &str,
+    db: DatabaseConnection,
+    select: Select<events::Entity>,
+    names: HashMap<Uuid, String>,
+    permit: Option<OwnedSemaphorePermit>,
+) -> AppResult<Response> {
+    let (tx, rx) = mpsc::channel::<Line>(100);
+    let format = format.to_string();
+
+    tokio::spawn(async move {
+        let _permit = permit;
+        let mut csv = CsvLines::new();
+        if format == "csv" {
+            csv.write(EVENT_COLUMNS);
+        }
+
+        let mut cursor = None;
+        loop {
+            match events_page(&db, &select, cursor).await {
+                Ok(rows) => {
+                    let last_page = (rows.len() as u64) < EVENT_EXPORT_PAGE_SIZE;
+                    cursor = rows.last().map(|row| (row.time, row.vaisala_event_num));
+                    for row in rows {
+                        if let Some(line) =
+                            event_line(&format, &mut csv, &event_response(row), &names)
+                            && tx.send(Ok(line)).await.is_err()
+                        {
+                            return;
+                        }
+                    }
+                    if last_page {
+                        if format == "csv" {
+                            let _ = tx.send(Ok(csv.take())).await;
+                        }
+                        return;
+                    }
+                }
+                Err(e) => {
+                    tracing::warn!(error = %e, "Events export failed");
+                    let _ = tx.send(Err(std::io::Error::other(e.to_string()))).await;
+                    return;
+                }
+            }
+        }
+    });
+
+    stream_response(rx, content_type(&format))
+}
@@ -1,34 +1,51 @@
 use axum::{
     extract::{Path, Query, State},
+    http::HeaderMap,
+    response::{IntoResponse, Response},
     Json,
 };
 use chrono::Utc;
-use sea_orm::{ColumnTrait, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder, QuerySelect};
+use sea_orm::{
+    sea_query::Expr, ColumnTrait, Condition, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder,
+    QuerySelect,
+};
 use uuid::Uuid;
 
-use crate::common::AppState;
+use crate::common::{format::negotiate_format, pagination, time, AppState};
 use crate::entity::{alarm_locations, alarms, events};
 use crate::error::{AppError, AppResult};
-use crate::routes::resolve_station;
+use crate::routes::{resolve_sensor, resolve_station};
 
+use super::export;
 use super::types::{
-    AlarmResponse, AlarmSummary, AlarmsQuery, EventResponse, EventsListResponse, EventsQuery,
+    ActiveAlarmsQuery, AlarmResponse, AlarmSummary, AlarmsQuery, EventResponse, EventsListResponse,
+    EventsQuery, SensorEventsQuery, StationAlarmsQuery, EVENT_CATEGORIES,
 };
 
 /// List alarms with optional filtering
+///
+/// Supports `format=csv` / `format=ndjson` (or the matching `Accept` header)
+/// for audit exports.
 #[utoipa::path(
     get,
-    path = "/api/alarms",
+    path = "/api/v1/alarms",
     params(AlarmsQuery),
     responses(
-        (status = 200, description = "Alarms retrieved successfully", body = Vec<AlarmSummary>),
+        (status = 200, description = "Alarms retrieved successfully (JSON, CSV or NDJSON)", body = Vec<AlarmSummary>),
+        (status = 400, description = "end is not after start"),
+        (status = 503, description = "Too many concurrent bulk (CSV/NDJSON) requests"),
     ),
     tag = "alarms"
 )]
 pub async fn list_alarms(
     State(state): State<AppState>,
     Query(query): Query<AlarmsQuery>,
-) -> AppResult<Json<Vec<AlarmSummary>>> {
+    headers: HeaderMap,
+) -> AppResult<Response> {
+    time::validate_range(query.start, query.end)?;
+    let format = negotiate_format(query.format.as_deref(), &headers);
+    let permit = state.bulk_limiter.acquire(&format)?;
+
     let mut db_query = alarms::Entity::find();
 
     // Filter by active status
@@ -51,71 +68,50 @@ pub async fn list_alarms(
 
     // Filter by station using the direct station_id column
     if let Some(station_id_str) = &query.station_id {
-        let station = resolve_station(&state.db, station_id_str).await?;
+        let station = resolve_station(&state.read_db, station_id_str).await?;
         db_query = db_query.filter(alarms::Column::StationId.eq(station.id));
     }
 
     let alarms_list = db_query
         .order_by_desc(alarms::Column::WhenOn)
-        .all(&state.db)
+        .all(&state.read_db)
         .await?;
 
-    let response: Vec<AlarmSummary> = alarms_list
-        .into_iter()
-        .map(|a| {
-            let duration = format_duration(a.duration_sec);
-            AlarmSummary {
-                id: a.id,
-                severity: a.severity,
-                description: a.description,
-                when_on: a.when_on.with_timezone(&Utc),
-                when_off: a.when_off.map(|t| t.with_timezone(&Utc)),
-                status: a.status,
-                is_system: a.is_system,
-                location_text: a.location_text,
-                station_id: a.station_id,
-                duration,
-            }
-        })
-        .collect();
+    let response: Vec<AlarmSummary> = alarms_list.into_iter().map(alarm_summary).collect();
 
-    Ok(Json(response))
+    alarms_response(&state, &format, response, permit).await
 }
 
 /// List only active alarms
+///
+/// With `since`, returns the alarms changed at or after that time instead,
+/// including alarms that closed since (`status: false`), so pollers see them
+/// clear.
 #[utoipa::path(
     get,
-    path = "/api/alarms/active",
+    path = "/api/v1/alarms/active",
+    params(ActiveAlarmsQuery),
     responses(
         (status = 200, description = "Active alarms retrieved successfully", body = Vec<AlarmSummary>),
     ),
     tag = "alarms"
 )]
-pub async fn list_active_alarms(State(state): State<AppState>) -> AppResult<Json<Vec<AlarmSummary>>> {
-    let alarms_list = alarms::Entity::find()
-        .filter(alarms::Column::Status.eq(true))
+pub async fn list_active_alarms(
+    State(state): State<AppState>,
+    Query(query): Query<ActiveAlarmsQuery>,
+) -> AppResult<Json<Vec<AlarmSummary>>> {
+    let db_query = match query.since {
+        // Changed since the client's last poll, whether still active or not
+        Some(since) => alarms::Entity::find().filter(alarms::Column::UpdatedAt.gte(since)),
+        None => alarms::Entity::find().filter(alarms::Column::Status.eq(true)),
+    };
+
+    let alarms_list = db_query
         .order_by_desc(alarms::Column::WhenOn)
-        .all(&state.db)
+        .all(&state.read_db)
         .await?;
 
-    let response: Vec<AlarmSummary> = alarms_list
-        .into_iter()
-        .map(|a| {
-            let duration = format_duration(a.duration_sec);
-            AlarmSummary {
-                id: a.id,
-                severity: a.severity,
-                description: a.description,
-                when_on: a.when_on.with_timezone(&Utc),
-                when_off: a.when_off.map(|t| t.with_timezone(&Utc)),
-                status: a.status,
-                is_system: a.is_system,
-                location_text: a.location_text,
-                station_id: a.station_id,
-                duration,
-            }
-        })
-        .collect();
+    let response: Vec<AlarmSummary> = alarms_list.into_iter().map(alarm_summary).collect();
 
     Ok(Json(response))
 }
@@ -123,7 +119,7 @@ pub async fn list_active_alarms(State(state): State<AppState>) -> AppResult<Json
 /// Get a specific alarm by ID
 #[utoipa::path(
     get,
-    path = "/api/alarms/{alarm_id}",
+    path = "/api/v1/alarms/{alarm_id}",
     params(
         ("alarm_id" = Uuid, Path, description = "Alarm UUID"),
     ),
@@ -138,14 +134,14 @@ pub async fn get_alarm(
     Path(alarm_id): Path<Uuid>,
 ) -> AppResult<Json<AlarmResponse>> {
     let alarm = alarms::Entity::find_by_id(alarm_id)
-        .one(&state.db)
+        .one(&state.read_db)
         .await?
-        .ok_or_else(|| AppError::NotFound("Alarm not found".to_string()))?;
+        .ok_or_else(|| AppError::not_found("alarm", alarm_id.to_string()))?;
 
     // Get associated sensor IDs
     let sensor_ids: Vec<Uuid> = alarm_locations::Entity::find()
         .filter(alarm_locations::Column::AlarmId.eq(alarm_id))
-        .all(&state.db)
+        .all(&state.read_db)
         .await?
         .into_iter()
         .map(|al| al.sensor_id)
@@ -176,65 +172,64 @@ pub async fn get_alarm(
 /// List alarms for a specific station
 #[utoipa::path(
     get,
-    path = "/api/stations/{station_id}/alarms",
+    path = "/api/v1/stations/{station_id}/alarms",
     params(
         ("station_id" = String, Path, description = "Station UUID or name"),
+        StationAlarmsQuery,
     ),
     responses(
-        (status = 200, description = "Station alarms retrieved successfully", body = Vec<AlarmSummary>),
+        (status = 200, description = "Station alarms retrieved successfully (JSON, CSV or NDJSON)", body = Vec<AlarmSummary>),
         (status = 404, description = "Station not found"),
+        (status = 503, description = "Too many concurrent bulk (CSV/NDJSON) requests"),
     ),
     tag = "alarms"
 )]
 pub async fn list_station_alarms(
     State(state): State<AppState>,
     Path(station_id): Path<String>,
-) -> AppResult<Json<Vec<AlarmSummary>>> {
-    let station = resolve_station(&state.db, &station_id).await?;
+    Query(query): Query<StationAlarmsQuery>,
+    headers: HeaderMap,
+) -> AppResult<Response> {
+    let station = resolve_station(&state.read_db, &station_id).await?;
+    let format = negotiate_format(query.format.as_deref(), &headers);
+    let permit = state.bulk_limiter.acquire(&format)?;
 
     // Use the direct station_id column for efficient querying
     let alarms_list = alarms::Entity::find()
         .filter(alarms::Column::StationId.eq(station.id))
         .order_by_desc(alarms::Column::WhenOn)
-        .all(&state.db)
+        .all(&state.read_db)
         .await?;
 
-    let response: Vec<AlarmSummary> = alarms_list
-        .into_iter()
-        .map(|a| {
-            let duration = format_duration(a.duration_sec);
-            AlarmSummary {
-                id: a.id,
-                severity: a.severity,
-                description: a.description,
-                when_on: a.when_on.with_timezone(&Utc),
-                when_off: a.when_off.map(|t| t.with_timezone(&Utc)),
-                status: a.status,
-                is_system: a.is_system,
-                location_text: a.location_text,
-                station_id: a.station_id,
-                duration,
-            }
-        })
-        .collect();
+    let response: Vec<AlarmSummary> = alarms_list.into_iter().map(alarm_summary).collect();
 
-    Ok(Json(response))
+    alarms_response(&state, &format, response, permit).await
 }
 
 /// List events with filtering and pagination
+///
+/// CSV and NDJSON exports (`format=csv` / `format=ndjson` or the matching
+/// `Accept` header) ignore pagination and stream every event in the range.
 #[utoipa::path(
     get,
-    path = "/api/events",
+    path = "/api/v1/events",
     params(EventsQuery),
     responses(
-        (status = 200, description = "Events retrieved successfully", body = EventsListResponse),
+        (status = 200, description = "Events retrieved successfully (JSON, CSV or NDJSON)", body = EventsListResponse),
+        (status = 400, description = "Invalid page number, unknown category, or end not after start"),
+        (status = 503, description = "Too many concurrent bulk (CSV/NDJSON) requests"),
     ),
     tag = "events"
 )]
 pub async fn list_events(
     State(state): State<AppState>,
     Query(query): Query<EventsQuery>,
-) -> AppResult<Json<EventsListResponse>> {
+    headers: HeaderMap,
+) -> AppResult<Response> {
+    time::validate_range(query.start, query.end)?;
+    let format = negotiate_format(query.format.as_deref(), &headers);
+    let permit = state.bulk_limiter.acquire(&format)?;
+
     let mut db_query = events::Entity::find();
 
     // Required time range filter
@@ -243,53 +238,179 @@ pub async fn list_events(
 
     // Optional category filter
     if let Some(category) = &query.category {
-        db_query = db_query.filter(events::Column::Category.eq(category));
+        let categories = parse_event_categories(category)?;
+        if !categories.is_empty() {
+            db_query = db_query.filter(events::Column::Category.is_in(categories));
+        }
     }
 
     // Optional station filter using direct station_id column
     if let Some(station_id_str) = &query.station_id {
-        let station = resolve_station(&state.db, station_id_str).await?;
+        let station = resolve_station(&state.read_db, station_id_str).await?;
         db_query = db_query.filter(events::Column::StationId.eq(station.id));
     }
 
+    if format == "csv" || format == "ndjson" {
+        let names = export::station_names(&state.read_db).await?;
+        return export::events_response(&format, state.read_db.clone(), db_query, names, permit);
+    }
+
     // Get total count
-    let total = db_query.clone().count(&state.db).await? as i64;
+    let total = db_query.clone().count(&state.read_db).await? as i64;
 
     // Apply pagination and ordering
-    let page_size = query.page_size.min(1000).max(1);
-    let offset = ((query.page - 1).max(0) * page_size) as u64;
+    let page_size = query.page_size.clamp(1, 1000);
+    let offset = pagination::page_offset(query.page, page_size)?;
 
     let events_list = db_query
         .order_by_desc(events::Column::Time)
         .offset(offset)
         .limit(page_size as u64)
-        .all(&state.db)
+        .all(&state.read_db)
         .await?;
 
-    let events_response: Vec<EventResponse> = events_list
-        .into_iter()
-        .map(|e| EventResponse {
-            time: e.time.with_timezone(&Utc),
-            vaisala_event_num: e.vaisala_event_num,
-            category: e.category,
-            message: e.message,
-            user_name: e.user_name,
-            entity: e.entity,
-            entity_id: e.entity_id,
-            sensor_id: e.sensor_id,
-            station_id: e.station_id,
-            device_id: e.device_id,
-        })
-        .collect();
+    let events_response: Vec<EventResponse> = events_list.into_iter().map(event_response).collect();
 
     Ok(Json(EventsListResponse {
         events: events_response,
         total,
         page: query.page,
         page_size,
+    })
+    .into_response())
+}
+
+/// List events affecting a sensor
+///
+/// Matches events linked to the sensor, events about its Vaisala location,
+/// and events listing it among several affected locations.
+#[utoipa::path(
+    get,
+    path = "/api/v1/sensors/{sensor_id}/events",
+    params(
+        ("sensor_id" = String, Path, description = "Sensor UUID"),
+        SensorEventsQuery,
+    ),
+    responses(
+        (status = 200, description = "Sensor events retrieved successfully", body = EventsListResponse),
+        (status = 400, description = "Invalid page number, unknown category, or end not after start"),
+        (status = 404, description = "Sensor not found"),
+    ),
+    tag = "events"
+)]
+pub async fn list_sensor_events(
+    State(state): State<AppState>,
+    Path(sensor_id): Path<String>,
+    Query(query): Query<SensorEventsQuery>,
+) -> AppResult<Json<EventsListResponse>> {
+    time::validate_range(query.start, query.end)?;
+    let sensor = resolve_sensor(&state.read_db, &sensor_id).await?;
+    let location_id = sensor.vaisala_location_id;
+
+    let mut db_query = events::Entity::find()
+        .filter(events::Column::Time.gte(query.start))
+        .filter(events::Column::Time.lte(query.end))
+        .filter(
+            Condition::any()
+                .add(events::Column::SensorId.eq(sensor.id))
+                .add(events::Column::LocationId.eq(location_id))
+                // An integer, so safe to inline
+                .add(Expr::cust(format!("affected_location_ids @> '[{location_id}]'"))),
+        );
+
+    if let Some(category) = &query.category {
+        let categories = parse_event_categories(category)?;
+        if !categories.is_empty() {
+            db_query = db_query.filter(events::Column::Category.is_in(categories));
+        }
+    }
+
+    let total = db_query.clone().count(&state.read_db).await? as i64;
+
+    let page_size = query.page_size.clamp(1, 1000);
+    let offset = pagination::page_offset(query.page, page_size)?;
+
+    let events_list = db_query
+        .order_by_desc(events::Column::Time)
+        .order_by_asc(events::Column::VaisalaEventNum)
+        .offset(offset)
+        .limit(page_size as u64)
+        .all(&state.read_db)
+        .await?;
+
+    Ok(Json(EventsListResponse {
+        events: events_list.into_iter().map(event_response).collect(),
+        total,
+        page: query.page,
+        page_size,
     }))
 }
 
+/// Parse a comma-separated event category list, rejecting unknown categories.
+///
+/// Matching is case-insensitive; blank entries are ignored.
+pub fn parse_event_categories(list: &str) -> AppResult<Vec<String>> {
+    let mut categories: Vec<String> = Vec::new();
+    for category in list.split(',').map(str::trim).filter(|c| !c.is_empty()) {
+        let category = category.to_lowercase();
+        if !EVENT_CATEGORIES.contains(&category.as_str()) {
+            return Err(AppError::BadRequest(format!(
+                "Unknown event category '{category}'. Valid categories: {}",
+                EVENT_CATEGORIES.join(", ")
+            )));
+        }
+        if !categories.contains(&category) {
+            categories.push(category);
+        }
+    }
+    Ok(categories)
+}
+
+/// Respond with alarm summaries as JSON, or stream them for bulk formats
+async fn alarms_response(
+    state: &AppState,
+    format: &str,
+    alarms: Vec<AlarmSummary>,
+    permit: Option<tokio::sync::OwnedSemaphorePermit>,
+) -> AppResult<Response> {
+    if format != "csv" && format != "ndjson" {
+        return Ok(Json(alarms).into_response());
+    }
+    let names = export::station_names(&state.read_db).await?;
+    export::alarms_response(format, alarms, names, permit)
+}
+
+fn alarm_summary(a: alarms::Model) -> AlarmSummary {
+    AlarmSummary {
+        id: a.id,
+        severity: a.severity,
+        description: a.description,
+        when_on: a.when_on.with_timezone(&Utc),
+        when_off: a.when_off.map(|t| t.with_timezone(&Utc)),
+        status: a.status,
+        is_system: a.is_system,
+        location_text: a.location_text,
+        station_id: a.station_id,
+        duration: format_duration(a.duration_sec),
+        updated_at: a.updated_at.map(|t| t.with_timezone(&Utc)),
+    }
+}
+
+pub(super) fn event_response(e: events::Model) -> EventResponse {
+    EventResponse {
+        time: e.time.with_timezone(&Utc),
+        vaisala_event_num: e.vaisala_event_num,
+        category: e.category,
+        message: e.message,
+        user_name: e.user_name,
+        entity: e.entity,
+        entity_id: e.entity_id,
+        sensor_id: e.sensor_id,
+        station_id: e.station_id,
+        device_id: e.device_id,
+    }
+}
+
 /// Format duration in seconds to human-readable string
 fn format_duration(duration_sec: Option<f64>) -> String {
     match duration_sec {
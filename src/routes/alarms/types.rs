@@ -3,6 +3,8 @@ use serde::{Deserialize, Serialize};
 use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 
+use crate::common::time;
+
 /// Alarm response
 #[derive(Debug, Serialize, ToSchema)]
 pub struct AlarmResponse {
@@ -41,6 +43,8 @@ pub struct AlarmSummary {
     pub station_id: Option<Uuid>,
     /// Human-readable duration
     pub duration: String,
+    /// Last time the alarm was changed by sync; pass as `since` to poll for changes
+    pub updated_at: Option<DateTime<Utc>>,
 }
 
 /// Event response
@@ -67,24 +71,69 @@ pub struct AlarmsQuery {
     pub station_id: Option<String>,
     /// Filter by severity (0-2)
     pub severity: Option<i16>,
-    /// Start of time range (ISO 8601)
+    /// Start of time range (ISO 8601 or Unix epoch seconds)
+    #[serde(default, deserialize_with = "time::deserialize_optional_timestamp")]
     pub start: Option<DateTime<Utc>>,
-    /// End of time range (ISO 8601)
+    /// End of time range (ISO 8601 or Unix epoch seconds)
+    #[serde(default, deserialize_with = "time::deserialize_optional_timestamp")]
     pub end: Option<DateTime<Utc>>,
+    /// Response format: json (default), csv, or ndjson
+    pub format: Option<String>,
+}
+
+/// Query parameters for the station alarms endpoint
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct StationAlarmsQuery {
+    /// Response format: json (default), csv, or ndjson
+    pub format: Option<String>,
+}
+
+/// Query parameters for the active alarms endpoint
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ActiveAlarmsQuery {
+    /// Only return alarms updated at or after this time (ISO 8601), including
+    /// those that closed since
+    pub since: Option<DateTime<Utc>>,
 }
 
+/// Event categories reported by viewLinc
+pub const EVENT_CATEGORIES: [&str; 4] = ["system", "admin", "alarm", "transfer"];
+
 /// Query parameters for events endpoint
 #[derive(Debug, Deserialize, IntoParams)]
 pub struct EventsQuery {
-    /// Start of time range (ISO 8601) - required
+    /// Start of time range (ISO 8601 or Unix epoch seconds) - required
+    #[serde(deserialize_with = "time::deserialize_timestamp")]
     pub start: DateTime<Utc>,
-    /// End of time range (ISO 8601) - required
+    /// End of time range (ISO 8601 or Unix epoch seconds) - required
+    #[serde(deserialize_with = "time::deserialize_timestamp")]
     pub end: DateTime<Utc>,
-    /// Filter by category (system, admin, alarm, transfer)
+    /// Filter by categories (comma-separated: system, admin, alarm, transfer)
     pub category: Option<String>,
     /// Filter by station ID (UUID or name)
     pub station_id: Option<String>,
-    /// Page number (1-indexed)
+    /// Page number (1-indexed, max 1000000)
+    #[serde(default = "default_page")]
+    pub page: i32,
+    /// Page size (max 1000)
+    #[serde(default = "default_page_size")]
+    pub page_size: i32,
+    /// Response format: json (default), csv, or ndjson; csv and ndjson stream all pages
+    pub format: Option<String>,
+}
+
+/// Query parameters for a sensor's events
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct SensorEventsQuery {
+    /// Start of time range (ISO 8601 or Unix epoch seconds) - required
+    #[serde(deserialize_with = "time::deserialize_timestamp")]
+    pub start: DateTime<Utc>,
+    /// End of time range (ISO 8601 or Unix epoch seconds) - required
+    #[serde(deserialize_with = "time::deserialize_timestamp")]
+    pub end: DateTime<Utc>,
+    /// Filter by categories (comma-separated: system, admin, alarm, transfer)
+    pub category: Option<String>,
+    /// Page number (1-indexed, max 1000000)
     #[serde(default = "default_page")]
     pub page: i32,
     /// Page size (max 1000)
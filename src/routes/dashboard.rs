@@ -537,8 +537,8 @@ function formatDuration(ms) {
 async function init() {
     // Fetch zones and stations
     const [zones, stations] = await Promise.all([
-        api('/api/zones'),
-        api('/api/stations')
+        api('/api/v1/zones'),
+        api('/api/v1/stations')
     ]);
 
     const container = document.getElementById('station-groups');
@@ -588,7 +588,7 @@ async function init() {
 
 async function loadStation(stationId) {
     // Always fetch fresh station data to get latest data_start/data_end
-    const station = await api(`/api/stations/${stationId}`, true);
+    const station = await api(`/api/v1/stations/${stationId}`, true);
     state.station = station;
 
     // Clear existing charts and their DOM elements
@@ -867,7 +867,7 @@ const fetchData = debounce(async () => {
         resolution = 'weekly avg';
     }
 
-    const url = `/api/stations/${state.station.id}/${endpoint}?start=${state.start.toISOString()}&end=${state.end.toISOString()}`;
+    const url = `/api/v1/stations/${state.station.id}/${endpoint}?start=${state.start.toISOString()}&end=${state.end.toISOString()}`;
 
     showLoading();
 
@@ -876,7 +876,7 @@ const fetchData = debounce(async () => {
 
         // Fallback to raw readings if aggregates return empty
         if (!data.times?.length && endpoint !== 'readings') {
-            const fallbackUrl = `/api/stations/${state.station.id}/readings?start=${state.start.toISOString()}&end=${state.end.toISOString()}`;
+            const fallbackUrl = `/api/v1/stations/${state.station.id}/readings?start=${state.start.toISOString()}&end=${state.end.toISOString()}`;
             data = await api(fallbackUrl);
             resolution = '10-min raw (fallback)';
         }
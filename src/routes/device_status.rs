@@ -0,0 +1,140 @@
+//! Latest device status of a station's sensors.
+
+use axum::{
+    Json,
+    extract::{Path, State},
+};
+use chrono::{DateTime, Utc};
+use sea_orm::{ConnectionTrait, FromQueryResult, Statement};
+use serde::Serialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::common::AppState;
+use crate::error::AppResult;
+use crate::routes::resolve_station;
+use crate::routes::stations::StationRef;
+use crate::services::device_events::DeviceSnapshot;
+
+#[derive(Debug, FromQueryResult)]
+struct DeviceStatusRow {
+    sensor_id: Uuid,
+    name: String,
+    sensor_type: String,
+    time: DateTime<Utc>,
+    battery_level: Option<i16>,
+    battery_state: Option<i16>,
+    signal_quality: Option<i16>,
+    device_status: Option<String>,
+    unreachable: Option<bool>,
+}
+
+/// Latest device status of every sensor of a station that reported one
+#[derive(Debug, Serialize, ToSchema)]
+pub struct StationDeviceStatusResponse {
+    pub station: StationRef,
+    /// Ordered by sensor name
+    pub sensors: Vec<SensorDeviceStatus>,
+}
+
+/// Most recent device status row of one sensor
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SensorDeviceStatus {
+    pub sensor_id: Uuid,
+    pub name: String,
+    #[serde(rename = "type")]
+    pub sensor_type: String,
+    /// Sync time of the status
+    pub time: DateTime<Utc>,
+    pub battery_level: Option<i16>,
+    pub battery_state: Option<i16>,
+    /// `ok`, `low`, `critical` or `unknown`, as in the device status stream
+    pub battery_label: String,
+    pub signal_quality: Option<i16>,
+    pub device_status: Option<String>,
+    pub unreachable: Option<bool>,
+    /// `ok`, `fault` or `unreachable`, as in the device status stream
+    pub status_label: String,
+}
+
+impl From<DeviceStatusRow> for SensorDeviceStatus {
+    fn from(row: DeviceStatusRow) -> Self {
+        let snapshot = DeviceSnapshot {
+            battery_level: row.battery_level,
+            battery_state: row.battery_state,
+            signal_quality: row.signal_quality,
+            device_status: row.device_status,
+            unreachable: row.unreachable,
+        };
+        Self {
+            sensor_id: row.sensor_id,
+            name: row.name,
+            sensor_type: row.sensor_type,
+            time: row.time,
+            battery_label: snapshot.battery_label().to_string(),
+            status_label: snapshot.status_label().to_string(),
+            battery_level: snapshot.battery_level,
+            battery_state: snapshot.battery_state,
+            signal_quality: snapshot.signal_quality,
+            device_status: snapshot.device_status,
+            unreachable: snapshot.unreachable,
+        }
+    }
+}
+
+/// Get the latest device status of a station's sensors
+///
+/// Returns each sensor's most recent battery, signal and device status, as
+/// recorded by the device status sync (every 30 minutes by default). Sensors
+/// without any status row are left out.
+#[utoipa::path(
+    get,
+    path = "/api/v1/stations/{station_id}/devices/status",
+    params(
+        ("station_id" = String, Path, description = "Station UUID or name"),
+    ),
+    responses(
+        (status = 200, description = "Device status retrieved successfully", body = StationDeviceStatusResponse),
+        (status = 404, description = "Station not found"),
+    ),
+    tag = "stations"
+)]
+pub async fn get_station_device_status(
+    State(state): State<AppState>,
+    Path(station_id): Path<String>,
+) -> AppResult<Json<StationDeviceStatusResponse>> {
+    let station = resolve_station(&state.read_db, &station_id).await?;
+
+    // DISTINCT ON picks each sensor's newest row from the hypertable
+    let sql = "SELECT DISTINCT ON (d.sensor_id) d.sensor_id, s.name, s.sensor_type, d.time,
+                d.battery_level, d.battery_state, d.signal_quality, d.device_status,
+                d.unreachable
+         FROM device_status d
+         JOIN sensors s ON s.id = d.sensor_id
+         WHERE s.station_id = $1
+         ORDER BY d.sensor_id, d.time DESC";
+
+    let mut sensors: Vec<SensorDeviceStatus> = state
+        .read_db
+        .query_all(Statement::from_sql_and_values(
+            sea_orm::DatabaseBackend::Postgres,
+            sql,
+            [station.id.into()],
+        ))
+        .await?
+        .into_iter()
+        .map(|row| DeviceStatusRow::from_query_result(&row, ""))
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
+        .map(SensorDeviceStatus::from)
+        .collect();
+    sensors.sort_by(|a, b| a.name.cmp(&b.name).then(a.sensor_id.cmp(&b.sensor_id)));
+
+    Ok(Json(StationDeviceStatusResponse {
+        station: StationRef {
+            id: station.id,
+            name: station.name,
+        },
+        sensors,
+    }))
+}
@@ -0,0 +1,138 @@
+use axum::{extract::State, Json};
+use chrono::{DateTime, Utc};
+use sea_orm::{ColumnTrait, ConnectionTrait, EntityTrait, FromQueryResult, QueryFilter, Statement};
+use std::collections::{BTreeMap, HashMap};
+use uuid::Uuid;
+
+use crate::common::AppState;
+use crate::entity::{sensors, stations};
+use crate::error::AppResult;
+use crate::routes::stations::StationRef;
+
+use super::types::{LoggerChannel, LoggerResponse, LoggerStatus};
+
+/// Latest device status row per sensor
+#[derive(Debug, FromQueryResult)]
+struct LatestStatusRow {
+    sensor_id: Uuid,
+    time: DateTime<Utc>,
+    battery_level: Option<i16>,
+    battery_state: Option<i16>,
+    signal_quality: Option<i16>,
+    device_status: Option<String>,
+    unreachable: Option<bool>,
+}
+
+/// List physical loggers
+///
+/// Groups active sensors by their device serial number so multi-channel
+/// loggers can be identified. Each logger includes the latest device status
+/// reported by any of its sensors.
+#[utoipa::path(
+    get,
+    path = "/api/v1/loggers",
+    responses(
+        (status = 200, description = "Loggers retrieved successfully", body = Vec<LoggerResponse>),
+    ),
+    tag = "loggers"
+)]
+pub async fn list_loggers(State(state): State<AppState>) -> AppResult<Json<Vec<LoggerResponse>>> {
+    let sensors_with_station = sensors::Entity::find()
+        .filter(sensors::Column::IsActive.eq(true))
+        .filter(sensors::Column::DeviceSerialNumber.is_not_null())
+        .find_also_related(stations::Entity)
+        .all(&state.read_db)
+        .await?;
+
+    // Latest device status per sensor (DISTINCT ON picks the newest row)
+    let sensor_ids_str = sensors_with_station
+        .iter()
+        .map(|(s, _)| format!("'{}'", s.id))
+        .collect::<Vec<_>>()
+        .join(",");
+
+    let latest_status: HashMap<Uuid, LatestStatusRow> = if sensor_ids_str.is_empty() {
+        HashMap::new()
+    } else {
+        let sql = format!(
+            "SELECT DISTINCT ON (sensor_id) sensor_id, time, battery_level, battery_state,
+                    signal_quality, device_status, unreachable
+             FROM device_status
+             WHERE sensor_id IN ({sensor_ids_str})
+             ORDER BY sensor_id, time DESC"
+        );
+
+        state
+            .read_db
+            .query_all(Statement::from_string(sea_orm::DatabaseBackend::Postgres, sql))
+            .await?
+            .into_iter()
+            .map(|row| LatestStatusRow::from_query_result(&row, ""))
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .map(|r| (r.sensor_id, r))
+            .collect()
+    };
+
+    // Group sensors by serial number (BTreeMap keeps loggers sorted)
+    let mut grouped: BTreeMap<String, Vec<(sensors::Model, Option<stations::Model>)>> =
+        BTreeMap::new();
+    for (sensor, station) in sensors_with_station {
+        if let Some(serial) = sensor.device_serial_number.clone() {
+            grouped.entry(serial).or_default().push((sensor, station));
+        }
+    }
+
+    let response: Vec<LoggerResponse> = grouped
+        .into_iter()
+        .map(|(serial_number, mut members)| {
+            members.sort_by(|(a, _), (b, _)| {
+                a.channel_id.cmp(&b.channel_id).then_with(|| a.name.cmp(&b.name))
+            });
+
+            let mut stations: Vec<StationRef> = Vec::new();
+            for station in members.iter().filter_map(|(_, st)| st.as_ref()) {
+                if !stations.iter().any(|s| s.id == station.id) {
+                    stations.push(StationRef {
+                        id: station.id,
+                        name: station.name.clone(),
+                    });
+                }
+            }
+
+            let status = members
+                .iter()
+                .filter_map(|(s, _)| latest_status.get(&s.id))
+                .max_by_key(|r| r.time)
+                .map(|r| LoggerStatus {
+                    time: r.time,
+                    battery_level: r.battery_level,
+                    battery_state: r.battery_state,
+                    signal_quality: r.signal_quality,
+                    device_status: r.device_status.clone(),
+                    unreachable: r.unreachable,
+                });
+
+            let channels = members
+                .into_iter()
+                .map(|(s, _)| LoggerChannel {
+                    sensor_id: s.id,
+                    name: s.name,
+                    sensor_type: s.sensor_type,
+                    channel_id: s.channel_id,
+                    probe_serial_number: s.probe_serial_number,
+                    station_id: s.station_id,
+                })
+                .collect();
+
+            LoggerResponse {
+                serial_number,
+                stations,
+                channels,
+                status,
+            }
+        })
+        .collect();
+
+    Ok(Json(response))
+}
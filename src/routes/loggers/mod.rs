@@ -0,0 +1,11 @@
+mod handlers;
+mod stream;
+mod types;
+
+pub use handlers::list_loggers;
+pub use stream::stream_device_status;
+pub use types::{DeviceStatusStreamQuery, LoggerChannel, LoggerResponse, LoggerStatus};
+
+// Re-export utoipa path structs for OpenAPI documentation
+pub use handlers::__path_list_loggers;
+pub use stream::__path_stream_device_status;
@@ -0,0 +1,73 @@
+use axum::{
+    extract::{Query, State},
+    response::sse::{Event, KeepAlive, Sse},
+};
+use futures::{Stream, StreamExt};
+use sea_orm::EntityTrait;
+use std::convert::Infallible;
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::common::AppState;
+use crate::entity::stations;
+use crate::error::{AppError, AppResult};
+
+use super::types::DeviceStatusStreamQuery;
+
+/// Stream device status changes (Server-Sent Events)
+///
+/// Emits a `device_status` event whenever the device status sync sees a
+/// sensor's battery or status label change, with the previous labels for
+/// alerting on transitions (e.g. `ok` to `low`). A subscriber that falls
+/// behind gets a `lagged` event carrying the number of skipped changes and
+/// should refetch `/loggers`. The stream ends when the server shuts down, and
+/// counts toward the client's concurrent data request limit while open.
+#[utoipa::path(
+    get,
+    path = "/api/v1/device-status/stream",
+    params(DeviceStatusStreamQuery),
+    responses(
+        (status = 200, description = "Event stream of device status changes", content_type = "text/event-stream", body = crate::services::device_events::DeviceStatusEvent),
+        (status = 404, description = "Station not found"),
+    ),
+    tag = "loggers"
+)]
+pub async fn stream_device_status(
+    State(state): State<AppState>,
+    Query(query): Query<DeviceStatusStreamQuery>,
+) -> AppResult<Sse<impl Stream<Item = Result<Event, Infallible>>>> {
+    if let Some(station_id) = query.station_id {
+        stations::Entity::find_by_id(station_id)
+            .one(&state.read_db)
+            .await?
+            .ok_or_else(|| AppError::not_found("station", station_id.to_string()))?;
+    }
+
+    let station_id = query.station_id;
+    let rx = state.device_events.subscribe();
+    let stream = futures::stream::unfold(rx, move |mut rx| async move {
+        loop {
+            match rx.recv().await {
+                Ok(event) => {
+                    if station_id.is_some_and(|id| id != event.station_id) {
+                        continue;
+                    }
+                    match Event::default().event("device_status").json_data(&event) {
+                        Ok(sse) => return Some((Ok(sse), rx)),
+                        Err(e) => {
+                            tracing::warn!(error = %e, "Failed to encode device status event");
+                        }
+                    }
+                }
+                Err(RecvError::Lagged(skipped)) => {
+                    tracing::warn!(skipped, "Device status subscriber lagged");
+                    let sse = Event::default().event("lagged").data(skipped.to_string());
+                    return Some((Ok(sse), rx));
+                }
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    })
+    .take_until(state.shutdown.clone().cancelled_owned());
+
+    Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
+}
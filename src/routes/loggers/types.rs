@@ -0,0 +1,48 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::routes::stations::StationRef;
+
+/// A physical logger and the sensor channels it hosts
+#[derive(Debug, Serialize, ToSchema)]
+pub struct LoggerResponse {
+    /// Logger (device) serial number
+    pub serial_number: String,
+    /// Stations the logger's sensors belong to
+    pub stations: Vec<StationRef>,
+    /// Sensors hosted on this logger, ordered by channel
+    pub channels: Vec<LoggerChannel>,
+    /// Most recent device status reported by any of the logger's sensors
+    pub status: Option<LoggerStatus>,
+}
+
+/// Sensor channel on a logger
+#[derive(Debug, Serialize, ToSchema)]
+pub struct LoggerChannel {
+    pub sensor_id: Uuid,
+    pub name: String,
+    pub sensor_type: String,
+    pub channel_id: Option<i32>,
+    pub probe_serial_number: Option<String>,
+    pub station_id: Uuid,
+}
+
+/// Latest device status snapshot for a logger
+#[derive(Debug, Serialize, ToSchema)]
+pub struct LoggerStatus {
+    pub time: DateTime<Utc>,
+    pub battery_level: Option<i16>,
+    pub battery_state: Option<i16>,
+    pub signal_quality: Option<i16>,
+    pub device_status: Option<String>,
+    pub unreachable: Option<bool>,
+}
+
+/// Query parameters for the device status stream
+#[derive(Debug, Default, Deserialize, IntoParams)]
+pub struct DeviceStatusStreamQuery {
+    /// Only stream changes of this station's sensors
+    pub station_id: Option<Uuid>,
+}
@@ -1,49 +1,113 @@
+pub mod admin;
 pub mod alarms;
 pub mod dashboard;
+pub mod device_status;
+pub mod loggers;
+pub mod sensors;
 pub mod stations;
+pub mod sync;
 pub mod zones;
 
 // Re-export cache from services for use in route handlers
 pub use crate::services::cache;
 
-use axum::{http::StatusCode, routing::get, Router};
-use sea_orm::{Condition, DatabaseConnection, EntityTrait, QueryFilter, sea_query::Expr};
+use axum::{
+    extract::State,
+    http::{HeaderValue, Method},
+    middleware,
+    response::Response,
+    routing::{get, post},
+    Json, Router,
+};
+use sea_orm::{ColumnTrait, Condition, DatabaseConnection, EntityTrait, QueryFilter, sea_query::Expr};
 use std::sync::Arc;
+use serde::Serialize;
+use std::time::Duration;
 use tower_governor::{governor::GovernorConfigBuilder, GovernorLayer};
 use uuid::Uuid;
 
-use crate::services::FallbackIpKeyExtractor;
+use crate::services::{concurrency, metrics, FallbackIpKeyExtractor, PerClientConcurrency};
 use tower_http::{
-    compression::CompressionLayer,
+    compression::{CompressionLayer, CompressionLevel},
     cors::{Any, CorsLayer},
+    decompression::RequestDecompressionLayer,
     limit::RequestBodyLimitLayer,
     trace::TraceLayer,
 };
-use utoipa::OpenApi;
+use utoipa::{OpenApi, ToSchema};
 use utoipa_scalar::{Scalar, Servable};
 
-use crate::common::AppState;
-use crate::entity::{stations as stations_entity, zones as zones_entity};
+use crate::common::{envelope::ListMeta, format::Compression, time::TimeFormat, AppState};
+use crate::entity::sync_runs::SyncRunStatus;
+use crate::entity::sync_state::SyncStatus;
+use crate::entity::{
+    sensors as sensors_entity, stations as stations_entity, zones as zones_entity,
+};
 use crate::error::{AppError, AppResult};
 
 // ============================================================================
 // Root Endpoints
 // ============================================================================
 
+/// Time allowed for each dependency check in `/healthz`
+const HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// How long `/healthz` reuses the Vaisala check, so frequent probes don't
+/// each call upstream
+const UPSTREAM_CHECK_TTL: Duration = Duration::from_secs(15);
+
+/// Service health with dependency reachability
+#[derive(Debug, Serialize, ToSchema)]
+pub struct HealthResponse {
+    /// `ok` when all dependencies are reachable, `degraded` otherwise
+    pub status: String,
+    /// Whether the primary database answered a ping
+    pub db: bool,
+    /// Whether the Vaisala API answered an HTTP request
+    pub vaisala: bool,
+    pub uptime_seconds: u64,
+    /// Service version
+    pub version: String,
+}
+
 /// Health check endpoint
 ///
-/// Returns 200 OK if the service is running.
+/// Always returns 200 while the service is running, so liveness probes don't
+/// restart it over an unreachable dependency; the body reports `status`,
+/// dependency checks (each bounded to 2 seconds), uptime and version. The
+/// Vaisala check is reused for 15 seconds; the database is pinged every time.
 /// This endpoint is not rate-limited and suitable for Kubernetes probes.
 #[utoipa::path(
     get,
     path = "/healthz",
     responses(
-        (status = 200, description = "Service is healthy"),
+        (status = 200, description = "Service is running", body = HealthResponse),
     ),
     tag = "health"
 )]
-async fn healthz() -> StatusCode {
-    StatusCode::OK
+async fn healthz(State(state): State<AppState>) -> Json<HealthResponse> {
+    let (db, vaisala) = tokio::join!(
+        tokio::time::timeout(HEALTH_CHECK_TIMEOUT, state.db.ping()),
+        state
+            .vaisala_health
+            .get_or_check(UPSTREAM_CHECK_TTL, state.vaisala_client.ping(HEALTH_CHECK_TIMEOUT)),
+    );
+    let db = matches!(db, Ok(Ok(())));
+
+    Json(HealthResponse {
+        status: if db && vaisala { "ok" } else { "degraded" }.to_string(),
+        db,
+        vaisala,
+        uptime_seconds: state.started_at.elapsed().as_secs(),
+        version: env!("CARGO_PKG_VERSION").to_string(),
+    })
+}
+
+/// Fallback for known routes hit with an unsupported method.
+///
+/// Axum adds the `Allow` header listing the route's methods to this response.
+async fn method_not_allowed(method: Method) -> AppError {
+    AppError::MethodNotAllowed(format!("Method {method} not allowed for this route"))
 }
 
 // ============================================================================
@@ -60,7 +124,7 @@ pub async fn resolve_zone(
         return zones_entity::Entity::find_by_id(uuid)
             .one(db)
             .await?
-            .ok_or_else(|| AppError::NotFound("Zone not found".to_string()));
+            .ok_or_else(|| AppError::not_found("zone", id_or_name));
     }
 
     // Fall back to case-insensitive name lookup using LOWER()
@@ -72,7 +136,7 @@ pub async fn resolve_zone(
         )
         .one(db)
         .await?
-        .ok_or_else(|| AppError::NotFound("Zone not found".to_string()))
+        .ok_or_else(|| AppError::not_found("zone", id_or_name))
 }
 
 /// Resolve a station by UUID or name (case-insensitive)
@@ -85,7 +149,7 @@ pub async fn resolve_station(
         return stations_entity::Entity::find_by_id(uuid)
             .one(db)
             .await?
-            .ok_or_else(|| AppError::NotFound("Station not found".to_string()));
+            .ok_or_else(|| AppError::not_found("station", id_or_name));
     }
 
     // Fall back to case-insensitive name lookup using LOWER()
@@ -97,7 +161,74 @@ pub async fn resolve_station(
         )
         .one(db)
         .await?
-        .ok_or_else(|| AppError::NotFound("Station not found".to_string()))
+        .ok_or_else(|| AppError::not_found("station", id_or_name))
+}
+
+/// Resolve a sensor by UUID
+pub async fn resolve_sensor(
+    db: &DatabaseConnection,
+    id: &str,
+) -> AppResult<sensors_entity::Model> {
+    let uuid = id
+        .parse::<Uuid>()
+        .map_err(|_| AppError::not_found("sensor", id))?;
+
+    sensors_entity::Entity::find_by_id(uuid)
+        .one(db)
+        .await?
+        .ok_or_else(|| AppError::not_found("sensor", id))
+}
+
+/// Resolve a sensor of a given station by UUID or name (case-insensitive)
+///
+/// A sensor that exists but belongs to another station is reported as not
+/// found, so station-scoped routes never expose another station's data.
+pub async fn resolve_station_sensor(
+    db: &DatabaseConnection,
+    station_id: Uuid,
+    id_or_name: &str,
+) -> AppResult<sensors_entity::Model> {
+    let query = sensors_entity::Entity::find()
+        .filter(sensors_entity::Column::StationId.eq(station_id));
+
+    // Try UUID first
+    let query = if let Ok(uuid) = id_or_name.parse::<Uuid>() {
+        query.filter(sensors_entity::Column::Id.eq(uuid))
+    } else {
+        query.filter(Expr::cust_with_values("LOWER(name) = LOWER($1)", [id_or_name]))
+    };
+
+    query
+        .one(db)
+        .await?
+        .ok_or_else(|| AppError::not_found("sensor", id_or_name))
+}
+
+/// Build a case-insensitive sensor name filter from a comma-separated list.
+///
+/// Returns `None` when the list contains no names.
+pub fn sensor_names_condition(names: &str) -> Option<Condition> {
+    let names: Vec<&str> = names.split(',').map(str::trim).filter(|n| !n.is_empty()).collect();
+    if names.is_empty() {
+        return None;
+    }
+
+    Some(names.into_iter().fold(Condition::any(), |cond, name| {
+        cond.add(Expr::cust_with_values("LOWER(name) = LOWER($1)", [name]))
+    }))
+}
+
+/// Reject a data request whose filters matched more than `limit` sensors.
+///
+/// A `limit` of 0 disables the check.
+pub fn check_sensor_limit(matched: usize, limit: usize) -> AppResult<()> {
+    if limit > 0 && matched > limit {
+        return Err(AppError::BadRequest(format!(
+            "Request matches {matched} sensors, over the limit of {limit}. \
+             Filter with sensor_types or sensor_names"
+        )));
+    }
+    Ok(())
 }
 
 // ============================================================================
@@ -111,33 +242,115 @@ pub async fn resolve_station(
         zones::list_zones,
         zones::get_zone,
         zones::list_zone_stations,
+        zones::get_hierarchy,
         stations::list_stations,
         stations::get_station,
+        stations::get_stations_batch,
+        stations::get_stations_overview,
         stations::list_station_sensors,
+        stations::list_station_sensors_grouped,
         stations::get_station_readings,
+        stations::get_station_readings_multiscale,
+        stations::get_station_latest,
         stations::get_station_aggregates,
         alarms::list_alarms,
         alarms::list_active_alarms,
         alarms::get_alarm,
         alarms::list_station_alarms,
         alarms::list_events,
+        alarms::list_sensor_events,
+        loggers::list_loggers,
+        loggers::stream_device_status,
+        device_status::get_station_device_status,
+        sensors::get_sensor_readings,
+        sensors::get_station_sensor_readings,
+        sensors::get_sensor_thresholds,
+        sensors::get_sensor_rolling_stats,
+        sensors::get_sensor_histogram,
+        sensors::list_problematic_sensors,
+        sensors::list_sensor_types,
+        sensors::get_catalog,
+        sync::get_last_sync_pass,
+        sync::list_sync_runs,
+        sync::get_sync_status,
+        admin::get_maintenance_window,
+        admin::set_maintenance_window,
+        admin::get_retention_policy,
+        admin::set_retention_policy,
+        admin::delete_retention_policy,
+        admin::drop_old_chunks,
+        admin::relink_orphans,
+        admin::get_diagnostics,
     ),
     components(
         schemas(
+            HealthResponse,
+            ListMeta,
             zones::ZoneResponse,
+            zones::HierarchyResponse,
+            zones::HierarchyZone,
+            zones::HierarchyStation,
             stations::StationResponse,
             stations::StationDetailResponse,
+            stations::StationBatchRequest,
+            stations::StationBatchResult,
             stations::StationRef,
             stations::ZoneRef,
             stations::SensorResponse,
+            stations::SensorTypeGroup,
+            stations::StationOverview,
+            stations::OverviewOrder,
             stations::ReadingsResponse,
+            stations::ReadingsEstimate,
             stations::SensorData,
+            stations::SensorMeta,
             stations::AggregatesResponse,
             stations::SensorAggregateData,
+            stations::SensorLimits,
+            stations::LimitSource,
+            stations::MultiscaleResponse,
+            stations::MultiscaleOverview,
+            stations::MultiscaleDetail,
+            stations::StationLatestResponse,
+            stations::LatestSensorReading,
+            TimeFormat,
+            Compression,
             alarms::AlarmResponse,
             alarms::AlarmSummary,
             alarms::EventResponse,
             alarms::EventsListResponse,
+            loggers::LoggerResponse,
+            loggers::LoggerChannel,
+            loggers::LoggerStatus,
+            device_status::StationDeviceStatusResponse,
+            device_status::SensorDeviceStatus,
+            crate::services::device_events::DeviceStatusEvent,
+            sensors::SensorReadingsResponse,
+            sensors::SensorRef,
+            sensors::ReadingPoint,
+            sensors::SensorTypeResponse,
+            sensors::CatalogEntry,
+            sensors::CatalogUnits,
+            sensors::SensorThresholdsResponse,
+            sensors::ThresholdResponse,
+            sensors::ProblematicSensorResponse,
+            sensors::RollingStatsResponse,
+            sensors::RollingChange,
+            sensors::HistogramResponse,
+            SyncStatus,
+            sync::SyncPassResponse,
+            sync::SyncRunResponse,
+            sync::SensorSyncStatusResponse,
+            SyncRunStatus,
+            admin::MaintenanceWindowRequest,
+            admin::MaintenanceWindowResponse,
+            admin::RetentionPolicyRequest,
+            admin::RetentionPolicyResponse,
+            admin::DropChunksRequest,
+            admin::DropChunksResponse,
+            admin::RelinkResponse,
+            admin::DiagnosticsResponse,
+            admin::HypertableDiagnostics,
         )
     ),
     tags(
@@ -146,19 +359,69 @@ pub async fn resolve_station(
         (name = "stations", description = "Station management and data"),
         (name = "alarms", description = "Alarm management"),
         (name = "events", description = "Event log"),
+        (name = "loggers", description = "Physical loggers and their channels"),
+        (name = "sensors", description = "Sensor types and single-sensor data"),
+        (name = "sync", description = "Vaisala sync status"),
+        (name = "admin", description = "Operations; requires `Authorization: Bearer <ADMIN_TOKEN>`"),
     ),
     info(
         title = "River DB API",
-        description = "Time-series sensor data API for Vaisala viewLinc",
+        description = "Time-series sensor data API for Vaisala viewLinc.\n\n\
+            Endpoints are served under `/api/v1`. The unversioned `/api` prefix \
+            is a deprecated alias (responses carry `Deprecation: true`) kept \
+            through the 0.x releases and removed in 1.0.",
         version = "0.1.0"
     )
 )]
 struct ApiDoc;
 
+/// Flag responses served through the unversioned `/api` alias.
+async fn mark_deprecated(mut response: Response) -> Response {
+    response
+        .headers_mut()
+        .insert("Deprecation", HeaderValue::from_static("true"));
+    response
+}
+
 // ============================================================================
 // Router Builder
 // ============================================================================
 
+/// Brotli or gzip response compression, chosen from `Accept-Encoding`.
+///
+/// Higher levels shrink large JSON/CSV bodies for slow field links at the cost
+/// of CPU; `None` keeps each algorithm's default.
+fn compression_layer(level: Option<i32>) -> CompressionLayer {
+    let quality = level.map_or(CompressionLevel::Default, CompressionLevel::Precise);
+    CompressionLayer::new().br(true).gzip(true).quality(quality)
+}
+
+/// Admin routes behind a bearer token; empty when no token is configured.
+fn admin_routes(token: Option<&str>) -> Router<AppState> {
+    let Some(token) = token else {
+        return Router::new();
+    };
+
+    Router::new()
+        .route(
+            "/admin/maintenance",
+            get(admin::get_maintenance_window).post(admin::set_maintenance_window),
+        )
+        .route(
+            "/admin/retention",
+            get(admin::get_retention_policy)
+                .post(admin::set_retention_policy)
+                .delete(admin::delete_retention_policy),
+        )
+        .route("/admin/retention/drop", post(admin::drop_old_chunks))
+        .route("/admin/relink", post(admin::relink_orphans))
+        .route("/admin/diagnostics", get(admin::get_diagnostics))
+        .layer(middleware::from_fn_with_state(
+            Arc::<str>::from(token),
+            admin::require_admin_token,
+        ))
+}
+
 pub fn build_router(state: AppState) -> Router {
     let config = &state.config;
 
@@ -169,6 +432,7 @@ pub fn build_router(state: AppState) -> Router {
             metadata_rate = %format!("{}/s burst {}", config.rate_limit_metadata_per_second, config.rate_limit_metadata_burst),
             data_rate = %format!("{}/s burst {}", config.rate_limit_data_per_second, config.rate_limit_data_burst),
             bulk_concurrent = config.bulk_concurrent_limit,
+            per_client_concurrent = config.per_client_concurrent_limit,
             "Rate limiting configured"
         );
     }
@@ -178,25 +442,68 @@ pub fn build_router(state: AppState) -> Router {
         .route("/zones", get(zones::list_zones))
         .route("/zones/{zone_id}", get(zones::get_zone))
         .route("/zones/{zone_id}/stations", get(zones::list_zone_stations))
+        .route("/hierarchy", get(zones::get_hierarchy))
         .route("/stations", get(stations::list_stations))
+        .route("/stations/batch", post(stations::get_stations_batch))
+        .route("/stations/overview", get(stations::get_stations_overview))
         .route("/stations/{station_id}", get(stations::get_station))
         .route("/stations/{station_id}/sensors", get(stations::list_station_sensors))
+        .route(
+            "/stations/{station_id}/sensors/grouped",
+            get(stations::list_station_sensors_grouped),
+        )
         .route("/stations/{station_id}/alarms", get(alarms::list_station_alarms))
         .route("/alarms", get(alarms::list_alarms))
         .route("/alarms/active", get(alarms::list_active_alarms))
         .route("/alarms/{alarm_id}", get(alarms::get_alarm))
-        .route("/events", get(alarms::list_events));
+        .route("/events", get(alarms::list_events))
+        .route("/sensors/{sensor_id}/events", get(alarms::list_sensor_events))
+        .route("/loggers", get(loggers::list_loggers))
+        .route(
+            "/stations/{station_id}/devices/status",
+            get(device_status::get_station_device_status),
+        )
+        .route("/sensors/problematic", get(sensors::list_problematic_sensors))
+        .route("/sensors/{sensor_id}/thresholds", get(sensors::get_sensor_thresholds))
+        .route("/sensors/{sensor_id}/stats/rolling", get(sensors::get_sensor_rolling_stats))
+        .route("/sensor-types", get(sensors::list_sensor_types))
+        .route("/catalog", get(sensors::get_catalog))
+        .route("/sync/last-pass", get(sync::get_last_sync_pass))
+        .route("/sync/runs", get(sync::list_sync_runs))
+        .route("/sync/status", get(sync::get_sync_status));
 
-    // Data routes (readings, aggregates)
+    // Data routes (readings, aggregates, event streams)
     let data_routes_base = Router::new()
         .route(
             "/stations/{station_id}/readings",
             get(stations::get_station_readings),
         )
+        .route(
+            "/stations/{station_id}/readings/multiscale",
+            get(stations::get_station_readings_multiscale),
+        )
+        .route("/stations/{station_id}/latest", get(stations::get_station_latest))
         .route(
             "/stations/{station_id}/aggregates/{resolution}",
             get(stations::get_station_aggregates),
-        );
+        )
+        .route("/sensors/{sensor_id}/readings", get(sensors::get_sensor_readings))
+        .route("/sensors/{sensor_id}/histogram", get(sensors::get_sensor_histogram))
+        .route(
+            "/stations/{station_id}/sensors/{sensor_id}/readings",
+            get(sensors::get_station_sensor_readings),
+        )
+        .route("/device-status/stream", get(loggers::stream_device_status));
+
+    // Cap simultaneous in-flight data requests per client (long-lived bulk and event streams)
+    let data_routes_base = if config.disable_rate_limiting || config.per_client_concurrent_limit == 0 {
+        data_routes_base
+    } else {
+        data_routes_base.layer(middleware::from_fn_with_state(
+            PerClientConcurrency::new(config.per_client_concurrent_limit),
+            concurrency::limit_per_client,
+        ))
+    };
 
     // Combine API routes, conditionally applying rate limiting
     let api_routes = if config.disable_rate_limiting {
@@ -226,10 +533,16 @@ pub fn build_router(state: AppState) -> Router {
                 config: Arc::new(data_limiter),
             }))
     }
-    .layer(RequestBodyLimitLayer::new(1024 * 1024)); // 1MB body limit
+    .merge(admin_routes(config.admin_token.as_deref()))
+    .layer(RequestBodyLimitLayer::new(1024 * 1024)) // 1MB body limit
+    // Decompress `Content-Encoding: gzip` bodies before the limit so it applies
+    // to the decompressed size
+    .layer(RequestDecompressionLayer::new());
 
-    // Health check routes (NO rate limiting)
-    let health_routes = Router::new().route("/healthz", get(healthz));
+    // Health check and Prometheus scrape routes (NO rate limiting)
+    let health_routes = Router::new()
+        .route("/healthz", get(healthz))
+        .route("/metrics", get(metrics::render));
 
     // OpenAPI documentation
     let docs_routes = Router::new().merge(Scalar::with_url("/docs", ApiDoc::openapi()));
@@ -237,19 +550,32 @@ pub fn build_router(state: AppState) -> Router {
     // Dashboard at root
     let dashboard_routes = Router::new().route("/", get(dashboard::dashboard));
 
-    // Combine all routes
+    // Combine all routes; `/api` stays as a deprecated alias of `/api/v1`
+    // (sharing its rate limiters) until 1.0
     Router::new()
-        .nest("/api", api_routes)
+        .nest("/api/v1", api_routes.clone())
+        .nest("/api", api_routes.layer(middleware::map_response(mark_deprecated)))
         .merge(health_routes)
         .merge(docs_routes)
         .merge(dashboard_routes)
-        .layer(CompressionLayer::new())
+        .method_not_allowed_fallback(method_not_allowed)
+        .layer(compression_layer(config.compression_level))
         .layer(
+            // Browsers only read, POST included for the station batch lookup
+            // (admin writes come from scripts); they cache preflight results
+            // for max_age
             CorsLayer::new()
                 .allow_origin(Any)
-                .allow_methods(Any)
-                .allow_headers(Any),
+                .allow_methods([Method::GET, Method::HEAD, Method::POST, Method::OPTIONS])
+                .allow_headers(Any)
+                .max_age(Duration::from_secs(config.cors_max_age_seconds)),
         )
         .layer(TraceLayer::new_for_http())
+        // Applied per route, so the matched route template is known
+        .layer(middleware::from_fn(metrics::track_requests))
+        .layer(middleware::from_fn_with_state(
+            state.in_flight.clone(),
+            concurrency::track_in_flight,
+        ))
         .with_state(state)
 }
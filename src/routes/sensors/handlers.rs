@@ -0,0 +1,786 @@
+use axum::{
+    extract::{Path, Query, State},
+    response::Response,
+    Json,
+};
+use chrono::{DateTime, Duration, Utc};
+use sea_orm::{
+    ColumnTrait, ConnectionTrait, EntityTrait, FromQueryResult, QueryFilter, QueryOrder, Statement,
+    Value,
+};
+use uuid::Uuid;
+
+use crate::common::{pagination, time, AppState};
+use crate::entity::sync_state::SyncStatus;
+use crate::entity::{sensor_thresholds, sensors, stations};
+use crate::error::{AppError, AppResult};
+use crate::routes::{cache, resolve_sensor, resolve_station, resolve_station_sensor, resolve_zone};
+use crate::routes::stations::StationRef;
+
+use super::types::{
+    CatalogEntry, CatalogUnits, HistogramQuery, HistogramResponse, ProblematicSensorResponse, ReadingPoint, RollingChange, RollingStatsResponse, SensorReadingsQuery,
+    SensorReadingsResponse, SensorRef, SensorThresholdsResponse, SensorTypeResponse, SensorTypesQuery,
+    ThresholdResponse,
+};
+
+/// Separator for aggregated display units (ASCII unit separator)
+const UNITS_SEPARATOR: char = '\u{1f}';
+
+/// Look-back periods reported by the rolling stats endpoint
+const ROLLING_PERIODS: [(&str, i64); 3] = [("1h", 3_600), ("24h", 86_400), ("7d", 604_800)];
+
+/// Histogram bins used when `bins` is not given
+const DEFAULT_HISTOGRAM_BINS: u32 = 20;
+
+/// Maximum number of histogram bins
+const MAX_HISTOGRAM_BINS: u32 = 1000;
+
+/// Maximum time range for a histogram (a year of raw readings)
+const MAX_HISTOGRAM_RANGE_DAYS: i64 = 366;
+
+#[derive(Debug, FromQueryResult)]
+struct SensorTypeRow {
+    sensor_type: String,
+    sensor_count: i64,
+    units: Option<String>,
+}
+
+#[derive(Debug, FromQueryResult)]
+struct CatalogRow {
+    sensor_type: String,
+    units: Option<String>,
+    sensor_count: i64,
+    units_min: Option<f64>,
+    units_max: Option<f64>,
+    decimal_places: Option<i16>,
+}
+
+#[derive(Debug, FromQueryResult)]
+struct ProblematicSensorRow {
+    sensor_id: Uuid,
+    sensor_name: String,
+    sensor_type: String,
+    display_units: Option<String>,
+    station_id: Uuid,
+    station_name: String,
+    sync_status: Option<SyncStatus>,
+    error_message: Option<String>,
+    retry_count: Option<i32>,
+    last_sync_attempt: Option<DateTime<Utc>>,
+    last_data_time: Option<DateTime<Utc>>,
+    lag_seconds: Option<i64>,
+}
+
+#[derive(Debug, FromQueryResult)]
+struct ValueRangeRow {
+    min_value: Option<f64>,
+    max_value: Option<f64>,
+    count: i64,
+}
+
+#[derive(Debug, FromQueryResult)]
+struct BucketRow {
+    bucket: i32,
+    count: i64,
+}
+
+#[derive(Debug, FromQueryResult)]
+struct ReadingRow {
+    time: DateTime<Utc>,
+    value: f64,
+    logged: Option<bool>,
+    raw_time: Option<DateTime<Utc>>,
+}
+
+/// Get readings for a single sensor
+///
+/// Returns long-format readings (one row per timestamp) with keyset pagination.
+/// Follow `next_cursor` by passing it as `after` until it is null.
+#[utoipa::path(
+    get,
+    path = "/api/v1/sensors/{sensor_id}/readings",
+    params(
+        ("sensor_id" = String, Path, description = "Sensor UUID"),
+        SensorReadingsQuery
+    ),
+    responses(
+        (status = 200, description = "Readings retrieved successfully", body = SensorReadingsResponse),
+        (status = 400, description = "Invalid query parameters"),
+        (status = 404, description = "Sensor not found"),
+    ),
+    tag = "sensors"
+)]
+pub async fn get_sensor_readings(
+    State(state): State<AppState>,
+    Path(sensor_id): Path<String>,
+    Query(query): Query<SensorReadingsQuery>,
+) -> AppResult<Json<SensorReadingsResponse>> {
+    let sensor = resolve_sensor(&state.read_db, &sensor_id).await?;
+
+    let station = stations::Entity::find_by_id(sensor.station_id)
+        .one(&state.read_db)
+        .await?
+        .ok_or_else(|| AppError::not_found("station", sensor.station_id.to_string()))?;
+
+    sensor_readings(&state, station, sensor, query).await
+}
+
+/// Get readings for a sensor of a station
+///
+/// Same as `/api/sensors/{sensor_id}/readings`, scoped to a station. The sensor
+/// may be given by UUID or name; a sensor of another station returns 404.
+#[utoipa::path(
+    get,
+    path = "/api/v1/stations/{station_id}/sensors/{sensor_id}/readings",
+    params(
+        ("station_id" = String, Path, description = "Station UUID or name"),
+        ("sensor_id" = String, Path, description = "Sensor UUID or name"),
+        SensorReadingsQuery
+    ),
+    responses(
+        (status = 200, description = "Readings retrieved successfully", body = SensorReadingsResponse),
+        (status = 400, description = "Invalid query parameters"),
+        (status = 404, description = "Station not found, or sensor not found in this station"),
+    ),
+    tag = "sensors"
+)]
+pub async fn get_station_sensor_readings(
+    State(state): State<AppState>,
+    Path((station_id, sensor_id)): Path<(String, String)>,
+    Query(query): Query<SensorReadingsQuery>,
+) -> AppResult<Json<SensorReadingsResponse>> {
+    let station = resolve_station(&state.read_db, &station_id).await?;
+    let sensor = resolve_station_sensor(&state.read_db, station.id, &sensor_id).await?;
+
+    sensor_readings(&state, station, sensor, query).await
+}
+
+/// Keyset-paginated long-format readings for one sensor.
+async fn sensor_readings(
+    state: &AppState,
+    station: stations::Model,
+    sensor: sensors::Model,
+    query: SensorReadingsQuery,
+) -> AppResult<Json<SensorReadingsResponse>> {
+    time::validate_range(query.start, query.end)?;
+
+    let limit = query
+        .limit
+        .unwrap_or(state.config.api_default_page_size)
+        .clamp(1, state.config.api_max_page_size.max(1));
+
+    // Keyset pagination: `time > after` walks the (sensor_id, time) index
+    let mut conditions = vec!["sensor_id = $1".to_string()];
+    let mut values: Vec<Value> = vec![sensor.id.into()];
+    if let Some(after) = query.after {
+        values.push(after.into());
+        conditions.push(format!("time > ${}", values.len()));
+    }
+    if let Some(start) = query.start {
+        values.push(start.into());
+        conditions.push(format!("time >= ${}", values.len()));
+    }
+    if let Some(end) = query.end {
+        values.push(end.into());
+        conditions.push(format!("time <= ${}", values.len()));
+    }
+
+    let raw_time = if query.with_raw_time {
+        "raw_time"
+    } else {
+        "NULL::timestamptz AS raw_time"
+    };
+
+    // Fetch one extra row to detect whether another page exists
+    let sql = format!(
+        "SELECT time, value, logged, {raw_time} FROM readings WHERE {} ORDER BY time LIMIT {}",
+        conditions.join(" AND "),
+        limit + 1
+    );
+
+    let rows: Vec<ReadingRow> = state
+        .read_db
+        .query_all(Statement::from_sql_and_values(
+            sea_orm::DatabaseBackend::Postgres,
+            &sql,
+            values,
+        ))
+        .await?
+        .into_iter()
+        .map(|row| ReadingRow::from_query_result(&row, ""))
+        .collect::<Result<_, _>>()?;
+
+    let page_size = usize::try_from(limit).unwrap_or(usize::MAX);
+    let (rows, next_cursor) = pagination::keyset_page(rows, page_size, |r| r.time);
+
+    Ok(Json(SensorReadingsResponse {
+        station: StationRef {
+            id: station.id,
+            name: station.name,
+        },
+        sensor: SensorRef {
+            id: sensor.id,
+            name: sensor.name,
+            sensor_type: sensor.sensor_type,
+            units: sensor.display_units,
+        },
+        readings: rows
+            .into_iter()
+            .map(|r| ReadingPoint {
+                time: r.time,
+                value: r.value,
+                logged: r.logged,
+                raw_time: r.raw_time,
+            })
+            .collect(),
+        next_cursor,
+    }))
+}
+
+/// Get rolling statistics for a sensor
+///
+/// Returns the latest reading and its change over the last 1h, 24h and 7d.
+/// Periods are measured back from the latest reading. The comparison value is
+/// the newest reading at or before the period start, provided it is no older
+/// than a tenth of the period (or two sample intervals); otherwise the delta
+/// is null.
+#[utoipa::path(
+    get,
+    path = "/api/v1/sensors/{sensor_id}/stats/rolling",
+    params(
+        ("sensor_id" = String, Path, description = "Sensor UUID"),
+    ),
+    responses(
+        (status = 200, description = "Rolling statistics retrieved successfully", body = RollingStatsResponse),
+        (status = 404, description = "Sensor not found"),
+    ),
+    tag = "sensors"
+)]
+pub async fn get_sensor_rolling_stats(
+    State(state): State<AppState>,
+    Path(sensor_id): Path<String>,
+) -> AppResult<Response> {
+    let sensor = resolve_sensor(&state.read_db, &sensor_id).await?;
+
+    let cache_key = cache::cache_key("rolling_stats", &[&sensor.id.to_string()]);
+
+    // Unbounded entry: invalidated as soon as a newer reading arrives
+    if let Some(cached) = cache::get_cached(&state, &cache_key, &[sensor.id], None).await {
+        return cache::json_response((*cached).to_vec(), true);
+    }
+
+    let latest = latest_reading_before(&state, sensor.id, None).await?;
+
+    let sample_interval = sensor.effective_interval(state.config.default_sample_interval_sec);
+
+    let mut changes = Vec::with_capacity(ROLLING_PERIODS.len());
+    for (period, seconds) in ROLLING_PERIODS {
+        let reference = match &latest {
+            Some(latest) => {
+                let target = latest.time - Duration::seconds(seconds);
+                let tolerance = Duration::seconds((seconds / 10).max(2 * sample_interval));
+                latest_reading_before(&state, sensor.id, Some(target))
+                    .await?
+                    .filter(|r| r.time >= target - tolerance)
+            }
+            None => None,
+        };
+
+        let delta = match (&latest, &reference) {
+            (Some(l), Some(r)) => Some(l.value - r.value),
+            _ => None,
+        };
+        let percent_change = match (delta, &reference) {
+            (Some(d), Some(r)) if r.value != 0.0 => Some(d / r.value.abs() * 100.0),
+            _ => None,
+        };
+
+        changes.push(RollingChange {
+            period: period.to_string(),
+            reference_time: reference.as_ref().map(|r| r.time),
+            reference_value: reference.as_ref().map(|r| r.value),
+            delta,
+            percent_change,
+        });
+    }
+
+    let max_time = latest.as_ref().map(|r| r.time);
+    let response = RollingStatsResponse {
+        sensor: SensorRef {
+            id: sensor.id,
+            name: sensor.name,
+            sensor_type: sensor.sensor_type,
+            units: sensor.display_units,
+        },
+        latest: latest.map(|r| ReadingPoint {
+            time: r.time,
+            value: r.value,
+            logged: r.logged,
+            raw_time: None,
+        }),
+        changes,
+    };
+
+    let ttl = std::time::Duration::from_secs(state.config.cache_latest_ttl_seconds);
+    cache::cache_and_respond(&state, cache_key, &response, max_time, false, Some(ttl)).await
+}
+
+/// Newest reading of a sensor, optionally at or before `at`.
+async fn latest_reading_before(
+    state: &AppState,
+    sensor_id: Uuid,
+    at: Option<DateTime<Utc>>,
+) -> AppResult<Option<ReadingRow>> {
+    let mut values: Vec<Value> = vec![sensor_id.into()];
+    let mut sql = "SELECT time, value, logged, NULL::timestamptz AS raw_time \
+                   FROM readings WHERE sensor_id = $1"
+        .to_string();
+    if let Some(at) = at {
+        values.push(at.into());
+        sql.push_str(" AND time <= $2");
+    }
+    sql.push_str(" ORDER BY time DESC LIMIT 1");
+
+    Ok(state
+        .read_db
+        .query_one(Statement::from_sql_and_values(
+            sea_orm::DatabaseBackend::Postgres,
+            &sql,
+            values,
+        ))
+        .await?
+        .map(|row| ReadingRow::from_query_result(&row, ""))
+        .transpose()?)
+}
+
+/// Get the distribution of a sensor's values
+///
+/// Counts readings in `bins` equal-width bins between `min` and `max`, which
+/// default to the smallest and largest value in the time range. Readings
+/// outside explicit bounds are reported as `below` / `above`.
+#[utoipa::path(
+    get,
+    path = "/api/v1/sensors/{sensor_id}/histogram",
+    params(
+        ("sensor_id" = String, Path, description = "Sensor UUID"),
+        HistogramQuery
+    ),
+    responses(
+        (status = 200, description = "Histogram computed successfully", body = HistogramResponse),
+        (status = 400, description = "Invalid time range, bins or bounds"),
+        (status = 404, description = "Sensor not found"),
+    ),
+    tag = "sensors"
+)]
+pub async fn get_sensor_histogram(
+    State(state): State<AppState>,
+    Path(sensor_id): Path<String>,
+    Query(query): Query<HistogramQuery>,
+) -> AppResult<Json<HistogramResponse>> {
+    time::validate_range(query.start, query.end)?;
+    if query.end - query.start > Duration::days(MAX_HISTOGRAM_RANGE_DAYS) {
+        return Err(AppError::BadRequest(format!(
+            "time range exceeds maximum of {MAX_HISTOGRAM_RANGE_DAYS} days"
+        )));
+    }
+    let bins = query.bins.unwrap_or(DEFAULT_HISTOGRAM_BINS);
+    if !(1..=MAX_HISTOGRAM_BINS).contains(&bins) {
+        return Err(AppError::BadRequest(format!(
+            "bins must be between 1 and {MAX_HISTOGRAM_BINS}"
+        )));
+    }
+    if query.min.is_some_and(|v| !v.is_finite()) || query.max.is_some_and(|v| !v.is_finite()) {
+        return Err(AppError::BadRequest("min and max must be finite numbers".to_string()));
+    }
+    if let (Some(min), Some(max)) = (query.min, query.max)
+        && min >= max
+    {
+        return Err(AppError::BadRequest("min must be less than max".to_string()));
+    }
+
+    let sensor = resolve_sensor(&state.read_db, &sensor_id).await?;
+    let window: Vec<Value> = vec![sensor.id.into(), query.start.into(), query.end.into()];
+
+    let stats = state
+        .read_db
+        .query_one(Statement::from_sql_and_values(
+            sea_orm::DatabaseBackend::Postgres,
+            "SELECT MIN(value) AS min_value, MAX(value) AS max_value, COUNT(*) AS count \
+             FROM readings WHERE sensor_id = $1 AND time >= $2 AND time <= $3",
+            window.clone(),
+        ))
+        .await?
+        .map(|row| ValueRangeRow::from_query_result(&row, ""))
+        .transpose()?;
+
+    let sensor_ref = SensorRef {
+        id: sensor.id,
+        name: sensor.name,
+        sensor_type: sensor.sensor_type,
+        units: sensor.display_units,
+    };
+    let (Some(data_min), Some(data_max)) = stats
+        .filter(|s| s.count > 0)
+        .map_or((None, None), |s| (s.min_value, s.max_value))
+    else {
+        // No data in range
+        return Ok(Json(HistogramResponse {
+            sensor: sensor_ref,
+            start: query.start,
+            end: query.end,
+            min: None,
+            max: None,
+            edges: vec![],
+            counts: vec![],
+            below: 0,
+            above: 0,
+        }));
+    };
+
+    let min = query.min.unwrap_or(data_min);
+    let max = query.max.unwrap_or(data_max);
+    if min > max {
+        return Err(AppError::BadRequest(format!(
+            "min must be less than max (values in range span {data_min} to {data_max})"
+        )));
+    }
+    // All values equal: a single zero-width bin
+    let bins = if min == max { 1 } else { bins };
+
+    // Bucket 0 is below `min`, bins + 1 above `max`; `max` itself falls in the
+    // last bin. The CASE keeps width_bucket away from a zero-width range.
+    let bins_i32 = i32::try_from(bins).unwrap_or(i32::MAX);
+    let mut values = window;
+    values.extend([min.into(), max.into(), bins_i32.into()]);
+    let rows: Vec<BucketRow> = state
+        .read_db
+        .query_all(Statement::from_sql_and_values(
+            sea_orm::DatabaseBackend::Postgres,
+            "SELECT CASE \
+                 WHEN value < $4 THEN 0 \
+                 WHEN value > $5 THEN $6 + 1 \
+                 WHEN value >= $5 THEN $6 \
+                 ELSE width_bucket(value, $4, $5, $6) \
+             END AS bucket, COUNT(*) AS count \
+             FROM readings WHERE sensor_id = $1 AND time >= $2 AND time <= $3 \
+             GROUP BY 1",
+            values,
+        ))
+        .await?
+        .into_iter()
+        .map(|row| BucketRow::from_query_result(&row, ""))
+        .collect::<Result<_, _>>()?;
+
+    let bin_count = bins as usize;
+    let mut counts = vec![0_i64; bin_count];
+    let (mut below, mut above) = (0, 0);
+    for row in rows {
+        match usize::try_from(row.bucket) {
+            Ok(0) | Err(_) => below += row.count,
+            Ok(i) if i > bin_count => above += row.count,
+            Ok(i) => counts[i - 1] += row.count,
+        }
+    }
+
+    let width = (max - min) / f64::from(bins);
+    let edges = (0..=bins)
+        .map(|i| if i == bins { max } else { min + width * f64::from(i) })
+        .collect();
+
+    Ok(Json(HistogramResponse {
+        sensor: sensor_ref,
+        start: query.start,
+        end: query.end,
+        min: Some(min),
+        max: Some(max),
+        edges,
+        counts,
+        below,
+        above,
+    }))
+}
+
+/// List problematic sensors
+///
+/// Returns active sensors whose last sync failed or whose newest reading is
+/// older than SYNC_STALE_AFTER_SECONDS (including sensors with no data at all),
+/// ordered by lag descending.
+#[utoipa::path(
+    get,
+    path = "/api/v1/sensors/problematic",
+    responses(
+        (status = 200, description = "Problematic sensors retrieved successfully", body = Vec<ProblematicSensorResponse>),
+    ),
+    tag = "sensors"
+)]
+pub async fn list_problematic_sensors(
+    State(state): State<AppState>,
+) -> AppResult<Json<Vec<ProblematicSensorResponse>>> {
+    let sql = "SELECT s.id AS sensor_id,
+                s.name AS sensor_name,
+                s.sensor_type,
+                s.display_units,
+                st.id AS station_id,
+                st.name AS station_name,
+                ss.sync_status,
+                ss.error_message,
+                ss.retry_count,
+                ss.last_sync_attempt,
+                ss.last_data_time,
+                EXTRACT(EPOCH FROM NOW() - ss.last_data_time)::bigint AS lag_seconds
+         FROM sensors s
+         JOIN stations st ON st.id = s.station_id
+         LEFT JOIN sync_state ss ON ss.sensor_id = s.id
+         WHERE s.is_active = true
+           AND (ss.sync_status = $2
+                OR ss.last_data_time IS NULL
+                OR ss.last_data_time < NOW() - $1 * INTERVAL '1 second')
+         ORDER BY lag_seconds DESC NULLS FIRST, st.name, s.name";
+
+    let response = state
+        .read_db
+        .query_all(Statement::from_sql_and_values(
+            sea_orm::DatabaseBackend::Postgres,
+            sql,
+            [
+                state.config.sync_stale_after_seconds.into(),
+                SyncStatus::Error.into(),
+            ],
+        ))
+        .await?
+        .into_iter()
+        .map(|row| ProblematicSensorRow::from_query_result(&row, ""))
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
+        .map(|r| ProblematicSensorResponse {
+            sensor: SensorRef {
+                id: r.sensor_id,
+                name: r.sensor_name,
+                sensor_type: r.sensor_type,
+                units: r.display_units,
+            },
+            station: StationRef {
+                id: r.station_id,
+                name: r.station_name,
+            },
+            sync_status: r.sync_status,
+            error_message: r.error_message,
+            retry_count: r.retry_count,
+            last_sync_attempt: r.last_sync_attempt,
+            last_data_time: r.last_data_time,
+            lag_seconds: r.lag_seconds,
+        })
+        .collect();
+
+    Ok(Json(response))
+}
+
+/// Get a sensor's alarm thresholds
+///
+/// Returns the high/low limits configured in viewLinc, as captured by the last
+/// readings sync. Empty when no thresholds are configured.
+#[utoipa::path(
+    get,
+    path = "/api/v1/sensors/{sensor_id}/thresholds",
+    params(
+        ("sensor_id" = String, Path, description = "Sensor UUID"),
+    ),
+    responses(
+        (status = 200, description = "Thresholds retrieved successfully", body = SensorThresholdsResponse),
+        (status = 404, description = "Sensor not found"),
+    ),
+    tag = "sensors"
+)]
+pub async fn get_sensor_thresholds(
+    State(state): State<AppState>,
+    Path(sensor_id): Path<String>,
+) -> AppResult<Json<SensorThresholdsResponse>> {
+    let sensor = resolve_sensor(&state.read_db, &sensor_id).await?;
+
+    let thresholds = sensor_thresholds::Entity::find()
+        .filter(sensor_thresholds::Column::SensorId.eq(sensor.id))
+        .order_by_asc(sensor_thresholds::Column::Kind)
+        .order_by_asc(sensor_thresholds::Column::Value)
+        .all(&state.read_db)
+        .await?
+        .into_iter()
+        .map(|t| ThresholdResponse {
+            kind: t.kind,
+            value: t.value,
+            severity: t.severity,
+            description: t.description,
+            updated_at: t.updated_at.map(|dt| dt.with_timezone(&Utc)),
+        })
+        .collect();
+
+    Ok(Json(SensorThresholdsResponse {
+        sensor: SensorRef {
+            id: sensor.id,
+            name: sensor.name,
+            sensor_type: sensor.sensor_type,
+            units: sensor.display_units,
+        },
+        thresholds,
+    }))
+}
+
+/// List sensor types
+///
+/// Returns each distinct sensor type with its number of active sensors and the
+/// display units seen for it. Optionally scoped to a station or zone.
+#[utoipa::path(
+    get,
+    path = "/api/v1/sensor-types",
+    params(SensorTypesQuery),
+    responses(
+        (status = 200, description = "Sensor types retrieved successfully", body = Vec<SensorTypeResponse>),
+        (status = 404, description = "Station or zone not found"),
+    ),
+    tag = "sensors"
+)]
+pub async fn list_sensor_types(
+    State(state): State<AppState>,
+    Query(query): Query<SensorTypesQuery>,
+) -> AppResult<Response> {
+    let mut conditions = vec!["s.is_active = true".to_string()];
+    let mut values: Vec<Value> = Vec::new();
+
+    let station_id = match &query.station_id {
+        Some(id) => Some(resolve_station(&state.read_db, id).await?.id),
+        None => None,
+    };
+    let zone_id = match &query.zone_id {
+        Some(id) => Some(resolve_zone(&state.read_db, id).await?.id),
+        None => None,
+    };
+
+    if let Some(id) = station_id {
+        values.push(id.into());
+        conditions.push(format!("s.station_id = ${}", values.len()));
+    }
+    if let Some(id) = zone_id {
+        values.push(id.into());
+        conditions.push(format!("st.zone_id = ${}", values.len()));
+    }
+
+    let cache_key = cache::cache_key(
+        "sensor_types",
+        &[
+            &station_id.map(|id| id.to_string()).unwrap_or_default(),
+            &zone_id.map(|id| id.to_string()).unwrap_or_default(),
+        ],
+    );
+
+    // Unbounded entry: expires after the short CACHE_UNBOUNDED_TTL_SECONDS
+    if let Some(cached) = cache::get_cached(&state, &cache_key, &[], None).await {
+        return cache::json_response((*cached).to_vec(), true);
+    }
+
+    let sql = format!(
+        "SELECT s.sensor_type,
+                COUNT(*) AS sensor_count,
+                STRING_AGG(DISTINCT s.display_units, chr(31) ORDER BY s.display_units) AS units
+         FROM sensors s
+         JOIN stations st ON st.id = s.station_id
+         WHERE {}
+         GROUP BY s.sensor_type
+         ORDER BY s.sensor_type",
+        conditions.join(" AND ")
+    );
+
+    let response: Vec<SensorTypeResponse> = state
+        .read_db
+        .query_all(Statement::from_sql_and_values(
+            sea_orm::DatabaseBackend::Postgres,
+            &sql,
+            values,
+        ))
+        .await?
+        .into_iter()
+        .map(|row| SensorTypeRow::from_query_result(&row, ""))
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
+        .map(|r| SensorTypeResponse {
+            sensor_type: r.sensor_type,
+            sensor_count: r.sensor_count,
+            units: r
+                .units
+                .map(|u| u.split(UNITS_SEPARATOR).map(str::to_string).collect())
+                .unwrap_or_default(),
+        })
+        .collect();
+
+    cache::cache_and_respond(&state, cache_key, &response, None, false, None).await
+}
+
+/// Get the sensor catalog
+///
+/// Returns every sensor type with, per display unit, its value range
+/// (`units_min`/`units_max`) and decimal places, so clients can format and
+/// validate values without hardcoding them. Unlike `/sensor-types` it is not
+/// scoped. Assembled from active sensors, cached for `CACHE_TTL_SECONDS` and
+/// dropped when the sync sees sensors, their alarming paused flags or their
+/// thresholds change.
+#[utoipa::path(
+    get,
+    path = "/api/v1/catalog",
+    responses(
+        (status = 200, description = "Catalog retrieved successfully", body = Vec<CatalogEntry>),
+    ),
+    tag = "sensors"
+)]
+pub async fn get_catalog(State(state): State<AppState>) -> AppResult<Response> {
+    if let Some(cached) = cache::get_cached(&state, cache::CATALOG_CACHE_KEY, &[], None).await {
+        return cache::json_response((*cached).to_vec(), true);
+    }
+
+    let sql = "SELECT sensor_type,
+                display_units AS units,
+                COUNT(*) AS sensor_count,
+                MIN(units_min) AS units_min,
+                MAX(units_max) AS units_max,
+                MAX(decimal_places) AS decimal_places
+         FROM sensors
+         WHERE is_active = true
+         GROUP BY sensor_type, display_units
+         ORDER BY sensor_type, display_units NULLS LAST";
+
+    let mut response: Vec<CatalogEntry> = Vec::new();
+    let rows = state
+        .read_db
+        .query_all(Statement::from_string(sea_orm::DatabaseBackend::Postgres, sql))
+        .await?;
+    for row in &rows {
+        let row = CatalogRow::from_query_result(row, "")?;
+        let units = CatalogUnits {
+            units: row.units,
+            sensor_count: row.sensor_count,
+            units_min: row.units_min,
+            units_max: row.units_max,
+            decimal_places: row.decimal_places,
+        };
+        // Rows are ordered by type, so a type's units are consecutive
+        match response.last_mut() {
+            Some(entry) if entry.sensor_type == row.sensor_type => {
+                entry.sensor_count += units.sensor_count;
+                entry.units.push(units);
+            }
+            _ => response.push(CatalogEntry {
+                sensor_type: row.sensor_type,
+                sensor_count: units.sensor_count,
+                units: vec![units],
+            }),
+        }
+    }
+
+    let ttl = std::time::Duration::from_secs(state.config.cache_ttl_seconds);
+    cache::cache_and_respond(
+        &state,
+        cache::CATALOG_CACHE_KEY.to_string(),
+        &response,
+        None,
+        false,
+        Some(ttl),
+    )
+    .await
+}
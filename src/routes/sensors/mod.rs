@@ -0,0 +1,22 @@
+mod handlers;
+mod types;
+
+pub use handlers::{
+    get_catalog, get_sensor_histogram, get_sensor_readings, get_sensor_rolling_stats,
+    get_sensor_thresholds, get_station_sensor_readings, list_problematic_sensors,
+    list_sensor_types,
+};
+pub use types::{
+    CatalogEntry, CatalogUnits, HistogramQuery, HistogramResponse, ProblematicSensorResponse,
+    ReadingPoint, RollingChange, RollingStatsResponse, SensorReadingsQuery,
+    SensorReadingsResponse, SensorRef, SensorThresholdsResponse, SensorTypeResponse,
+    SensorTypesQuery, ThresholdResponse,
+};
+
+// Re-export utoipa path structs for OpenAPI documentation
+pub use handlers::{
+    __path_get_catalog, __path_get_sensor_histogram, __path_get_sensor_readings,
+    __path_get_sensor_rolling_stats, __path_get_sensor_thresholds,
+    __path_get_station_sensor_readings, __path_list_problematic_sensors,
+    __path_list_sensor_types,
+};
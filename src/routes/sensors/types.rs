@@ -0,0 +1,212 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::common::time;
+use crate::entity::sync_state::SyncStatus;
+use crate::routes::stations::StationRef;
+
+/// Brief sensor reference for embedding in responses
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct SensorRef {
+    pub id: Uuid,
+    pub name: String,
+    #[serde(rename = "type")]
+    pub sensor_type: String,
+    pub units: Option<String>,
+}
+
+/// A single reading in long format
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ReadingPoint {
+    pub time: DateTime<Utc>,
+    pub value: f64,
+    /// True for logged (historical) samples, false for realtime samples
+    pub logged: Option<bool>,
+    /// Sample time before alignment to the 10-minute grid; only with
+    /// `with_raw_time=true`, and omitted for readings synced before it was recorded
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub raw_time: Option<DateTime<Utc>>,
+}
+
+/// Long-format readings for a single sensor
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SensorReadingsResponse {
+    /// Station this sensor belongs to
+    pub station: StationRef,
+    /// Sensor these readings belong to
+    pub sensor: SensorRef,
+    /// Readings ordered by time ascending
+    pub readings: Vec<ReadingPoint>,
+    /// Pass as `after` to fetch the next page (null when there is no more data)
+    pub next_cursor: Option<DateTime<Utc>>,
+}
+
+/// Query parameters for single-sensor readings.
+///
+/// Pagination contract: request without `after`, then repeat the request with
+/// `after=<next_cursor>` until `next_cursor` is null.
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct SensorReadingsQuery {
+    /// Start time (optional, ISO 8601 or Unix epoch seconds)
+    #[serde(default, deserialize_with = "time::deserialize_optional_timestamp")]
+    pub start: Option<DateTime<Utc>>,
+    /// End time (optional, ISO 8601 or Unix epoch seconds)
+    #[serde(default, deserialize_with = "time::deserialize_optional_timestamp")]
+    pub end: Option<DateTime<Utc>>,
+    /// Keyset cursor: only return readings strictly after this time
+    pub after: Option<DateTime<Utc>>,
+    /// Maximum readings per page (defaults to API_DEFAULT_PAGE_SIZE, capped at API_MAX_PAGE_SIZE)
+    pub limit: Option<u64>,
+    /// Add each reading's unrounded sample time as `raw_time`. Readings synced
+    /// before raw times were recorded have none
+    #[serde(default)]
+    pub with_raw_time: bool,
+}
+
+/// A sensor type present in the network
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SensorTypeResponse {
+    #[serde(rename = "type")]
+    pub sensor_type: String,
+    /// Number of active sensors of this type
+    pub sensor_count: i64,
+    /// Distinct display units seen for this type
+    pub units: Vec<String>,
+}
+
+/// Query parameters for the sensor type catalog
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct SensorTypesQuery {
+    /// Only count sensors in this station (UUID or name)
+    pub station_id: Option<String>,
+    /// Only count sensors in this zone (UUID or name)
+    pub zone_id: Option<String>,
+}
+
+/// Formatting and validation metadata of one sensor type
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CatalogEntry {
+    #[serde(rename = "type")]
+    pub sensor_type: String,
+    /// Number of active sensors of this type
+    pub sensor_count: i64,
+    /// One entry per display unit seen for this type
+    pub units: Vec<CatalogUnits>,
+}
+
+/// Value range and precision of the sensors of one type reporting one unit
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CatalogUnits {
+    /// Display units (null for sensors without any)
+    pub units: Option<String>,
+    /// Number of active sensors of this type with these units
+    pub sensor_count: i64,
+    /// Lowest `units_min` of these sensors
+    pub units_min: Option<f64>,
+    /// Highest `units_max` of these sensors
+    pub units_max: Option<f64>,
+    /// Most decimal places configured on these sensors
+    pub decimal_places: Option<i16>,
+}
+
+/// An active sensor whose sync is failing or whose data is stale
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ProblematicSensorResponse {
+    pub sensor: SensorRef,
+    pub station: StationRef,
+    /// Null if the sensor was never synced
+    pub sync_status: Option<SyncStatus>,
+    /// Last error reported by the sync worker
+    pub error_message: Option<String>,
+    pub retry_count: Option<i32>,
+    pub last_sync_attempt: Option<DateTime<Utc>>,
+    /// Timestamp of the newest stored reading
+    pub last_data_time: Option<DateTime<Utc>>,
+    /// Seconds since `last_data_time` (null when the sensor has no data)
+    pub lag_seconds: Option<i64>,
+}
+
+/// An alarm limit configured for a sensor in viewLinc
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ThresholdResponse {
+    /// "high" or "low"
+    pub kind: String,
+    /// Limit value in the sensor's display units
+    pub value: f64,
+    pub severity: Option<i16>,
+    pub description: Option<String>,
+    /// When this threshold was last changed in viewLinc (as seen by the sync)
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// Configured thresholds for a single sensor
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SensorThresholdsResponse {
+    pub sensor: SensorRef,
+    /// Thresholds ordered by kind, then value
+    pub thresholds: Vec<ThresholdResponse>,
+}
+
+/// Change of a sensor's value over one look-back period
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RollingChange {
+    /// Look-back period (`1h`, `24h` or `7d`)
+    pub period: String,
+    /// Time of the comparison reading (null if none was found near the period start)
+    pub reference_time: Option<DateTime<Utc>>,
+    pub reference_value: Option<f64>,
+    /// Latest value minus the reference value
+    pub delta: Option<f64>,
+    /// Delta relative to the reference value, in percent (null when the reference is 0)
+    pub percent_change: Option<f64>,
+}
+
+/// Latest value of a sensor and its change over standard periods
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RollingStatsResponse {
+    pub sensor: SensorRef,
+    /// Most recent reading (null if the sensor has no data)
+    pub latest: Option<ReadingPoint>,
+    pub changes: Vec<RollingChange>,
+}
+
+/// Query parameters for a sensor value histogram
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct HistogramQuery {
+    /// Start of time range (ISO 8601 or Unix epoch seconds)
+    #[serde(deserialize_with = "time::deserialize_timestamp")]
+    pub start: DateTime<Utc>,
+    /// End of time range (ISO 8601 or Unix epoch seconds, max 366 days after
+    /// start)
+    #[serde(deserialize_with = "time::deserialize_timestamp")]
+    pub end: DateTime<Utc>,
+    /// Number of equal-width bins (1-1000, default 20)
+    pub bins: Option<u32>,
+    /// Lower bound of the first bin (defaults to the minimum value in range)
+    pub min: Option<f64>,
+    /// Upper bound of the last bin (defaults to the maximum value in range)
+    pub max: Option<f64>,
+}
+
+/// Distribution of a sensor's values over a time range
+#[derive(Debug, Serialize, ToSchema)]
+pub struct HistogramResponse {
+    pub sensor: SensorRef,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    /// Lower bound used for bucketing (null when there is no data)
+    pub min: Option<f64>,
+    /// Upper bound used for bucketing (null when there is no data)
+    pub max: Option<f64>,
+    /// Bin edges, one more than `counts`; bin `i` is `[edges[i], edges[i+1])`,
+    /// the last bin also includes `max`
+    pub edges: Vec<f64>,
+    /// Readings per bin (empty when there is no data)
+    pub counts: Vec<i64>,
+    /// Readings below `min` (only with an explicit `min`)
+    pub below: i64,
+    /// Readings above `max` (only with an explicit `max`)
+    pub above: i64,
+}
@@ -1,45 +1,39 @@
 use axum::{
     extract::{Path, Query, State},
-    http::{
-        header::{self, HeaderMap, HeaderValue},
-        StatusCode,
-    },
+    http::header::{self, HeaderMap, HeaderValue},
     response::{IntoResponse, Response},
     Json,
 };
 use chrono::{DateTime, Duration, Utc};
-use sea_orm::{ColumnTrait, ConnectionTrait, EntityTrait, FromQueryResult, QueryFilter, Statement};
+use sea_orm::{
+    ColumnTrait, ConnectionTrait, DbErr, EntityTrait, FromQueryResult, QueryFilter, QueryOrder,
+    QueryResult, Statement, TryGetable,
+};
 use serde::{Deserialize, Serialize};
 use std::collections::{BTreeMap, HashMap};
-use std::sync::Arc;
-use tokio::sync::Semaphore;
 use tokio_stream::wrappers::ReceiverStream;
 use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 
+use crate::common::format::{
+    check_compress, compress_export, export_filename, negotiate_format, ordered_columns,
+    Compression, CsvLines,
+};
+use crate::common::round::{average_decimal_places, format_decimal, round_values};
+use crate::common::time::{self, TimeArray, TimeFormat};
 use crate::common::AppState;
+use crate::config::Config;
 use crate::entity::{sensors, zones};
 use crate::error::{AppError, AppResult};
-use crate::routes::{cache, resolve_station};
+use crate::routes::{cache, check_sensor_limit, resolve_station, sensor_names_condition};
+use crate::services::timescale;
 
+use super::thresholds::{sensor_limits, SensorLimits};
 use super::types::{StationRef, ZoneRef};
 
 /// Maximum time range allowed (90 days)
 const MAX_TIME_RANGE_DAYS: i64 = 90;
 
-/// Global semaphore limiting concurrent bulk (CSV/NDJSON) requests.
-static BULK_SEMAPHORE: std::sync::LazyLock<Arc<Semaphore>> = std::sync::LazyLock::new(|| {
-    let limit = std::env::var("BULK_CONCURRENT_LIMIT")
-        .ok()
-        .and_then(|s| s.parse().ok())
-        .unwrap_or(5);
-    Arc::new(Semaphore::new(limit))
-});
-
-fn default_format() -> String {
-    "json".to_string()
-}
-
 #[derive(Debug, Serialize, ToSchema)]
 pub struct AggregatesResponse {
     /// Zone this data belongs to
@@ -52,10 +46,14 @@ pub struct AggregatesResponse {
     pub start: DateTime<Utc>,
     /// End of time range
     pub end: DateTime<Utc>,
-    /// Array of bucket timestamps
-    pub times: Vec<DateTime<Utc>>,
+    /// Array of bucket timestamps, as RFC 3339 strings or epoch seconds
+    /// depending on `time_format`
+    #[schema(value_type = Vec<String>)]
+    pub times: TimeArray<DateTime<Utc>>,
     /// Array of sensors with their aggregated values
     pub sensors: Vec<SensorAggregateData>,
+    /// Sensors whose on-the-fly aggregation failed (returned with null arrays)
+    pub errors: Vec<Uuid>,
 }
 
 #[derive(Debug, Clone, Serialize, ToSchema)]
@@ -65,43 +63,409 @@ pub struct SensorAggregateData {
     #[serde(rename = "type")]
     pub sensor_type: String,
     pub units: Option<String>,
-    /// Average values array (same length as times)
-    pub avg: Vec<Option<f64>>,
+    /// False for retired sensors (only returned with `include_inactive=true`)
+    pub is_active: bool,
+    /// Average values array (same length as times; omitted unless `avg` is
+    /// in `stats`, as are the other statistics)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub avg: Option<Vec<Option<f64>>>,
     /// Minimum values array
-    pub min: Vec<Option<f64>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min: Option<Vec<Option<f64>>>,
     /// Maximum values array
-    pub max: Vec<Option<f64>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max: Option<Vec<Option<f64>>>,
+    /// Median (50th percentile) values array (only with `p50` in `stats`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub p50: Option<Vec<Option<f64>>>,
+    /// 95th percentile values array
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub p95: Option<Vec<Option<f64>>>,
+    /// Time of the minimum value within each bucket (selected with `min`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<Vec<Option<String>>>)]
+    pub min_time: Option<TimeArray<Option<DateTime<Utc>>>>,
+    /// Time of the maximum value within each bucket (selected with `max`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<Vec<Option<String>>>)]
+    pub max_time: Option<TimeArray<Option<DateTime<Utc>>>>,
     /// Count of readings per bucket
-    pub count: Vec<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub count: Option<Vec<i64>>,
+    /// Limit lines; only with `include_thresholds=true`, and omitted for
+    /// sensors without thresholds or units range
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thresholds: Option<SensorLimits>,
+}
+
+/// Statistics selected with `stats`; `min` and `max` include their times.
+///
+/// Percentiles are opt-in: the raw-readings fallback has to sort every
+/// bucket's readings to compute them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AggregateStats {
+    pub avg: bool,
+    pub min: bool,
+    pub max: bool,
+    pub p50: bool,
+    pub p95: bool,
+    pub count: bool,
+}
+
+impl Default for AggregateStats {
+    fn default() -> Self {
+        Self {
+            avg: true,
+            min: true,
+            max: true,
+            p50: false,
+            p95: false,
+            count: true,
+        }
+    }
+}
+
+impl AggregateStats {
+    /// Parse a comma-separated list of `avg`, `min`, `max`, `p50`, `p95` and
+    /// `count`; `None` selects the default (all but the percentiles).
+    ///
+    /// # Errors
+    ///
+    /// Returns `BadRequest` for an unknown or empty selection.
+    pub fn parse(list: Option<&str>) -> AppResult<Self> {
+        let Some(list) = list else {
+            return Ok(Self::default());
+        };
+
+        let mut stats = Self {
+            avg: false,
+            min: false,
+            max: false,
+            p50: false,
+            p95: false,
+            count: false,
+        };
+        for name in list.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+            match name.to_ascii_lowercase().as_str() {
+                "avg" => stats.avg = true,
+                "min" => stats.min = true,
+                "max" => stats.max = true,
+                "p50" => stats.p50 = true,
+                "p95" => stats.p95 = true,
+                "count" => stats.count = true,
+                _ => {
+                    return Err(AppError::BadRequest(format!(
+                        "Invalid stat: {name}. Must be one of: avg, min, max, p50, p95, count"
+                    )));
+                }
+            }
+        }
+        if !(stats.avg || stats.min || stats.max || stats.p50 || stats.p95 || stats.count) {
+            return Err(AppError::BadRequest(
+                "stats must name at least one of: avg, min, max, p50, p95, count".to_string(),
+            ));
+        }
+        Ok(stats)
+    }
+
+    /// Canonical form for cache keys
+    pub fn as_key(self) -> String {
+        [
+            (self.avg, "avg"),
+            (self.min, "min"),
+            (self.max, "max"),
+            (self.p50, "p50"),
+            (self.p95, "p95"),
+            (self.count, "count"),
+        ]
+        .iter()
+        .filter(|(selected, _)| *selected)
+        .map(|(_, name)| *name)
+        .collect::<Vec<_>>()
+        .join(",")
+    }
+
+    /// Selected columns of a continuous aggregate view (`v`) and its
+    /// percentiles view (`p`)
+    fn view_columns(self) -> Vec<&'static str> {
+        self.select(
+            ["v.avg_value"],
+            ["v.min_value", "v.min_time"],
+            ["v.max_value", "v.max_time"],
+            ["p.p50_value"],
+            ["p.p95_value"],
+            ["v.count"],
+        )
+    }
+
+    /// Selected expressions computing the view's columns from raw readings
+    fn fallback_columns(self) -> Vec<&'static str> {
+        self.select(
+            ["AVG(value) AS avg_value"],
+            ["MIN(value) AS min_value", "first(time, value) AS min_time"],
+            ["MAX(value) AS max_value", "last(time, value) AS max_time"],
+            ["percentile_cont(0.5) WITHIN GROUP (ORDER BY value) AS p50_value"],
+            ["percentile_cont(0.95) WITHIN GROUP (ORDER BY value) AS p95_value"],
+            ["COUNT(*) AS count"],
+        )
+    }
+
+    fn select(
+        self,
+        avg: [&'static str; 1],
+        min: [&'static str; 2],
+        max: [&'static str; 2],
+        p50: [&'static str; 1],
+        p95: [&'static str; 1],
+        count: [&'static str; 1],
+    ) -> Vec<&'static str> {
+        let mut columns = Vec::new();
+        if self.avg {
+            columns.extend(avg);
+        }
+        if self.min {
+            columns.extend(min);
+        }
+        if self.max {
+            columns.extend(max);
+        }
+        if self.p50 {
+            columns.extend(p50);
+        }
+        if self.p95 {
+            columns.extend(p95);
+        }
+        if self.count {
+            columns.extend(count);
+        }
+        columns
+    }
+}
+
+/// Minimum readings a bucket needs for its statistics to be returned, set
+/// with `min_count` and `min_coverage`
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct MinCount {
+    /// Absolute number of readings
+    pub count: Option<i64>,
+    /// Fraction (0 to 1) of the readings a bucket is expected to hold at the
+    /// sensor's sample interval
+    pub coverage: Option<f64>,
+}
+
+impl MinCount {
+    /// Validate `min_count` and `min_coverage`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `BadRequest` for a negative count or a coverage outside 0 to 1.
+    pub fn new(count: Option<i64>, coverage: Option<f64>) -> AppResult<Self> {
+        if count.is_some_and(|c| c < 0) {
+            return Err(AppError::BadRequest(
+                "min_count must not be negative".to_string(),
+            ));
+        }
+        if coverage.is_some_and(|c| !(0.0..=1.0).contains(&c)) {
+            return Err(AppError::BadRequest(
+                "min_coverage must be between 0 and 1".to_string(),
+            ));
+        }
+        Ok(Self { count, coverage })
+    }
+
+    pub fn is_set(self) -> bool {
+        self.count.is_some() || self.coverage.is_some()
+    }
+
+    /// Readings required in a `bucket_seconds` bucket of a sensor sampling
+    /// every `sample_interval_sec`; the stricter of both settings
+    pub fn threshold(self, bucket_seconds: i64, sample_interval_sec: i64) -> i64 {
+        let expected = bucket_seconds / sample_interval_sec.max(1);
+        let from_coverage = self
+            .coverage
+            .map_or(0, |fraction| (expected as f64 * fraction).ceil() as i64);
+        self.count.unwrap_or(0).max(from_coverage)
+    }
+
+    /// Canonical form for cache keys
+    pub fn as_key(self) -> String {
+        format!(
+            "{}/{}",
+            self.count.map(|c| c.to_string()).unwrap_or_default(),
+            self.coverage.map(|c| c.to_string()).unwrap_or_default()
+        )
+    }
+}
+
+/// Query of the continuous aggregate `view_name`, fetching only `stats`.
+///
+/// Percentiles come from the separate `<view_name>_percentiles` aggregate,
+/// joined only when selected; they are null for buckets it doesn't cover
+/// (e.g. history whose raw readings were dropped before it existed).
+///
+/// Binds the bucket range as `$1` and `$2`.
+pub fn view_query(view_name: &str, sensor_ids: &[Uuid], stats: AggregateStats) -> String {
+    let sensor_ids_str = sensor_ids
+        .iter()
+        .map(|id| format!("'{id}'"))
+        .collect::<Vec<_>>()
+        .join(",");
+    let columns = stats.view_columns().join(", ");
+    let percentiles = if stats.p50 || stats.p95 {
+        format!(
+            "LEFT JOIN {view_name}_percentiles p ON p.bucket = v.bucket AND p.sensor_id = v.sensor_id"
+        )
+    } else {
+        String::new()
+    };
+
+    format!(
+        r"
+        SELECT v.bucket, v.sensor_id, {columns}
+        FROM {view_name} v
+        {percentiles}
+        WHERE v.sensor_id IN ({sensor_ids_str})
+          AND v.bucket >= $1
+          AND v.bucket <= $2
+        ORDER BY v.bucket ASC, v.sensor_id ASC
+        "
+    )
+}
+
+/// On-the-fly aggregation of one sensor's raw readings, computing only `stats`.
+///
+/// Binds the time range as `$1` and `$2` and the sensor as `$3`.
+pub fn fallback_query(bucket_interval: &str, stats: AggregateStats) -> String {
+    let columns = stats.fallback_columns().join(",\n                ");
+
+    format!(
+        r"
+            SELECT
+                time_bucket('{bucket_interval}', time) AS bucket,
+                sensor_id,
+                {columns}
+            FROM readings
+            WHERE sensor_id = $3
+              AND time >= $1
+              AND time <= $2
+            GROUP BY time_bucket('{bucket_interval}', time), sensor_id
+            ORDER BY bucket ASC
+            "
+    )
+}
+
+/// Every bucket start in the queried range, whether or not it holds data.
+///
+/// Binds the range start as `$1` and the exclusive gapfill finish as `$2`.
+/// `time_bucket_gapfill` needs a row to group, so a single placeholder row
+/// stands in for the data; buckets before `$1` (the one containing a
+/// mid-bucket start) are dropped, as in [`view_query`].
+pub fn gapfill_query(bucket_interval: &str) -> String {
+    format!(
+        r"
+        SELECT bucket FROM (
+            SELECT time_bucket_gapfill('{bucket_interval}', t, $1, $2) AS bucket
+            FROM (VALUES ($1::timestamptz)) AS placeholder(t)
+            GROUP BY 1
+        ) AS grid
+        WHERE bucket >= $1
+        ORDER BY bucket ASC
+        "
+    )
 }
 
 #[derive(Debug, FromQueryResult)]
+struct BucketRow {
+    bucket: DateTime<Utc>,
+}
+
+#[derive(Debug)]
 struct AggregateRow {
     bucket: DateTime<Utc>,
     sensor_id: Uuid,
     avg_value: Option<f64>,
     min_value: Option<f64>,
     max_value: Option<f64>,
-    count: i64,
+    min_time: Option<DateTime<Utc>>,
+    max_time: Option<DateTime<Utc>>,
+    p50_value: Option<f64>,
+    p95_value: Option<f64>,
+    count: Option<i64>,
 }
 
-fn determine_format(query_format: &str, headers: &HeaderMap) -> String {
-    if query_format != "json" {
-        return query_format.to_lowercase();
+impl AggregateRow {
+    /// Null the statistics of a bucket with too few readings, keeping its count
+    fn suppress(&mut self) {
+        self.avg_value = None;
+        self.min_value = None;
+        self.max_value = None;
+        self.min_time = None;
+        self.max_time = None;
+        self.p50_value = None;
+        self.p95_value = None;
     }
+}
 
-    if let Some(accept) = headers.get(header::ACCEPT)
-        && let Ok(accept_str) = accept.to_str()
-    {
-        if accept_str.contains("application/x-ndjson") {
-            return "ndjson".to_string();
-        }
-        if accept_str.contains("text/csv") {
-            return "csv".to_string();
-        }
+/// Column that may be left out of the query by `stats`.
+///
+/// A column the query did not select reads as `None`, like a NULL; any other
+/// error (e.g. a type mismatch) is returned rather than hidden.
+fn optional_column<T: TryGetable>(
+    res: &QueryResult,
+    pre: &str,
+    col: &str,
+) -> Result<Option<T>, DbErr> {
+    // SeaORM reads a missing column as `None` for `Option`
+    res.try_get::<Option<T>>(pre, col)
+}
+
+// Not derived: the derive requires every column, while `stats` leaves some out
+impl FromQueryResult for AggregateRow {
+    fn from_query_result(res: &QueryResult, pre: &str) -> Result<Self, DbErr> {
+        Ok(Self {
+            bucket: res.try_get(pre, "bucket")?,
+            sensor_id: res.try_get(pre, "sensor_id")?,
+            avg_value: optional_column(res, pre, "avg_value")?,
+            min_value: optional_column(res, pre, "min_value")?,
+            max_value: optional_column(res, pre, "max_value")?,
+            min_time: optional_column(res, pre, "min_time")?,
+            max_time: optional_column(res, pre, "max_time")?,
+            p50_value: optional_column(res, pre, "p50_value")?,
+            p95_value: optional_column(res, pre, "p95_value")?,
+            count: optional_column(res, pre, "count")?,
+        })
+    }
+}
+
+/// Map aggregate query errors, reporting missing TimescaleDB objects as 503
+fn aggregation_error(err: DbErr) -> AppError {
+    if timescale::is_missing_object(&err) {
+        tracing::warn!(error = %err, "aggregation_unavailable");
+        return AppError::ServiceUnavailable(
+            "aggregation not available on this database".to_string(),
+        );
+    }
+    AppError::Database(err)
+}
+
+/// Append a CSV cell per bucket value if the statistic was selected
+fn push_value_cell(row: &mut Vec<String>, values: Option<&[Option<f64>]>, i: usize) {
+    if let Some(values) = values {
+        let value = values.get(i).and_then(|v| *v);
+        row.push(value.map(|v| format_decimal(v, None)).unwrap_or_default());
     }
+}
 
-    "json".to_string()
+/// Append a CSV cell per bucket time if the statistic was selected
+fn push_time_cell(
+    row: &mut Vec<String>,
+    times: Option<&TimeArray<Option<DateTime<Utc>>>>,
+    i: usize,
+) {
+    if let Some(times) = times {
+        let time = times.get(i).and_then(|t| *t);
+        row.push(time.map(|t| t.to_rfc3339()).unwrap_or_default());
+    }
 }
 
 fn build_csv_response(
@@ -112,50 +476,54 @@ fn build_csv_response(
     let (tx, rx) = tokio::sync::mpsc::channel::<Result<String, std::io::Error>>(100);
 
     let times = times.to_vec();
-    let sensors = sensors.to_vec();
+    let sensors = ordered_columns(sensors, |s| (s.name.as_str(), s.id));
 
     tokio::spawn(async move {
-        // Header row: time, sensor1_avg, sensor1_min, sensor1_max, sensor1_count, sensor2_avg, ...
-        let mut header = "time".to_string();
+        // Header row: time, sensor1_avg, sensor1_min, sensor1_max, sensor1_p50,
+        // sensor1_p95, sensor1_min_time, sensor1_max_time, sensor1_count,
+        // sensor2_avg, ... (selected stats only)
+        let mut header = vec!["time".to_string()];
         for sensor in &sensors {
-            header.push_str(&format!(
-                ",{}_avg,{}_min,{}_max,{}_count",
-                sensor.name, sensor.name, sensor.name, sensor.name
-            ));
+            let name = &sensor.name;
+            let columns = [
+                (sensor.avg.is_some(), "avg"),
+                (sensor.min.is_some(), "min"),
+                (sensor.max.is_some(), "max"),
+                (sensor.p50.is_some(), "p50"),
+                (sensor.p95.is_some(), "p95"),
+                (sensor.min_time.is_some(), "min_time"),
+                (sensor.max_time.is_some(), "max_time"),
+                (sensor.count.is_some(), "count"),
+            ];
+            for (_, stat) in columns.iter().filter(|(selected, _)| *selected) {
+                header.push(format!("{name}_{stat}"));
+            }
         }
-        header.push('\n');
-        let _ = tx.send(Ok(header)).await;
+        // Names are quoted where needed
+        let mut csv = CsvLines::new();
+        csv.write(header);
 
         // Data rows
         for (i, time) in times.iter().enumerate() {
-            let mut row = time.to_rfc3339();
+            let mut row = vec![time.to_rfc3339()];
             for sensor in &sensors {
-                // avg
-                row.push(',');
-                if let Some(v) = sensor.avg.get(i).and_then(|v| *v) {
-                    row.push_str(&v.to_string());
-                }
-                // min
-                row.push(',');
-                if let Some(v) = sensor.min.get(i).and_then(|v| *v) {
-                    row.push_str(&v.to_string());
-                }
-                // max
-                row.push(',');
-                if let Some(v) = sensor.max.get(i).and_then(|v| *v) {
-                    row.push_str(&v.to_string());
-                }
-                // count
-                row.push(',');
-                if let Some(c) = sensor.count.get(i) {
-                    row.push_str(&c.to_string());
+                push_value_cell(&mut row, sensor.avg.as_deref(), i);
+                push_value_cell(&mut row, sensor.min.as_deref(), i);
+                push_value_cell(&mut row, sensor.max.as_deref(), i);
+                push_value_cell(&mut row, sensor.p50.as_deref(), i);
+                push_value_cell(&mut row, sensor.p95.as_deref(), i);
+                push_time_cell(&mut row, sensor.min_time.as_ref(), i);
+                push_time_cell(&mut row, sensor.max_time.as_ref(), i);
+                if let Some(count) = &sensor.count {
+                    row.push(count.get(i).map(ToString::to_string).unwrap_or_default());
                 }
             }
-            row.push('\n');
-            if tx.send(Ok(row)).await.is_err() {
-                break;
+            csv.write(row);
+            if csv.is_full() && tx.send(Ok(csv.take())).await.is_err() {
+                return;
             }
         }
+        let _ = tx.send(Ok(csv.take())).await;
     });
 
     let stream = ReceiverStream::new(rx);
@@ -174,7 +542,23 @@ fn build_ndjson_response(
     let (tx, rx) = tokio::sync::mpsc::channel::<Result<String, std::io::Error>>(100);
 
     let times = times.to_vec();
-    let sensors = sensors.to_vec();
+    let sensors = ordered_columns(sensors, |s| (s.name.as_str(), s.id));
+
+    // Only selected stats get keys
+    let value_at = |values: &Vec<Option<f64>>, i: usize| {
+        values
+            .get(i)
+            .and_then(|v| *v)
+            .map_or(serde_json::Value::Null, |v| serde_json::json!(v))
+    };
+    let time_at = |times: &TimeArray<Option<DateTime<Utc>>>, i: usize| {
+        times
+            .get(i)
+            .and_then(|t| *t)
+            .map_or(serde_json::Value::Null, |t| {
+                serde_json::json!(t.to_rfc3339())
+            })
+    };
 
     tokio::spawn(async move {
         for (i, time) in times.iter().enumerate() {
@@ -182,24 +566,31 @@ fn build_ndjson_response(
             obj.insert("time".to_string(), serde_json::json!(time.to_rfc3339()));
 
             for sensor in &sensors {
-                let avg = sensor.avg.get(i).and_then(|v| *v);
-                let min = sensor.min.get(i).and_then(|v| *v);
-                let max = sensor.max.get(i).and_then(|v| *v);
-                let count = sensor.count.get(i).copied().unwrap_or(0);
-
-                obj.insert(
-                    format!("{}_avg", sensor.name),
-                    avg.map_or(serde_json::Value::Null, |v| serde_json::json!(v)),
-                );
-                obj.insert(
-                    format!("{}_min", sensor.name),
-                    min.map_or(serde_json::Value::Null, |v| serde_json::json!(v)),
-                );
-                obj.insert(
-                    format!("{}_max", sensor.name),
-                    max.map_or(serde_json::Value::Null, |v| serde_json::json!(v)),
-                );
-                obj.insert(format!("{}_count", sensor.name), serde_json::json!(count));
+                if let Some(avg) = &sensor.avg {
+                    obj.insert(format!("{}_avg", sensor.name), value_at(avg, i));
+                }
+                if let Some(min) = &sensor.min {
+                    obj.insert(format!("{}_min", sensor.name), value_at(min, i));
+                }
+                if let Some(max) = &sensor.max {
+                    obj.insert(format!("{}_max", sensor.name), value_at(max, i));
+                }
+                if let Some(p50) = &sensor.p50 {
+                    obj.insert(format!("{}_p50", sensor.name), value_at(p50, i));
+                }
+                if let Some(p95) = &sensor.p95 {
+                    obj.insert(format!("{}_p95", sensor.name), value_at(p95, i));
+                }
+                if let Some(min_time) = &sensor.min_time {
+                    obj.insert(format!("{}_min_time", sensor.name), time_at(min_time, i));
+                }
+                if let Some(max_time) = &sensor.max_time {
+                    obj.insert(format!("{}_max_time", sensor.name), time_at(max_time, i));
+                }
+                if let Some(count) = &sensor.count {
+                    let count = count.get(i).copied().unwrap_or(0);
+                    obj.insert(format!("{}_count", sensor.name), serde_json::json!(count));
+                }
             }
 
             let line = format!("{}\n", serde_json::Value::Object(obj));
@@ -221,17 +612,328 @@ fn build_ndjson_response(
         .map_err(|e| AppError::Internal(e.to_string()))
 }
 
+/// Continuous aggregate view and matching bucket width for a resolution
+#[derive(Debug, Clone, Copy)]
+pub(super) struct AggregateSource {
+    /// Canonical resolution name (see [`time::normalize_resolution`])
+    pub(super) resolution: &'static str,
+    view_name: &'static str,
+    /// Bucket interval for the on-the-fly fallback over raw readings
+    bucket_interval: &'static str,
+    /// Approximate bucket width in seconds (months count as 30 days)
+    pub(super) bucket_seconds: i64,
+}
+
+impl AggregateSource {
+    /// Source for a resolution name, matched case-insensitively with aliases
+    pub(super) fn for_resolution(resolution: &str) -> Option<Self> {
+        let resolution = time::normalize_resolution(resolution)?;
+        let (view_name, bucket_interval, bucket_seconds) = match resolution {
+            "hourly" => ("readings_hourly", "1 hour", 3_600),
+            "daily" => ("readings_daily", "1 day", 86_400),
+            "weekly" => ("readings_weekly", "1 week", 604_800),
+            "monthly" => ("readings_monthly", "1 month", 2_592_000),
+            _ => return None,
+        };
+        Some(Self {
+            resolution,
+            view_name,
+            bucket_interval,
+            bucket_seconds,
+        })
+    }
+}
+
+/// Reject `resolution` if operators disabled it (`ENABLED_RESOLUTIONS`).
+///
+/// # Errors
+///
+/// Returns `BadRequest` listing the enabled resolutions.
+pub(super) fn check_resolution_enabled(config: &Config, resolution: &str) -> AppResult<()> {
+    if config.resolution_enabled(resolution) {
+        return Ok(());
+    }
+    Err(AppError::BadRequest(format!(
+        "Resolution {resolution} is disabled. Enabled resolutions: {}",
+        config.enabled_resolutions.join(", ")
+    )))
+}
+
+/// Aggregate `sensors_list` over `[query_start, query_end]`.
+///
+/// Reads the continuous aggregate view, falling back to on-the-fly
+/// aggregation of raw readings when the view has no rows yet. Returns the
+/// bucket times, one column per sensor (in `sensors_list` order) and the
+/// sensors whose fallback aggregation failed. A disabled resolution is
+/// rejected before either is queried. Only the `stats` columns are
+/// queried (plus `count` when `min_count` is set); the others are `None` in
+/// every column. Buckets below `min_count` have null statistics. With
+/// `fill_gaps`, the times cover every bucket of the range, and buckets without
+/// readings are null (count 0).
+#[allow(clippy::too_many_arguments)]
+pub(super) async fn aggregate_series(
+    state: &AppState,
+    sensors_list: &[sensors::Model],
+    source: AggregateSource,
+    query_start: DateTime<Utc>,
+    query_end: DateTime<Utc>,
+    stats: AggregateStats,
+    min_count: MinCount,
+    fill_gaps: bool,
+    strict: bool,
+    round: bool,
+    time_format: TimeFormat,
+) -> AppResult<(Vec<DateTime<Utc>>, Vec<SensorAggregateData>, Vec<Uuid>)> {
+    // The raw fallback is the expensive path operators disable a resolution
+    // to avoid, so check here whichever endpoint asks
+    check_resolution_enabled(&state.config, source.resolution)?;
+
+    let sensor_ids: Vec<Uuid> = sensors_list.iter().map(|s| s.id).collect();
+
+    let AggregateSource {
+        view_name,
+        bucket_interval,
+        bucket_seconds,
+        ..
+    } = source;
+
+    // Suppressing buckets needs their counts, returned or not
+    let query_stats = AggregateStats {
+        count: stats.count || min_count.is_set(),
+        ..stats
+    };
+
+    // Query the continuous aggregate view first
+    let sql = view_query(view_name, &sensor_ids, query_stats);
+
+    let mut results: Vec<AggregateRow> = state
+        .read_db
+        .query_all(Statement::from_sql_and_values(
+            sea_orm::DatabaseBackend::Postgres,
+            &sql,
+            vec![query_start.into(), query_end.into()],
+        ))
+        .await
+        .map_err(aggregation_error)?
+        .iter()
+        .map(|row| AggregateRow::from_query_result(row, ""))
+        .collect::<Result<_, _>>()?;
+
+    let mut failed_sensors: Vec<Uuid> = Vec::new();
+
+    // Fallback to on-the-fly aggregation if continuous aggregate has no data
+    // This handles cases where the materialized view hasn't been refreshed yet
+    if results.is_empty() {
+        tracing::info!(
+            view = view_name,
+            start = %query_start,
+            end = %query_end,
+            "continuous_aggregate_empty_fallback_to_raw"
+        );
+
+        // Aggregate each sensor separately so one failing sensor doesn't fail the request
+        let fallback_sql = fallback_query(bucket_interval, query_stats);
+
+        let queries = sensor_ids.iter().map(|sensor_id| {
+            state.read_db.query_all(Statement::from_sql_and_values(
+                sea_orm::DatabaseBackend::Postgres,
+                &fallback_sql,
+                vec![query_start.into(), query_end.into(), (*sensor_id).into()],
+            ))
+        });
+
+        for (sensor_id, result) in sensor_ids
+            .iter()
+            .zip(futures::future::join_all(queries).await)
+        {
+            match result {
+                Ok(rows) => {
+                    for row in &rows {
+                        results.push(AggregateRow::from_query_result(row, "")?);
+                    }
+                }
+                // A missing TimescaleDB function affects every sensor; never partial
+                Err(e) if strict || timescale::is_missing_object(&e) => {
+                    return Err(aggregation_error(e));
+                }
+                Err(e) => {
+                    tracing::warn!(error = %e, sensor_id = %sensor_id, "sensor_aggregation_failed");
+                    failed_sensors.push(*sensor_id);
+                }
+            }
+        }
+    }
+
+    if min_count.is_set() {
+        let default_interval = state.config.default_sample_interval_sec;
+        let thresholds: HashMap<Uuid, i64> = sensors_list
+            .iter()
+            .map(|s| {
+                let interval = s.effective_interval(default_interval);
+                (s.id, min_count.threshold(bucket_seconds, interval))
+            })
+            .collect();
+        for row in &mut results {
+            let threshold = thresholds.get(&row.sensor_id).copied().unwrap_or(0);
+            if row.count.unwrap_or(0) < threshold {
+                row.suppress();
+            }
+        }
+    }
+
+    // Build time index and sensor value maps
+    let mut time_set: BTreeMap<DateTime<Utc>, usize> = BTreeMap::new();
+
+    // Seed the full bucket grid so gaps show up as null rather than missing
+    if fill_gaps {
+        // Gapfill's finish is exclusive while the range end is inclusive
+        let finish = query_end + Duration::microseconds(1);
+        let grid = state
+            .read_db
+            .query_all(Statement::from_sql_and_values(
+                sea_orm::DatabaseBackend::Postgres,
+                gapfill_query(bucket_interval),
+                vec![query_start.into(), finish.into()],
+            ))
+            .await
+            .map_err(aggregation_error)?;
+        for row in grid {
+            let row = BucketRow::from_query_result(&row, "")?;
+            time_set.entry(row.bucket).or_insert(0);
+        }
+    }
+    let mut sensor_aggs: HashMap<Uuid, HashMap<DateTime<Utc>, AggregateRow>> = HashMap::new();
+
+    for row in results {
+        time_set.entry(row.bucket).or_insert(0);
+        sensor_aggs
+            .entry(row.sensor_id)
+            .or_default()
+            .insert(row.bucket, row);
+    }
+
+    // Build sorted times array
+    let times: Vec<DateTime<Utc>> = time_set.keys().copied().collect();
+
+    // Build sensor aggregate data
+    let sensor_data: Vec<SensorAggregateData> = sensors_list
+        .iter()
+        .map(|sensor| {
+            let aggs_map = sensor_aggs.get(&sensor.id);
+
+            let row_at = |t: &DateTime<Utc>| aggs_map.and_then(|m| m.get(t));
+            let values = |value: fn(&AggregateRow) -> Option<f64>| -> Vec<Option<f64>> {
+                times.iter().map(|t| row_at(t).and_then(value)).collect()
+            };
+            let bucket_times = |time: fn(&AggregateRow) -> Option<DateTime<Utc>>| {
+                let values = times.iter().map(|t| row_at(t).and_then(time)).collect();
+                TimeArray::new(values, time_format)
+            };
+
+            let mut avg = stats.avg.then(|| values(|r| r.avg_value));
+            let mut min = stats.min.then(|| values(|r| r.min_value));
+            let mut max = stats.max.then(|| values(|r| r.max_value));
+            let mut p50 = stats.p50.then(|| values(|r| r.p50_value));
+            let mut p95 = stats.p95.then(|| values(|r| r.p95_value));
+            if round {
+                // Percentiles interpolate between readings, like an average
+                for column in [avg.as_mut(), p50.as_mut(), p95.as_mut()]
+                    .into_iter()
+                    .flatten()
+                {
+                    round_values(column, average_decimal_places(sensor.decimal_places));
+                }
+                for column in [min.as_mut(), max.as_mut()].into_iter().flatten() {
+                    round_values(column, sensor.decimal_places);
+                }
+            }
+
+            SensorAggregateData {
+                id: sensor.id,
+                name: sensor.name.clone(),
+                sensor_type: sensor.sensor_type.clone(),
+                units: sensor.display_units.clone(),
+                is_active: sensor.is_active == Some(true),
+                avg,
+                min,
+                max,
+                p50,
+                p95,
+                min_time: stats.min.then(|| bucket_times(|r| r.min_time)),
+                max_time: stats.max.then(|| bucket_times(|r| r.max_time)),
+                count: stats.count.then(|| {
+                    times
+                        .iter()
+                        .map(|t| row_at(t).and_then(|r| r.count).unwrap_or(0))
+                        .collect()
+                }),
+                thresholds: None,
+            }
+        })
+        .collect();
+
+    Ok((times, sensor_data, failed_sensors))
+}
+
 #[derive(Debug, Deserialize, IntoParams)]
 pub struct StationAggregatesQuery {
-    /// Start time (required, ISO 8601)
-    pub start: DateTime<Utc>,
-    /// End time (required, ISO 8601)
-    pub end: DateTime<Utc>,
+    /// Start time (ISO 8601 or Unix epoch seconds). Required unless `window`
+    /// is given.
+    #[serde(default, deserialize_with = "time::deserialize_optional_timestamp")]
+    pub start: Option<DateTime<Utc>>,
+    /// End time (ISO 8601 or Unix epoch seconds). Required unless `window`
+    /// is given.
+    #[serde(default, deserialize_with = "time::deserialize_optional_timestamp")]
+    pub end: Option<DateTime<Utc>>,
+    /// Relative window ending now (e.g. 24h, 7d, 30d). Ignored if start or end is given.
+    pub window: Option<String>,
     /// Filter by sensor types (comma-separated)
     pub sensor_types: Option<String>,
-    /// Response format: json (default), ndjson, csv
-    #[serde(default = "default_format")]
-    pub format: String,
+    /// Filter by sensor names (comma-separated, case-insensitive)
+    pub sensor_names: Option<String>,
+    /// Include inactive (retired) sensors, labeled `is_active: false` (default: false)
+    #[serde(default)]
+    pub include_inactive: bool,
+    /// Response format: json, ndjson, csv. Takes precedence over the Accept
+    /// header, which is used when omitted (q-values honored); default json.
+    pub format: Option<String>,
+    /// CSV and NDJSON only: `gzip` compresses the export whatever the
+    /// `Accept-Encoding`, named `<station>-<resolution>.<format>.gz` for download
+    pub compress: Option<Compression>,
+    /// Fail the whole request if any sensor's aggregation fails (default: partial results)
+    #[serde(default)]
+    pub strict: bool,
+    /// JSON only: serialize `times`, `min_time` and `max_time` as `iso` strings
+    /// (default) or `epoch` seconds
+    #[serde(default)]
+    pub time_format: TimeFormat,
+    /// JSON and CSV: round min/max to each sensor's `decimal_places` and avg,
+    /// p50 and p95 to one more place (sensors without one are left exact)
+    #[serde(default)]
+    pub round: bool,
+    /// Statistics to return (comma-separated: avg, min, max, p50, p95, count;
+    /// default avg, min, max, count). `min` and `max` include their times;
+    /// `p50` and `p95` are exact percentiles of the bucket's readings, only
+    /// returned when asked for (null for history older than the raw readings
+    /// retention). Unselected statistics are not queried, which cuts work for
+    /// large exports
+    pub stats: Option<String>,
+    /// Null the statistics of buckets holding fewer readings than this,
+    /// keeping their `count` so clients can tell why
+    pub min_count: Option<i64>,
+    /// Null the statistics of buckets holding less than this fraction (0 to 1)
+    /// of the readings expected at the sensor's sample interval. Combined
+    /// with `min_count`, the stricter threshold applies
+    pub min_coverage: Option<f64>,
+    /// JSON only: attach each sensor's high/low limit lines as `thresholds`
+    /// (configured alarm thresholds, else its units range)
+    #[serde(default)]
+    pub include_thresholds: bool,
+    /// Return every bucket between `start` and `end`, so `times` is evenly
+    /// spaced: buckets without readings have null statistics and count 0
+    /// (default: only buckets with data)
+    #[serde(default)]
+    pub fill_gaps: bool,
 }
 
 /// Get aggregates for a specific station
@@ -240,16 +942,17 @@ pub struct StationAggregatesQuery {
 /// Supports JSON, CSV, and NDJSON formats.
 #[utoipa::path(
     get,
-    path = "/api/stations/{station_id}/aggregates/{resolution}",
+    path = "/api/v1/stations/{station_id}/aggregates/{resolution}",
     params(
         ("station_id" = String, Path, description = "Station UUID or name"),
-        ("resolution" = String, Path, description = "Aggregation resolution: hourly, daily, weekly, monthly"),
+        ("resolution" = String, Path, description = "Aggregation resolution: hourly, daily, weekly, monthly (case-insensitive; 1h, 1d, 1w accepted)"),
         StationAggregatesQuery
     ),
     responses(
         (status = 200, description = "Aggregates retrieved successfully", body = AggregatesResponse),
-        (status = 400, description = "Invalid resolution or query parameters"),
+        (status = 400, description = "Invalid or disabled resolution, invalid query parameters, or more sensors than MAX_SENSORS_PER_REQUEST"),
         (status = 404, description = "Station not found"),
+        (status = 503, description = "Aggregation not available on this database"),
     ),
     tag = "stations"
 )]
@@ -259,12 +962,12 @@ pub async fn get_station_aggregates(
     Query(query): Query<StationAggregatesQuery>,
     headers: HeaderMap,
 ) -> AppResult<Response> {
-    let station = resolve_station(&state.db, &station_id).await?;
+    let station = resolve_station(&state.read_db, &station_id).await?;
 
     // Fetch zone info if available
     let zone_ref = if let Some(zone_id) = station.zone_id {
         zones::Entity::find_by_id(zone_id)
-            .one(&state.db)
+            .one(&state.read_db)
             .await?
             .map(|z| ZoneRef {
                 id: z.id,
@@ -280,40 +983,50 @@ pub async fn get_station_aggregates(
     };
 
     // Validate resolution
-    let view_name = match resolution.as_str() {
-        "hourly" => "readings_hourly",
-        "daily" => "readings_daily",
-        "weekly" => "readings_weekly",
-        "monthly" => "readings_monthly",
+    let Some(source) = AggregateSource::for_resolution(&resolution) else {
+        return Err(AppError::BadRequest(format!(
+            "Invalid resolution: {resolution}. Must be one of: hourly, daily, weekly, monthly (or 1h, 1d, 1w)"
+        )));
+    };
+    check_resolution_enabled(&state.config, source.resolution)?;
+    // Canonical name for the response and cache key, so `Hourly` and `hourly`
+    // share a cache entry
+    let resolution = source.resolution.to_string();
+
+    // Explicit start/end take precedence over a relative window
+    let (query_start, query_end) = match (&query.window, query.start, query.end) {
+        (_, Some(start), Some(end)) => (start, end),
+        (Some(window), None, None) => time::resolve_window(window)?,
         _ => {
-            return Err(AppError::BadRequest(format!(
-                "Invalid resolution: {resolution}. Must be one of: hourly, daily, weekly, monthly"
-            )));
+            return Err(AppError::BadRequest(
+                "start and end are required unless window is given".to_string(),
+            ));
         }
     };
 
-    // Validate time range
-    if query.end <= query.start {
-        return Err(AppError::BadRequest(
-            "end time must be after start time".to_string(),
-        ));
-    }
+    time::validate_range(query_start, query_end)?;
 
     // Enforce max time range
-    let duration = query.end - query.start;
+    let duration = query_end - query_start;
     if duration > Duration::days(MAX_TIME_RANGE_DAYS) {
         return Err(AppError::BadRequest(format!(
             "time range exceeds maximum of {MAX_TIME_RANGE_DAYS} days"
         )));
     }
 
+    let stats = AggregateStats::parse(query.stats.as_deref())?;
+    let min_count = MinCount::new(query.min_count, query.min_coverage)?;
+
     // Determine format
-    let format = determine_format(&query.format, &headers);
+    let format = negotiate_format(query.format.as_deref(), &headers);
+    check_compress(query.compress, &format)?;
 
     // Build sensor query for this station only
-    let mut sensor_query = sensors::Entity::find()
-        .filter(sensors::Column::IsActive.eq(true))
-        .filter(sensors::Column::StationId.eq(station.id));
+    let mut sensor_query =
+        sensors::Entity::find().filter(sensors::Column::StationId.eq(station.id));
+    if !query.include_inactive {
+        sensor_query = sensor_query.filter(sensors::Column::IsActive.eq(true));
+    }
 
     if let Some(ref types) = query.sensor_types {
         let type_list: Vec<String> = types.split(',').map(|s| s.trim().to_string()).collect();
@@ -322,8 +1035,22 @@ pub async fn get_station_aggregates(
         }
     }
 
+    // Name and type filters intersect
+    if let Some(name_filter) = query
+        .sensor_names
+        .as_deref()
+        .and_then(sensor_names_condition)
+    {
+        sensor_query = sensor_query.filter(name_filter);
+    }
+
     // Get matching sensors (needed for cache freshness check)
-    let sensors_list = sensor_query.all(&state.db).await?;
+    let sensors_list = sensor_query
+        .order_by_asc(sensors::Column::Name)
+        .order_by_asc(sensors::Column::Id)
+        .all(&state.read_db)
+        .await?;
+    check_sensor_limit(sensors_list.len(), state.config.max_sensors_per_request)?;
     let sensor_ids: Vec<Uuid> = sensors_list.iter().map(|s| s.id).collect();
 
     // Build cache key
@@ -332,213 +1059,122 @@ pub async fn get_station_aggregates(
         &[
             &station.id.to_string(),
             &resolution,
-            &query.start.to_rfc3339(),
-            &query.end.to_rfc3339(),
+            &query_start.to_rfc3339(),
+            &query_end.to_rfc3339(),
             query.sensor_types.as_deref().unwrap_or(""),
+            query.sensor_names.as_deref().unwrap_or(""),
+            if query.include_inactive {
+                "inactive"
+            } else {
+                ""
+            },
             &format,
+            query.time_format.as_str(),
+            if query.round { "round" } else { "" },
+            &stats.as_key(),
+            &min_count.as_key(),
+            if query.include_thresholds {
+                "thresholds"
+            } else {
+                ""
+            },
+            if query.fill_gaps { "fill_gaps" } else { "" },
         ],
     );
 
     // Check cache with freshness validation (JSON only)
     // Aggregates always have end time, so skip freshness check (historical data won't change)
     if format == "json" {
-        if let Some(cached) = cache::get_cached(&state, &cache_key, &sensor_ids, Some(query.end)).await {
+        if let Some(cached) =
+            cache::get_cached(&state, &cache_key, &sensor_ids, Some(query_end)).await
+        {
             return cache::json_response((*cached).to_vec(), true);
         }
     }
 
-    // For bulk formats, acquire semaphore to limit concurrent requests
-    let _permit = if format == "csv" || format == "ndjson" {
-        match BULK_SEMAPHORE.clone().try_acquire_owned() {
-            Ok(permit) => Some(permit),
-            Err(_) => {
-                tracing::warn!(
-                    format = %format,
-                    status = StatusCode::SERVICE_UNAVAILABLE.as_u16(),
-                    "bulk_request_rejected"
-                );
-                return Err(AppError::ServiceUnavailable(
-                    "Too many concurrent bulk requests. Please try again later.".to_string(),
-                ));
-            }
-        }
-    } else {
-        None
-    };
+    // Bulk formats (CSV/NDJSON) share one concurrency limit across endpoints;
+    // small exports (sensors x buckets) skip it
+    let buckets = duration.num_seconds() / source.bucket_seconds + 1;
+    let estimate = u64::try_from(buckets)
+        .unwrap_or(0)
+        .saturating_mul(sensor_ids.len() as u64);
+    let _permit = state.bulk_limiter.acquire_sized(&format, estimate)?;
 
     if sensor_ids.is_empty() {
         return Ok(Json(AggregatesResponse {
             zone: zone_ref,
             station: station_ref,
             resolution: resolution.clone(),
-            start: query.start,
-            end: query.end,
-            times: vec![],
+            start: query_start,
+            end: query_end,
+            times: TimeArray::new(vec![], query.time_format),
             sensors: vec![],
+            errors: vec![],
         })
         .into_response());
     }
 
-    // Build the sensor_ids array for SQL
-    let sensor_ids_str = sensor_ids
-        .iter()
-        .map(|id| format!("'{id}'"))
-        .collect::<Vec<_>>()
-        .join(",");
-
-    // Determine bucket interval for on-the-fly aggregation fallback
-    let bucket_interval = match resolution.as_str() {
-        "hourly" => "1 hour",
-        "daily" => "1 day",
-        "weekly" => "1 week",
-        "monthly" => "1 month",
-        _ => "1 hour",
-    };
-
-    // Query the continuous aggregate view first
-    let sql = format!(
-        r"
-        SELECT
-            bucket,
-            sensor_id,
-            avg_value,
-            min_value,
-            max_value,
-            count
-        FROM {view_name}
-        WHERE sensor_id IN ({sensor_ids_str})
-          AND bucket >= $1
-          AND bucket <= $2
-        ORDER BY bucket ASC, sensor_id ASC
-        "
-    );
-
-    let mut results: Vec<AggregateRow> = state
-        .db
-        .query_all(Statement::from_sql_and_values(
-            sea_orm::DatabaseBackend::Postgres,
-            &sql,
-            vec![query.start.into(), query.end.into()],
-        ))
-        .await?
-        .into_iter()
-        .filter_map(|row| AggregateRow::from_query_result(&row, "").ok())
-        .collect();
-
-    // Fallback to on-the-fly aggregation if continuous aggregate has no data
-    // This handles cases where the materialized view hasn't been refreshed yet
-    if results.is_empty() {
-        tracing::info!(
-            resolution = %resolution,
-            start = %query.start,
-            end = %query.end,
-            "continuous_aggregate_empty_fallback_to_raw"
-        );
+    // NDJSON exports keep full precision
+    let round = query.round && format != "ndjson";
 
-        let fallback_sql = format!(
-            r"
-            SELECT
-                time_bucket('{bucket_interval}', time) AS bucket,
-                sensor_id,
-                AVG(value) AS avg_value,
-                MIN(value) AS min_value,
-                MAX(value) AS max_value,
-                COUNT(*) AS count
-            FROM readings
-            WHERE sensor_id IN ({sensor_ids_str})
-              AND time >= $1
-              AND time <= $2
-            GROUP BY time_bucket('{bucket_interval}', time), sensor_id
-            ORDER BY bucket ASC, sensor_id ASC
-            "
-        );
+    let (times, mut sensor_data, failed_sensors) = aggregate_series(
+        &state,
+        &sensors_list,
+        source,
+        query_start,
+        query_end,
+        stats,
+        min_count,
+        query.fill_gaps,
+        query.strict,
+        round,
+        query.time_format,
+    )
+    .await?;
 
-        results = state
-            .db
-            .query_all(Statement::from_sql_and_values(
-                sea_orm::DatabaseBackend::Postgres,
-                &fallback_sql,
-                vec![query.start.into(), query.end.into()],
-            ))
-            .await?
-            .into_iter()
-            .filter_map(|row| AggregateRow::from_query_result(&row, "").ok())
-            .collect();
-    }
-
-    // Build time index and sensor value maps
-    let mut time_set: BTreeMap<DateTime<Utc>, usize> = BTreeMap::new();
-    let mut sensor_aggs: HashMap<Uuid, HashMap<DateTime<Utc>, (Option<f64>, Option<f64>, Option<f64>, i64)>> =
-        HashMap::new();
-
-    for row in results {
-        let time = row.bucket;
-        time_set.entry(time).or_insert(0);
-        sensor_aggs
-            .entry(row.sensor_id)
-            .or_default()
-            .insert(time, (row.avg_value, row.min_value, row.max_value, row.count));
+    if query.include_thresholds && format == "json" {
+        let mut limits = sensor_limits(&state, &sensors_list).await?;
+        for sensor in &mut sensor_data {
+            sensor.thresholds = limits.remove(&sensor.id);
+        }
     }
 
-    // Build sorted times array
-    let times: Vec<DateTime<Utc>> = time_set.keys().copied().collect();
-
-    // Build sensor aggregate data
-    let sensor_data: Vec<SensorAggregateData> = sensors_list
-        .iter()
-        .map(|sensor| {
-            let aggs_map = sensor_aggs.get(&sensor.id);
-
-            let mut avg = Vec::with_capacity(times.len());
-            let mut min = Vec::with_capacity(times.len());
-            let mut max = Vec::with_capacity(times.len());
-            let mut count = Vec::with_capacity(times.len());
-
-            for t in &times {
-                if let Some(aggs) = aggs_map.and_then(|m| m.get(t)) {
-                    avg.push(aggs.0);
-                    min.push(aggs.1);
-                    max.push(aggs.2);
-                    count.push(aggs.3);
-                } else {
-                    avg.push(None);
-                    min.push(None);
-                    max.push(None);
-                    count.push(0);
-                }
-            }
-
-            SensorAggregateData {
-                id: sensor.id,
-                name: sensor.name.clone(),
-                sensor_type: sensor.sensor_type.clone(),
-                units: sensor.display_units.clone(),
-                avg,
-                min,
-                max,
-                count,
-            }
-        })
-        .collect();
-
     // Get max time for cache freshness tracking
     let max_time = times.last().copied();
 
     // Return appropriate format
     match format.as_str() {
-        "csv" => build_csv_response(&resolution, &times, &sensor_data),
-        "ndjson" => build_ndjson_response(&times, &sensor_data),
+        "csv" => compress_export(
+            build_csv_response(&resolution, &times, &sensor_data)?,
+            query.compress,
+            &export_filename(&station.name, &resolution, &format),
+        ),
+        "ndjson" => compress_export(
+            build_ndjson_response(&times, &sensor_data)?,
+            query.compress,
+            &export_filename(&station.name, &resolution, &format),
+        ),
         _ => {
             let response = AggregatesResponse {
                 zone: zone_ref,
                 station: station_ref,
                 resolution,
-                start: query.start,
-                end: query.end,
-                times,
+                start: query_start,
+                end: query_end,
+                times: TimeArray::new(times, query.time_format),
                 sensors: sensor_data,
+                errors: failed_sensors,
             };
-            cache::cache_and_respond(&state, cache_key, &response, max_time).await
+
+            // Don't cache partial results
+            if !response.errors.is_empty() {
+                let json_bytes =
+                    serde_json::to_vec(&response).map_err(|e| AppError::Internal(e.to_string()))?;
+                return cache::json_response(json_bytes, false);
+            }
+
+            let ttl = std::time::Duration::from_secs(state.config.cache_aggregates_ttl_seconds);
+            cache::cache_and_respond(&state, cache_key, &response, max_time, true, Some(ttl)).await
         }
     }
 }
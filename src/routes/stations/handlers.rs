@@ -1,16 +1,29 @@
 use axum::{
     extract::{Path, Query, State},
+    response::Response,
     Json,
 };
 use chrono::{DateTime, Utc};
-use sea_orm::{ColumnTrait, ConnectionTrait, EntityTrait, FromQueryResult, QueryFilter, QueryOrder, Statement};
+use sea_orm::{
+    sea_query::{Expr, Func}, ColumnTrait, Condition, ConnectionTrait, EntityTrait, FromQueryResult,
+    QueryFilter, QueryOrder, Statement,
+};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use uuid::Uuid;
 
+use crate::common::envelope::list_response;
 use crate::common::AppState;
 use crate::entity::{sensors, stations, zones};
-use crate::error::AppResult;
-use crate::routes::resolve_station;
+use crate::error::{AppError, AppResult};
+use crate::routes::{cache, resolve_station};
+
+use super::types::{
+    SensorResponse, SensorTypeGroup, StationBatchRequest, StationBatchResult,
+    StationDetailResponse, StationResponse, StationSensorsQuery, StationsQuery, ZoneRef,
+};
 
-use super::types::{SensorResponse, StationDetailResponse, StationResponse, StationsQuery, ZoneRef};
+/// Maximum number of stations per batch request
+pub const MAX_BATCH_STATIONS: usize = 100;
 
 #[derive(Debug, FromQueryResult)]
 struct DataRangeRow {
@@ -19,20 +32,104 @@ struct DataRangeRow {
     count: i64,
 }
 
+#[derive(Debug, FromQueryResult)]
+struct StationDataRangeRow {
+    station_id: Uuid,
+    min_time: Option<DateTime<Utc>>,
+    max_time: Option<DateTime<Utc>>,
+    count: i64,
+}
+
+fn sensor_response(s: sensors::Model) -> SensorResponse {
+    SensorResponse {
+        id: s.id,
+        name: s.name,
+        sensor_type: s.sensor_type,
+        display_units: s.display_units,
+        sample_interval_sec: s.sample_interval_sec,
+        is_active: s.is_active,
+        alarming_paused: s.alarming_paused,
+        has_active_alarm: None,
+        max_active_severity: None,
+    }
+}
+
+#[derive(Debug, FromQueryResult)]
+struct ActiveAlarmRow {
+    sensor_id: Uuid,
+    max_severity: i16,
+}
+
+/// Highest active alarm severity per sensor of a station, in one grouped query.
+///
+/// Sensors without an active alarm are absent from the map.
+async fn active_alarm_severities(
+    state: &AppState,
+    station_id: Uuid,
+) -> AppResult<HashMap<Uuid, i16>> {
+    let sql = "SELECT al.sensor_id, MAX(a.severity) AS max_severity
+               FROM alarm_locations al
+               JOIN alarms a ON a.id = al.alarm_id
+               JOIN sensors s ON s.id = al.sensor_id
+               WHERE a.status AND s.station_id = $1
+               GROUP BY al.sensor_id";
+
+    Ok(state
+        .read_db
+        .query_all(Statement::from_sql_and_values(
+            sea_orm::DatabaseBackend::Postgres,
+            sql,
+            [station_id.into()],
+        ))
+        .await?
+        .iter()
+        .map(|row| ActiveAlarmRow::from_query_result(row, ""))
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
+        .map(|r| (r.sensor_id, r.max_severity))
+        .collect())
+}
+
+/// Build sensor responses, filling the active alarm fields when `with_alarms` is set
+async fn station_sensor_responses(
+    state: &AppState,
+    station_id: Uuid,
+    sensors_list: Vec<sensors::Model>,
+    with_alarms: bool,
+) -> AppResult<Vec<SensorResponse>> {
+    let severities = if with_alarms {
+        Some(active_alarm_severities(state, station_id).await?)
+    } else {
+        None
+    };
+
+    Ok(sensors_list
+        .into_iter()
+        .map(|s| {
+            let mut response = sensor_response(s);
+            if let Some(severities) = &severities {
+                response.max_active_severity = severities.get(&response.id).copied();
+                response.has_active_alarm = Some(response.max_active_severity.is_some());
+            }
+            response
+        })
+        .collect())
+}
+
 /// List all stations
 #[utoipa::path(
     get,
-    path = "/api/stations",
+    path = "/api/v1/stations",
     params(StationsQuery),
     responses(
-        (status = 200, description = "Stations retrieved successfully", body = Vec<StationResponse>),
+        (status = 200, description = "Stations retrieved successfully. Wrapped in `{data, meta}` with `envelope=true`", body = Vec<StationResponse>),
     ),
     tag = "stations"
 )]
 pub async fn list_stations(
     State(state): State<AppState>,
     Query(query): Query<StationsQuery>,
-) -> AppResult<Json<Vec<StationResponse>>> {
+) -> AppResult<Response> {
     let mut db_query = stations::Entity::find();
 
     if let Some(zone_id) = query.zone_id {
@@ -41,7 +138,7 @@ pub async fn list_stations(
 
     let stations_list = db_query
         .order_by_asc(stations::Column::Name)
-        .all(&state.db)
+        .all(&state.read_db)
         .await?;
 
     let response: Vec<StationResponse> = stations_list
@@ -56,13 +153,13 @@ pub async fn list_stations(
         })
         .collect();
 
-    Ok(Json(response))
+    Ok(list_response(response, query.envelope))
 }
 
 /// Get a specific station by ID or name
 #[utoipa::path(
     get,
-    path = "/api/stations/{station_id}",
+    path = "/api/v1/stations/{station_id}",
     params(
         ("station_id" = String, Path, description = "Station UUID or name"),
     ),
@@ -76,12 +173,12 @@ pub async fn get_station(
     State(state): State<AppState>,
     Path(station_id): Path<String>,
 ) -> AppResult<Json<StationDetailResponse>> {
-    let station = resolve_station(&state.db, &station_id).await?;
+    let station = resolve_station(&state.read_db, &station_id).await?;
 
     // Fetch zone info if available
     let zone = if let Some(zone_id) = station.zone_id {
         zones::Entity::find_by_id(zone_id)
-            .one(&state.db)
+            .one(&state.read_db)
             .await?
             .map(|z| ZoneRef {
                 id: z.id,
@@ -96,20 +193,10 @@ pub async fn get_station(
         .filter(sensors::Column::StationId.eq(station.id))
         .filter(sensors::Column::IsActive.eq(true))
         .order_by_asc(sensors::Column::Name)
-        .all(&state.db)
+        .all(&state.read_db)
         .await?;
 
-    let sensors: Vec<SensorResponse> = sensors_list
-        .into_iter()
-        .map(|s| SensorResponse {
-            id: s.id,
-            name: s.name,
-            sensor_type: s.sensor_type,
-            display_units: s.display_units,
-            sample_interval_sec: s.sample_interval_sec,
-            is_active: s.is_active,
-        })
-        .collect();
+    let sensors: Vec<SensorResponse> = sensors_list.into_iter().map(sensor_response).collect();
 
     // Get data time range and count for this station's sensors
     let sql = format!(
@@ -121,10 +208,11 @@ pub async fn get_station(
     );
 
     let data_range = state
-        .db
+        .read_db
         .query_one(Statement::from_string(sea_orm::DatabaseBackend::Postgres, sql))
         .await?
-        .and_then(|row| DataRangeRow::from_query_result(&row, "").ok());
+        .map(|row| DataRangeRow::from_query_result(&row, ""))
+        .transpose()?;
 
     let (data_start, data_end, reading_count) = data_range
         .map(|r| (r.min_time, r.max_time, r.count))
@@ -147,12 +235,13 @@ pub async fn get_station(
 /// List sensors for a station
 #[utoipa::path(
     get,
-    path = "/api/stations/{station_id}/sensors",
+    path = "/api/v1/stations/{station_id}/sensors",
     params(
         ("station_id" = String, Path, description = "Station UUID or name"),
+        StationSensorsQuery,
     ),
     responses(
-        (status = 200, description = "Sensors retrieved successfully", body = Vec<SensorResponse>),
+        (status = 200, description = "Sensors retrieved successfully. Wrapped in `{data, meta}` with `envelope=true`", body = Vec<SensorResponse>),
         (status = 404, description = "Station not found"),
     ),
     tag = "stations"
@@ -160,28 +249,240 @@ pub async fn get_station(
 pub async fn list_station_sensors(
     State(state): State<AppState>,
     Path(station_id): Path<String>,
-) -> AppResult<Json<Vec<SensorResponse>>> {
-    let station = resolve_station(&state.db, &station_id).await?;
+    Query(query): Query<StationSensorsQuery>,
+) -> AppResult<Response> {
+    let station = resolve_station(&state.read_db, &station_id).await?;
+
+    let sensors_list = sensors::Entity::find()
+        .filter(sensors::Column::StationId.eq(station.id))
+        .filter(sensors::Column::IsActive.eq(true))
+        .order_by_asc(sensors::Column::Name)
+        .all(&state.read_db)
+        .await?;
+
+    let response =
+        station_sensor_responses(&state, station.id, sensors_list, query.with_alarms).await?;
+
+    Ok(list_response(response, query.envelope))
+}
+
+/// List a station's sensors grouped by type
+///
+/// Returns an object keyed by sensor type, with types in alphabetical order
+/// and each group's sensors ordered by name, matching how the dashboard
+/// charts are organized. Cached for `CACHE_LATEST_TTL_SECONDS`.
+#[utoipa::path(
+    get,
+    path = "/api/v1/stations/{station_id}/sensors/grouped",
+    params(
+        ("station_id" = String, Path, description = "Station UUID or name"),
+        StationSensorsQuery,
+    ),
+    responses(
+        (status = 200, description = "Active sensors keyed by sensor type", body = BTreeMap<String, SensorTypeGroup>),
+        (status = 404, description = "Station not found"),
+    ),
+    tag = "stations"
+)]
+pub async fn list_station_sensors_grouped(
+    State(state): State<AppState>,
+    Path(station_id): Path<String>,
+    Query(query): Query<StationSensorsQuery>,
+) -> AppResult<Response> {
+    let station = resolve_station(&state.read_db, &station_id).await?;
+
+    let cache_key = cache::cache_key(
+        "sensors_grouped",
+        &[&station.id.to_string(), &query.with_alarms.to_string()],
+    );
+    if let Some(cached) = cache::get_cached(&state, &cache_key, &[], None).await {
+        return cache::json_response((*cached).to_vec(), true);
+    }
 
     let sensors_list = sensors::Entity::find()
         .filter(sensors::Column::StationId.eq(station.id))
         .filter(sensors::Column::IsActive.eq(true))
         .order_by_asc(sensors::Column::Name)
-        .all(&state.db)
+        .all(&state.read_db)
         .await?;
 
-    let response: Vec<SensorResponse> = sensors_list
+    let response = group_by_type(
+        station_sensor_responses(&state, station.id, sensors_list, query.with_alarms).await?,
+    );
+
+    let ttl = std::time::Duration::from_secs(state.config.cache_latest_ttl_seconds);
+    cache::cache_and_respond(&state, cache_key, &response, None, false, Some(ttl)).await
+}
+
+/// Group sensors by type, keeping their order within each group
+fn group_by_type(
+    sensors: impl IntoIterator<Item = SensorResponse>,
+) -> BTreeMap<String, SensorTypeGroup> {
+    let mut groups: BTreeMap<String, Vec<SensorResponse>> = BTreeMap::new();
+    for sensor in sensors {
+        groups.entry(sensor.sensor_type.clone()).or_default().push(sensor);
+    }
+
+    groups
         .into_iter()
-        .map(|s| SensorResponse {
-            id: s.id,
-            name: s.name,
-            sensor_type: s.sensor_type,
-            display_units: s.display_units,
-            sample_interval_sec: s.sample_interval_sec,
-            is_active: s.is_active,
+        .map(|(sensor_type, sensors)| {
+            let units: BTreeSet<String> =
+                sensors.iter().filter_map(|s| s.display_units.clone()).collect();
+            let group = SensorTypeGroup {
+                count: sensors.len(),
+                units: units.into_iter().collect(),
+                sensors,
+            };
+            (sensor_type, group)
+        })
+        .collect()
+}
+
+/// Get several stations' details in one call
+///
+/// Accepts up to 100 station UUIDs or names and returns one result per
+/// requested identifier, in request order. Unresolved identifiers come back
+/// with `found: false` instead of failing the whole batch. Zones, sensors and
+/// data ranges are fetched with one query each for all stations.
+#[utoipa::path(
+    post,
+    path = "/api/v1/stations/batch",
+    request_body = StationBatchRequest,
+    responses(
+        (status = 200, description = "Station details retrieved", body = Vec<StationBatchResult>),
+        (status = 400, description = "Empty or oversized batch"),
+    ),
+    tag = "stations"
+)]
+pub async fn get_stations_batch(
+    State(state): State<AppState>,
+    Json(request): Json<StationBatchRequest>,
+) -> AppResult<Json<Vec<StationBatchResult>>> {
+    if request.station_ids.is_empty() {
+        return Err(AppError::BadRequest("station_ids must not be empty".to_string()));
+    }
+    if request.station_ids.len() > MAX_BATCH_STATIONS {
+        return Err(AppError::BadRequest(format!(
+            "At most {MAX_BATCH_STATIONS} stations per batch"
+        )));
+    }
+
+    // UUIDs match ids only and anything else names, as in `resolve_station`
+    let mut uuids: Vec<Uuid> = Vec::new();
+    let mut names: Vec<String> = Vec::new();
+    for id in &request.station_ids {
+        match id.parse::<Uuid>() {
+            Ok(uuid) => uuids.push(uuid),
+            Err(_) => names.push(id.to_lowercase()),
+        }
+    }
+
+    let mut condition = Condition::any();
+    if !uuids.is_empty() {
+        condition = condition.add(stations::Column::Id.is_in(uuids));
+    }
+    if !names.is_empty() {
+        condition = condition.add(Expr::expr(Func::lower(Expr::col(stations::Column::Name))).is_in(names));
+    }
+    let stations_list = stations::Entity::find()
+        .filter(condition)
+        .all(&state.read_db)
+        .await?;
+
+    let station_ids: Vec<Uuid> = stations_list.iter().map(|s| s.id).collect();
+    let zone_ids: Vec<Uuid> = stations_list.iter().filter_map(|s| s.zone_id).collect();
+
+    let zones_by_id: HashMap<Uuid, ZoneRef> = if zone_ids.is_empty() {
+        HashMap::new()
+    } else {
+        zones::Entity::find()
+            .filter(zones::Column::Id.is_in(zone_ids))
+            .all(&state.read_db)
+            .await?
+            .into_iter()
+            .map(|z| (z.id, ZoneRef { id: z.id, name: z.name }))
+            .collect()
+    };
+
+    let mut sensors_by_station: HashMap<Uuid, Vec<SensorResponse>> = HashMap::new();
+    let mut ranges: HashMap<Uuid, StationDataRangeRow> = HashMap::new();
+    if !station_ids.is_empty() {
+        let sensors_list = sensors::Entity::find()
+            .filter(sensors::Column::StationId.is_in(station_ids.clone()))
+            .filter(sensors::Column::IsActive.eq(true))
+            .order_by_asc(sensors::Column::Name)
+            .all(&state.read_db)
+            .await?;
+        for sensor in sensors_list {
+            sensors_by_station
+                .entry(sensor.station_id)
+                .or_default()
+                .push(sensor_response(sensor));
+        }
+
+        // Data ranges of all stations in one grouped query
+        let id_list = station_ids
+            .iter()
+            .map(|id| format!("'{id}'"))
+            .collect::<Vec<_>>()
+            .join(",");
+        let sql = format!(
+            "SELECT s.station_id, MIN(r.time) as min_time, MAX(r.time) as max_time, COUNT(*) as count
+             FROM readings r
+             JOIN sensors s ON r.sensor_id = s.id
+             WHERE s.station_id IN ({id_list})
+             GROUP BY s.station_id"
+        );
+        ranges = state
+            .read_db
+            .query_all(Statement::from_string(sea_orm::DatabaseBackend::Postgres, sql))
+            .await?
+            .into_iter()
+            .map(|row| StationDataRangeRow::from_query_result(&row, ""))
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .map(|r| (r.station_id, r))
+            .collect();
+    }
+
+    let by_id: HashMap<Uuid, &stations::Model> = stations_list.iter().map(|s| (s.id, s)).collect();
+    let by_name: HashMap<String, &stations::Model> = stations_list
+        .iter()
+        .map(|s| (s.name.to_lowercase(), s))
+        .collect();
+
+    let results = request
+        .station_ids
+        .into_iter()
+        .map(|requested| {
+            let station = match requested.parse::<Uuid>() {
+                Ok(uuid) => by_id.get(&uuid),
+                Err(_) => by_name.get(&requested.to_lowercase()),
+            };
+            let detail = station.map(|station| {
+                let range = ranges.get(&station.id);
+                StationDetailResponse {
+                    id: station.id,
+                    name: station.name.clone(),
+                    latitude: station.latitude,
+                    longitude: station.longitude,
+                    altitude_m: station.altitude_m,
+                    zone: station.zone_id.and_then(|id| zones_by_id.get(&id).cloned()),
+                    // Shared when a station is requested twice
+                    sensors: sensors_by_station.get(&station.id).cloned().unwrap_or_default(),
+                    data_start: range.and_then(|r| r.min_time),
+                    data_end: range.and_then(|r| r.max_time),
+                    reading_count: range.map_or(0, |r| r.count),
+                }
+            });
+            StationBatchResult {
+                id: requested,
+                found: detail.is_some(),
+                station: detail,
+            }
         })
         .collect();
 
-    Ok(Json(response))
+    Ok(Json(results))
 }
 
@@ -0,0 +1,143 @@
+use axum::{
+    extract::{Path, State},
+    response::Response,
+};
+use chrono::{DateTime, Utc};
+use sea_orm::{
+    ColumnTrait, ConnectionTrait, EntityTrait, FromQueryResult, QueryFilter, QueryOrder, Statement,
+};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::time::Duration;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::common::AppState;
+use crate::entity::sensors;
+use crate::error::AppResult;
+use crate::routes::{cache, resolve_station};
+
+use super::types::StationRef;
+
+#[derive(Debug, FromQueryResult)]
+struct LatestReadingRow {
+    sensor_id: Uuid,
+    time: DateTime<Utc>,
+    value: f64,
+}
+
+/// Most recent reading of one sensor
+#[derive(Debug, Serialize, ToSchema)]
+pub struct LatestSensorReading {
+    pub id: Uuid,
+    pub name: String,
+    #[serde(rename = "type")]
+    pub sensor_type: String,
+    pub units: Option<String>,
+    /// Time of the newest reading (null if the sensor has no data)
+    pub time: Option<DateTime<Utc>>,
+    pub value: Option<f64>,
+}
+
+/// Current conditions at a station
+#[derive(Debug, Serialize, ToSchema)]
+pub struct StationLatestResponse {
+    pub station: StationRef,
+    /// Newest reading time across all sensors
+    pub time: Option<DateTime<Utc>>,
+    /// Active sensors, ordered by name
+    pub sensors: Vec<LatestSensorReading>,
+}
+
+/// Get the latest reading of every active sensor of a station
+///
+/// A compact "current conditions" snapshot, fetched with a single
+/// `DISTINCT ON` query. Cached for `CACHE_LATEST_TTL_SECONDS` and refreshed
+/// as soon as newer readings arrive.
+#[utoipa::path(
+    get,
+    path = "/api/v1/stations/{station_id}/latest",
+    params(
+        ("station_id" = String, Path, description = "Station UUID or name"),
+    ),
+    responses(
+        (status = 200, description = "Latest readings retrieved successfully", body = StationLatestResponse),
+        (status = 404, description = "Station not found"),
+    ),
+    tag = "stations"
+)]
+pub async fn get_station_latest(
+    State(state): State<AppState>,
+    Path(station_id): Path<String>,
+) -> AppResult<Response> {
+    let station = resolve_station(&state.read_db, &station_id).await?;
+
+    let sensors_list = sensors::Entity::find()
+        .filter(sensors::Column::StationId.eq(station.id))
+        .filter(sensors::Column::IsActive.eq(true))
+        .order_by_asc(sensors::Column::Name)
+        .order_by_asc(sensors::Column::Id)
+        .all(&state.read_db)
+        .await?;
+    let sensor_ids: Vec<Uuid> = sensors_list.iter().map(|s| s.id).collect();
+
+    let cache_key = cache::cache_key("latest", &[&station.id.to_string()]);
+
+    // Unbounded entry: invalidated as soon as a newer reading arrives
+    if let Some(cached) = cache::get_cached(&state, &cache_key, &sensor_ids, None).await {
+        return cache::json_response((*cached).to_vec(), true);
+    }
+
+    let latest: HashMap<Uuid, LatestReadingRow> = if sensor_ids.is_empty() {
+        HashMap::new()
+    } else {
+        let ids = sensor_ids
+            .iter()
+            .map(|id| format!("'{id}'"))
+            .collect::<Vec<_>>()
+            .join(",");
+        let sql = format!(
+            "SELECT DISTINCT ON (sensor_id) sensor_id, time, value
+             FROM readings
+             WHERE sensor_id IN ({ids})
+             ORDER BY sensor_id, time DESC"
+        );
+
+        state
+            .read_db
+            .query_all(Statement::from_string(sea_orm::DatabaseBackend::Postgres, sql))
+            .await?
+            .into_iter()
+            .map(|row| LatestReadingRow::from_query_result(&row, ""))
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .map(|r| (r.sensor_id, r))
+            .collect()
+    };
+
+    let max_time = latest.values().map(|r| r.time).max();
+    let response = StationLatestResponse {
+        station: StationRef {
+            id: station.id,
+            name: station.name,
+        },
+        time: max_time,
+        sensors: sensors_list
+            .into_iter()
+            .map(|s| {
+                let reading = latest.get(&s.id);
+                LatestSensorReading {
+                    id: s.id,
+                    name: s.name,
+                    sensor_type: s.sensor_type,
+                    units: s.display_units,
+                    time: reading.map(|r| r.time),
+                    value: reading.map(|r| r.value),
+                }
+            })
+            .collect(),
+    };
+
+    let ttl = Duration::from_secs(state.config.cache_latest_ttl_seconds);
+    cache::cache_and_respond(&state, cache_key, &response, max_time, false, Some(ttl)).await
+}
@@ -1,15 +1,47 @@
 mod aggregates;
 mod handlers;
+mod latest;
+mod multiscale;
+mod overview;
 mod readings;
+mod thresholds;
 mod types;
 
-pub use aggregates::{get_station_aggregates, AggregatesResponse, SensorAggregateData};
-pub use handlers::{get_station, list_station_sensors, list_stations};
-pub use readings::StationReadingsQuery;
-pub use readings::{get_station_readings, ReadingsResponse, SensorData};
-pub use types::{SensorResponse, StationDetailResponse, StationRef, StationResponse, StationsQuery, ZoneRef};
+pub use aggregates::{
+    fallback_query, gapfill_query, get_station_aggregates, view_query, AggregateStats,
+    AggregatesResponse, MinCount, SensorAggregateData,
+};
+pub use handlers::{
+    get_station, get_stations_batch, list_station_sensors, list_station_sensors_grouped,
+    list_stations, MAX_BATCH_STATIONS,
+};
+pub use latest::{get_station_latest, LatestSensorReading, StationLatestResponse};
+pub use multiscale::{
+    get_station_readings_multiscale, MultiscaleDetail, MultiscaleOverview, MultiscaleQuery,
+    MultiscaleResponse,
+};
+pub use overview::{get_stations_overview, OverviewOrder, StationOverview, StationOverviewQuery};
+pub use readings::{
+    build_csv_response, build_ndjson_response, csv_header_meta, estimated_json_bytes,
+    estimated_points, json_chunks, StationReadingsQuery,
+};
+pub use readings::{
+    get_station_readings, ReadingsEstimate, ReadingsResponse, SensorColumns, SensorData, SensorMeta,
+};
+pub use thresholds::{LimitSource, SensorLimits};
+pub use types::{
+    SensorResponse, SensorTypeGroup, StationBatchRequest, StationBatchResult,
+    StationDetailResponse, StationRef, StationResponse, StationSensorsQuery, StationsQuery,
+    ZoneRef,
+};
 
 // Re-export utoipa path structs for OpenAPI documentation
 pub use aggregates::__path_get_station_aggregates;
-pub use handlers::{__path_get_station, __path_list_station_sensors, __path_list_stations};
+pub use handlers::{
+    __path_get_station, __path_get_stations_batch, __path_list_station_sensors,
+    __path_list_station_sensors_grouped, __path_list_stations,
+};
+pub use latest::__path_get_station_latest;
+pub use multiscale::__path_get_station_readings_multiscale;
+pub use overview::__path_get_stations_overview;
 pub use readings::__path_get_station_readings;
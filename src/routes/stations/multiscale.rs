@@ -0,0 +1,284 @@
+use axum::{
+    extract::{Path, Query, State},
+    response::Response,
+};
+use chrono::{DateTime, Duration, Utc};
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, QueryOrder};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::common::time::{self, TimeArray, TimeFormat};
+use crate::common::AppState;
+use crate::entity::{sensors, zones};
+use crate::error::{AppError, AppResult};
+use crate::routes::{cache, resolve_station, sensor_names_condition};
+
+use super::aggregates::{
+    aggregate_series, check_resolution_enabled, AggregateSource, AggregateStats, MinCount,
+    SensorAggregateData,
+};
+use super::readings::{raw_series, SensorData};
+use super::types::{StationRef, ZoneRef};
+
+/// Maximum overall range (one year of daily buckets)
+const MAX_OVERVIEW_DAYS: i64 = 365;
+
+/// Maximum detail window; matches the raw threshold of `time::resolution_hint`
+const MAX_DETAIL_DAYS: i64 = 14;
+
+/// Resolution of the overview series
+const OVERVIEW_RESOLUTION: &str = "daily";
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct MultiscaleQuery {
+    /// Start of the overview range (ISO 8601 or Unix epoch seconds)
+    #[serde(deserialize_with = "time::deserialize_timestamp")]
+    pub start: DateTime<Utc>,
+    /// End of the overview range (ISO 8601 or Unix epoch seconds)
+    #[serde(deserialize_with = "time::deserialize_timestamp")]
+    pub end: DateTime<Utc>,
+    /// Start of the raw detail window (ISO 8601 or Unix epoch seconds), within
+    /// the overview range
+    #[serde(deserialize_with = "time::deserialize_timestamp")]
+    pub detail_start: DateTime<Utc>,
+    /// End of the raw detail window (ISO 8601 or Unix epoch seconds), within
+    /// the overview range
+    #[serde(deserialize_with = "time::deserialize_timestamp")]
+    pub detail_end: DateTime<Utc>,
+    /// Filter by sensor types (comma-separated)
+    pub sensor_types: Option<String>,
+    /// Filter by sensor names (comma-separated, case-insensitive)
+    pub sensor_names: Option<String>,
+    /// Serialize timestamp arrays as `iso` strings (default) or `epoch` seconds
+    #[serde(default)]
+    pub time_format: TimeFormat,
+}
+
+/// Daily aggregates over the full range
+#[derive(Debug, Serialize, ToSchema)]
+pub struct MultiscaleOverview {
+    /// Always `daily`
+    pub resolution: String,
+    /// Bucket timestamps
+    #[schema(value_type = Vec<String>)]
+    pub times: TimeArray<DateTime<Utc>>,
+    pub sensors: Vec<SensorAggregateData>,
+    /// Sensors whose on-the-fly aggregation failed (returned with null arrays)
+    pub errors: Vec<Uuid>,
+}
+
+/// Raw readings within the detail window
+#[derive(Debug, Serialize, ToSchema)]
+pub struct MultiscaleDetail {
+    /// Reading timestamps
+    #[schema(value_type = Vec<String>)]
+    pub times: TimeArray<DateTime<Utc>>,
+    pub sensors: Vec<SensorData>,
+}
+
+/// Overview and detail series for one station in a single payload.
+///
+/// `overview` has the same shape as the aggregates response body and
+/// `detail` the same shape as the readings response body; sensor columns are
+/// in the same order in both.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct MultiscaleResponse {
+    /// Zone this data belongs to
+    pub zone: Option<ZoneRef>,
+    /// Station this data belongs to
+    pub station: StationRef,
+    /// Start of the overview range
+    pub start: DateTime<Utc>,
+    /// End of the overview range
+    pub end: DateTime<Utc>,
+    /// Start of the detail window
+    pub detail_start: DateTime<Utc>,
+    /// End of the detail window
+    pub detail_end: DateTime<Utc>,
+    pub overview: MultiscaleOverview,
+    pub detail: MultiscaleDetail,
+}
+
+/// Check the overview range and that the detail window lies inside it.
+fn validate_windows(query: &MultiscaleQuery) -> AppResult<()> {
+    time::validate_range(query.start, query.end)?;
+    if query.end - query.start > Duration::days(MAX_OVERVIEW_DAYS) {
+        return Err(AppError::BadRequest(format!(
+            "time range exceeds maximum of {MAX_OVERVIEW_DAYS} days"
+        )));
+    }
+    if query.detail_end <= query.detail_start {
+        return Err(AppError::BadRequest(
+            "detail_end must be after detail_start".to_string(),
+        ));
+    }
+    if query.detail_start < query.start || query.detail_end > query.end {
+        return Err(AppError::BadRequest(
+            "detail window must lie within start and end".to_string(),
+        ));
+    }
+    if query.detail_end - query.detail_start > Duration::days(MAX_DETAIL_DAYS) {
+        return Err(AppError::BadRequest(format!(
+            "detail window exceeds maximum of {MAX_DETAIL_DAYS} days for raw readings"
+        )));
+    }
+    Ok(())
+}
+
+/// Get overview and detail readings for a station
+///
+/// Returns daily aggregates over `[start, end]` together with raw readings
+/// within `[detail_start, detail_end]`, for overview+detail charts. The
+/// detail window must lie within the overall range and span at most 14 days.
+/// JSON only.
+#[utoipa::path(
+    get,
+    path = "/api/v1/stations/{station_id}/readings/multiscale",
+    params(
+        ("station_id" = String, Path, description = "Station UUID or name"),
+        MultiscaleQuery
+    ),
+    responses(
+        (status = 200, description = "Overview and detail retrieved successfully", body = MultiscaleResponse),
+        (status = 400, description = "Invalid or inconsistent time windows, or daily resolution disabled"),
+        (status = 404, description = "Station not found"),
+        (status = 503, description = "Aggregation not available on this database"),
+    ),
+    tag = "stations"
+)]
+pub async fn get_station_readings_multiscale(
+    State(state): State<AppState>,
+    Path(station_id): Path<String>,
+    Query(query): Query<MultiscaleQuery>,
+) -> AppResult<Response> {
+    let station = resolve_station(&state.read_db, &station_id).await?;
+
+    validate_windows(&query)?;
+    check_resolution_enabled(&state.config, OVERVIEW_RESOLUTION)?;
+
+    // Fetch zone info if available
+    let zone_ref = if let Some(zone_id) = station.zone_id {
+        zones::Entity::find_by_id(zone_id)
+            .one(&state.read_db)
+            .await?
+            .map(|z| ZoneRef {
+                id: z.id,
+                name: z.name,
+            })
+    } else {
+        None
+    };
+
+    // Build sensor query for this station only
+    let mut sensor_query = sensors::Entity::find()
+        .filter(sensors::Column::IsActive.eq(true))
+        .filter(sensors::Column::StationId.eq(station.id));
+
+    if let Some(ref types) = query.sensor_types {
+        let type_list: Vec<String> = types.split(',').map(|s| s.trim().to_string()).collect();
+        if !type_list.is_empty() {
+            sensor_query = sensor_query.filter(sensors::Column::SensorType.is_in(type_list));
+        }
+    }
+
+    // Name and type filters intersect
+    if let Some(name_filter) = query.sensor_names.as_deref().and_then(sensor_names_condition) {
+        sensor_query = sensor_query.filter(name_filter);
+    }
+
+    let sensors_list = sensor_query
+        .order_by_asc(sensors::Column::Name)
+        .order_by_asc(sensors::Column::Id)
+        .all(&state.read_db)
+        .await?;
+    let sensor_ids: Vec<Uuid> = sensors_list.iter().map(|s| s.id).collect();
+
+    let cache_key = cache::cache_key(
+        "multiscale",
+        &[
+            &station.id.to_string(),
+            &query.start.to_rfc3339(),
+            &query.end.to_rfc3339(),
+            &query.detail_start.to_rfc3339(),
+            &query.detail_end.to_rfc3339(),
+            query.sensor_types.as_deref().unwrap_or(""),
+            query.sensor_names.as_deref().unwrap_or(""),
+            query.time_format.as_str(),
+        ],
+    );
+
+    // Both windows are bounded, so no freshness check is needed
+    if let Some(cached) = cache::get_cached(&state, &cache_key, &sensor_ids, Some(query.end)).await {
+        return cache::json_response((*cached).to_vec(), true);
+    }
+
+    let ((overview_times, overview_sensors, errors), (detail_times, detail_sensors)) =
+        if sensors_list.is_empty() {
+            ((vec![], vec![], vec![]), (vec![], vec![]))
+        } else {
+            let source = AggregateSource::for_resolution(OVERVIEW_RESOLUTION)
+                .expect("daily is a valid resolution");
+            (
+                aggregate_series(
+                    &state,
+                    &sensors_list,
+                    source,
+                    query.start,
+                    query.end,
+                    AggregateStats::default(),
+                    MinCount::default(),
+                    false,
+                    false,
+                    false,
+                    query.time_format,
+                )
+                .await?,
+                raw_series(
+                    &state,
+                    &sensors_list,
+                    Some(query.detail_start),
+                    Some(query.detail_end),
+                    None,
+                    None,
+                    false,
+                    None,
+                )
+                .await?,
+            )
+        };
+
+    // Freshness tracking uses the newest timestamp in either series
+    let max_time = overview_times.last().copied().max(detail_times.last().copied());
+
+    let response = MultiscaleResponse {
+        zone: zone_ref,
+        station: StationRef {
+            id: station.id,
+            name: station.name,
+        },
+        start: query.start,
+        end: query.end,
+        detail_start: query.detail_start,
+        detail_end: query.detail_end,
+        overview: MultiscaleOverview {
+            resolution: OVERVIEW_RESOLUTION.to_string(),
+            times: TimeArray::new(overview_times, query.time_format),
+            sensors: overview_sensors,
+            errors,
+        },
+        detail: MultiscaleDetail {
+            times: TimeArray::new(detail_times, query.time_format),
+            sensors: detail_sensors,
+        },
+    };
+
+    // Don't cache partial results
+    if !response.overview.errors.is_empty() {
+        let json_bytes =
+            serde_json::to_vec(&response).map_err(|e| AppError::Internal(e.to_string()))?;
+        return cache::json_response(json_bytes, false);
+    }
+
+    cache::cache_and_respond(&state, cache_key, &response, max_time, true, None).await
+}
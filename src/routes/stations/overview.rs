@@ -0,0 +1,184 @@
+use axum::{
+    extract::{Query, State},
+    response::Response,
+};
+use chrono::{DateTime, Utc};
+use sea_orm::{ConnectionTrait, EntityTrait, FromQueryResult, QueryOrder, Statement};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::Duration;
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::common::AppState;
+use crate::entity::stations;
+use crate::entity::sync_state::SyncStatus;
+use crate::error::AppResult;
+use crate::routes::cache;
+
+#[derive(Debug, FromQueryResult)]
+struct SensorStatsRow {
+    station_id: Uuid,
+    sensor_count: i64,
+    data_end: Option<DateTime<Utc>>,
+    stale_sensor_count: i64,
+}
+
+#[derive(Debug, FromQueryResult)]
+struct AlarmCountRow {
+    station_id: Uuid,
+    active_alarm_count: i64,
+}
+
+/// Sort order of the station overview
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum OverviewOrder {
+    /// Alphabetical by station name
+    #[default]
+    Name,
+    /// Stalest first: stations without data, then by descending lag
+    Staleness,
+}
+
+impl OverviewOrder {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Name => "name",
+            Self::Staleness => "staleness",
+        }
+    }
+}
+
+/// Query parameters for the station overview
+#[derive(Debug, Default, Deserialize, IntoParams)]
+pub struct StationOverviewQuery {
+    /// `name` (default) or `staleness`
+    #[serde(default)]
+    pub order: OverviewOrder,
+}
+
+/// Status summary of one station
+#[derive(Debug, Serialize, ToSchema)]
+pub struct StationOverview {
+    pub id: Uuid,
+    pub name: String,
+    pub zone_id: Option<Uuid>,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+    /// Number of active sensors
+    pub sensor_count: i64,
+    /// Newest reading time across active sensors, as recorded by the sync
+    pub data_end: Option<DateTime<Utc>>,
+    /// Seconds since `data_end` (null when the station has no data)
+    pub lag_seconds: Option<i64>,
+    /// Active sensors whose sync is failing or whose data is older than
+    /// `SYNC_STALE_AFTER_SECONDS`
+    pub stale_sensor_count: i64,
+    /// Number of active alarms linked to the station
+    pub active_alarm_count: i64,
+}
+
+/// Get every station's data freshness, sensor and alarm counts
+///
+/// Backs fleet-status tables and maps with one call. Computed with one
+/// grouped query over sensors and their sync state and one over active
+/// alarms, and cached for `CACHE_LATEST_TTL_SECONDS`.
+#[utoipa::path(
+    get,
+    path = "/api/v1/stations/overview",
+    params(StationOverviewQuery),
+    responses(
+        (status = 200, description = "Station overview retrieved successfully", body = Vec<StationOverview>),
+    ),
+    tag = "stations"
+)]
+pub async fn get_stations_overview(
+    State(state): State<AppState>,
+    Query(query): Query<StationOverviewQuery>,
+) -> AppResult<Response> {
+    let cache_key = cache::cache_key("overview", &[query.order.as_str()]);
+    if let Some(cached) = cache::get_cached(&state, &cache_key, &[], None).await {
+        return cache::json_response((*cached).to_vec(), true);
+    }
+
+    let stations_list = stations::Entity::find()
+        .order_by_asc(stations::Column::Name)
+        .all(&state.read_db)
+        .await?;
+
+    // Same staleness criteria as `/sensors/problematic`
+    let sensor_sql = "SELECT s.station_id,
+                COUNT(*) AS sensor_count,
+                MAX(ss.last_data_time) AS data_end,
+                COUNT(*) FILTER (
+                    WHERE ss.sync_status = $2
+                       OR ss.last_data_time IS NULL
+                       OR ss.last_data_time < NOW() - $1 * INTERVAL '1 second'
+                ) AS stale_sensor_count
+         FROM sensors s
+         LEFT JOIN sync_state ss ON ss.sensor_id = s.id
+         WHERE s.is_active = true
+         GROUP BY s.station_id";
+    let sensor_stats: HashMap<Uuid, SensorStatsRow> = state
+        .read_db
+        .query_all(Statement::from_sql_and_values(
+            sea_orm::DatabaseBackend::Postgres,
+            sensor_sql,
+            [
+                state.config.sync_stale_after_seconds.into(),
+                SyncStatus::Error.into(),
+            ],
+        ))
+        .await?
+        .into_iter()
+        .map(|row| SensorStatsRow::from_query_result(&row, ""))
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
+        .map(|r| (r.station_id, r))
+        .collect();
+
+    let alarm_sql = "SELECT station_id, COUNT(*) AS active_alarm_count
+         FROM alarms
+         WHERE status = true AND station_id IS NOT NULL
+         GROUP BY station_id";
+    let alarm_counts: HashMap<Uuid, i64> = state
+        .read_db
+        .query_all(Statement::from_string(sea_orm::DatabaseBackend::Postgres, alarm_sql))
+        .await?
+        .into_iter()
+        .map(|row| AlarmCountRow::from_query_result(&row, ""))
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
+        .map(|r| (r.station_id, r.active_alarm_count))
+        .collect();
+
+    let now = Utc::now();
+    let mut response: Vec<StationOverview> = stations_list
+        .into_iter()
+        .map(|s| {
+            let stats = sensor_stats.get(&s.id);
+            let data_end = stats.and_then(|st| st.data_end);
+            StationOverview {
+                id: s.id,
+                zone_id: s.zone_id,
+                latitude: s.latitude,
+                longitude: s.longitude,
+                sensor_count: stats.map_or(0, |st| st.sensor_count),
+                data_end,
+                lag_seconds: data_end.map(|t| (now - t).num_seconds()),
+                stale_sensor_count: stats.map_or(0, |st| st.stale_sensor_count),
+                active_alarm_count: alarm_counts.get(&s.id).copied().unwrap_or(0),
+                name: s.name,
+            }
+        })
+        .collect();
+
+    if query.order == OverviewOrder::Staleness {
+        // Stable sort keeps name order among equally stale stations
+        response.sort_by_key(|s| std::cmp::Reverse(s.lag_seconds.unwrap_or(i64::MAX)));
+    }
+
+    let ttl = Duration::from_secs(state.config.cache_latest_ttl_seconds);
+    cache::cache_and_respond(&state, cache_key, &response, None, false, Some(ttl)).await
+}
@@ -1,27 +1,30 @@
 use axum::{
     extract::{Path, Query, State},
-    http::{
-        header::{self, HeaderMap, HeaderValue},
-        StatusCode,
-    },
+    http::header::{self, HeaderMap, HeaderValue},
     response::{IntoResponse, Response},
     Json,
 };
 use chrono::{DateTime, Utc};
 use sea_orm::{ColumnTrait, ConnectionTrait, EntityTrait, FromQueryResult, QueryFilter, QueryOrder, Statement};
 use serde::{Deserialize, Serialize};
-use std::collections::{HashMap, HashSet};
-use std::sync::Arc;
-use tokio::sync::Semaphore;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::ops::{Deref, DerefMut};
 use tokio_stream::wrappers::ReceiverStream;
 use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 
+use crate::common::time::{self, TimeArray, TimeFormat};
+use crate::common::format::{
+    check_compress, compress_export, export_filename, negotiate_format, ordered_columns,
+    Compression, CsvLines,
+};
+use crate::common::round::{format_decimal, round_values};
 use crate::common::AppState;
 use crate::entity::{sensors, zones};
 use crate::error::{AppError, AppResult};
-use crate::routes::{cache, resolve_station};
+use crate::routes::{cache, check_sensor_limit, resolve_station, sensor_names_condition};
 
+use super::thresholds::{sensor_limits, SensorLimits};
 use super::types::{StationRef, ZoneRef};
 
 /// Minimal struct for efficient readings query
@@ -30,21 +33,14 @@ struct ReadingRow {
     sensor_id: Uuid,
     time: chrono::DateTime<chrono::FixedOffset>,
     value: f64,
+    raw_time: Option<chrono::DateTime<chrono::FixedOffset>>,
 }
 
-/// Global semaphore limiting concurrent bulk (CSV/NDJSON) requests.
-/// Protects the database from distributed DDoS attacks.
-/// Configurable via BULK_CONCURRENT_LIMIT env var (default: 5).
-static BULK_SEMAPHORE: std::sync::LazyLock<Arc<Semaphore>> = std::sync::LazyLock::new(|| {
-    let limit = std::env::var("BULK_CONCURRENT_LIMIT")
-        .ok()
-        .and_then(|s| s.parse().ok())
-        .unwrap_or(5);
-    Arc::new(Semaphore::new(limit))
-});
-
-fn default_format() -> String {
-    "json".to_string()
+/// Result row for the count-only estimate query
+#[derive(Debug, FromQueryResult)]
+struct EstimateRow {
+    readings: i64,
+    timestamps: i64,
 }
 
 #[derive(Debug, Serialize, ToSchema)]
@@ -57,10 +53,41 @@ pub struct ReadingsResponse {
     pub start: Option<DateTime<Utc>>,
     /// End of time range (null if no data)
     pub end: Option<DateTime<Utc>>,
-    /// Array of timestamps (aligned to 10-minute intervals)
-    pub times: Vec<DateTime<Utc>>,
-    /// Array of sensors with their values
-    pub sensors: Vec<SensorData>,
+    /// Start of the window actually queried, after resolving `window` or the
+    /// default window (null if unbounded)
+    pub effective_start: Option<DateTime<Utc>>,
+    /// End of the window actually queried, after resolving `window` or the
+    /// default window (null if unbounded)
+    pub effective_end: Option<DateTime<Utc>>,
+    /// Recommended source for this range: raw, hourly, daily or weekly (null if
+    /// neither the window nor the data has a known extent)
+    pub resolution_hint: Option<String>,
+    /// Sensor metadata keyed by sensor ID; only present with `compact_meta=true`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sensors_meta: Option<BTreeMap<Uuid, SensorMeta>>,
+    /// Array of timestamps (aligned to 10-minute intervals), as RFC 3339
+    /// strings or epoch seconds depending on `time_format`
+    #[schema(value_type = Vec<String>)]
+    pub times: TimeArray<DateTime<Utc>>,
+    /// Array of sensors with their values. With `compact_meta=true` each entry
+    /// only has `id` and `values`; the rest is in `sensors_meta`
+    #[schema(value_type = Vec<SensorData>)]
+    pub sensors: SensorColumns,
+}
+
+/// Size estimate returned when `count_only=true`
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ReadingsEstimate {
+    /// Zone this data belongs to
+    pub zone: Option<ZoneRef>,
+    /// Station this data belongs to
+    pub station: StationRef,
+    /// Number of sensors matching the filters
+    pub sensors: usize,
+    /// Number of readings that would be returned
+    pub readings: i64,
+    /// Number of distinct timestamps (length of the `times` array)
+    pub timestamps: i64,
 }
 
 #[derive(Debug, Clone, Serialize, ToSchema)]
@@ -70,62 +97,173 @@ pub struct SensorData {
     #[serde(rename = "type")]
     pub sensor_type: String,
     pub units: Option<String>,
+    /// False for retired sensors (only returned with `include_inactive=true`)
+    pub is_active: bool,
     /// Values array (same length as times, null for missing data)
     pub values: Vec<Option<f64>>,
+    /// Unrounded sample times in `time_format` (same length as times, null for
+    /// missing data or rows synced before raw times were recorded); only with
+    /// `with_raw_time=true`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<Vec<Option<String>>>)]
+    pub raw_times: Option<TimeArray<Option<DateTime<Utc>>>>,
+    /// Limit lines; only with `include_thresholds=true`, and omitted for
+    /// sensors without thresholds or units range
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thresholds: Option<SensorLimits>,
+    /// Places used for CSV values (not serialized)
+    #[serde(skip)]
+    pub decimal_places: Option<i16>,
 }
 
-fn determine_format(query_format: &str, headers: &HeaderMap) -> String {
-    // Query parameter takes precedence
-    if query_format != "json" {
-        return query_format.to_lowercase();
-    }
+/// Sensor metadata emitted once per sensor with `compact_meta=true`
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct SensorMeta {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub sensor_type: String,
+    pub units: Option<String>,
+    pub is_active: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thresholds: Option<SensorLimits>,
+}
 
-    // Check Accept header
-    if let Some(accept) = headers.get(header::ACCEPT)
-        && let Ok(accept_str) = accept.to_str()
-    {
-        if accept_str.contains("application/x-ndjson") {
-            return "ndjson".to_string();
+impl From<&SensorData> for SensorMeta {
+    fn from(sensor: &SensorData) -> Self {
+        Self {
+            name: sensor.name.clone(),
+            sensor_type: sensor.sensor_type.clone(),
+            units: sensor.units.clone(),
+            is_active: sensor.is_active,
+            thresholds: sensor.thresholds.clone(),
         }
-        if accept_str.contains("text/csv") {
-            return "csv".to_string();
+    }
+}
+
+/// A sensor column without its metadata
+#[derive(Serialize)]
+struct CompactSensorData<'a> {
+    id: Uuid,
+    values: &'a [Option<f64>],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    raw_times: &'a Option<TimeArray<Option<DateTime<Utc>>>>,
+}
+
+/// Sensor columns of a [`ReadingsResponse`], serialized in full or, when
+/// `compact`, as `{id, values}` only.
+///
+/// Derefs to the underlying `Vec`, so it can be used like one.
+#[derive(Debug, Clone, Default)]
+pub struct SensorColumns {
+    pub values: Vec<SensorData>,
+    pub compact: bool,
+}
+
+impl SensorColumns {
+    pub fn new(values: Vec<SensorData>, compact: bool) -> Self {
+        Self { values, compact }
+    }
+
+    /// `sensors_meta` for these columns when compact, else `None`
+    pub fn meta(&self) -> Option<BTreeMap<Uuid, SensorMeta>> {
+        self.compact
+            .then(|| self.values.iter().map(|s| (s.id, SensorMeta::from(s))).collect())
+    }
+}
+
+/// One entry of the `sensors` array, shaped by [`SensorColumns::compact`]
+struct SensorColumn<'a>(&'a SensorColumns, &'a SensorData);
+
+impl Serialize for SensorColumn<'_> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let Self(columns, sensor) = self;
+        if columns.compact {
+            CompactSensorData {
+                id: sensor.id,
+                values: &sensor.values,
+                raw_times: &sensor.raw_times,
+            }
+            .serialize(serializer)
+        } else {
+            sensor.serialize(serializer)
         }
     }
+}
+
+impl Deref for SensorColumns {
+    type Target = Vec<SensorData>;
 
-    "json".to_string()
+    fn deref(&self) -> &Vec<SensorData> {
+        &self.values
+    }
 }
 
-fn build_csv_response(times: &[DateTime<Utc>], sensors: &[SensorData]) -> AppResult<Response> {
+impl DerefMut for SensorColumns {
+    fn deref_mut(&mut self) -> &mut Vec<SensorData> {
+        &mut self.values
+    }
+}
+
+impl Serialize for SensorColumns {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.values.iter().map(|sensor| SensorColumn(self, sensor)))
+    }
+}
+
+/// Fields of the optional CSV metadata comment line mapping columns to sensor UUIDs.
+///
+/// Written as a CSV record, `# columns: time,<sensor uuid>=<name>,...`, in the
+/// same order as the header row; entries are quoted like any other field.
+pub fn csv_header_meta(sensors: &[SensorData]) -> Vec<String> {
+    std::iter::once("# columns: time".to_string())
+        .chain(sensors.iter().map(|s| format!("{}={}", s.id, s.name)))
+        .collect()
+}
+
+/// Resolution hint for a range, if both ends are known
+fn hint_for(start: Option<DateTime<Utc>>, end: Option<DateTime<Utc>>) -> Option<String> {
+    let (start, end) = start.zip(end)?;
+    Some(time::resolution_hint(end - start).to_string())
+}
+
+/// Stream readings as CSV, one column per sensor in [`ordered_columns`] order.
+pub fn build_csv_response(
+    times: &[DateTime<Utc>],
+    sensors: &[SensorData],
+    with_header_meta: bool,
+) -> AppResult<Response> {
     let (tx, rx) = tokio::sync::mpsc::channel::<Result<String, std::io::Error>>(100);
 
     let times = times.to_vec();
-    let sensors = sensors.to_vec();
+    let sensors = ordered_columns(sensors, |s| (s.name.as_str(), s.id));
 
     tokio::spawn(async move {
-        // Header row
-        let mut header = "time".to_string();
-        for sensor in &sensors {
-            header.push(',');
-            header.push_str(&sensor.name);
+        let mut csv = CsvLines::new();
+
+        // Optional comment line before the header (lines starting with `#`)
+        if with_header_meta {
+            csv.write(csv_header_meta(&sensors));
         }
-        header.push('\n');
-        let _ = tx.send(Ok(header)).await;
+
+        // Header row; names are quoted where needed
+        csv.write(std::iter::once("time").chain(sensors.iter().map(|s| s.name.as_str())));
 
         // Data rows
         for (i, time) in times.iter().enumerate() {
-            let mut row = time.to_rfc3339();
-            for sensor in &sensors {
-                row.push(',');
-                match sensor.values.get(i).and_then(|v| *v) {
-                    Some(v) => row.push_str(&v.to_string()),
-                    None => {} // Empty for null
-                }
-            }
-            row.push('\n');
-            if tx.send(Ok(row)).await.is_err() {
-                break;
+            let values = sensors.iter().map(|sensor| {
+                sensor
+                    .values
+                    .get(i)
+                    .and_then(|v| *v)
+                    .map(|v| format_decimal(v, sensor.decimal_places))
+                    .unwrap_or_default() // Empty for null
+            });
+            csv.write(std::iter::once(time.to_rfc3339()).chain(values));
+            if csv.is_full() && tx.send(Ok(csv.take())).await.is_err() {
+                return;
             }
         }
+        let _ = tx.send(Ok(csv.take())).await;
     });
 
     let stream = ReceiverStream::new(rx);
@@ -137,14 +275,15 @@ fn build_csv_response(times: &[DateTime<Utc>], sensors: &[SensorData]) -> AppRes
         .map_err(|e| AppError::Internal(e.to_string()))
 }
 
-fn build_ndjson_response(
+/// Stream readings as NDJSON, one object per timestamp.
+pub fn build_ndjson_response(
     times: &[DateTime<Utc>],
     sensors: &[SensorData],
 ) -> AppResult<Response> {
     let (tx, rx) = tokio::sync::mpsc::channel::<Result<String, std::io::Error>>(100);
 
     let times = times.to_vec();
-    let sensors = sensors.to_vec();
+    let sensors = ordered_columns(sensors, |s| (s.name.as_str(), s.id));
 
     tokio::spawn(async move {
         // Each row is a JSON object with time and sensor values
@@ -182,33 +321,433 @@ fn build_ndjson_response(
         .map_err(|e| AppError::Internal(e.to_string()))
 }
 
+/// Timestamps serialized per chunk when streaming a JSON response
+const JSON_STREAM_TIMES_CHUNK: usize = 4096;
+
+/// Rough serialized size of a JSON readings response: an ISO timestamp plus
+/// one value per sensor for each row.
+pub fn estimated_json_bytes(timestamps: usize, sensors: usize) -> usize {
+    timestamps.saturating_mul(24 + 12 * sensors)
+}
+
+/// Fields of [`ReadingsResponse`] that precede the `times` array
+#[derive(Serialize)]
+struct ReadingsHead<'a> {
+    zone: &'a Option<ZoneRef>,
+    station: &'a StationRef,
+    start: Option<DateTime<Utc>>,
+    end: Option<DateTime<Utc>>,
+    effective_start: Option<DateTime<Utc>>,
+    effective_end: Option<DateTime<Utc>>,
+    resolution_hint: &'a Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sensors_meta: &'a Option<BTreeMap<Uuid, SensorMeta>>,
+}
+
+/// Serialize `response` lazily in pieces: the head, `times` in chunks, then
+/// one sensor at a time.
+///
+/// The concatenated chunks equal `serde_json::to_vec(response)`.
+pub fn json_chunks(
+    response: &ReadingsResponse,
+) -> impl Iterator<Item = serde_json::Result<String>> + '_ {
+    let head = std::iter::once_with(|| {
+        let mut head = serde_json::to_string(&ReadingsHead {
+            zone: &response.zone,
+            station: &response.station,
+            start: response.start,
+            end: response.end,
+            effective_start: response.effective_start,
+            effective_end: response.effective_end,
+            resolution_hint: &response.resolution_hint,
+            sensors_meta: &response.sensors_meta,
+        })?;
+        head.pop(); // Reopen the object
+        head.push_str(",\"times\":[");
+        Ok(head)
+    });
+
+    let times = response
+        .times
+        .chunks(JSON_STREAM_TIMES_CHUNK)
+        .enumerate()
+        .map(|(i, chunk)| {
+            let array = serde_json::to_string(&TimeArray::new(chunk.to_vec(), response.times.format))?;
+            let separator = if i == 0 { "" } else { "," };
+            Ok(format!("{separator}{}", &array[1..array.len() - 1]))
+        });
+
+    let sensors = response.sensors.iter().enumerate().map(|(i, sensor)| {
+        let separator = if i == 0 { "" } else { "," };
+        let column = SensorColumn(&response.sensors, sensor);
+        Ok(format!("{separator}{}", serde_json::to_string(&column)?))
+    });
+
+    head.chain(times)
+        .chain(std::iter::once(Ok("],\"sensors\":[".to_string())))
+        .chain(sensors)
+        .chain(std::iter::once(Ok("]}".to_string())))
+}
+
+/// Stream a large JSON readings response instead of buffering its bytes.
+///
+/// Streamed responses are not cached (`X-Cache: BYPASS`).
+fn build_json_stream_response(response: ReadingsResponse) -> AppResult<Response> {
+    let (tx, rx) = tokio::sync::mpsc::channel::<Result<String, std::io::Error>>(16);
+
+    tokio::spawn(async move {
+        for chunk in json_chunks(&response) {
+            let chunk = chunk.map_err(std::io::Error::other);
+            let failed = chunk.is_err();
+            if tx.send(chunk).await.is_err() || failed {
+                break;
+            }
+        }
+    });
+
+    let stream = ReceiverStream::new(rx);
+    let body = axum::body::Body::from_stream(stream);
+
+    Response::builder()
+        .header(header::CONTENT_TYPE, HeaderValue::from_static("application/json"))
+        .header("X-Cache", HeaderValue::from_static("BYPASS"))
+        .body(body)
+        .map_err(|e| AppError::Internal(e.to_string()))
+}
+
+/// Raw readings of `sensors_list` as one column per sensor over shared times.
+///
+/// Columns follow `sensors_list` order; missing samples are null. With
+/// `as_of`, only readings ingested by then are included (see [`as_of_filter`]).
+/// With `after`, only readings strictly newer than it are included.
+/// With `raw_time_format`, each column also carries its unrounded sample times.
+#[allow(clippy::too_many_arguments)]
+pub(super) async fn raw_series(
+    state: &AppState,
+    sensors_list: &[sensors::Model],
+    start: Option<DateTime<Utc>>,
+    end: Option<DateTime<Utc>>,
+    after: Option<DateTime<Utc>>,
+    as_of: Option<DateTime<Utc>>,
+    round: bool,
+    raw_time_format: Option<TimeFormat>,
+) -> AppResult<(Vec<DateTime<Utc>>, Vec<SensorData>)> {
+    let num_sensors = sensors_list.len();
+    let sensor_ids: Vec<Uuid> = sensors_list.iter().map(|s| s.id).collect();
+    let sensor_ids_str = sensor_id_list(&sensor_ids);
+    let time_filter = format!(
+        "{}{}{}",
+        time_filter(start, end),
+        after_filter(after),
+        as_of_filter(as_of)
+    );
+    // Skip reading the column unless asked for
+    let raw_time = if raw_time_format.is_some() {
+        "raw_time"
+    } else {
+        "NULL::timestamptz AS raw_time"
+    };
+
+    // Build optimized raw SQL query - only fetch needed columns.
+    // ORDER BY sensor_id, time matches index (sensor_id, time DESC) for efficient retrieval.
+    // Data arrives grouped by sensor, sorted by time - enables streaming processing in Rust.
+    let sql = format!(
+        "SELECT sensor_id, time, value, {raw_time} FROM readings WHERE sensor_id IN ({sensor_ids_str}){time_filter} ORDER BY sensor_id, time"
+    );
+
+    let readings_list: Vec<ReadingRow> = state
+        .read_db
+        .query_all(Statement::from_string(
+            sea_orm::DatabaseBackend::Postgres,
+            sql,
+        ))
+        .await?
+        .into_iter()
+        .map(|row| ReadingRow::from_query_result(&row, ""))
+        .collect::<Result<_, _>>()?;
+
+    // Data arrives sorted by (sensor_id, time) from DB.
+    // 1. Collect unique times and group values by sensor in single pass
+    let estimated_times = readings_list.len() / num_sensors.max(1);
+    let mut time_set: HashSet<DateTime<Utc>> = HashSet::with_capacity(estimated_times);
+    let mut sensor_values: HashMap<Uuid, Vec<(DateTime<Utc>, f64, Option<DateTime<Utc>>)>> =
+        HashMap::with_capacity(num_sensors);
+
+    for row in readings_list {
+        let time = row.time.with_timezone(&Utc);
+        time_set.insert(time);
+        sensor_values
+            .entry(row.sensor_id)
+            .or_insert_with(|| Vec::with_capacity(estimated_times))
+            .push((time, row.value, row.raw_time.map(|t| t.with_timezone(&Utc))));
+    }
+
+    // 2. Sort times once (HashSet -> sorted Vec)
+    let mut times: Vec<DateTime<Utc>> = time_set.into_iter().collect();
+    times.sort_unstable();
+
+    // 3. Build time -> index map for O(1) lookup
+    let time_index: HashMap<DateTime<Utc>, usize> = times
+        .iter()
+        .enumerate()
+        .map(|(i, t)| (*t, i))
+        .collect();
+
+    // 4. Build sensor data using index map (no nested HashMap lookups)
+    let sensor_data: Vec<SensorData> = sensors_list
+        .iter()
+        .map(|sensor| {
+            let mut values: Vec<Option<f64>> = vec![None; times.len()];
+            let mut raw_times =
+                raw_time_format.map(|format| TimeArray::new(vec![None; times.len()], format));
+
+            if let Some(readings) = sensor_values.get(&sensor.id) {
+                for (time, value, raw_time) in readings {
+                    if let Some(&idx) = time_index.get(time) {
+                        values[idx] = Some(*value);
+                        if let Some(raw_times) = raw_times.as_mut() {
+                            raw_times.values[idx] = *raw_time;
+                        }
+                    }
+                }
+            }
+            if round {
+                round_values(&mut values, sensor.decimal_places);
+            }
+
+            SensorData {
+                id: sensor.id,
+                name: sensor.name.clone(),
+                sensor_type: sensor.sensor_type.clone(),
+                units: sensor.display_units.clone(),
+                is_active: sensor.is_active == Some(true),
+                values,
+                raw_times,
+                thresholds: None,
+                decimal_places: sensor.decimal_places,
+            }
+        })
+        .collect();
+
+    Ok((times, sensor_data))
+}
+
+/// Estimated raw readings for `sensors` over `[start, end]`.
+///
+/// Each sensor contributes `range_seconds / interval`, using its sample
+/// interval or `default_interval_sec` when unknown. Gaps are ignored, so this
+/// is an upper bound for regularly sampled data.
+pub fn estimated_points(
+    sensors: &[sensors::Model],
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    default_interval_sec: i64,
+) -> u64 {
+    let range_secs = u64::try_from((end - start).num_seconds()).unwrap_or(0);
+    sensors
+        .iter()
+        .map(|sensor| {
+            let interval = u64::try_from(sensor.effective_interval(default_interval_sec).max(1))
+                .unwrap_or(1);
+            range_secs / interval
+        })
+        .fold(0, u64::saturating_add)
+}
+
+/// Reject requests whose estimated size exceeds `READINGS_POINT_BUDGET`.
+///
+/// An open start is taken from the sensors' earliest reading and an open end
+/// as now.
+async fn check_point_budget(
+    state: &AppState,
+    sensors_list: &[sensors::Model],
+    start: Option<DateTime<Utc>>,
+    end: Option<DateTime<Utc>>,
+) -> AppResult<()> {
+    let budget = state.config.readings_point_budget;
+    if budget == 0 || sensors_list.is_empty() {
+        return Ok(());
+    }
+
+    let start = match start {
+        Some(start) => Some(start),
+        None => {
+            let ids: Vec<Uuid> = sensors_list.iter().map(|s| s.id).collect();
+            let sql = format!(
+                "SELECT MIN(time) AS min_time FROM readings WHERE sensor_id IN ({})",
+                sensor_id_list(&ids)
+            );
+            state
+                .read_db
+                .query_one(Statement::from_string(sea_orm::DatabaseBackend::Postgres, sql))
+                .await?
+                .and_then(|row| row.try_get::<Option<DateTime<Utc>>>("", "min_time").ok())
+                .flatten()
+        }
+    };
+    // No data at all
+    let Some(start) = start else {
+        return Ok(());
+    };
+    let end = end.unwrap_or_else(Utc::now);
+
+    let estimate = estimated_points(
+        sensors_list,
+        start,
+        end,
+        state.config.default_sample_interval_sec,
+    );
+    if estimate > budget {
+        return Err(AppError::BadRequest(format!(
+            "Request would return about {estimate} readings, over the limit of {budget}. \
+             Narrow the time range or sensors, or use the aggregates endpoint"
+        )));
+    }
+    Ok(())
+}
+
+/// Quoted, comma-separated sensor IDs for an SQL `IN (...)` list
+fn sensor_id_list(sensor_ids: &[Uuid]) -> String {
+    sensor_ids
+        .iter()
+        .map(|id| format!("'{id}'"))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// SQL conditions restricting `time` to the given bounds (each side optional)
+fn time_filter(start: Option<DateTime<Utc>>, end: Option<DateTime<Utc>>) -> String {
+    let mut filter = String::new();
+    if let Some(start) = start {
+        filter.push_str(&format!(" AND time >= '{}'", start.to_rfc3339()));
+    }
+    if let Some(end) = end {
+        filter.push_str(&format!(" AND time <= '{}'", end.to_rfc3339()));
+    }
+    filter
+}
+
+/// SQL condition keeping readings strictly newer than `after` (an exclusive
+/// lower bound, served by the `(sensor_id, time)` index like `time_filter`)
+fn after_filter(after: Option<DateTime<Utc>>) -> String {
+    after.map_or(String::new(), |after| {
+        format!(" AND time > '{}'", after.to_rfc3339())
+    })
+}
+
+/// SQL condition keeping readings ingested at or before `as_of`.
+///
+/// Rows ingested before `ingested_at` was tracked have it null and are always
+/// included, so snapshots are only reproducible for data ingested since.
+/// Replacing a row (a logged value overriding a realtime one) moves its
+/// `ingested_at` and no prior version is kept, so earlier snapshots lose that
+/// timestamp altogether.
+fn as_of_filter(as_of: Option<DateTime<Utc>>) -> String {
+    as_of.map_or(String::new(), |as_of| {
+        format!(
+            " AND (ingested_at IS NULL OR ingested_at <= '{}')",
+            as_of.to_rfc3339()
+        )
+    })
+}
+
 #[derive(Debug, Deserialize, IntoParams)]
 pub struct StationReadingsQuery {
-    /// Start time (optional, ISO 8601). If omitted, returns from earliest data.
+    /// Start time (optional, ISO 8601 or Unix epoch seconds). If omitted,
+    /// returns from earliest data.
+    /// When `start`, `end` and `window` are all omitted, the server's default
+    /// window (`DEFAULT_READINGS_WINDOW`, 7d unless configured) applies.
+    #[serde(default, deserialize_with = "time::deserialize_optional_timestamp")]
     pub start: Option<DateTime<Utc>>,
-    /// End time (optional, ISO 8601). If omitted, returns to latest data.
+    /// End time (optional, ISO 8601 or Unix epoch seconds). If omitted,
+    /// returns to latest data.
+    #[serde(default, deserialize_with = "time::deserialize_optional_timestamp")]
     pub end: Option<DateTime<Utc>>,
+    /// Relative window ending now (e.g. 24h, 7d, 30d). Ignored if start or end
+    /// is given. Defaults to `DEFAULT_READINGS_WINDOW` when no bound is given.
+    pub window: Option<String>,
+    /// Only readings ingested at or before this time (ISO 8601), for
+    /// reproducible snapshots despite Vaisala backfills. Readings ingested
+    /// before ingest times were tracked are always included; readings replaced
+    /// after `as_of` are left out, as their earlier value is not kept.
+    pub as_of: Option<DateTime<Utc>>,
+    /// Only readings strictly newer than this time (ISO 8601 or Unix epoch
+    /// seconds), for incremental polling: pass the last `end` seen. Without
+    /// `start`, `end` or `window`, the default window does not apply
+    #[serde(default, deserialize_with = "time::deserialize_optional_timestamp")]
+    pub after: Option<DateTime<Utc>>,
     /// Filter by sensor types (comma-separated)
     pub sensor_types: Option<String>,
-    /// Response format: json (default), ndjson, csv
-    #[serde(default = "default_format")]
-    pub format: String,
+    /// Filter by sensor names (comma-separated, case-insensitive)
+    pub sensor_names: Option<String>,
+    /// Include inactive (retired) sensors, labeled `is_active: false` (default: false)
+    #[serde(default)]
+    pub include_inactive: bool,
+    /// Response format: json, ndjson, csv. Takes precedence over the Accept
+    /// header, which is used when omitted (q-values honored); default json.
+    pub format: Option<String>,
+    /// CSV only: prepend a `# columns: time,<sensor uuid>=<name>,...` comment line
+    #[serde(default)]
+    pub with_header_meta: bool,
+    /// CSV and NDJSON only: `gzip` compresses the export whatever the
+    /// `Accept-Encoding`, named `<station>-readings.<format>.gz` for download
+    pub compress: Option<Compression>,
+    /// Only return the number of readings and timestamps that would be returned
+    #[serde(default)]
+    pub count_only: bool,
+    /// JSON only: serialize `times` as `iso` strings (default) or `epoch` seconds
+    #[serde(default)]
+    pub time_format: TimeFormat,
+    /// JSON only: round values to each sensor's `decimal_places` (sensors
+    /// without one are left exact). CSV always writes values with the
+    /// sensor's `decimal_places`
+    #[serde(default)]
+    pub round: bool,
+    /// JSON only: emit sensor metadata once in `sensors_meta` (keyed by
+    /// sensor ID) and only `id` and `values` per entry of `sensors`, for
+    /// clients that keep the metadata and only consume the value columns
+    #[serde(default)]
+    pub compact_meta: bool,
+    /// JSON only: add each sensor's unrounded sample times as `raw_times`,
+    /// alongside the aligned `times`. Null for readings synced before raw
+    /// times were recorded
+    #[serde(default)]
+    pub with_raw_time: bool,
+    /// JSON only: attach each sensor's high/low limit lines as `thresholds`
+    /// (configured alarm thresholds, else its units range)
+    #[serde(default)]
+    pub include_thresholds: bool,
 }
 
 /// Get readings for a specific station
 ///
 /// Returns time-series data for all sensors in the specified station.
-/// Supports JSON, CSV, and NDJSON formats.
+/// Supports JSON, CSV, and NDJSON formats. With `count_only=true`, returns a
+/// `ReadingsEstimate` instead so clients can check the size before exporting.
+/// Requests estimated above `READINGS_POINT_BUDGET` raw points (sum over
+/// sensors of range / sample interval) are rejected with 400.
+/// Without `start`, `end` or `window`, only the last `DEFAULT_READINGS_WINDOW`
+/// (7d unless configured) is returned, not all history; the applied range is
+/// reported in `effective_start`/`effective_end`. Pass `start` to read further
+/// back.
+/// With `as_of`, readings ingested after that time are left out, so a query
+/// repeated later returns the same data. A realtime reading later replaced by
+/// its logged value counts as ingested at the replacement: snapshots taken
+/// before it show neither value for that timestamp.
+/// With `after`, only readings newer than that time are returned, in the same
+/// shape, so a dashboard can poll for the delta after its initial load. There
+/// is no readings stream to subscribe to instead: `/api/v1/device-status/stream`
+/// only pushes device status changes, so new readings are fetched this way.
 #[utoipa::path(
     get,
-    path = "/api/stations/{station_id}/readings",
+    path = "/api/v1/stations/{station_id}/readings",
     params(
         ("station_id" = String, Path, description = "Station UUID or name"),
         StationReadingsQuery
     ),
     responses(
-        (status = 200, description = "Readings retrieved successfully", body = ReadingsResponse),
-        (status = 400, description = "Invalid query parameters"),
+        (status = 200, description = "Readings retrieved successfully (ReadingsEstimate when count_only=true)", body = ReadingsResponse),
+        (status = 400, description = "Invalid query parameters, more sensors than MAX_SENSORS_PER_REQUEST, or estimated size over READINGS_POINT_BUDGET"),
         (status = 404, description = "Station not found"),
     ),
     tag = "stations"
@@ -219,12 +758,12 @@ pub async fn get_station_readings(
     Query(query): Query<StationReadingsQuery>,
     headers: HeaderMap,
 ) -> AppResult<Response> {
-    let station = resolve_station(&state.db, &station_id).await?;
+    let station = resolve_station(&state.read_db, &station_id).await?;
 
     // Fetch zone info if available
     let zone_ref = if let Some(zone_id) = station.zone_id {
         zones::Entity::find_by_id(zone_id)
-            .one(&state.db)
+            .one(&state.read_db)
             .await?
             .map(|z| ZoneRef {
                 id: z.id,
@@ -239,22 +778,39 @@ pub async fn get_station_readings(
         name: station.name.clone(),
     };
 
-    // Validate time range if both provided
-    if let (Some(start), Some(end)) = (query.start, query.end) {
-        if end <= start {
-            return Err(AppError::BadRequest(
-                "end time must be after start time".to_string(),
-            ));
+    // Explicit start/end take precedence over a relative window; with neither,
+    // the default window keeps a naive request from pulling all history
+    let (query_start, query_end) = match (&query.window, query.start, query.end) {
+        (Some(window), None, None) => {
+            let (start, end) = time::resolve_window(window)?;
+            (Some(start), Some(end))
         }
-    }
+        // Polling from a cursor: the cursor bounds the request instead
+        (None, None, None) if query.after.is_some() => (None, None),
+        (None, None, None) => match state.config.default_readings_window {
+            Some(duration) => {
+                let (start, end) = time::window_ending_now(duration);
+                (Some(start), Some(end))
+            }
+            None => (None, None),
+        },
+        _ => (query.start, query.end),
+    };
+
+    time::validate_range(query_start, query_end)?;
+    time::validate_range(query.after, query_end)?;
+    // Tighter of `start` and `after`, for sizing the request
+    let lower_bound = query_start.max(query.after);
 
     // Determine format from query or Accept header
-    let format = determine_format(&query.format, &headers);
+    let format = negotiate_format(query.format.as_deref(), &headers);
+    check_compress(query.compress, &format)?;
 
     // Build sensor query for this station only
-    let mut sensor_query = sensors::Entity::find()
-        .filter(sensors::Column::IsActive.eq(true))
-        .filter(sensors::Column::StationId.eq(station.id));
+    let mut sensor_query = sensors::Entity::find().filter(sensors::Column::StationId.eq(station.id));
+    if !query.include_inactive {
+        sensor_query = sensor_query.filter(sensors::Column::IsActive.eq(true));
+    }
 
     if let Some(ref types) = query.sensor_types {
         let type_list: Vec<String> = types.split(',').map(|s| s.trim().to_string()).collect();
@@ -263,52 +819,93 @@ pub async fn get_station_readings(
         }
     }
 
+    // Name and type filters intersect
+    if let Some(name_filter) = query.sensor_names.as_deref().and_then(sensor_names_condition) {
+        sensor_query = sensor_query.filter(name_filter);
+    }
+
     // Get matching sensors (needed for cache key validation)
     let sensors_list = sensor_query
         .order_by_asc(sensors::Column::Name)
-        .all(&state.db)
+        .order_by_asc(sensors::Column::Id)
+        .all(&state.read_db)
         .await?;
 
     let sensor_ids: Vec<Uuid> = sensors_list.iter().map(|s| s.id).collect();
 
+    // Dry run: count what would be returned without materializing the arrays
+    if query.count_only {
+        let estimate = if sensor_ids.is_empty() {
+            None
+        } else {
+            let sql = format!(
+                "SELECT COUNT(*) AS readings, COUNT(DISTINCT time) AS timestamps FROM readings WHERE sensor_id IN ({}){}{}{}",
+                sensor_id_list(&sensor_ids),
+                time_filter(query_start, query_end),
+                after_filter(query.after),
+                as_of_filter(query.as_of)
+            );
+            state
+                .read_db
+                .query_one(Statement::from_string(sea_orm::DatabaseBackend::Postgres, sql))
+                .await?
+                .map(|row| EstimateRow::from_query_result(&row, ""))
+                .transpose()?
+        };
+
+        return Ok(Json(ReadingsEstimate {
+            zone: zone_ref,
+            station: station_ref,
+            sensors: sensor_ids.len(),
+            readings: estimate.as_ref().map_or(0, |e| e.readings),
+            timestamps: estimate.as_ref().map_or(0, |e| e.timestamps),
+        })
+        .into_response());
+    }
+
+    check_sensor_limit(sensors_list.len(), state.config.max_sensors_per_request)?;
+    check_point_budget(&state, &sensors_list, lower_bound, query_end).await?;
+
     // Build cache key from request parameters
     let cache_key = cache::cache_key(
         "readings",
         &[
             &station.id.to_string(),
-            &query.start.map(|t| t.to_rfc3339()).unwrap_or_default(),
-            &query.end.map(|t| t.to_rfc3339()).unwrap_or_default(),
+            &query_start.map(|t| t.to_rfc3339()).unwrap_or_default(),
+            &query_end.map(|t| t.to_rfc3339()).unwrap_or_default(),
+            &query.as_of.map(|t| t.to_rfc3339()).unwrap_or_default(),
+            &query.after.map(|t| t.to_rfc3339()).unwrap_or_default(),
             query.sensor_types.as_deref().unwrap_or(""),
+            query.sensor_names.as_deref().unwrap_or(""),
+            if query.include_inactive { "inactive" } else { "" },
             &format,
+            query.time_format.as_str(),
+            if query.round { "round" } else { "" },
+            if query.compact_meta { "compact" } else { "" },
+            if query.with_raw_time { "raw_time" } else { "" },
+            if query.include_thresholds { "thresholds" } else { "" },
         ],
     );
 
     // Check cache with freshness validation (JSON only)
-    // Pass query.end so bounded queries skip freshness check (historical data won't change)
+    // Pass query_end so bounded queries skip freshness check (historical data won't change)
     if format == "json" {
-        if let Some(cached) = cache::get_cached(&state, &cache_key, &sensor_ids, query.end).await {
+        if let Some(cached) = cache::get_cached(&state, &cache_key, &sensor_ids, query_end).await {
             return cache::json_response((*cached).to_vec(), true);
         }
     }
 
-    // For bulk formats (CSV/NDJSON), acquire semaphore to limit concurrent requests
-    let _permit = if format == "csv" || format == "ndjson" {
-        match BULK_SEMAPHORE.clone().try_acquire_owned() {
-            Ok(permit) => Some(permit),
-            Err(_) => {
-                tracing::warn!(
-                    format = %format,
-                    status = StatusCode::SERVICE_UNAVAILABLE.as_u16(),
-                    "bulk_request_rejected"
-                );
-                return Err(AppError::ServiceUnavailable(
-                    "Too many concurrent bulk requests. Please try again later.".to_string(),
-                ));
-            }
-        }
-    } else {
-        None
-    };
+    // Bulk formats (CSV/NDJSON) share one concurrency limit across endpoints;
+    // small pulls skip it. An open start can't be sized without a query.
+    let estimate = lower_bound.map_or(u64::MAX, |start| {
+        estimated_points(
+            &sensors_list,
+            start,
+            query_end.unwrap_or_else(Utc::now),
+            state.config.default_sample_interval_sec,
+        )
+    });
+    let _permit = state.bulk_limiter.acquire_sized(&format, estimate)?;
 
     if sensors_list.is_empty() {
         return Ok(Json(ReadingsResponse {
@@ -316,127 +913,85 @@ pub async fn get_station_readings(
             station: station_ref,
             start: None,
             end: None,
-            times: vec![],
-            sensors: vec![],
+            effective_start: query_start,
+            effective_end: query_end,
+            resolution_hint: hint_for(query_start, query_end),
+            sensors_meta: query.compact_meta.then(BTreeMap::new),
+            times: TimeArray::new(vec![], query.time_format),
+            sensors: SensorColumns::new(vec![], query.compact_meta),
         })
         .into_response());
     }
 
-    let num_sensors = sensors_list.len();
-
-    // Build optimized raw SQL query - only fetch needed columns
-    let sensor_ids_str = sensor_ids
-        .iter()
-        .map(|id| format!("'{id}'"))
-        .collect::<Vec<_>>()
-        .join(",");
-
-    // ORDER BY sensor_id, time matches index (sensor_id, time DESC) for efficient retrieval.
-    // Data arrives grouped by sensor, sorted by time - enables streaming processing in Rust.
-    let sql = match (query.start, query.end) {
-        (Some(start), Some(end)) => format!(
-            "SELECT sensor_id, time, value FROM readings WHERE sensor_id IN ({}) AND time >= '{}' AND time <= '{}' ORDER BY sensor_id, time",
-            sensor_ids_str,
-            start.to_rfc3339(),
-            end.to_rfc3339()
-        ),
-        (Some(start), None) => format!(
-            "SELECT sensor_id, time, value FROM readings WHERE sensor_id IN ({}) AND time >= '{}' ORDER BY sensor_id, time",
-            sensor_ids_str,
-            start.to_rfc3339()
-        ),
-        (None, Some(end)) => format!(
-            "SELECT sensor_id, time, value FROM readings WHERE sensor_id IN ({}) AND time <= '{}' ORDER BY sensor_id, time",
-            sensor_ids_str,
-            end.to_rfc3339()
-        ),
-        (None, None) => format!(
-            "SELECT sensor_id, time, value FROM readings WHERE sensor_id IN ({}) ORDER BY sensor_id, time",
-            sensor_ids_str
-        ),
-    };
-
-    let readings_list: Vec<ReadingRow> = state
-        .db
-        .query_all(Statement::from_string(
-            sea_orm::DatabaseBackend::Postgres,
-            sql,
-        ))
-        .await?
-        .into_iter()
-        .filter_map(|row| ReadingRow::from_query_result(&row, "").ok())
-        .collect();
-
-    // Data arrives sorted by (sensor_id, time) from DB.
-    // 1. Collect unique times and group values by sensor in single pass
-    let estimated_times = readings_list.len() / num_sensors.max(1);
-    let mut time_set: HashSet<DateTime<Utc>> = HashSet::with_capacity(estimated_times);
-    let mut sensor_values: HashMap<Uuid, Vec<(DateTime<Utc>, f64)>> =
-        HashMap::with_capacity(num_sensors);
-
-    for row in readings_list {
-        let time = row.time.with_timezone(&Utc);
-        time_set.insert(time);
-        sensor_values
-            .entry(row.sensor_id)
-            .or_insert_with(|| Vec::with_capacity(estimated_times))
-            .push((time, row.value));
+    // `round` and raw times only apply to JSON. Bulk exports keep one time
+    // column; CSV writes each sensor's `decimal_places` itself (see
+    // `format_decimal`) and NDJSON keeps full precision
+    let round = query.round && format == "json";
+    let raw_time_format = (query.with_raw_time && format == "json").then_some(query.time_format);
+
+    let (times, mut sensor_data) = raw_series(
+        &state,
+        &sensors_list,
+        query_start,
+        query_end,
+        query.after,
+        query.as_of,
+        round,
+        raw_time_format,
+    )
+    .await?;
+
+    if query.include_thresholds && format == "json" {
+        let mut limits = sensor_limits(&state, &sensors_list).await?;
+        for sensor in &mut sensor_data {
+            sensor.thresholds = limits.remove(&sensor.id);
+        }
     }
 
-    // 2. Sort times once (HashSet -> sorted Vec)
-    let mut times: Vec<DateTime<Utc>> = time_set.into_iter().collect();
-    times.sort_unstable();
-
-    // 3. Build time -> index map for O(1) lookup
-    let time_index: HashMap<DateTime<Utc>, usize> = times
-        .iter()
-        .enumerate()
-        .map(|(i, t)| (*t, i))
-        .collect();
-
-    // 4. Build sensor data using index map (no nested HashMap lookups)
-    let sensor_data: Vec<SensorData> = sensors_list
-        .iter()
-        .map(|sensor| {
-            let mut values: Vec<Option<f64>> = vec![None; times.len()];
-
-            if let Some(readings) = sensor_values.get(&sensor.id) {
-                for (time, value) in readings {
-                    if let Some(&idx) = time_index.get(time) {
-                        values[idx] = Some(*value);
-                    }
-                }
-            }
-
-            SensorData {
-                id: sensor.id,
-                name: sensor.name.clone(),
-                sensor_type: sensor.sensor_type.clone(),
-                units: sensor.display_units.clone(),
-                values,
-            }
-        })
-        .collect();
-
     // Use actual data range
     let actual_start = times.first().copied();
     let actual_end = times.last().copied();
 
     // Return appropriate format
     match format.as_str() {
-        "csv" => build_csv_response(&times, &sensor_data),
-        "ndjson" => build_ndjson_response(&times, &sensor_data),
+        "csv" => compress_export(
+            build_csv_response(&times, &sensor_data, query.with_header_meta)?,
+            query.compress,
+            &export_filename(&station.name, "readings", &format),
+        ),
+        "ndjson" => compress_export(
+            build_ndjson_response(&times, &sensor_data)?,
+            query.compress,
+            &export_filename(&station.name, "readings", &format),
+        ),
         _ => {
+            let sensors = SensorColumns::new(sensor_data, query.compact_meta);
             let response = ReadingsResponse {
                 zone: zone_ref,
                 station: station_ref,
                 start: actual_start,
                 end: actual_end,
-                times,
-                sensors: sensor_data,
+                effective_start: query_start,
+                effective_end: query_end,
+                // Unbounded sides fall back to the data extent
+                resolution_hint: hint_for(query_start.or(actual_start), query_end.or(actual_end)),
+                sensors_meta: sensors.meta(),
+                times: TimeArray::new(times, query.time_format),
+                sensors,
             };
+
+            // Stream large one-off pulls rather than buffering (and caching)
+            // their serialized bytes
+            let threshold = state.config.json_stream_threshold_bytes;
+            if threshold > 0
+                && estimated_json_bytes(response.times.len(), response.sensors.len()) > threshold
+            {
+                return build_json_stream_response(response);
+            }
+
             // Cache with max_time for freshness tracking
-            cache::cache_and_respond(&state, cache_key, &response, actual_end).await
+            let bounded = query_end.is_some();
+            cache::cache_and_respond(&state, cache_key, &response, actual_end, bounded, None).await
         }
     }
 }
@@ -0,0 +1,78 @@
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
+use serde::Serialize;
+use std::collections::HashMap;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::common::AppState;
+use crate::entity::{sensor_thresholds, sensors};
+use crate::error::AppResult;
+use crate::vaisala::models::ThresholdKind;
+
+/// Where a sensor's limits come from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LimitSource {
+    /// Alarm thresholds configured in viewLinc
+    Thresholds,
+    /// The sensor's `units_min`/`units_max` (no thresholds configured)
+    UnitsRange,
+}
+
+/// High/low limit lines for charting, attached with `include_thresholds=true`
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct SensorLimits {
+    /// Lowest high threshold (the first one crossed when rising)
+    pub high: Option<f64>,
+    /// Highest low threshold (the first one crossed when falling)
+    pub low: Option<f64>,
+    pub source: LimitSource,
+}
+
+/// Limits of each of `sensors_list` that has any, keyed by sensor ID.
+///
+/// Configured thresholds win; sensors without any fall back to their units
+/// range.
+pub(super) async fn sensor_limits(
+    state: &AppState,
+    sensors_list: &[sensors::Model],
+) -> AppResult<HashMap<Uuid, SensorLimits>> {
+    let sensor_ids: Vec<Uuid> = sensors_list.iter().map(|s| s.id).collect();
+    let thresholds = sensor_thresholds::Entity::find()
+        .filter(sensor_thresholds::Column::SensorId.is_in(sensor_ids))
+        .all(&state.read_db)
+        .await?;
+
+    let mut limits: HashMap<Uuid, SensorLimits> = HashMap::new();
+    for threshold in thresholds {
+        let entry = limits.entry(threshold.sensor_id).or_insert(SensorLimits {
+            high: None,
+            low: None,
+            source: LimitSource::Thresholds,
+        });
+        let value = threshold.value;
+        if threshold.kind == ThresholdKind::High.as_str() {
+            entry.high = Some(entry.high.map_or(value, |high| high.min(value)));
+        } else if threshold.kind == ThresholdKind::Low.as_str() {
+            entry.low = Some(entry.low.map_or(value, |low| low.max(value)));
+        }
+    }
+
+    for sensor in sensors_list {
+        if limits.contains_key(&sensor.id)
+            || (sensor.units_min.is_none() && sensor.units_max.is_none())
+        {
+            continue;
+        }
+        limits.insert(
+            sensor.id,
+            SensorLimits {
+                high: sensor.units_max,
+                low: sensor.units_min,
+                source: LimitSource::UnitsRange,
+            },
+        );
+    }
+
+    Ok(limits)
+}
@@ -28,7 +28,7 @@ pub struct StationResponse {
 }
 
 /// Sensor information embedded in station responses
-#[derive(Debug, Serialize, ToSchema)]
+#[derive(Debug, Clone, Serialize, ToSchema)]
 pub struct SensorResponse {
     pub id: Uuid,
     pub name: String,
@@ -36,6 +36,34 @@ pub struct SensorResponse {
     pub display_units: Option<String>,
     pub sample_interval_sec: Option<i32>,
     pub is_active: Option<bool>,
+    /// Alarming is paused in viewLinc: the sensor raises no alarms
+    pub alarming_paused: bool,
+    /// Whether an active alarm applies to this sensor (null unless `with_alarms=true`)
+    pub has_active_alarm: Option<bool>,
+    /// Highest severity among the sensor's active alarms (null if none or not requested)
+    pub max_active_severity: Option<i16>,
+}
+
+/// Query parameters for a station's sensor listings
+#[derive(Debug, Default, Deserialize, IntoParams)]
+pub struct StationSensorsQuery {
+    /// Fill `has_active_alarm` and `max_active_severity` (default: false)
+    #[serde(default)]
+    pub with_alarms: bool,
+    /// Wrap the array in `{data, meta}` (default: false; ignored by `/sensors/grouped`)
+    #[serde(default)]
+    pub envelope: bool,
+}
+
+/// A station's active sensors of one type
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SensorTypeGroup {
+    /// Number of sensors in this group
+    pub count: usize,
+    /// Distinct display units of the group's sensors, sorted
+    pub units: Vec<String>,
+    /// Sensors ordered by name
+    pub sensors: Vec<SensorResponse>,
 }
 
 /// Detailed station response with zone info, sensors, and data range
@@ -60,4 +88,25 @@ pub struct StationDetailResponse {
 pub struct StationsQuery {
     /// Filter by zone ID
     pub zone_id: Option<Uuid>,
+    /// Wrap the array in `{data, meta}` (default: false)
+    #[serde(default)]
+    pub envelope: bool,
+}
+
+/// Stations to fetch in one batch
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct StationBatchRequest {
+    /// Station UUIDs or names (at most 100)
+    pub station_ids: Vec<String>,
+}
+
+/// Result for one requested station
+#[derive(Debug, Serialize, ToSchema)]
+pub struct StationBatchResult {
+    /// Identifier as given in the request
+    pub id: String,
+    /// Whether the station was found
+    pub found: bool,
+    /// Station detail; null when not found
+    pub station: Option<StationDetailResponse>,
 }
@@ -0,0 +1,219 @@
+use axum::{
+    extract::{Query, State},
+    Json,
+};
+use chrono::{DateTime, Utc};
+use sea_orm::{
+    ActiveEnum, ColumnTrait, ConnectionTrait, EntityTrait, FromQueryResult, QueryFilter,
+    QueryOrder, QuerySelect, Statement,
+};
+use uuid::Uuid;
+
+use crate::common::AppState;
+use crate::entity::sync_runs;
+use crate::entity::sync_state::SyncStatus;
+use crate::error::{AppError, AppResult};
+use crate::routes::sensors::SensorRef;
+use crate::routes::stations::StationRef;
+use crate::sync::history::SYNC_TASKS;
+
+use super::types::{
+    SensorSyncStatusResponse, SyncPassResponse, SyncRunResponse, SyncRunsQuery, SyncStatusQuery,
+};
+
+#[derive(Debug, FromQueryResult)]
+struct SensorSyncRow {
+    sensor_id: Uuid,
+    sensor_name: String,
+    sensor_type: String,
+    display_units: Option<String>,
+    station_id: Uuid,
+    station_name: String,
+    last_data_time: Option<DateTime<Utc>>,
+    last_sync_attempt: Option<DateTime<Utc>>,
+    sync_status: Option<SyncStatus>,
+    error_message: Option<String>,
+    retry_count: Option<i32>,
+    last_full_sync: Option<DateTime<Utc>>,
+    staleness_seconds: Option<i64>,
+}
+
+/// Get the last readings sync pass
+///
+/// Returns the time window the last successful readings sync requested from
+/// Vaisala and how many points it inserted. Useful to check whether sync is
+/// working without reading the logs.
+#[utoipa::path(
+    get,
+    path = "/api/v1/sync/last-pass",
+    responses(
+        (status = 200, description = "Last sync pass retrieved successfully", body = SyncPassResponse),
+        (status = 404, description = "No sync pass has completed yet"),
+    ),
+    tag = "sync"
+)]
+pub async fn get_last_sync_pass(State(state): State<AppState>) -> AppResult<Json<SyncPassResponse>> {
+    let last = state
+        .last_sync_pass
+        .read()
+        .map_err(|e| AppError::Internal(e.to_string()))?
+        .clone()
+        // No sync pass has completed yet
+        .ok_or_else(|| AppError::not_found("sync_pass", "last"))?;
+
+    Ok(Json(SyncPassResponse {
+        started_at: last.started_at,
+        from: last.from,
+        to: last.to,
+        sensors: last.sensors,
+        points_inserted: last.points_inserted,
+        backfill_points: last.backfill_points,
+        duration_ms: last.duration_ms,
+        full_sync: last.full_sync,
+    }))
+}
+
+/// List recent sync runs
+///
+/// Returns recorded scheduler iterations, newest first, with their outcome
+/// and error. Useful to correlate data gaps with sync failures. History is
+/// kept for `SYNC_RUNS_RETENTION_DAYS`.
+#[utoipa::path(
+    get,
+    path = "/api/v1/sync/runs",
+    params(SyncRunsQuery),
+    responses(
+        (status = 200, description = "Sync runs retrieved successfully", body = Vec<SyncRunResponse>),
+        (status = 400, description = "Unknown task"),
+    ),
+    tag = "sync"
+)]
+pub async fn list_sync_runs(
+    State(state): State<AppState>,
+    Query(query): Query<SyncRunsQuery>,
+) -> AppResult<Json<Vec<SyncRunResponse>>> {
+    let limit = query
+        .limit
+        .unwrap_or(state.config.api_default_page_size)
+        .clamp(1, state.config.api_max_page_size.max(1));
+
+    let mut db_query = sync_runs::Entity::find();
+    if let Some(task) = query.task.as_deref() {
+        if !SYNC_TASKS.contains(&task) {
+            return Err(AppError::BadRequest(format!(
+                "Unknown task: {task}. Valid tasks: {}",
+                SYNC_TASKS.join(", ")
+            )));
+        }
+        db_query = db_query.filter(sync_runs::Column::Task.eq(task));
+    }
+
+    let runs = db_query
+        .order_by_desc(sync_runs::Column::StartedAt)
+        .limit(limit)
+        .all(&state.read_db)
+        .await?;
+
+    Ok(Json(
+        runs.into_iter()
+            .map(|r| SyncRunResponse {
+                id: r.id,
+                task: r.task,
+                started_at: r.started_at.with_timezone(&Utc),
+                finished_at: r.finished_at.with_timezone(&Utc),
+                status: r.status,
+                records_affected: r.records_affected,
+                error: r.error,
+            })
+            .collect(),
+    ))
+}
+
+/// Get per-sensor sync status
+///
+/// Returns the sync state of every active sensor with its station: newest
+/// stored reading, last attempt and its outcome, and how stale the data is.
+/// Inactive sensors are left out, as the sync worker no longer fetches them.
+/// Sensors never seen by the sync worker have null sync fields. Ordered by station and
+/// sensor name.
+#[utoipa::path(
+    get,
+    path = "/api/v1/sync/status",
+    params(SyncStatusQuery),
+    responses(
+        (status = 200, description = "Sync status retrieved successfully", body = Vec<SensorSyncStatusResponse>),
+        (status = 400, description = "Invalid status or negative stale_over_seconds"),
+    ),
+    tag = "sync"
+)]
+pub async fn get_sync_status(
+    State(state): State<AppState>,
+    Query(query): Query<SyncStatusQuery>,
+) -> AppResult<Json<Vec<SensorSyncStatusResponse>>> {
+    if query.stale_over_seconds.is_some_and(|s| s < 0) {
+        return Err(AppError::BadRequest(
+            "stale_over_seconds must not be negative".to_string(),
+        ));
+    }
+
+    let sql = "SELECT s.id AS sensor_id,
+                s.name AS sensor_name,
+                s.sensor_type,
+                s.display_units,
+                st.id AS station_id,
+                st.name AS station_name,
+                ss.last_data_time,
+                ss.last_sync_attempt,
+                ss.sync_status,
+                ss.error_message,
+                ss.retry_count,
+                ss.last_full_sync,
+                EXTRACT(EPOCH FROM NOW() - ss.last_data_time)::bigint AS staleness_seconds
+         FROM sensors s
+         JOIN stations st ON st.id = s.station_id
+         LEFT JOIN sync_state ss ON ss.sensor_id = s.id
+         WHERE s.is_active = true
+           AND ($1::text IS NULL OR ss.sync_status = $1)
+           AND ($2::bigint IS NULL
+                OR ss.last_data_time IS NULL
+                OR ss.last_data_time < NOW() - $2 * INTERVAL '1 second')
+         ORDER BY st.name, s.name";
+
+    let status = query.status.map(|s| s.to_value());
+    let rows = state
+        .read_db
+        .query_all(Statement::from_sql_and_values(
+            sea_orm::DatabaseBackend::Postgres,
+            sql,
+            [status.into(), query.stale_over_seconds.into()],
+        ))
+        .await?
+        .iter()
+        .map(|row| SensorSyncRow::from_query_result(row, ""))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let response = rows
+        .into_iter()
+        .map(|r| SensorSyncStatusResponse {
+            sensor: SensorRef {
+                id: r.sensor_id,
+                name: r.sensor_name,
+                sensor_type: r.sensor_type,
+                units: r.display_units,
+            },
+            station: StationRef {
+                id: r.station_id,
+                name: r.station_name,
+            },
+            last_data_time: r.last_data_time,
+            last_sync_attempt: r.last_sync_attempt,
+            sync_status: r.sync_status,
+            error_message: r.error_message,
+            retry_count: r.retry_count,
+            last_full_sync: r.last_full_sync,
+            staleness_seconds: r.staleness_seconds,
+        })
+        .collect();
+
+    Ok(Json(response))
+}
@@ -0,0 +1,10 @@
+mod handlers;
+mod types;
+
+pub use handlers::{get_last_sync_pass, get_sync_status, list_sync_runs};
+pub use types::{
+    SensorSyncStatusResponse, SyncPassResponse, SyncRunResponse, SyncRunsQuery, SyncStatusQuery,
+};
+
+// Re-export utoipa path structs for OpenAPI documentation
+pub use handlers::{__path_get_last_sync_pass, __path_get_sync_status, __path_list_sync_runs};
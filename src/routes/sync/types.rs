@@ -0,0 +1,83 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::entity::sync_runs::SyncRunStatus;
+use crate::entity::sync_state::SyncStatus;
+use crate::routes::sensors::SensorRef;
+use crate::routes::stations::StationRef;
+
+/// Summary of the most recent readings sync pass
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SyncPassResponse {
+    /// When the pass started
+    pub started_at: DateTime<Utc>,
+    /// Start of the window requested from Vaisala
+    pub from: DateTime<Utc>,
+    /// End of the window requested from Vaisala
+    pub to: DateTime<Utc>,
+    /// Number of sensors included in the request
+    pub sensors: usize,
+    /// Rows inserted across all sensors
+    pub points_inserted: u64,
+    /// Inserted rows older than their sensor's previous newest reading, i.e.
+    /// history Vaisala delivered late. Only full re-syncs fetch such points.
+    pub backfill_points: u64,
+    /// Wall-clock duration of the pass, including retries
+    pub duration_ms: u64,
+    /// Whether this was a full re-sync rather than incremental
+    pub full_sync: bool,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct SyncRunsQuery {
+    /// Only runs of this task (`readings`, `device_status`, `alarms`, `events`)
+    pub task: Option<String>,
+    /// Maximum number of runs (default `API_DEFAULT_PAGE_SIZE`, capped at `API_MAX_PAGE_SIZE`)
+    pub limit: Option<u64>,
+}
+
+/// One recorded scheduler iteration
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SyncRunResponse {
+    pub id: Uuid,
+    /// Scheduler task
+    pub task: String,
+    pub started_at: DateTime<Utc>,
+    /// When the iteration finished, including retries and aggregate refreshes
+    pub finished_at: DateTime<Utc>,
+    pub status: SyncRunStatus,
+    /// Rows written, for tasks that report it (readings)
+    pub records_affected: Option<i64>,
+    /// Error of the last attempt, for failed runs
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct SyncStatusQuery {
+    /// Only sensors whose last sync ended with this status (`pending`, `success`, `error`)
+    pub status: Option<SyncStatus>,
+    /// Only sensors whose newest reading is older than this many seconds,
+    /// including sensors with no data at all
+    pub stale_over_seconds: Option<i64>,
+}
+
+/// Sync state of one sensor
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SensorSyncStatusResponse {
+    pub sensor: SensorRef,
+    pub station: StationRef,
+    /// Timestamp of the newest stored reading
+    pub last_data_time: Option<DateTime<Utc>>,
+    pub last_sync_attempt: Option<DateTime<Utc>>,
+    /// Null if the sensor was never synced
+    pub sync_status: Option<SyncStatus>,
+    /// Last error reported by the sync worker
+    pub error_message: Option<String>,
+    pub retry_count: Option<i32>,
+    /// When the sensor's history was last fully re-synced
+    pub last_full_sync: Option<DateTime<Utc>>,
+    /// Seconds since `last_data_time` (null when the sensor has no data)
+    pub staleness_seconds: Option<i64>,
+}
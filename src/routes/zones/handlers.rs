@@ -1,30 +1,39 @@
+use std::collections::HashMap;
+
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
+    response::Response,
     Json,
 };
 use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, QueryOrder};
+use uuid::Uuid;
 
+use crate::common::envelope::{list_response, EnvelopeQuery};
 use crate::common::AppState;
-use crate::entity::{stations, zones};
+use crate::entity::{sensors, stations, zones};
 use crate::error::AppResult;
-use crate::routes::resolve_zone;
-use crate::routes::stations::StationResponse;
+use crate::routes::{cache, resolve_zone};
+use crate::routes::stations::{SensorResponse, StationResponse};
 
-use super::types::ZoneResponse;
+use super::types::{HierarchyQuery, HierarchyResponse, HierarchyStation, HierarchyZone, ZoneResponse};
 
 /// List all zones
 #[utoipa::path(
     get,
-    path = "/api/zones",
+    path = "/api/v1/zones",
+    params(EnvelopeQuery),
     responses(
-        (status = 200, description = "Zones retrieved successfully", body = Vec<ZoneResponse>),
+        (status = 200, description = "Zones retrieved successfully. Wrapped in `{data, meta}` with `envelope=true`", body = Vec<ZoneResponse>),
     ),
     tag = "zones"
 )]
-pub async fn list_zones(State(state): State<AppState>) -> AppResult<Json<Vec<ZoneResponse>>> {
+pub async fn list_zones(
+    State(state): State<AppState>,
+    Query(query): Query<EnvelopeQuery>,
+) -> AppResult<Response> {
     let zones_list = zones::Entity::find()
         .order_by_asc(zones::Column::Name)
-        .all(&state.db)
+        .all(&state.read_db)
         .await?;
 
     let response: Vec<ZoneResponse> = zones_list
@@ -36,13 +45,13 @@ pub async fn list_zones(State(state): State<AppState>) -> AppResult<Json<Vec<Zon
         })
         .collect();
 
-    Ok(Json(response))
+    Ok(list_response(response, query.envelope))
 }
 
 /// Get a specific zone by ID or name
 #[utoipa::path(
     get,
-    path = "/api/zones/{zone_id}",
+    path = "/api/v1/zones/{zone_id}",
     params(
         ("zone_id" = String, Path, description = "Zone UUID or name"),
     ),
@@ -56,7 +65,7 @@ pub async fn get_zone(
     State(state): State<AppState>,
     Path(zone_id): Path<String>,
 ) -> AppResult<Json<ZoneResponse>> {
-    let zone = resolve_zone(&state.db, &zone_id).await?;
+    let zone = resolve_zone(&state.read_db, &zone_id).await?;
 
     Ok(Json(ZoneResponse {
         id: zone.id,
@@ -68,12 +77,13 @@ pub async fn get_zone(
 /// List stations belonging to a zone
 #[utoipa::path(
     get,
-    path = "/api/zones/{zone_id}/stations",
+    path = "/api/v1/zones/{zone_id}/stations",
     params(
         ("zone_id" = String, Path, description = "Zone UUID or name"),
+        EnvelopeQuery,
     ),
     responses(
-        (status = 200, description = "Stations retrieved successfully", body = Vec<StationResponse>),
+        (status = 200, description = "Stations retrieved successfully. Wrapped in `{data, meta}` with `envelope=true`", body = Vec<StationResponse>),
         (status = 404, description = "Zone not found"),
     ),
     tag = "zones"
@@ -81,13 +91,14 @@ pub async fn get_zone(
 pub async fn list_zone_stations(
     State(state): State<AppState>,
     Path(zone_id): Path<String>,
-) -> AppResult<Json<Vec<StationResponse>>> {
-    let zone = resolve_zone(&state.db, &zone_id).await?;
+    Query(query): Query<EnvelopeQuery>,
+) -> AppResult<Response> {
+    let zone = resolve_zone(&state.read_db, &zone_id).await?;
 
     let stations_list = stations::Entity::find()
         .filter(stations::Column::ZoneId.eq(zone.id))
         .order_by_asc(stations::Column::Name)
-        .all(&state.db)
+        .all(&state.read_db)
         .await?;
 
     let response: Vec<StationResponse> = stations_list
@@ -102,5 +113,117 @@ pub async fn list_zone_stations(
         })
         .collect();
 
-    Ok(Json(response))
+    Ok(list_response(response, query.envelope))
+}
+
+/// Get the zone → station → sensor tree
+///
+/// Returns every zone with its stations and their sensors in one response.
+/// Only active sensors are included unless `include_inactive=true`.
+#[utoipa::path(
+    get,
+    path = "/api/v1/hierarchy",
+    params(HierarchyQuery),
+    responses(
+        (status = 200, description = "Hierarchy retrieved successfully", body = HierarchyResponse),
+        (status = 404, description = "Zone not found"),
+    ),
+    tag = "zones"
+)]
+pub async fn get_hierarchy(
+    State(state): State<AppState>,
+    Query(query): Query<HierarchyQuery>,
+) -> AppResult<Response> {
+    let zone = match &query.zone_id {
+        Some(id) => Some(resolve_zone(&state.read_db, id).await?),
+        None => None,
+    };
+
+    let cache_key = cache::cache_key(
+        "hierarchy",
+        &[
+            &zone.as_ref().map(|z| z.id.to_string()).unwrap_or_default(),
+            &query.include_inactive.to_string(),
+        ],
+    );
+
+    // Unbounded entry: expires after the short CACHE_UNBOUNDED_TTL_SECONDS
+    if let Some(cached) = cache::get_cached(&state, &cache_key, &[], None).await {
+        return cache::json_response((*cached).to_vec(), true);
+    }
+
+    let zones_list = match zone {
+        Some(z) => vec![z],
+        None => {
+            zones::Entity::find()
+                .order_by_asc(zones::Column::Name)
+                .all(&state.read_db)
+                .await?
+        }
+    };
+
+    let mut stations_query = stations::Entity::find().order_by_asc(stations::Column::Name);
+    if query.zone_id.is_some() {
+        stations_query =
+            stations_query.filter(stations::Column::ZoneId.is_in(zones_list.iter().map(|z| z.id)));
+    }
+    let stations_list = stations_query.all(&state.read_db).await?;
+
+    let mut sensors_query = sensors::Entity::find()
+        .filter(sensors::Column::StationId.is_in(stations_list.iter().map(|s| s.id)))
+        .order_by_asc(sensors::Column::Name);
+    if !query.include_inactive {
+        sensors_query = sensors_query.filter(sensors::Column::IsActive.eq(true));
+    }
+    let sensors_list = sensors_query.all(&state.read_db).await?;
+
+    let mut sensors_by_station: HashMap<Uuid, Vec<SensorResponse>> = HashMap::new();
+    for s in sensors_list {
+        sensors_by_station
+            .entry(s.station_id)
+            .or_default()
+            .push(SensorResponse {
+                id: s.id,
+                name: s.name,
+                sensor_type: s.sensor_type,
+                display_units: s.display_units,
+                sample_interval_sec: s.sample_interval_sec,
+                is_active: s.is_active,
+                alarming_paused: s.alarming_paused,
+                has_active_alarm: None,
+                max_active_severity: None,
+            });
+    }
+
+    let mut stations_by_zone: HashMap<Option<Uuid>, Vec<HierarchyStation>> = HashMap::new();
+    for s in stations_list {
+        stations_by_zone
+            .entry(s.zone_id)
+            .or_default()
+            .push(HierarchyStation {
+                sensors: sensors_by_station.remove(&s.id).unwrap_or_default(),
+                id: s.id,
+                name: s.name,
+                latitude: s.latitude,
+                longitude: s.longitude,
+                altitude_m: s.altitude_m,
+            });
+    }
+
+    let zones_out: Vec<HierarchyZone> = zones_list
+        .into_iter()
+        .map(|z| HierarchyZone {
+            stations: stations_by_zone.remove(&Some(z.id)).unwrap_or_default(),
+            id: z.id,
+            name: z.name,
+            description: z.description,
+        })
+        .collect();
+
+    let response = HierarchyResponse {
+        zones: zones_out,
+        unassigned_stations: stations_by_zone.remove(&None).unwrap_or_default(),
+    };
+
+    cache::cache_and_respond(&state, cache_key, &response, None, false, None).await
 }
@@ -1,8 +1,8 @@
 mod handlers;
 mod types;
 
-pub use handlers::{get_zone, list_zone_stations, list_zones};
-pub use types::ZoneResponse;
+pub use handlers::{get_hierarchy, get_zone, list_zone_stations, list_zones};
+pub use types::{HierarchyQuery, HierarchyResponse, HierarchyStation, HierarchyZone, ZoneResponse};
 
 // Re-export utoipa path structs for OpenAPI documentation
-pub use handlers::{__path_get_zone, __path_list_zone_stations, __path_list_zones};
+pub use handlers::{__path_get_hierarchy, __path_get_zone, __path_list_zone_stations, __path_list_zones};
@@ -1,10 +1,49 @@
-use serde::Serialize;
-use utoipa::ToSchema;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 
+use crate::routes::stations::SensorResponse;
+
 #[derive(Debug, Serialize, ToSchema)]
 pub struct ZoneResponse {
     pub id: Uuid,
     pub name: String,
     pub description: Option<String>,
 }
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct HierarchyQuery {
+    /// Include inactive sensors (default: false)
+    #[serde(default)]
+    pub include_inactive: bool,
+    /// Only return this zone (UUID or name)
+    pub zone_id: Option<String>,
+}
+
+/// A station with its sensors, nested inside a zone
+#[derive(Debug, Serialize, ToSchema)]
+pub struct HierarchyStation {
+    pub id: Uuid,
+    pub name: String,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+    pub altitude_m: Option<f64>,
+    pub sensors: Vec<SensorResponse>,
+}
+
+/// A zone with its stations
+#[derive(Debug, Serialize, ToSchema)]
+pub struct HierarchyZone {
+    pub id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    pub stations: Vec<HierarchyStation>,
+}
+
+/// Full zone → station → sensor tree
+#[derive(Debug, Serialize, ToSchema)]
+pub struct HierarchyResponse {
+    pub zones: Vec<HierarchyZone>,
+    /// Stations not assigned to any zone (empty when scoped to a zone)
+    pub unassigned_stations: Vec<HierarchyStation>,
+}
@@ -7,6 +7,7 @@
 //!
 //! - **Unbounded queries** (no end time): Check for new data on each request.
 //!   If new readings exist beyond the cached max_time, invalidate and refresh.
+//!   These expire after the shorter `CACHE_UNBOUNDED_TTL_SECONDS`.
 //!
 //! # Usage
 //!
@@ -21,32 +22,57 @@
 //!
 //! // ... compute response ...
 //!
-//! // Cache and return
-//! cache::cache_and_respond(&state, cache_key, &response, actual_end).await
+//! // Cache and return (bounded selects the longer TTL, None keeps the defaults)
+//! cache::cache_and_respond(&state, cache_key, &response, actual_end, query.end.is_some(), None).await
 //! ```
 //!
 //! # Cache Invalidation Strategy
 //!
 //! | Query Type | Invalidation |
 //! |------------|--------------|
-//! | Bounded (end specified) | TTL only - data won't change |
-//! | Unbounded (no end) | TTL + freshness check via MAX(time) |
+//! | Bounded (end specified) | `CACHE_TTL_SECONDS` only - data won't change |
+//! | Unbounded (no end) | `CACHE_UNBOUNDED_TTL_SECONDS` + freshness check via MAX(time) |
 //!
 //! The freshness check queries `MAX(time)` for the relevant sensors (~1-2ms)
 //! and compares against the cached response's max_time. If new data exists,
 //! the cache entry is invalidated and fresh data is fetched.
+//!
+//! In addition, the readings sync invalidates the entries of every station
+//! whose sensors received new data at the end of each pass
+//! ([`invalidate_sensors`]), so the next request usually skips the stale hit.
+//!
+//! Routes with different freshness needs pass a per-entry TTL override to
+//! [`cache_and_respond`], which takes precedence over both defaults: station
+//! latest readings and rolling stats use `CACHE_LATEST_TTL_SECONDS`, and
+//! aggregates use the longer `CACHE_AGGREGATES_TTL_SECONDS`. All entries share
+//! one cache and its byte budget.
+//!
+//! With `DATABASE_REPLICA_URL` set, the check runs on the replica, so readings
+//! written by the sync worker become visible only after replication lag.
 
 use axum::{
     http::{header, HeaderValue},
     response::Response,
 };
 use chrono::{DateTime, Utc};
-use sea_orm::{ConnectionTrait, FromQueryResult, Statement};
+use sea_orm::{ColumnTrait, ConnectionTrait, EntityTrait, FromQueryResult, QueryFilter, Statement};
 use serde::Serialize;
+use std::collections::BTreeSet;
 use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
 
 use crate::common::{AppState, CachedResponse};
+use crate::entity::sensors;
 use crate::error::{AppError, AppResult};
+use crate::services::metrics;
+
+/// Cache key prefixes of per-station data endpoints, keyed by station ID first
+const STATION_CACHE_PREFIXES: [&str; 4] = ["readings", "aggregates", "multiscale", "latest"];
+
+/// Key of the sensor catalog, dropped by the sync when sensors, their alarming
+/// paused flags or their thresholds change
+pub const CATALOG_CACHE_KEY: &str = "catalog";
 
 /// Result of checking the latest data time in the database
 #[derive(Debug, FromQueryResult)]
@@ -93,7 +119,7 @@ pub async fn get_latest_time(
     );
 
     let result = state
-        .db
+        .read_db
         .query_one(Statement::from_string(
             sea_orm::DatabaseBackend::Postgres,
             sql,
@@ -101,7 +127,8 @@ pub async fn get_latest_time(
         .await?;
 
     Ok(result
-        .and_then(|row| MaxTimeRow::from_query_result(&row, "").ok())
+        .map(|row| MaxTimeRow::from_query_result(&row, ""))
+        .transpose()?
         .and_then(|r| r.max_time))
 }
 
@@ -134,7 +161,10 @@ pub async fn get_cached(
     sensor_ids: &[uuid::Uuid],
     query_end: Option<DateTime<Utc>>,
 ) -> Option<Arc<Vec<u8>>> {
-    let cached = state.response_cache.get(cache_key).await?;
+    let Some(cached) = state.response_cache.get(cache_key).await else {
+        metrics::record_cache_lookup(false);
+        return None;
+    };
 
     // Only do freshness check for unbounded queries (no end time specified)
     // Bounded queries asking for historical data won't change
@@ -150,6 +180,7 @@ pub async fn get_cached(
                         "cache_stale"
                     );
                     state.response_cache.invalidate(cache_key).await;
+                    metrics::record_cache_lookup(false);
                     return None;
                 }
             }
@@ -157,6 +188,7 @@ pub async fn get_cached(
     }
 
     tracing::debug!(cache_key = %cache_key, "cache_hit");
+    metrics::record_cache_lookup(true);
     Some(cached.data.clone())
 }
 
@@ -168,11 +200,15 @@ pub async fn get_cached(
 /// * `cache_key` - Unique key for this query
 /// * `data` - Serialized response data
 /// * `max_time` - The latest timestamp in the response data (for freshness tracking)
+/// * `bounded` - Whether the query had an end time (selects the TTL)
+/// * `ttl` - Per-route TTL override, or None for the bounded/unbounded default
 pub async fn store_cached(
     state: &AppState,
     cache_key: String,
     data: Vec<u8>,
     max_time: Option<DateTime<Utc>>,
+    bounded: bool,
+    ttl: Option<Duration>,
 ) {
     let size = data.len();
     state
@@ -182,6 +218,8 @@ pub async fn store_cached(
             CachedResponse {
                 data: Arc::new(data),
                 max_time,
+                bounded,
+                ttl,
             },
         )
         .await;
@@ -190,6 +228,7 @@ pub async fn store_cached(
         cache_key = %cache_key,
         size_bytes = size,
         max_time = ?max_time,
+        ttl = ?ttl,
         "cache_stored"
     );
 }
@@ -225,6 +264,8 @@ pub fn json_response(data: Vec<u8>, cache_hit: bool) -> AppResult<Response> {
 /// * `cache_key` - Unique key for this query
 /// * `response` - Response struct to serialize
 /// * `max_time` - Latest timestamp in response (for freshness tracking)
+/// * `bounded` - Whether the query had an end time (selects the TTL)
+/// * `ttl` - Per-route TTL override, or None for the bounded/unbounded default
 ///
 /// # Returns
 ///
@@ -234,11 +275,13 @@ pub async fn cache_and_respond<T: Serialize>(
     cache_key: String,
     response: &T,
     max_time: Option<DateTime<Utc>>,
+    bounded: bool,
+    ttl: Option<Duration>,
 ) -> AppResult<Response> {
     let json_bytes = serde_json::to_vec(response)
         .map_err(|e| AppError::Internal(e.to_string()))?;
 
-    store_cached(state, cache_key, json_bytes.clone(), max_time).await;
+    store_cached(state, cache_key, json_bytes.clone(), max_time, bounded, ttl).await;
 
     json_response(json_bytes, false)
 }
@@ -257,9 +300,69 @@ pub async fn invalidate(state: &AppState, cache_key: &str) {
 /// Useful for invalidating all cached data for a specific station
 /// or data type.
 pub async fn invalidate_prefix(state: &AppState, prefix: &str) {
-    let prefix_owned = prefix.to_string();
-    let _ = state.response_cache.invalidate_entries_if(move |key, _| {
-        key.starts_with(&prefix_owned)
-    });
-    tracing::debug!(prefix = %prefix, "cache_prefix_invalidated");
+    invalidate_prefixes(state, vec![prefix.to_string()]).await;
+}
+
+/// Invalidate all cache entries matching any of `prefixes`.
+///
+/// Registers a single predicate with the cache, so invalidating many
+/// stations at once costs one scan rather than one per prefix.
+pub async fn invalidate_prefixes(state: &AppState, prefixes: Vec<String>) {
+    if prefixes.is_empty() {
+        return;
+    }
+    let count = prefixes.len();
+    let result = state
+        .response_cache
+        .invalidate_entries_if(move |key, _| prefixes.iter().any(|p| key.starts_with(p.as_str())));
+    match result {
+        Ok(_) => tracing::debug!(prefixes = count, "cache_prefix_invalidated"),
+        Err(e) => tracing::warn!(error = %e, "cache_prefix_invalidation_failed"),
+    }
+}
+
+/// Invalidate cached data for sensors that just received new readings.
+///
+/// Drops the station-level readings, aggregates, multiscale and latest
+/// entries of each sensor's station, plus the sensor's rolling stats, so the next
+/// request is served fresh without waiting for the `MAX(time)` freshness
+/// check. The sync scheduler calls this once per pass with every updated
+/// sensor, which keeps a full re-sync from invalidating station by station.
+pub async fn invalidate_sensors(state: &AppState, sensor_ids: &[Uuid]) {
+    if sensor_ids.is_empty() {
+        return;
+    }
+
+    let station_ids: BTreeSet<Uuid> = match sensors::Entity::find()
+        .filter(sensors::Column::Id.is_in(sensor_ids.to_vec()))
+        .all(&state.db)
+        .await
+    {
+        Ok(rows) => rows.into_iter().map(|s| s.station_id).collect(),
+        Err(e) => {
+            tracing::warn!(error = %e, "Failed to map synced sensors to stations");
+            return;
+        }
+    };
+
+    let mut prefixes: Vec<String> = station_ids
+        .iter()
+        .flat_map(|id| {
+            let id = id.to_string();
+            // UUIDs have a fixed length, so `prefix:<id>` cannot match another station
+            STATION_CACHE_PREFIXES.map(|prefix| cache_key(prefix, &[&id]))
+        })
+        .collect();
+    prefixes.extend(
+        sensor_ids
+            .iter()
+            .map(|id| cache_key("rolling_stats", &[&id.to_string()])),
+    );
+
+    tracing::debug!(
+        sensors = sensor_ids.len(),
+        stations = station_ids.len(),
+        "cache_invalidated_after_sync"
+    );
+    invalidate_prefixes(state, prefixes).await;
 }
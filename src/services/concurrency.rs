@@ -0,0 +1,199 @@
+//! Per-client concurrency limiting for data and streaming routes.
+//!
+//! Rate limiting bounds how often a client may start requests, but not how many
+//! long-lived responses (bulk CSV/NDJSON) it holds open at once. This limiter
+//! caps in-flight requests per client key (see [`FallbackIpKeyExtractor`]) and
+//! rejects the excess with 503. A slot is released only once the response body
+//! has been fully sent or dropped, so streamed responses count for their whole
+//! lifetime.
+//!
+//! [`InFlightRequests`] counts requests across all routes the same way, for
+//! reporting what a shutdown would cut off.
+
+use axum::{
+    body::{Body, Bytes},
+    extract::{Request, State},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use http_body::{Body as HttpBody, Frame, SizeHint};
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::task::{Context, Poll};
+use tower_governor::key_extractor::KeyExtractor;
+
+use crate::error::AppError;
+use crate::services::FallbackIpKeyExtractor;
+
+/// Tracks in-flight requests per client IP.
+#[derive(Debug, Clone)]
+pub struct PerClientConcurrency {
+    limit: usize,
+    in_flight: Arc<Mutex<HashMap<IpAddr, usize>>>,
+}
+
+/// Held for the lifetime of a request; frees the client's slot on drop.
+#[derive(Debug)]
+pub struct ConcurrencyPermit {
+    key: IpAddr,
+    in_flight: Arc<Mutex<HashMap<IpAddr, usize>>>,
+}
+
+impl PerClientConcurrency {
+    pub fn new(limit: usize) -> Self {
+        Self {
+            limit,
+            in_flight: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Claim a slot for `key`, or `None` if the client is at its limit.
+    ///
+    /// A poisoned lock is recovered rather than treated as "at limit": the
+    /// counts are only ever updated in single steps, so they stay consistent.
+    pub fn try_acquire(&self, key: IpAddr) -> Option<ConcurrencyPermit> {
+        let mut in_flight = self.in_flight.lock().unwrap_or_else(PoisonError::into_inner);
+        let count = in_flight.entry(key).or_insert(0);
+        if *count >= self.limit {
+            return None;
+        }
+        *count += 1;
+
+        Some(ConcurrencyPermit {
+            key,
+            in_flight: self.in_flight.clone(),
+        })
+    }
+}
+
+impl Drop for ConcurrencyPermit {
+    fn drop(&mut self) {
+        let mut in_flight = self.in_flight.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Some(count) = in_flight.get_mut(&self.key) {
+            *count = count.saturating_sub(1);
+            if *count == 0 {
+                in_flight.remove(&self.key);
+            }
+        }
+    }
+}
+
+/// Middleware enforcing the per-client limit.
+///
+/// Use with `axum::middleware::from_fn_with_state`.
+pub async fn limit_per_client(
+    State(limiter): State<PerClientConcurrency>,
+    req: Request,
+    next: Next,
+) -> Response {
+    let Ok(key) = FallbackIpKeyExtractor.extract(&req) else {
+        return next.run(req).await;
+    };
+
+    let Some(permit) = limiter.try_acquire(key) else {
+        tracing::warn!(client = %key, limit = limiter.limit, "client_concurrency_rejected");
+        return AppError::ServiceUnavailable(
+            "Too many concurrent requests from this client. Please try again later.".to_string(),
+        )
+        .into_response();
+    };
+
+    let (parts, body) = next.run(req).await.into_parts();
+
+    // Keep the permit alive until the body stream finishes or is dropped
+    Response::from_parts(parts, guarded(body, permit))
+}
+
+/// Response body that holds `guard` until the body is finished or dropped.
+///
+/// Frames, `size_hint` and `is_end_stream` are passed through, so responses of
+/// known size keep their `Content-Length`.
+struct GuardedBody<G> {
+    inner: Body,
+    guard: Option<G>,
+}
+
+fn guarded<G: Send + Unpin + 'static>(inner: Body, guard: G) -> Body {
+    Body::new(GuardedBody {
+        inner,
+        guard: Some(guard),
+    })
+}
+
+impl<G: Unpin> HttpBody for GuardedBody<G> {
+    type Data = Bytes;
+    type Error = axum::Error;
+
+    fn poll_frame(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Bytes>, axum::Error>>> {
+        let frame = Pin::new(&mut self.inner).poll_frame(cx);
+        if matches!(frame, Poll::Ready(None)) {
+            self.guard = None;
+        }
+        frame
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.inner.is_end_stream()
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        self.inner.size_hint()
+    }
+}
+
+/// Number of requests whose response has not been fully sent yet.
+#[derive(Debug, Clone, Default)]
+pub struct InFlightRequests {
+    count: Arc<AtomicUsize>,
+}
+
+/// Held for the lifetime of a request; decrements the count on drop.
+#[derive(Debug)]
+pub struct InFlightGuard {
+    count: Arc<AtomicUsize>,
+}
+
+impl InFlightRequests {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Count a request until the returned guard is dropped.
+    pub fn track(&self) -> InFlightGuard {
+        self.count.fetch_add(1, Ordering::Relaxed);
+        InFlightGuard {
+            count: self.count.clone(),
+        }
+    }
+
+    pub fn count(&self) -> usize {
+        self.count.load(Ordering::Relaxed)
+    }
+}
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        self.count.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Middleware counting every request until its response body is done.
+///
+/// Use with `axum::middleware::from_fn_with_state`.
+pub async fn track_in_flight(
+    State(in_flight): State<InFlightRequests>,
+    req: Request,
+    next: Next,
+) -> Response {
+    let guard = in_flight.track();
+    let (parts, body) = next.run(req).await.into_parts();
+
+    // Same as `limit_per_client`: streamed bodies count until finished or dropped
+    Response::from_parts(parts, guarded(body, guard))
+}
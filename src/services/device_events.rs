@@ -0,0 +1,123 @@
+//! Device status change events.
+//!
+//! The device status sync publishes a [`DeviceStatusEvent`] whenever a
+//! sensor's decoded battery or status label (or the raw state behind them)
+//! differs from its previous status row. Subscribers read them through
+//! `GET /api/v1/device-status/stream`.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tokio::sync::broadcast;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Events buffered per subscriber before slow ones start missing events
+pub const DEVICE_EVENTS_CAPACITY: usize = 256;
+
+/// Battery level (percent) at or below which the battery is reported `low`
+pub const BATTERY_LOW_PERCENT: i16 = 25;
+
+/// Battery level (percent) at or below which the battery is reported `critical`
+pub const BATTERY_CRITICAL_PERCENT: i16 = 10;
+
+/// Sender half shared by the sync worker and the stream endpoint
+pub type DeviceEventSender = broadcast::Sender<DeviceStatusEvent>;
+
+/// Create the device status channel.
+pub fn channel() -> DeviceEventSender {
+    broadcast::channel(DEVICE_EVENTS_CAPACITY).0
+}
+
+/// Decoded device status fields compared between two status rows
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeviceSnapshot {
+    pub battery_level: Option<i16>,
+    pub battery_state: Option<i16>,
+    pub signal_quality: Option<i16>,
+    pub device_status: Option<String>,
+    pub unreachable: Option<bool>,
+}
+
+impl DeviceSnapshot {
+    /// `ok`, `low`, `critical` or `unknown` (no level reported)
+    pub fn battery_label(&self) -> &'static str {
+        match self.battery_level {
+            Some(level) if level < 0 => "unknown",
+            Some(level) if level <= BATTERY_CRITICAL_PERCENT => "critical",
+            Some(level) if level <= BATTERY_LOW_PERCENT => "low",
+            Some(_) => "ok",
+            None => "unknown",
+        }
+    }
+
+    /// `unreachable`, `ok` (no or an `OK` device status) or `fault`
+    pub fn status_label(&self) -> &'static str {
+        if self.unreachable == Some(true) {
+            return "unreachable";
+        }
+        match self.device_status.as_deref().map(str::trim) {
+            None | Some("") => "ok",
+            Some(s) if s.eq_ignore_ascii_case("ok") => "ok",
+            Some(_) => "fault",
+        }
+    }
+
+    /// Whether `self` differs from `previous` in a way worth publishing.
+    ///
+    /// Raw battery level and signal quality drift between every poll, so only
+    /// the battery label, battery state, device status and reachability count.
+    pub fn changed_from(&self, previous: &Self) -> bool {
+        self.battery_label() != previous.battery_label()
+            || self.status_label() != previous.status_label()
+            || self.battery_state != previous.battery_state
+            || self.device_status != previous.device_status
+            || self.unreachable != previous.unreachable
+    }
+}
+
+/// A sensor's device status changed
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct DeviceStatusEvent {
+    pub sensor_id: Uuid,
+    pub station_id: Uuid,
+    /// Sync time of the new status
+    pub time: DateTime<Utc>,
+    pub battery_level: Option<i16>,
+    pub battery_state: Option<i16>,
+    /// `ok`, `low`, `critical` or `unknown`
+    pub battery_label: String,
+    /// Battery label of the previous status
+    pub previous_battery_label: String,
+    pub signal_quality: Option<i16>,
+    pub device_status: Option<String>,
+    pub unreachable: Option<bool>,
+    /// `ok`, `fault` or `unreachable`
+    pub status_label: String,
+    /// Status label of the previous status
+    pub previous_status_label: String,
+}
+
+impl DeviceStatusEvent {
+    pub fn new(
+        sensor_id: Uuid,
+        station_id: Uuid,
+        time: DateTime<Utc>,
+        current: DeviceSnapshot,
+        previous: &DeviceSnapshot,
+    ) -> Self {
+        Self {
+            sensor_id,
+            station_id,
+            time,
+            battery_label: current.battery_label().to_string(),
+            previous_battery_label: previous.battery_label().to_string(),
+            status_label: current.status_label().to_string(),
+            previous_status_label: previous.status_label().to_string(),
+            battery_level: current.battery_level,
+            battery_state: current.battery_state,
+            signal_quality: current.signal_quality,
+            device_status: current.device_status,
+            unreachable: current.unreachable,
+        }
+    }
+}
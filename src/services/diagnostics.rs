@@ -0,0 +1,106 @@
+//! Storage diagnostics for capacity planning.
+//!
+//! Reports the database size and, per TimescaleDB hypertable, its size, chunk
+//! count, compression savings and an approximate row count. Without the
+//! TimescaleDB extension (or on versions lacking these functions) only the
+//! database size is reported.
+
+use sea_orm::{ConnectionTrait, DatabaseConnection, FromQueryResult, Statement};
+
+use crate::error::AppResult;
+use crate::services::timescale;
+
+/// Storage statistics of one hypertable
+#[derive(Debug, FromQueryResult)]
+pub struct HypertableStats {
+    pub name: String,
+    /// Total size including indexes, TOAST and compressed chunks
+    pub total_bytes: Option<i64>,
+    pub chunk_count: i64,
+    pub compressed_chunk_count: i64,
+    /// Size of the compressed chunks before compression (null if compression is off)
+    pub before_compression_bytes: Option<i64>,
+    /// Size of the compressed chunks after compression (null if compression is off)
+    pub after_compression_bytes: Option<i64>,
+    /// Row estimate from TimescaleDB's statistics (run ANALYZE for accuracy)
+    pub row_estimate: Option<i64>,
+}
+
+impl HypertableStats {
+    /// Size before compression divided by size after, for compressed chunks
+    pub fn compression_ratio(&self) -> Option<f64> {
+        match (self.before_compression_bytes, self.after_compression_bytes) {
+            (Some(before), Some(after)) if after > 0 => Some(before as f64 / after as f64),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, FromQueryResult)]
+struct DatabaseSizeRow {
+    size_bytes: i64,
+}
+
+/// Size of the current database in bytes.
+///
+/// # Errors
+///
+/// Returns an error if the query fails.
+pub async fn database_size(db: &DatabaseConnection) -> AppResult<i64> {
+    let row = db
+        .query_one(Statement::from_string(
+            sea_orm::DatabaseBackend::Postgres,
+            "SELECT pg_database_size(current_database()) AS size_bytes",
+        ))
+        .await?;
+
+    Ok(row
+        .map(|r| DatabaseSizeRow::from_query_result(&r, ""))
+        .transpose()?
+        .map_or(0, |r| r.size_bytes))
+}
+
+/// Statistics of every hypertable, largest first.
+///
+/// Returns `None` when TimescaleDB or one of its size functions is missing.
+///
+/// # Errors
+///
+/// Returns an error if the query fails for another reason.
+pub async fn hypertable_stats(
+    db: &DatabaseConnection,
+) -> AppResult<Option<Vec<HypertableStats>>> {
+    let sql = "SELECT h.hypertable_name::text AS name,
+                hypertable_size(t.oid) AS total_bytes,
+                (SELECT COUNT(*) FROM chunks_detailed_size(t.oid)) AS chunk_count,
+                (SELECT COUNT(*)
+                 FROM timescaledb_information.chunks c
+                 WHERE c.hypertable_schema = h.hypertable_schema
+                   AND c.hypertable_name = h.hypertable_name
+                   AND c.is_compressed) AS compressed_chunk_count,
+                cs.before_compression_total_bytes AS before_compression_bytes,
+                cs.after_compression_total_bytes AS after_compression_bytes,
+                approximate_row_count(t.oid) AS row_estimate
+         FROM timescaledb_information.hypertables h
+         CROSS JOIN LATERAL (
+             SELECT format('%I.%I', h.hypertable_schema, h.hypertable_name)::regclass AS oid
+         ) t
+         LEFT JOIN LATERAL hypertable_compression_stats(t.oid) cs ON true
+         ORDER BY total_bytes DESC NULLS LAST, name";
+
+    match db
+        .query_all(Statement::from_string(sea_orm::DatabaseBackend::Postgres, sql))
+        .await
+    {
+        Ok(rows) => Ok(Some(
+            rows.iter()
+                .map(|row| HypertableStats::from_query_result(row, ""))
+                .collect::<Result<_, _>>()?,
+        )),
+        Err(e) if timescale::is_missing_object(&e) => {
+            tracing::warn!(error = %e, "Hypertable diagnostics unavailable");
+            Ok(None)
+        }
+        Err(e) => Err(e.into()),
+    }
+}
@@ -0,0 +1,55 @@
+//! Planned maintenance windows.
+//!
+//! During a window every sensor tends to alarm at once. Alarms are still
+//! synced and stored as usual. Nothing sends alarm notifications yet; when
+//! delivery lands it should consult [`active_window`] and hold back while one
+//! is in effect.
+
+use chrono::{DateTime, Utc};
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder};
+
+use crate::entity::maintenance_windows;
+use crate::error::AppResult;
+
+/// Whether `window` is in effect at `now` (start inclusive, end exclusive).
+pub fn covers(window: &maintenance_windows::Model, now: DateTime<Utc>) -> bool {
+    window.starts_at <= now && now < window.ends_at
+}
+
+/// The window in effect at `now`, if any. Overlapping windows resolve to the
+/// one that ends last.
+///
+/// # Errors
+///
+/// Returns an error if the database query fails.
+pub async fn active_window(
+    db: &DatabaseConnection,
+    now: DateTime<Utc>,
+) -> AppResult<Option<maintenance_windows::Model>> {
+    Ok(maintenance_windows::Entity::find()
+        .filter(maintenance_windows::Column::StartsAt.lte(now))
+        .filter(maintenance_windows::Column::EndsAt.gt(now))
+        .order_by_desc(maintenance_windows::Column::EndsAt)
+        .one(db)
+        .await?)
+}
+
+/// The window in effect at `now`, or else the next scheduled one.
+///
+/// # Errors
+///
+/// Returns an error if the database query fails.
+pub async fn current_or_next_window(
+    db: &DatabaseConnection,
+    now: DateTime<Utc>,
+) -> AppResult<Option<maintenance_windows::Model>> {
+    if let Some(window) = active_window(db, now).await? {
+        return Ok(Some(window));
+    }
+
+    Ok(maintenance_windows::Entity::find()
+        .filter(maintenance_windows::Column::StartsAt.gt(now))
+        .order_by_asc(maintenance_windows::Column::StartsAt)
+        .one(db)
+        .await?)
+}
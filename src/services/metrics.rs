@@ -0,0 +1,133 @@
+//! Prometheus metrics, scraped from `/metrics`.
+//!
+//! Metrics are recorded through the `metrics` facade into a process-wide
+//! Prometheus recorder, installed on first use. [`AppState`] holds the handle
+//! that renders the scrape output.
+//!
+//! | Metric | Type | Labels |
+//! |--------|------|--------|
+//! | `http_requests_total` | counter | `method`, `route`, `status` |
+//! | `http_request_duration_seconds` | histogram | `method`, `route` |
+//! | `cache_hits_total`, `cache_misses_total` | counter | |
+//! | `vaisala_requests_total`, `vaisala_errors_total` | counter | |
+//! | `sync_rows_inserted` | histogram | |
+//!
+//! `route` is the matched route template (e.g. `/api/v1/stations/{station_id}`)
+//! rather than the raw path, so label cardinality stays bounded; requests that
+//! match no route are labelled `unmatched`.
+//!
+//! [`AppState`]: crate::common::AppState
+
+use axum::{
+    extract::{MatchedPath, Request, State},
+    http::{header, HeaderValue},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use metrics::{counter, histogram};
+use metrics_exporter_prometheus::{Matcher, PrometheusBuilder, PrometheusHandle};
+use std::sync::OnceLock;
+use std::time::Instant;
+
+use crate::common::AppState;
+
+/// Latency buckets in seconds, from cached hits to long bulk exports
+const LATENCY_BUCKETS: [f64; 12] = [
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0,
+];
+
+/// Buckets for rows inserted by one readings sync pass
+const SYNC_ROWS_BUCKETS: [f64; 8] = [0.0, 1e1, 1e2, 1e3, 1e4, 1e5, 1e6, 1e7];
+
+static HANDLE: OnceLock<PrometheusHandle> = OnceLock::new();
+
+/// Handle of the process-wide Prometheus recorder, installing it on first call.
+///
+/// # Panics
+///
+/// Panics if another global `metrics` recorder was installed first.
+pub fn handle() -> PrometheusHandle {
+    HANDLE
+        .get_or_init(|| {
+            PrometheusBuilder::new()
+                .set_buckets_for_metric(
+                    Matcher::Full("http_request_duration_seconds".to_string()),
+                    &LATENCY_BUCKETS,
+                )
+                .and_then(|b| {
+                    b.set_buckets_for_metric(
+                        Matcher::Full("sync_rows_inserted".to_string()),
+                        &SYNC_ROWS_BUCKETS,
+                    )
+                })
+                .expect("Metric buckets are not empty")
+                .install_recorder()
+                .expect("Failed to install Prometheus recorder")
+        })
+        .clone()
+}
+
+/// Middleware counting requests and timing them per route.
+///
+/// Latency covers producing the response head; streamed CSV/NDJSON bodies
+/// keep sending after it is recorded.
+pub async fn track_requests(request: Request, next: Next) -> Response {
+    let method = request.method().to_string();
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map_or_else(|| "unmatched".to_string(), |path| path.as_str().to_string());
+    let started = Instant::now();
+
+    let response = next.run(request).await;
+
+    let status = response.status().as_u16().to_string();
+    histogram!(
+        "http_request_duration_seconds",
+        "method" => method.clone(),
+        "route" => route.clone()
+    )
+    .record(started.elapsed().as_secs_f64());
+    counter!(
+        "http_requests_total",
+        "method" => method,
+        "route" => route,
+        "status" => status
+    )
+    .increment(1);
+
+    response
+}
+
+/// Prometheus scrape output
+pub async fn render(State(state): State<AppState>) -> impl IntoResponse {
+    (
+        [(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static("text/plain; version=0.0.4"),
+        )],
+        state.metrics.render(),
+    )
+}
+
+/// Count a response cache lookup
+pub fn record_cache_lookup(hit: bool) {
+    if hit {
+        counter!("cache_hits_total").increment(1);
+    } else {
+        counter!("cache_misses_total").increment(1);
+    }
+}
+
+/// Count a request sent to the Vaisala API and whether it failed
+pub fn record_vaisala_request(failed: bool) {
+    counter!("vaisala_requests_total").increment(1);
+    if failed {
+        counter!("vaisala_errors_total").increment(1);
+    }
+}
+
+/// Record the rows inserted by one readings sync pass
+pub fn record_sync_rows(inserted: u64) {
+    histogram!("sync_rows_inserted").record(inserted as f64);
+}
@@ -1,4 +1,12 @@
 pub mod cache;
+pub mod concurrency;
+pub mod device_events;
+pub mod diagnostics;
+pub mod maintenance;
+pub mod metrics;
 pub mod rate_limit;
+pub mod retention;
+pub mod timescale;
 
+pub use concurrency::{InFlightRequests, PerClientConcurrency};
 pub use rate_limit::FallbackIpKeyExtractor;
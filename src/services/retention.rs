@@ -0,0 +1,224 @@
+//! Raw readings retention (TimescaleDB `drop_chunks`).
+//!
+//! Dropping old `readings` chunks keeps the continuous aggregates, as long as
+//! nothing refreshes them over the dropped range afterwards: a refresh over a
+//! range without raw data empties its buckets. Two things guard against that:
+//!
+//! - The retention age must exceed [`MIN_RETENTION_DAYS`] (the widest
+//!   aggregate refresh policy window, `readings_monthly`) and
+//!   `VAISALA_MAX_HISTORY_DAYS` (otherwise the daily full re-sync re-inserts
+//!   what was dropped).
+//! - Once chunks have been dropped, the full aggregate refresh starts at the
+//!   oldest raw reading (see `worker::refresh_continuous_aggregates_full`).
+
+use chrono::{DateTime, Utc};
+use sea_orm::{ConnectionTrait, DatabaseConnection, DbErr, FromQueryResult, Statement, TransactionTrait};
+
+use crate::error::{AppError, AppResult};
+use crate::services::timescale;
+
+/// Shortest allowed retention age: the `readings_monthly` refresh policy
+/// starts 3 months back, plus one monthly bucket
+pub const MIN_RETENTION_DAYS: u32 = 124;
+
+/// Units accepted in a retention interval, with their length in days
+/// (months and years rounded down, so the minimum check errs on the safe side)
+const UNITS: [(&str, u32); 4] = [("day", 1), ("week", 7), ("month", 30), ("year", 365)];
+
+/// A validated retention age such as `400 days` or `2 years`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetentionInterval {
+    amount: u32,
+    unit: &'static str,
+    unit_days: u32,
+}
+
+impl RetentionInterval {
+    /// Parse `<positive integer> <day|week|month|year>[s]`, e.g. `18 months`.
+    ///
+    /// Nothing else is accepted: the value ends up in SQL.
+    ///
+    /// # Errors
+    ///
+    /// Returns a message describing the expected format.
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let invalid = || {
+            format!("Invalid interval '{input}': expected '<number> <days|weeks|months|years>', e.g. '400 days'")
+        };
+
+        let mut parts = input.split_whitespace();
+        let (Some(amount), Some(unit), None) = (parts.next(), parts.next(), parts.next()) else {
+            return Err(invalid());
+        };
+        if !amount.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(invalid());
+        }
+        let amount: u32 = amount.parse().map_err(|_| invalid())?;
+        let unit = unit.to_ascii_lowercase();
+        let singular = unit.strip_suffix('s').unwrap_or(&unit);
+        let &(unit, unit_days) = UNITS
+            .iter()
+            .find(|(name, _)| *name == singular)
+            .ok_or_else(invalid)?;
+        if amount == 0 {
+            return Err(invalid());
+        }
+
+        Ok(Self {
+            amount,
+            unit,
+            unit_days,
+        })
+    }
+
+    /// Approximate length in days
+    pub fn days(&self) -> u64 {
+        u64::from(self.amount) * u64::from(self.unit_days)
+    }
+
+    /// SQL interval literal, e.g. `INTERVAL '400 days'`
+    fn sql(&self) -> String {
+        format!("INTERVAL '{self}'")
+    }
+}
+
+impl std::fmt::Display for RetentionInterval {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let plural = if self.amount == 1 { "" } else { "s" };
+        write!(f, "{} {}{plural}", self.amount, self.unit)
+    }
+}
+
+/// Parse a retention age and check it is long enough to be safe.
+///
+/// # Errors
+///
+/// Returns `BadRequest` for a malformed interval or one of at most
+/// `max(MIN_RETENTION_DAYS, max_history_days)` days.
+pub fn validate(input: &str, max_history_days: i64) -> AppResult<RetentionInterval> {
+    let interval = RetentionInterval::parse(input).map_err(AppError::BadRequest)?;
+    let min_days = u64::from(MIN_RETENTION_DAYS).max(u64::try_from(max_history_days).unwrap_or(0));
+    if interval.days() <= min_days {
+        return Err(AppError::BadRequest(format!(
+            "Retention of {interval} is too short: must exceed {min_days} days so that \
+             aggregates are not refreshed over dropped data and the full re-sync does not refetch it"
+        )));
+    }
+    Ok(interval)
+}
+
+/// The retention policy job on `readings`
+#[derive(Debug, FromQueryResult)]
+pub struct RetentionPolicy {
+    pub job_id: i32,
+    /// Age past which chunks are dropped, as stored by TimescaleDB
+    pub drop_after: Option<String>,
+    pub schedule_interval: Option<String>,
+    pub next_start: Option<DateTime<Utc>>,
+}
+
+/// Map TimescaleDB errors, reporting a missing extension as 503
+fn retention_error(err: DbErr) -> AppError {
+    if timescale::is_missing_object(&err) {
+        return AppError::ServiceUnavailable(
+            "retention is not available on this database".to_string(),
+        );
+    }
+    AppError::Database(err)
+}
+
+/// Current retention policy on `readings`, if any.
+///
+/// # Errors
+///
+/// Returns an error if the query fails.
+pub async fn current_policy(db: &DatabaseConnection) -> AppResult<Option<RetentionPolicy>> {
+    let row = db
+        .query_one(Statement::from_string(
+            sea_orm::DatabaseBackend::Postgres,
+            "SELECT job_id, config->>'drop_after' AS drop_after,
+                    schedule_interval::text AS schedule_interval, next_start
+             FROM timescaledb_information.jobs
+             WHERE proc_name = 'policy_retention' AND hypertable_name = 'readings'
+             ORDER BY job_id
+             LIMIT 1",
+        ))
+        .await
+        .map_err(retention_error)?;
+
+    Ok(row.map(|r| RetentionPolicy::from_query_result(&r, "")).transpose()?)
+}
+
+/// Replace the retention policy on `readings`.
+///
+/// # Errors
+///
+/// Returns an error if removing or adding the policy fails.
+pub async fn set_policy(
+    db: &DatabaseConnection,
+    interval: RetentionInterval,
+) -> AppResult<Option<RetentionPolicy>> {
+    let txn = db.begin().await?;
+    txn.execute_unprepared("SELECT remove_retention_policy('readings', if_exists => true)")
+        .await
+        .map_err(retention_error)?;
+    txn.execute_unprepared(&format!(
+        "SELECT add_retention_policy('readings', {})",
+        interval.sql()
+    ))
+    .await
+    .map_err(retention_error)?;
+    txn.commit().await?;
+
+    tracing::warn!(older_than = %interval, "Readings retention policy set");
+    current_policy(db).await
+}
+
+/// Remove the retention policy on `readings`. Returns whether one existed.
+///
+/// # Errors
+///
+/// Returns an error if the policy cannot be removed.
+pub async fn remove_policy(db: &DatabaseConnection) -> AppResult<bool> {
+    let existed = current_policy(db).await?.is_some();
+    db.execute_unprepared("SELECT remove_retention_policy('readings', if_exists => true)")
+        .await
+        .map_err(retention_error)?;
+    if existed {
+        tracing::warn!("Readings retention policy removed");
+    }
+    Ok(existed)
+}
+
+#[derive(Debug, FromQueryResult)]
+struct ChunkRow {
+    chunk: String,
+}
+
+/// Drop `readings` chunks entirely older than `interval` now, returning
+/// the dropped chunk names.
+///
+/// # Errors
+///
+/// Returns an error if `drop_chunks` fails.
+pub async fn drop_chunks(db: &DatabaseConnection, interval: RetentionInterval) -> AppResult<Vec<String>> {
+    let chunks: Vec<String> = db
+        .query_all(Statement::from_string(
+            sea_orm::DatabaseBackend::Postgres,
+            format!(
+                "SELECT chunk::text AS chunk FROM drop_chunks('readings', older_than => {}) AS chunk",
+                interval.sql()
+            ),
+        ))
+        .await
+        .map_err(retention_error)?
+        .into_iter()
+        .map(|row| ChunkRow::from_query_result(&row, ""))
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
+        .map(|r| r.chunk)
+        .collect();
+
+    tracing::warn!(older_than = %interval, dropped = chunks.len(), "Dropped readings chunks");
+    Ok(chunks)
+}
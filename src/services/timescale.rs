@@ -0,0 +1,170 @@
+//! TimescaleDB availability checks.
+//!
+//! The aggregates endpoint and the sync worker rely on the TimescaleDB extension
+//! and its continuous aggregate views. On a plain Postgres (or a database where
+//! migrations did not complete) these are missing, so queries fail with
+//! undefined-table or undefined-function errors. This module detects those
+//! errors and verifies the setup at startup so the cause is obvious in logs.
+
+use sea_orm::{ConnectionTrait, DatabaseConnection, DbErr, FromQueryResult, RuntimeErr, Statement};
+
+/// Continuous aggregate views created by the migrations
+pub const CONTINUOUS_AGGREGATES: [&str; 8] = [
+    "readings_hourly",
+    "readings_daily",
+    "readings_weekly",
+    "readings_monthly",
+    "readings_hourly_percentiles",
+    "readings_daily_percentiles",
+    "readings_weekly_percentiles",
+    "readings_monthly_percentiles",
+];
+
+/// Postgres SQLSTATE for `undefined_table` (missing view)
+const UNDEFINED_TABLE: &str = "42P01";
+/// Postgres SQLSTATE for `undefined_function` (e.g. `time_bucket` without TimescaleDB)
+const UNDEFINED_FUNCTION: &str = "42883";
+
+#[derive(Debug, FromQueryResult)]
+struct ViewNameRow {
+    view_name: String,
+}
+
+/// Whether a database error means a TimescaleDB object (view or function) is missing.
+pub fn is_missing_object(err: &DbErr) -> bool {
+    let (DbErr::Query(RuntimeErr::SqlxError(sea_orm::sqlx::Error::Database(e)))
+    | DbErr::Exec(RuntimeErr::SqlxError(sea_orm::sqlx::Error::Database(e)))) = err
+    else {
+        return false;
+    };
+
+    e.code()
+        .is_some_and(|code| code == UNDEFINED_TABLE || code == UNDEFINED_FUNCTION)
+}
+
+/// Verify the TimescaleDB extension and continuous aggregates exist.
+///
+/// Only logs: the API still serves metadata and raw readings without them.
+pub async fn check_setup(db: &DatabaseConnection) {
+    let extension = db
+        .query_one(Statement::from_string(
+            sea_orm::DatabaseBackend::Postgres,
+            "SELECT extversion FROM pg_extension WHERE extname = 'timescaledb'",
+        ))
+        .await;
+
+    match extension {
+        Ok(Some(_)) => {}
+        Ok(None) => {
+            tracing::warn!(
+                "TimescaleDB extension is NOT installed: aggregates and compression are unavailable"
+            );
+            return;
+        }
+        Err(e) => {
+            tracing::warn!(error = %e, "Could not check for the TimescaleDB extension");
+            return;
+        }
+    }
+
+    let views: Vec<String> = match db
+        .query_all(Statement::from_string(
+            sea_orm::DatabaseBackend::Postgres,
+            "SELECT view_name FROM timescaledb_information.continuous_aggregates",
+        ))
+        .await
+        .and_then(|rows| {
+            rows.iter()
+                .map(|row| ViewNameRow::from_query_result(row, "").map(|r| r.view_name))
+                .collect()
+        }) {
+        Ok(views) => views,
+        Err(e) => {
+            tracing::warn!(error = %e, "Could not list continuous aggregates");
+            return;
+        }
+    };
+
+    let missing: Vec<&str> = CONTINUOUS_AGGREGATES
+        .into_iter()
+        .filter(|name| !views.iter().any(|v| v == name))
+        .collect();
+
+    if missing.is_empty() {
+        tracing::info!("TimescaleDB extension and continuous aggregates present");
+    } else {
+        tracing::warn!(
+            missing = ?missing,
+            "Continuous aggregates are MISSING: the aggregates endpoint will return 503"
+        );
+    }
+}
+
+#[derive(Debug, FromQueryResult)]
+struct ExistsRow {
+    exists: bool,
+}
+
+/// Whether `table` (a table or view name from a trusted constant) has any row.
+async fn has_rows(db: &DatabaseConnection, table: &str) -> Result<bool, DbErr> {
+    let row = db
+        .query_one(Statement::from_string(
+            sea_orm::DatabaseBackend::Postgres,
+            format!("SELECT EXISTS (SELECT 1 FROM {table}) AS exists"),
+        ))
+        .await?;
+    Ok(row
+        .map(|r| ExistsRow::from_query_result(&r, ""))
+        .transpose()?
+        .is_some_and(|r| r.exists))
+}
+
+/// Fill empty continuous aggregates from existing readings.
+///
+/// The views are created `WITH NO DATA` and the refresh policies only cover
+/// recent buckets, so after restoring historical readings the aggregates stay
+/// empty. Each empty view is refreshed over its full range; views that
+/// already have rows are left to their policies. Runs once at startup (see
+/// `REFRESH_AGGREGATES_ON_START`); a full refresh can take minutes on a large
+/// history, so callers should spawn it.
+pub async fn refresh_empty_aggregates(db: DatabaseConnection) {
+    match has_rows(&db, "readings").await {
+        Ok(true) => {}
+        Ok(false) => {
+            tracing::info!("No readings yet, skipping continuous aggregate refresh");
+            return;
+        }
+        Err(e) => {
+            tracing::warn!(error = %e, "Could not check readings before aggregate refresh");
+            return;
+        }
+    }
+
+    for view in CONTINUOUS_AGGREGATES {
+        match has_rows(&db, view).await {
+            Ok(false) => {}
+            Ok(true) => continue,
+            Err(e) => {
+                tracing::warn!(view, error = %e, "Could not check continuous aggregate");
+                continue;
+            }
+        }
+
+        tracing::info!(view, "Continuous aggregate is empty, refreshing from readings");
+        let started = std::time::Instant::now();
+        // CALL cannot run in a transaction block; execute_unprepared autocommits
+        match db
+            .execute_unprepared(&format!(
+                "CALL refresh_continuous_aggregate('{view}', NULL, NULL)"
+            ))
+            .await
+        {
+            Ok(_) => tracing::info!(
+                view,
+                elapsed_secs = started.elapsed().as_secs(),
+                "Continuous aggregate refreshed"
+            ),
+            Err(e) => tracing::warn!(view, error = %e, "Continuous aggregate refresh failed"),
+        }
+    }
+}
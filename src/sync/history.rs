@@ -0,0 +1,53 @@
+use chrono::{DateTime, Duration, Utc};
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, Set};
+use uuid::Uuid;
+
+use crate::entity::sync_runs::{self, SyncRunStatus};
+
+/// Scheduler tasks that record their runs
+pub const SYNC_TASKS: [&str; 4] = ["readings", "device_status", "alarms", "events"];
+
+/// Record one scheduler iteration and prune runs older than `retention_days`.
+///
+/// `outcome` is the number of records written (if the task reports it) or the
+/// error of the last attempt. Failures are logged; sync carries on either way.
+pub async fn record_run(
+    db: &DatabaseConnection,
+    task: &str,
+    started_at: DateTime<Utc>,
+    outcome: Result<Option<u64>, String>,
+    retention_days: i64,
+) {
+    let (status, records_affected, error) = match outcome {
+        Ok(records) => (
+            SyncRunStatus::Success,
+            records.map(|n| i64::try_from(n).unwrap_or(i64::MAX)),
+            None,
+        ),
+        Err(e) => (SyncRunStatus::Error, None, Some(e)),
+    };
+
+    let run = sync_runs::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        task: Set(task.to_string()),
+        started_at: Set(started_at.into()),
+        finished_at: Set(Utc::now().into()),
+        status: Set(status),
+        records_affected: Set(records_affected),
+        error: Set(error),
+    };
+    if let Err(e) = sync_runs::Entity::insert(run).exec_without_returning(db).await {
+        tracing::warn!(task, error = %e, "Failed to record sync run");
+    }
+
+    if retention_days > 0 {
+        let cutoff = Utc::now() - Duration::days(retention_days);
+        if let Err(e) = sync_runs::Entity::delete_many()
+            .filter(sync_runs::Column::StartedAt.lt(cutoff))
+            .exec(db)
+            .await
+        {
+            tracing::warn!(error = %e, "Failed to prune sync run history");
+        }
+    }
+}
@@ -1,2 +1,7 @@
+pub mod history;
+pub mod relink;
+pub mod sanity;
 pub mod scheduler;
+pub mod sensor_filter;
+pub mod sensor_types;
 pub mod worker;
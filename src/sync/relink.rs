@@ -0,0 +1,181 @@
+//! Link alarms and events stored before their sensor was discovered.
+//!
+//! Alarms and events are linked to sensors at sync time. One that arrives
+//! before location discovery knows its sensor is stored unlinked; this pass
+//! resolves the stored Vaisala location IDs again and fills in the links.
+//!
+//! Location IDs are only stored since the `alarm_event_location_ids`
+//! migration. Older orphans cannot be matched and are counted instead: an
+//! alarm Vaisala still reports gets its location IDs on the next alarm sync
+//! and can be linked afterwards, while older events stay unlinked.
+
+use sea_orm::sea_query::{Expr, OnConflict, Query};
+use sea_orm::{
+    ColumnTrait, Condition, DatabaseConnection, EntityTrait, PaginatorTrait, QueryFilter,
+    QuerySelect, Set, TransactionTrait,
+};
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+use crate::entity::{alarm_locations, alarms, events, sensors};
+use crate::error::AppResult;
+
+/// Outcome of a relink pass
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RelinkCounts {
+    /// Alarms that gained a station or at least one sensor link
+    pub alarms: u64,
+    /// `alarm_locations` rows created
+    pub alarm_locations: u64,
+    /// Events that gained a sensor and station
+    pub events: u64,
+    /// Orphaned alarms without stored location IDs, which cannot be linked
+    pub alarms_without_locations: u64,
+    /// Orphaned events without a stored location ID, which cannot be linked
+    pub events_without_location: u64,
+}
+
+/// Alarms without a station or without any `alarm_locations` rows
+fn orphan_alarm_condition() -> Condition {
+    Condition::any()
+        .add(alarms::Column::StationId.is_null())
+        .add(
+            alarms::Column::Id.not_in_subquery(
+                Query::select()
+                    .column(alarm_locations::Column::AlarmId)
+                    .from(alarm_locations::Entity)
+                    .to_owned(),
+            ),
+        )
+}
+
+/// Link orphaned alarms and events to sensors that are now known.
+///
+/// An alarm is orphaned when it has no station or no `alarm_locations` rows;
+/// an event when it has no sensor. Only rows with stored location IDs can be
+/// linked; rows synced before those were recorded are left alone and counted
+/// in [`RelinkCounts::alarms_without_locations`] and
+/// [`RelinkCounts::events_without_location`]. Runs in one transaction.
+///
+/// # Errors
+///
+/// Returns an error if a database operation fails; nothing is committed then.
+pub async fn relink_orphans(db: &DatabaseConnection) -> AppResult<RelinkCounts> {
+    // vaisala_location_id -> (sensor_id, station_id)
+    let sensor_map: HashMap<i32, (Uuid, Uuid)> = sensors::Entity::find()
+        .select_only()
+        .columns([
+            sensors::Column::VaisalaLocationId,
+            sensors::Column::Id,
+            sensors::Column::StationId,
+        ])
+        .into_tuple::<(i32, Uuid, Uuid)>()
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|(location_id, sensor_id, station_id)| (location_id, (sensor_id, station_id)))
+        .collect();
+
+    let orphan_alarms = alarms::Entity::find()
+        .filter(alarms::Column::LocationIds.is_not_null())
+        .filter(orphan_alarm_condition())
+        .all(db)
+        .await?;
+
+    let txn = db.begin().await?;
+    let mut counts = RelinkCounts::default();
+
+    for alarm in orphan_alarms {
+        let location_ids: Vec<i32> = alarm
+            .location_ids
+            .and_then(|ids| serde_json::from_value(ids).ok())
+            .unwrap_or_default();
+        let known: Vec<(Uuid, Uuid)> = location_ids
+            .iter()
+            .filter_map(|id| sensor_map.get(id).copied())
+            .collect();
+        if known.is_empty() {
+            continue;
+        }
+
+        let mut relinked = false;
+        if alarm.station_id.is_none() {
+            // Same rule as sync: the first location that maps to a sensor
+            alarms::Entity::update_many()
+                .col_expr(alarms::Column::StationId, Expr::value(known[0].1))
+                .filter(alarms::Column::Id.eq(alarm.id))
+                .exec(&txn)
+                .await?;
+            relinked = true;
+        }
+
+        let sensor_ids: HashSet<Uuid> = known.iter().map(|(sensor_id, _)| *sensor_id).collect();
+        let links = sensor_ids.into_iter().map(|sensor_id| alarm_locations::ActiveModel {
+            alarm_id: Set(alarm.id),
+            sensor_id: Set(sensor_id),
+        });
+        let inserted = alarm_locations::Entity::insert_many(links)
+            .on_conflict(
+                OnConflict::columns([
+                    alarm_locations::Column::AlarmId,
+                    alarm_locations::Column::SensorId,
+                ])
+                .do_nothing()
+                .to_owned(),
+            )
+            .exec_without_returning(&txn)
+            .await?;
+        counts.alarm_locations += inserted;
+        if relinked || inserted > 0 {
+            counts.alarms += 1;
+        }
+    }
+
+    let orphan_locations: Vec<i32> = events::Entity::find()
+        .select_only()
+        .column(events::Column::LocationId)
+        .distinct()
+        .filter(events::Column::SensorId.is_null())
+        .filter(events::Column::LocationId.is_not_null())
+        .into_tuple::<i32>()
+        .all(&txn)
+        .await?;
+
+    for location_id in orphan_locations {
+        let Some(&(sensor_id, station_id)) = sensor_map.get(&location_id) else {
+            continue;
+        };
+        counts.events += events::Entity::update_many()
+            .col_expr(events::Column::SensorId, Expr::value(sensor_id))
+            .col_expr(events::Column::StationId, Expr::value(station_id))
+            .filter(events::Column::SensorId.is_null())
+            .filter(events::Column::LocationId.eq(location_id))
+            .exec(&txn)
+            .await?
+            .rows_affected;
+    }
+
+    counts.alarms_without_locations = alarms::Entity::find()
+        .filter(alarms::Column::LocationIds.is_null())
+        .filter(orphan_alarm_condition())
+        .count(&txn)
+        .await?;
+    counts.events_without_location = events::Entity::find()
+        .filter(events::Column::SensorId.is_null())
+        .filter(events::Column::LocationId.is_null())
+        .count(&txn)
+        .await?;
+
+    txn.commit().await?;
+
+    tracing::info!(
+        alarms = counts.alarms,
+        alarm_locations = counts.alarm_locations,
+        events = counts.events,
+        alarms_without_locations = counts.alarms_without_locations,
+        events_without_location = counts.events_without_location,
+        "Relinked orphaned alarms and events"
+    );
+
+    Ok(counts)
+}
@@ -0,0 +1,102 @@
+//! Sanity range for readings received from Vaisala.
+//!
+//! viewLinc occasionally reports impossible values, such as `1e38` sentinels,
+//! that would otherwise end up in charts and the continuous aggregates. Points
+//! outside a sensor's range are dropped before they are stored.
+
+use uuid::Uuid;
+
+use crate::config::Config;
+use crate::entity::sensors;
+use crate::vaisala::models::DataPoint;
+
+/// Inclusive range of plausible values
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SanityRange {
+    pub min: f64,
+    pub max: f64,
+}
+
+impl SanityRange {
+    /// Whether `value` is finite and within the range.
+    pub fn contains(&self, value: f64) -> bool {
+        value.is_finite() && value >= self.min && value <= self.max
+    }
+}
+
+/// Sanity settings for a sync pass
+#[derive(Debug, Clone, Copy)]
+pub struct SanityCheck {
+    /// Range applied to every sensor
+    pub global: SanityRange,
+    /// Narrow the range to each sensor's `units_min`/`units_max`
+    pub use_units_range: bool,
+}
+
+impl SanityCheck {
+    #[must_use]
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            global: SanityRange {
+                min: config.sync_sanity_min,
+                max: config.sync_sanity_max,
+            },
+            use_units_range: config.sync_sanity_use_units_range,
+        }
+    }
+
+    /// Range for one sensor: the global range, narrowed to the sensor's units
+    /// range when enabled and the sensor reports a valid one.
+    pub fn range_for(&self, sensor: &sensors::Model) -> SanityRange {
+        let mut range = self.global;
+        if !self.use_units_range {
+            return range;
+        }
+
+        match (sensor.units_min, sensor.units_max) {
+            (Some(min), Some(max)) if min < max => {
+                range.min = range.min.max(min);
+                range.max = range.max.min(max);
+            }
+            (Some(min), None) => range.min = range.min.max(min),
+            (None, Some(max)) => range.max = range.max.min(max),
+            _ => {}
+        }
+        range
+    }
+}
+
+/// Drop points outside `range`.
+///
+/// Returns the points to store and how many were out of range.
+pub fn screen(sensor_id: Uuid, points: Vec<DataPoint>, range: SanityRange) -> (Vec<DataPoint>, usize) {
+    let mut anomalies = 0;
+    let mut kept = Vec::with_capacity(points.len());
+
+    for point in points {
+        if range.contains(point.value) {
+            kept.push(point);
+            continue;
+        }
+
+        anomalies += 1;
+        tracing::debug!(
+            sensor_id = %sensor_id,
+            timestamp = point.timestamp,
+            value = point.value,
+            "Dropped reading outside sanity range"
+        );
+    }
+
+    if anomalies > 0 {
+        tracing::warn!(
+            sensor_id = %sensor_id,
+            anomalies,
+            min = range.min,
+            max = range.max,
+            "Dropped readings outside sanity range"
+        );
+    }
+
+    (kept, anomalies)
+}
@@ -1,28 +1,54 @@
-use std::time::Duration;
+use chrono::Utc;
+use std::collections::HashSet;
+use std::time::{Duration, Instant};
 use tokio::time::interval;
+use uuid::Uuid;
 
-use crate::common::AppState;
-use crate::sync::worker;
+use crate::common::{AppState, SyncPassRecord};
+use crate::services::cache;
+use crate::sync::history;
+use crate::sync::sanity::SanityCheck;
+use crate::sync::sensor_filter::SensorTypeFilter;
+use crate::sync::worker::{self, GapWindow};
 
 /// Run the readings sync task on a schedule.
 ///
 /// On startup, first discovers locations (zones/stations/sensors) from Vaisala,
 /// then performs incremental syncs every interval, with a full re-sync every 24 hours.
+/// After each incremental sync, recent gaps longer than SYNC_GAP_THRESHOLD_SECONDS
+/// are backfilled with targeted fetches. Cached responses of stations that
+/// received new data are invalidated once per pass, after the aggregate refresh,
+/// and the cached catalog whenever sensors or their thresholds change.
 pub async fn run_readings_sync(state: AppState) {
     let interval_secs = state.config.sync_readings_interval_seconds;
     let max_history_days = state.config.vaisala_max_history_days;
+    let logged_overrides_realtime = state.config.sync_logged_overrides_realtime;
     let retry_delay_secs = state.config.sync_retry_delay_seconds;
     let max_retries = state.config.sync_retry_max;
+    let gap_threshold_secs = state.config.sync_gap_threshold_seconds;
+    let sanity = SanityCheck::from_config(&state.config);
 
     tracing::info!(
         interval_secs,
         max_history_days,
+        gap_threshold_secs,
         "Starting readings sync scheduler"
     );
 
+    // Gaps already backfilled once; a gap that is still there is genuinely
+    // missing upstream and is left to the daily full re-sync
+    let mut attempted_gaps: HashSet<GapWindow> = HashSet::new();
+
     // Discover locations from Vaisala on startup
-    if let Err(e) = worker::sync_locations(&state.db, &state.vaisala_client).await {
-        tracing::error!(error = %e, "Failed to discover locations from Vaisala");
+    let sensor_filter = SensorTypeFilter::from_config(&state.config);
+    match worker::sync_locations(&state.db, &state.vaisala_client, &sensor_filter).await {
+        // New, removed and restored sensors change the catalog and the
+        // sensors listed by their stations
+        Ok(pass) => {
+            cache::invalidate(&state, cache::CATALOG_CACHE_KEY).await;
+            cache::invalidate_sensors(&state, &pass.activity_changed).await;
+        }
+        Err(e) => tracing::error!(error = %e, "Failed to discover locations from Vaisala"),
     }
 
     let mut ticker = interval(Duration::from_secs(interval_secs));
@@ -41,25 +67,44 @@ pub async fn run_readings_sync(state: AppState) {
         }
 
         let mut retries = 0;
-        let mut sync_succeeded = false;
+        let started_at = Utc::now();
+        let started = Instant::now();
+        let mut updated_sensors = Vec::new();
+        let mut thresholds_changed = 0;
 
-        loop {
+        let outcome = loop {
             match worker::sync_readings(
                 &state.db,
                 &state.vaisala_client,
                 max_history_days,
                 force_full_sync,
+                logged_overrides_realtime,
+                &sanity,
             )
             .await
             {
-                Ok(()) => {
-                    sync_succeeded = true;
+                Ok(pass) => {
+                    let record = SyncPassRecord {
+                        started_at,
+                        from: pass.from,
+                        to: pass.to,
+                        sensors: pass.sensors,
+                        points_inserted: pass.points_inserted,
+                        backfill_points: pass.backfill_points,
+                        duration_ms: started.elapsed().as_millis().try_into().unwrap_or(u64::MAX),
+                        full_sync: force_full_sync,
+                    };
+                    if let Ok(mut last) = state.last_sync_pass.write() {
+                        *last = Some(record);
+                    }
                     if force_full_sync {
                         tracing::info!("Full re-sync completed successfully");
                     } else {
                         tracing::debug!("Readings sync completed successfully");
                     }
-                    break;
+                    updated_sensors = pass.updated_sensors;
+                    thresholds_changed = pass.thresholds_changed;
+                    break Ok(Some(pass.points_inserted));
                 }
                 Err(e) => {
                     retries += 1;
@@ -85,11 +130,13 @@ pub async fn run_readings_sync(state: AppState) {
                             max_retries,
                             "Readings sync failed after max retries"
                         );
-                        break;
+                        break Err(e.to_string());
                     }
                 }
             }
-        }
+        };
+
+        let sync_succeeded = outcome.is_ok();
 
         // If full sync succeeded, update the last_full_sync timestamp for all sensors
         // and refresh aggregates for the entire history
@@ -97,21 +144,101 @@ pub async fn run_readings_sync(state: AppState) {
             worker::update_last_full_sync_for_all_sensors(&state.db).await;
             worker::refresh_continuous_aggregates_full(&state.db).await;
         } else if sync_succeeded {
+            if gap_threshold_secs > 0 {
+                let backfilled =
+                    backfill_new_gaps(&state, gap_threshold_secs, &sanity, &mut attempted_gaps).await;
+                updated_sensors.extend(backfilled);
+            }
             // Incremental sync: only refresh recent data
             worker::refresh_continuous_aggregates(&state.db).await;
         }
 
+        // After the aggregate refresh, so aggregates are not re-cached stale
+        updated_sensors.sort_unstable();
+        updated_sensors.dedup();
+        cache::invalidate_sensors(&state, &updated_sensors).await;
+        if thresholds_changed > 0 {
+            cache::invalidate(&state, cache::CATALOG_CACHE_KEY).await;
+        }
+
+        history::record_run(
+            &state.db,
+            "readings",
+            started_at,
+            outcome,
+            state.config.sync_runs_retention_days,
+        )
+        .await;
+
         // Wait for next tick
         ticker.tick().await;
     }
 }
 
+/// Backfill recent gaps that have not been attempted yet.
+///
+/// Returns the sensors that received backfilled rows.
+async fn backfill_new_gaps(
+    state: &AppState,
+    gap_threshold_secs: i64,
+    sanity: &SanityCheck,
+    attempted: &mut HashSet<GapWindow>,
+) -> Vec<Uuid> {
+    let lookback_start = Utc::now() - chrono::Duration::hours(worker::GAP_LOOKBACK_HOURS);
+    attempted.retain(|g| g.to >= lookback_start);
+
+    let gaps = match worker::find_recent_gaps(&state.db, chrono::Duration::seconds(gap_threshold_secs)).await {
+        Ok(gaps) => gaps,
+        Err(e) => {
+            tracing::warn!(error = %e, "Failed to detect readings gaps");
+            return Vec::new();
+        }
+    };
+    let new_gaps: Vec<GapWindow> = gaps.into_iter().filter(|g| !attempted.contains(g)).collect();
+    if new_gaps.is_empty() {
+        return Vec::new();
+    }
+
+    tracing::info!(gaps = new_gaps.len(), "Backfilling readings gaps");
+    match worker::backfill_gaps(
+        &state.db,
+        &state.vaisala_client,
+        &new_gaps,
+        state.config.sync_logged_overrides_realtime,
+        sanity,
+    )
+    .await
+    {
+        Ok(inserted) => {
+            tracing::info!(gaps = new_gaps.len(), inserted, "Gap backfill completed");
+            let sensors = if inserted > 0 {
+                new_gaps.iter().map(|g| g.sensor_id).collect()
+            } else {
+                Vec::new()
+            };
+            attempted.extend(new_gaps);
+            sensors
+        }
+        // Not marked as attempted, so the next pass retries them
+        Err(e) => {
+            tracing::warn!(error = %e, "Gap backfill failed");
+            Vec::new()
+        }
+    }
+}
+
 /// Run the device status sync task on a schedule.
+///
+/// Each pass also refreshes the sensors' alarming paused flags.
 pub async fn run_device_status_sync(state: AppState) {
     let interval_secs = state.config.sync_device_status_interval_seconds;
     let retry_delay_secs = state.config.sync_retry_delay_seconds;
     let max_retries = state.config.sync_retry_max;
 
+    // Statuses compared against for change events: a few passes back, so one
+    // failed pass doesn't hide the next change
+    let lookback = chrono::Duration::seconds(interval_secs.min(86_400) as i64 * 3);
+
     tracing::info!(interval_secs, "Starting device status sync scheduler");
 
     let mut ticker = interval(Duration::from_secs(interval_secs));
@@ -123,11 +250,22 @@ pub async fn run_device_status_sync(state: AppState) {
         tracing::debug!("Running device status sync...");
 
         let mut retries = 0;
-        loop {
-            match worker::sync_device_status(&state.db, &state.vaisala_client).await {
+        let started_at = Utc::now();
+        let outcome = loop {
+            let result = worker::sync_device_status(
+                &state.db,
+                &state.vaisala_client,
+                &state.device_events,
+                lookback,
+            )
+            .await;
+            match result {
                 Ok(()) => {
                     tracing::debug!("Device status sync completed successfully");
-                    break;
+                    // Alarming can be paused at any time in viewLinc, while
+                    // discovery only runs on startup
+                    refresh_alarming_paused(&state).await;
+                    break Ok(None);
                 }
                 Err(e) => {
                     retries += 1;
@@ -153,17 +291,38 @@ pub async fn run_device_status_sync(state: AppState) {
                             max_retries,
                             "Device status sync failed after max retries"
                         );
-                        break;
+                        break Err(e.to_string());
                     }
                 }
             }
-        }
+        };
+
+        history::record_run(
+            &state.db,
+            "device_status",
+            started_at,
+            outcome,
+            state.config.sync_runs_retention_days,
+        )
+        .await;
 
         // Wait for next tick
         ticker.tick().await;
     }
 }
 
+/// Refresh the sensors' alarming paused flags from Vaisala.
+///
+/// Drops the cached catalog when any flag changed. Failures are logged: the
+/// flags are retried with the next device status pass.
+pub async fn refresh_alarming_paused(state: &AppState) {
+    match worker::refresh_alarming_paused(&state.db, &state.vaisala_client).await {
+        Ok(0) => {}
+        Ok(_) => cache::invalidate(state, cache::CATALOG_CACHE_KEY).await,
+        Err(e) => tracing::warn!(error = %e, "Failed to refresh alarming paused flags"),
+    }
+}
+
 /// Run the alarms sync task on a schedule.
 pub async fn run_alarms_sync(state: AppState) {
     let interval_secs = state.config.sync_alarms_interval_seconds;
@@ -181,11 +340,12 @@ pub async fn run_alarms_sync(state: AppState) {
         tracing::debug!("Running alarms sync...");
 
         let mut retries = 0;
-        loop {
+        let started_at = Utc::now();
+        let outcome = loop {
             match worker::sync_alarms(&state.db, &state.vaisala_client).await {
                 Ok(()) => {
                     tracing::debug!("Alarms sync completed successfully");
-                    break;
+                    break Ok(None);
                 }
                 Err(e) => {
                     retries += 1;
@@ -211,11 +371,20 @@ pub async fn run_alarms_sync(state: AppState) {
                             max_retries,
                             "Alarms sync failed after max retries"
                         );
-                        break;
+                        break Err(e.to_string());
                     }
                 }
             }
-        }
+        };
+
+        history::record_run(
+            &state.db,
+            "alarms",
+            started_at,
+            outcome,
+            state.config.sync_runs_retention_days,
+        )
+        .await;
 
         // Wait for next tick
         ticker.tick().await;
@@ -239,11 +408,12 @@ pub async fn run_events_sync(state: AppState) {
         tracing::debug!("Running events sync...");
 
         let mut retries = 0;
-        loop {
+        let started_at = Utc::now();
+        let outcome = loop {
             match worker::sync_events(&state.db, &state.vaisala_client).await {
                 Ok(()) => {
                     tracing::debug!("Events sync completed successfully");
-                    break;
+                    break Ok(None);
                 }
                 Err(e) => {
                     retries += 1;
@@ -269,11 +439,20 @@ pub async fn run_events_sync(state: AppState) {
                             max_retries,
                             "Events sync failed after max retries"
                         );
-                        break;
+                        break Err(e.to_string());
                     }
                 }
             }
-        }
+        };
+
+        history::record_run(
+            &state.db,
+            "events",
+            started_at,
+            outcome,
+            state.config.sync_runs_retention_days,
+        )
+        .await;
 
         // Wait for next tick
         ticker.tick().await;
@@ -0,0 +1,42 @@
+//! Sensor types location discovery creates.
+//!
+//! Some viewLinc locations expose channels nobody charts (e.g. internal
+//! diagnostics). Sensors matching `SYNC_SENSOR_TYPE_DENYLIST`, or missing from
+//! a non-empty `SYNC_SENSOR_TYPE_ALLOWLIST`, are never created, so they cost
+//! neither API calls nor storage. Entries match the derived sensor type or the
+//! location name, case-insensitively; the denylist wins when both match.
+//! Sensors created before an entry was added are left alone.
+
+use crate::config::Config;
+
+/// Allow and deny lists applied during discovery
+#[derive(Debug, Clone, Default)]
+pub struct SensorTypeFilter {
+    /// Empty allows every sensor not denied
+    pub allow: Vec<String>,
+    pub deny: Vec<String>,
+}
+
+impl SensorTypeFilter {
+    #[must_use]
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            allow: config.sync_sensor_type_allowlist.clone(),
+            deny: config.sync_sensor_type_denylist.clone(),
+        }
+    }
+
+    /// Whether a sensor named `name` with derived type `sensor_type` is synced.
+    pub fn admits(&self, name: &str, sensor_type: &str) -> bool {
+        let matches = |list: &[String]| {
+            list.iter().any(|entry| {
+                entry.eq_ignore_ascii_case(sensor_type) || entry.eq_ignore_ascii_case(name)
+            })
+        };
+
+        if matches(&self.deny) {
+            return false;
+        }
+        self.allow.is_empty() || matches(&self.allow)
+    }
+}
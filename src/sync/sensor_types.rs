@@ -0,0 +1,54 @@
+//! Sensor types derived from viewLinc location names.
+//!
+//! Names look like `MDepthmm` or `MCDOMppb`: a station prefix, the measured
+//! quantity, then units. Keywords in the `sensor_type_patterns` table map a
+//! name to its type (`MDepthmm` -> `Depth`), so operators can add probe types
+//! with SQL instead of a release, e.g.:
+//!
+//! ```sql
+//! INSERT INTO sensor_type_patterns (keyword, sensor_type, priority) VALUES ('pH', 'pH', 90);
+//! ```
+//!
+//! Patterns are loaded once per discovery pass; a name matching no keyword is
+//! its own type.
+
+use sea_orm::{DatabaseConnection, EntityTrait, QueryOrder};
+
+use crate::entity::sensor_type_patterns;
+use crate::error::AppResult;
+
+/// Keyword to sensor type mappings in match order
+#[derive(Debug, Clone, Default)]
+pub struct SensorTypePatterns {
+    patterns: Vec<(String, String)>,
+}
+
+impl SensorTypePatterns {
+    /// Load the mappings, highest priority first (ties by keyword).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database query fails.
+    pub async fn load(db: &DatabaseConnection) -> AppResult<Self> {
+        let patterns = sensor_type_patterns::Entity::find()
+            .order_by_desc(sensor_type_patterns::Column::Priority)
+            .order_by_asc(sensor_type_patterns::Column::Keyword)
+            .all(db)
+            .await?
+            .into_iter()
+            // An empty keyword would match every name
+            .filter(|p| !p.keyword.is_empty())
+            .map(|p| (p.keyword, p.sensor_type))
+            .collect();
+        Ok(Self { patterns })
+    }
+
+    /// Type of the sensor named `name`: the first pattern whose keyword it
+    /// contains, else the name itself.
+    pub fn derive(&self, name: &str) -> String {
+        self.patterns
+            .iter()
+            .find(|(keyword, _)| name.contains(keyword.as_str()))
+            .map_or_else(|| name.to_string(), |(_, sensor_type)| sensor_type.clone())
+    }
+}
@@ -1,17 +1,55 @@
 use chrono::{Duration, Utc};
-use sea_orm::{ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder, Set, Statement};
-use std::collections::HashMap;
+use sea_orm::{ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, DatabaseTransaction, EntityTrait, FromQueryResult, IdenStatic, QueryFilter, QueryOrder, QuerySelect, QueryTrait, Set, Statement, TransactionTrait};
+use sea_orm::sea_query::{Expr, OnConflict, Query};
+use std::collections::{HashMap, HashSet};
 use uuid::Uuid;
 
 use crate::entity::{
-    alarm_locations, alarms, device_status, events, readings, sensors, stations, sync_state, zones,
+    alarm_locations, alarms, device_status, events, readings, sensor_thresholds, sensors, stations,
+    sync_state, zones,
 };
+use crate::entity::sync_state::SyncStatus;
 use crate::error::AppResult;
+use crate::services::device_events::{DeviceEventSender, DeviceSnapshot, DeviceStatusEvent};
+use crate::services::{metrics, timescale};
+use crate::sync::sanity::{self, SanityCheck, SanityRange};
+use crate::sync::sensor_filter::SensorTypeFilter;
+use crate::sync::sensor_types::SensorTypePatterns;
 use crate::vaisala::VaisalaClient;
+use crate::vaisala::models::{
+    parse_location_ids, parse_thresholds, ActiveAlarmAttributes, DataPoint, JsonApiResource,
+    LocationAttributes, Threshold,
+};
 
 /// Batch size for bulk inserts
 const BATCH_SIZE: usize = 1000;
 
+/// Alarm columns refreshed from Vaisala when they change
+const ALARM_UPDATE_COLUMNS: [alarms::Column; 10] = [
+    alarms::Column::Severity,
+    alarms::Column::Description,
+    alarms::Column::ErrorText,
+    alarms::Column::WhenOff,
+    alarms::Column::WhenAck,
+    alarms::Column::DurationSec,
+    alarms::Column::Status,
+    alarms::Column::AckComments,
+    alarms::Column::AckActionTaken,
+    alarms::Column::LocationIds,
+];
+
+/// SQL condition: the stored alarm's `columns` differ from the upserted row's
+fn alarm_columns_differ(columns: &[alarms::Column]) -> String {
+    let list = |table: &str| {
+        columns
+            .iter()
+            .map(|c| format!("{table}.{}", c.as_str()))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    format!("({}) IS DISTINCT FROM ({})", list("alarms"), list("EXCLUDED"))
+}
+
 /// Discover and sync zones, stations, and sensors from Vaisala.
 ///
 /// Parses the location hierarchy from Vaisala's `/locations` endpoint and creates
@@ -23,14 +61,24 @@ const BATCH_SIZE: usize = 1000;
 ///     - Station (depth 2, e.g., "Martigny")
 ///       - Sensor (depth 3, leaf=true, e.g., "MDepthmm")
 ///
+/// New sensors get the type their name maps to in `sensor_type_patterns` (see
+/// [`SensorTypePatterns`]); those rejected by `filter` are not created. Active sensors whose
+/// location is gone from `/locations` or marked deleted are deactivated, and
+/// reactivated once the location is listed again and not deleted.
+///
 /// # Errors
 ///
 /// Returns an error if the Vaisala API or database operations fail.
-pub async fn sync_locations(db: &DatabaseConnection, vaisala: &VaisalaClient) -> AppResult<()> {
+pub async fn sync_locations(
+    db: &DatabaseConnection,
+    vaisala: &VaisalaClient,
+    filter: &SensorTypeFilter,
+) -> AppResult<LocationsPass> {
     tracing::info!("Discovering locations from Vaisala...");
 
     // Fetch all locations from Vaisala
     let locations = vaisala.get_locations().await?;
+    let sensor_types = SensorTypePatterns::load(db).await?;
 
     let now = Utc::now();
 
@@ -59,7 +107,9 @@ pub async fn sync_locations(db: &DatabaseConnection, vaisala: &VaisalaClient) ->
     // Track created entities for logging
     let mut zones_created = 0;
     let mut stations_created = 0;
+    let mut stations_moved = 0;
     let mut sensors_created = 0;
+    let mut sensors_skipped = 0;
 
     // Maps to track newly created zones/stations by name for FK lookups
     let mut zone_ids: HashMap<String, Uuid> = existing_zones
@@ -74,8 +124,16 @@ pub async fn sync_locations(db: &DatabaseConnection, vaisala: &VaisalaClient) ->
     // Collect sensor location IDs for fetching detailed info
     let mut new_sensor_location_ids: Vec<i32> = Vec::new();
 
+    let paused = paused_location_ids(&locations.data);
+
+    // Zones before stations before sensors, so a station can be placed in (or
+    // moved to) a zone discovered in the same pass. The sort is stable, so
+    // siblings keep Vaisala's order
+    let mut ordered: Vec<_> = locations.data.iter().collect();
+    ordered.sort_by_key(|r| r.attributes.path.matches('/').count());
+
     // Process each location
-    for resource in &locations.data {
+    for resource in ordered {
         let attrs = &resource.attributes;
 
         // Skip deleted locations
@@ -127,10 +185,32 @@ pub async fn sync_locations(db: &DatabaseConnection, vaisala: &VaisalaClient) ->
             (3, false) => {
                 let zone_name = parts[1];
                 let station_name = parts[2];
-
-                if !station_ids.contains_key(&attrs.node_id) {
-                    let zone_id = zone_ids.get(zone_name).copied();
-
+                let zone_id = zone_ids.get(zone_name).copied();
+
+                if let Some(existing) = existing_stations.get(&attrs.node_id) {
+                    // Moved to another zone in viewLinc; an unresolved zone
+                    // leaves the current assignment alone
+                    if zone_id.is_some() && existing.zone_id != zone_id {
+                        let mut active: stations::ActiveModel = existing.clone().into();
+                        active.zone_id = Set(zone_id);
+                        active.vaisala_path = Set(Some(attrs.path.clone()));
+
+                        match active.update(db).await {
+                            Ok(_) => {
+                                stations_moved += 1;
+                                tracing::info!(
+                                    name = station_name,
+                                    node_id = attrs.node_id,
+                                    zone = zone_name,
+                                    "Moved station to another zone"
+                                );
+                            }
+                            Err(e) => {
+                                tracing::warn!(error = %e, name = station_name, "Failed to move station");
+                            }
+                        }
+                    }
+                } else if !station_ids.contains_key(&attrs.node_id) {
                     let station = stations::ActiveModel {
                         id: Set(Uuid::new_v4()),
                         zone_id: Set(zone_id),
@@ -159,8 +239,19 @@ pub async fn sync_locations(db: &DatabaseConnection, vaisala: &VaisalaClient) ->
 
             // Sensor: leaf=true with path like "viewLinc/BREATHE/Martigny/MDepthmm"
             (_, true) if parts.len() >= 4 => {
-                if !existing_sensors.contains_key(&attrs.node_id) {
+                if existing_sensors.contains_key(&attrs.node_id) {
+                    continue;
+                }
+                let name = parts[parts.len() - 1];
+                if filter.admits(name, &sensor_types.derive(name)) {
                     new_sensor_location_ids.push(attrs.node_id);
+                } else {
+                    sensors_skipped += 1;
+                    tracing::debug!(
+                        name,
+                        location_id = attrs.node_id,
+                        "Skipped filtered sensor type"
+                    );
                 }
             }
 
@@ -206,8 +297,7 @@ pub async fn sync_locations(db: &DatabaseConnection, vaisala: &VaisalaClient) ->
             };
 
             // Derive sensor_type from the name (e.g., "MDepthmm" -> "Depth")
-            // This is a simple heuristic; adjust as needed
-            let sensor_type = derive_sensor_type(&attrs.location_name);
+            let sensor_type = sensor_types.derive(&attrs.location_name);
 
             let sensor = sensors::ActiveModel {
                 id: Set(Uuid::new_v4()),
@@ -241,6 +331,8 @@ pub async fn sync_locations(db: &DatabaseConnection, vaisala: &VaisalaClient) ->
                     Some(attrs.sample_interval_sec)
                 }),
                 is_active: Set(Some(true)),
+                align_timestamps: Set(true),
+                alarming_paused: Set(paused.contains(&attrs.id)),
                 created_at: Set(Some(now.into())),
                 updated_at: Set(Some(now.into())),
                 discovered_at: Set(Some(now.into())),
@@ -253,7 +345,7 @@ pub async fn sync_locations(db: &DatabaseConnection, vaisala: &VaisalaClient) ->
                         sensor_id: Set(s.id),
                         last_data_time: Set(None),
                         last_sync_attempt: Set(None),
-                        sync_status: Set(Some("pending".to_string())),
+                        sync_status: Set(Some(SyncStatus::Pending)),
                         error_message: Set(None),
                         retry_count: Set(Some(0)),
                         last_full_sync: Set(None),
@@ -278,42 +370,251 @@ pub async fn sync_locations(db: &DatabaseConnection, vaisala: &VaisalaClient) ->
         }
     }
 
+    let paused_changed = apply_alarming_paused(db, &paused).await?;
+    let present = sensor_location_ids(&locations.data);
+    let deactivated = deactivate_missing_sensors(db, &present).await?;
+    let reactivated = reactivate_present_sensors(db, &present).await?;
+
     tracing::info!(
         zones = zones_created,
         stations = stations_created,
+        stations_moved,
         sensors = sensors_created,
+        sensors_skipped,
+        sensors_deactivated = deactivated.len(),
+        sensors_reactivated = reactivated.len(),
+        paused_changed,
         "Location discovery complete"
     );
 
-    Ok(())
+    Ok(LocationsPass {
+        sensors_created,
+        activity_changed: deactivated.into_iter().chain(reactivated).collect(),
+    })
 }
 
-/// Derive sensor type from the sensor name.
-/// E.g., "MDepthmm" -> "Depth", "MCDOMppb" -> "CDOM"
-fn derive_sensor_type(name: &str) -> String {
-    // Common patterns: first char is station prefix, then type, then units
-    // MDepthmm, MCDOMppb, MTurbNTU, MBattV, MDOdegC, MConduSCm, MDOuM, MCondTdegC
-    let patterns: &[(&str, &[&str])] = &[
-        ("Depth", &["depth", "Depth"]),
-        ("CDOM", &["cdom", "CDOM"]),
-        ("Turbidity", &["turb", "Turb"]),
-        ("Battery", &["batt", "Batt"]),
-        ("DO_Temperature", &["DOdegC", "DOTdegC"]),
-        ("Dissolved_O2", &["DOuM"]),
-        ("Conductivity", &["Condu", "condu"]),
-        ("Cond_Temperature", &["CondT"]),
-    ];
+/// Outcome of one `sync_locations` pass
+#[derive(Debug, Clone, Default)]
+pub struct LocationsPass {
+    /// Sensors discovered and created
+    pub sensors_created: u64,
+    /// Sensors deactivated or reactivated, whose stations' cached data is stale
+    pub activity_changed: Vec<Uuid>,
+}
+
+/// Location IDs of sensors whose alarming is paused in viewLinc
+fn paused_location_ids(locations: &[JsonApiResource<LocationAttributes>]) -> HashSet<i32> {
+    locations
+        .iter()
+        .map(|r| &r.attributes)
+        .filter(|attrs| attrs.leaf && attrs.pause && !attrs.deleted)
+        .map(|attrs| attrs.node_id)
+        .collect()
+}
+
+/// Location IDs of sensors listed in viewLinc and not deleted
+fn sensor_location_ids(locations: &[JsonApiResource<LocationAttributes>]) -> HashSet<i32> {
+    locations
+        .iter()
+        .map(|r| &r.attributes)
+        .filter(|attrs| attrs.leaf && !attrs.deleted)
+        .map(|attrs| attrs.node_id)
+        .collect()
+}
+
+/// Set `is_active = false` on active sensors whose location is not in `present`.
+///
+/// An empty `present` is taken as a bad `/locations` response rather than
+/// every sensor being removed, and deactivates nothing. Returns the IDs of the
+/// sensors deactivated.
+async fn deactivate_missing_sensors(db: &DatabaseConnection, present: &HashSet<i32>) -> AppResult<Vec<Uuid>> {
+    if present.is_empty() {
+        tracing::warn!("No sensors listed by Vaisala, skipping deactivation");
+        return Ok(Vec::new());
+    }
+
+    let missing: Vec<sensors::Model> = sensors::Entity::find()
+        .filter(sensors::Column::IsActive.eq(true))
+        .all(db)
+        .await?
+        .into_iter()
+        .filter(|s| !present.contains(&s.vaisala_location_id))
+        .collect();
+    if missing.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    for sensor in &missing {
+        tracing::info!(
+            name = sensor.name,
+            location_id = sensor.vaisala_location_id,
+            "Deactivating sensor removed from Vaisala"
+        );
+    }
+
+    let ids: Vec<Uuid> = missing.iter().map(|s| s.id).collect();
+    sensors::Entity::update_many()
+        .col_expr(sensors::Column::IsActive, Expr::value(false))
+        .col_expr(sensors::Column::UpdatedAt, Expr::value(Utc::now()))
+        .filter(sensors::Column::Id.is_in(ids.clone()))
+        .exec(db)
+        .await?;
+
+    Ok(ids)
+}
+
+/// Set `is_active = true` on inactive sensors whose location is in `present`.
+///
+/// Undoes [`deactivate_missing_sensors`] for locations restored in viewLinc.
+/// Returns the IDs of the sensors reactivated.
+async fn reactivate_present_sensors(db: &DatabaseConnection, present: &HashSet<i32>) -> AppResult<Vec<Uuid>> {
+    let returned: Vec<sensors::Model> = sensors::Entity::find()
+        .filter(sensors::Column::VaisalaLocationId.is_in(present.iter().copied()))
+        .all(db)
+        .await?
+        .into_iter()
+        .filter(|s| s.is_active != Some(true))
+        .collect();
+    if returned.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    for sensor in &returned {
+        tracing::info!(
+            name = sensor.name,
+            location_id = sensor.vaisala_location_id,
+            "Reactivating sensor restored in Vaisala"
+        );
+    }
+
+    let ids: Vec<Uuid> = returned.iter().map(|s| s.id).collect();
+    sensors::Entity::update_many()
+        .col_expr(sensors::Column::IsActive, Expr::value(true))
+        .col_expr(sensors::Column::UpdatedAt, Expr::value(Utc::now()))
+        .filter(sensors::Column::Id.is_in(ids.clone()))
+        .exec(db)
+        .await?;
+
+    Ok(ids)
+}
+
+/// Set `alarming_paused` on every sensor whose flag differs from `paused`.
+///
+/// Returns the number of sensors updated.
+async fn apply_alarming_paused(db: &DatabaseConnection, paused: &HashSet<i32>) -> AppResult<u64> {
+    let sensors = sensors::Entity::find().all(db).await?;
+    let (pause, resume): (Vec<_>, Vec<_>) = sensors
+        .iter()
+        .filter(|s| s.alarming_paused != paused.contains(&s.vaisala_location_id))
+        .partition(|s| !s.alarming_paused);
+
+    let now = Utc::now();
+    let mut changed = 0;
+    for (ids, value) in [(pause, true), (resume, false)] {
+        if ids.is_empty() {
+            continue;
+        }
+        changed += sensors::Entity::update_many()
+            .col_expr(sensors::Column::AlarmingPaused, Expr::value(value))
+            .col_expr(sensors::Column::UpdatedAt, Expr::value(now))
+            .filter(sensors::Column::Id.is_in(ids.iter().map(|s| s.id)))
+            .exec(db)
+            .await?
+            .rows_affected;
+    }
+
+    Ok(changed)
+}
+
+/// Refresh the `alarming_paused` flag of known sensors from Vaisala.
+///
+/// Cheaper than a full [`sync_locations`]: only `/locations` is fetched and no
+/// zones, stations, or sensors are created. Returns the number of sensors
+/// whose flag changed.
+///
+/// # Errors
+///
+/// Returns an error if the Vaisala API or database operations fail.
+pub async fn refresh_alarming_paused(db: &DatabaseConnection, vaisala: &VaisalaClient) -> AppResult<u64> {
+    let locations = vaisala.get_locations().await?;
+    let changed = apply_alarming_paused(db, &paused_location_ids(&locations.data)).await?;
+    if changed > 0 {
+        tracing::info!(sensors = changed, "Updated alarming paused flags");
+    }
+    Ok(changed)
+}
+
+/// Timestamp (epoch seconds) a reading is stored under.
+///
+/// With `align` set, rounds to the nearest 10 minutes: different sensors report
+/// at slightly different times, so rounding aligns them to common timestamps
+/// (same approach as the R Shiny portal). Otherwise the raw timestamp is kept.
+pub fn align_timestamp(epoch: i64, align: bool) -> i64 {
+    if align {
+        ((epoch + 300) / 600) * 600 // round to nearest 600s
+    } else {
+        epoch
+    }
+}
+
+/// Window and outcome of one `sync_readings` pass
+#[derive(Debug, Clone)]
+pub struct ReadingsPass {
+    /// Earliest `date_from` requested from Vaisala
+    pub from: chrono::DateTime<Utc>,
+    /// End of the requested window
+    pub to: chrono::DateTime<Utc>,
+    /// Number of sensors included in the request
+    pub sensors: usize,
+    /// Rows inserted across all sensors
+    pub points_inserted: u64,
+    /// Rows for new timestamps older than their sensor's `last_data_time`
+    /// before the pass, i.e. history Vaisala delivered late (only full syncs
+    /// see these); logged rows replacing realtime ones are not counted
+    pub backfill_points: u64,
+    /// Sensors that received at least one new row
+    pub updated_sensors: Vec<Uuid>,
+    /// Sensors whose alarm thresholds were replaced
+    pub thresholds_changed: usize,
+}
+
+/// Merge points that share a (rounded) timestamp into a single point each.
+///
+/// By default the first point wins, matching `ON CONFLICT DO NOTHING`. When
+/// `logged_overrides_realtime` is true, a logged point replaces a realtime one
+/// at the same timestamp, but never the reverse. First-seen order is preserved.
+pub fn merge_points(
+    points: Vec<(chrono::DateTime<Utc>, f64, bool)>,
+    logged_overrides_realtime: bool,
+) -> Vec<(chrono::DateTime<Utc>, f64, bool)> {
+    merge_points_by(points, |p| (p.0, p.2), logged_overrides_realtime)
+}
 
-    for (sensor_type, keywords) in patterns {
-        for keyword in *keywords {
-            if name.contains(keyword) {
-                return (*sensor_type).to_string();
+/// [`merge_points`] over any point type, given its `(time, logged)`.
+fn merge_points_by<P>(
+    points: Vec<P>,
+    key: impl Fn(&P) -> (chrono::DateTime<Utc>, bool),
+    logged_overrides_realtime: bool,
+) -> Vec<P> {
+    let mut index: HashMap<chrono::DateTime<Utc>, usize> = HashMap::with_capacity(points.len());
+    let mut merged: Vec<P> = Vec::with_capacity(points.len());
+
+    for point in points {
+        let (time, logged) = key(&point);
+        match index.get(&time) {
+            Some(&i) => {
+                if logged_overrides_realtime && logged && !key(&merged[i]).1 {
+                    merged[i] = point;
+                }
+            }
+            None => {
+                index.insert(time, merged.len());
+                merged.push(point);
             }
         }
     }
 
-    // Default: use the name itself
-    name.to_string()
+    merged
 }
 
 /// Sync readings for all active sensors.
@@ -322,6 +623,13 @@ fn derive_sensor_type(name: &str) -> String {
 /// history (up to `max_history_days`). This is used for periodic full re-syncs
 /// to catch any backfilled data from Vaisala.
 ///
+/// If `logged_overrides_realtime` is true, a logged reading replaces a stored
+/// realtime reading at the same timestamp instead of being discarded.
+///
+/// Values outside each sensor's sanity range (see `sanity`) are dropped.
+///
+/// Returns the window requested from Vaisala and the number of rows inserted.
+///
 /// # Errors
 ///
 /// Returns an error if the database or Vaisala API operations fail.
@@ -330,7 +638,11 @@ pub async fn sync_readings(
     vaisala: &VaisalaClient,
     max_history_days: i64,
     force_full_sync: bool,
-) -> AppResult<()> {
+    logged_overrides_realtime: bool,
+    sanity: &SanityCheck,
+) -> AppResult<ReadingsPass> {
+    let now = Utc::now();
+
     // Get all active sensors with their sync state
     let sensors_with_state: Vec<(sensors::Model, Option<sync_state::Model>)> =
         sensors::Entity::find()
@@ -341,12 +653,21 @@ pub async fn sync_readings(
 
     if sensors_with_state.is_empty() {
         tracing::debug!("No active sensors to sync");
-        return Ok(());
+        return Ok(ReadingsPass {
+            from: now,
+            to: now,
+            sensors: 0,
+            points_inserted: 0,
+            backfill_points: 0,
+            updated_sensors: Vec::new(),
+            thresholds_changed: 0,
+        });
     }
 
-    // Build a map of vaisala_location_id -> (sensor_id, last_data_time)
+    // Build a map of vaisala_location_id -> (sensor_id, last_data_time, align_timestamps, sanity range)
     // If force_full_sync is true, we ignore last_data_time to re-fetch everything
-    let mut location_map: HashMap<i32, (Uuid, Option<chrono::DateTime<Utc>>)> = HashMap::new();
+    let mut location_map: HashMap<i32, (Uuid, Option<chrono::DateTime<Utc>>, bool, SanityRange)> =
+        HashMap::new();
     for (sensor, state) in &sensors_with_state {
         let last_time = if force_full_sync {
             None
@@ -355,12 +676,28 @@ pub async fn sync_readings(
                 .as_ref()
                 .and_then(|s| s.last_data_time.map(|dt| dt.with_timezone(&Utc)))
         };
-        location_map.insert(sensor.vaisala_location_id, (sensor.id, last_time));
+        location_map.insert(
+            sensor.vaisala_location_id,
+            (
+                sensor.id,
+                last_time,
+                sensor.align_timestamps,
+                sanity.range_for(sensor),
+            ),
+        );
     }
 
+    // Stored last_data_time, kept even on a full sync to detect backfill
+    let previous_last_times: HashMap<Uuid, chrono::DateTime<Utc>> = sensors_with_state
+        .iter()
+        .filter_map(|(sensor, state)| {
+            let last = state.as_ref()?.last_data_time?;
+            Some((sensor.id, last.with_timezone(&Utc)))
+        })
+        .collect();
+
     // Group by earliest date_from to minimize API calls
     // For initial sync, use max_history_days; for incremental, use last_data_time
-    let now = Utc::now();
     let max_history_start = now - Duration::days(max_history_days);
 
     // Collect all location IDs
@@ -369,7 +706,7 @@ pub async fn sync_readings(
     // Determine the earliest date_from across all sensors
     let earliest_from = location_map
         .values()
-        .map(|(_, last_time)| last_time.unwrap_or(max_history_start))
+        .map(|(_, last_time, _, _)| last_time.unwrap_or(max_history_start))
         .min()
         .unwrap_or(max_history_start);
 
@@ -395,10 +732,15 @@ pub async fn sync_readings(
         }
     };
 
+    let mut points_inserted = 0;
+    let mut backfill_points = 0;
+    let mut updated_sensors = Vec::new();
+    let mut thresholds_changed = 0;
+
     // Process each location's samples from JSON API data array
     for resource in history.data {
         let attrs = resource.attributes;
-        let Some((sensor_id, last_time)) = location_map.get(&attrs.id) else {
+        let Some((sensor_id, last_time, align, range)) = location_map.get(&attrs.id) else {
             tracing::warn!(
                 location_id = attrs.id,
                 "Received data for unknown location"
@@ -406,6 +748,19 @@ pub async fn sync_readings(
             continue;
         };
 
+        let thresholds = parse_thresholds(&attrs.thresholds);
+        match store_sensor_thresholds(db, *sensor_id, &thresholds).await {
+            Ok(true) => thresholds_changed += 1,
+            Ok(false) => {}
+            Err(e) => {
+                tracing::warn!(
+                    error = %e,
+                    sensor_id = %sensor_id,
+                    "Failed to store sensor thresholds"
+                );
+            }
+        }
+
         // Filter data points to only those after last_data_time (if any)
         // Convert epoch timestamps to DateTime for comparison
         let last_timestamp = last_time.map(|lt| lt.timestamp());
@@ -425,85 +780,505 @@ pub async fn sync_readings(
         }
 
         let sample_count = new_points.len();
+        // Sync state advances past dropped points too, so they are not re-fetched
+        let latest_timestamp = new_points.iter().map(|p| p.timestamp).max();
+        let earliest_timestamp = new_points.iter().map(|p| p.timestamp).min();
+        let (new_points, _) = sanity::screen(*sensor_id, new_points, *range);
+        let models = reading_models(*sensor_id, *align, new_points, logged_overrides_realtime);
+
+        let latest = latest_timestamp.and_then(|ts| chrono::DateTime::from_timestamp(ts, 0));
+
+        // Only points older than the previous last_data_time can be backfill;
+        // most of them are already stored, so count the timestamps this pass
+        // actually added
+        let previous_last = previous_last_times
+            .get(sensor_id)
+            .filter(|last| earliest_timestamp.is_some_and(|ts| ts < last.timestamp()));
+
+        match insert_readings(
+            db,
+            *sensor_id,
+            models,
+            logged_overrides_realtime,
+            latest,
+            previous_last.copied(),
+        )
+        .await
+        {
+            Ok((inserted, backfilled)) => {
+                points_inserted += inserted;
+                if inserted > 0 {
+                    updated_sensors.push(*sensor_id);
+                }
+                if let Some(previous_last) = previous_last
+                    && backfilled > 0
+                {
+                    backfill_points += backfilled;
+                    tracing::info!(
+                        count = backfilled,
+                        sensor_id = %sensor_id,
+                        location_id = attrs.id,
+                        last_data_time = %previous_last,
+                        "Vaisala backfilled readings"
+                    );
+                }
+                tracing::info!(
+                    count = sample_count,
+                    inserted,
+                    sensor_id = %sensor_id,
+                    location_id = attrs.id,
+                    "Synced readings"
+                );
+            }
+            Err(e) => {
+                tracing::warn!(
+                    error = %e,
+                    sensor_id = %sensor_id,
+                    location_id = attrs.id,
+                    "Failed to store readings, transaction rolled back"
+                );
+                update_sync_state_error(db, *sensor_id, &e.to_string()).await;
+            }
+        }
+    }
 
-        // Build all models and track latest timestamp
-        let mut models: Vec<readings::ActiveModel> = Vec::with_capacity(new_points.len());
-        let mut latest_timestamp: Option<i64> = None;
+    metrics::record_sync_rows(points_inserted);
+
+    Ok(ReadingsPass {
+        from: earliest_from,
+        to: now,
+        sensors: location_ids.len(),
+        points_inserted,
+        backfill_points,
+        updated_sensors,
+        thresholds_changed,
+    })
+}
 
-        for point in new_points {
-            // Convert epoch timestamp to DateTime, rounded to nearest 10 minutes.
-            // Different sensors report at slightly different times, so rounding
-            // aligns them to common timestamps (same approach as the R Shiny portal).
-            let raw_time = chrono::DateTime::from_timestamp(point.timestamp, 0)
-                .unwrap_or_else(Utc::now);
-            let epoch = raw_time.timestamp();
-            let rounded_epoch = ((epoch + 300) / 600) * 600; // round to nearest 600s
-            let time = chrono::DateTime::from_timestamp(rounded_epoch, 0)
-                .unwrap_or(raw_time);
+/// Sort key used to compare stored and freshly parsed thresholds
+type ThresholdKey = (String, u64, Option<i16>, Option<String>);
 
-            models.push(readings::ActiveModel {
-                sensor_id: Set(*sensor_id),
-                time: Set(time.into()),
-                value: Set(point.value),
-                logged: Set(Some(point.logged)),
-            });
+/// Replace a sensor's stored thresholds with those reported by Vaisala.
+///
+/// Does nothing when the set is unchanged, so `updated_at` reflects the last
+/// actual configuration change. Returns whether the thresholds were replaced.
+///
+/// # Errors
+///
+/// Returns an error if the lookup, delete, insert, or commit fails.
+pub async fn store_sensor_thresholds(
+    db: &DatabaseConnection,
+    sensor_id: Uuid,
+    thresholds: &[Threshold],
+) -> AppResult<bool> {
+    let mut current: Vec<ThresholdKey> = sensor_thresholds::Entity::find()
+        .filter(sensor_thresholds::Column::SensorId.eq(sensor_id))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|t| (t.kind, t.value.to_bits(), t.severity, t.description))
+        .collect();
+    let mut incoming: Vec<ThresholdKey> = thresholds
+        .iter()
+        .map(|t| {
+            (
+                t.kind.as_str().to_string(),
+                t.value.to_bits(),
+                t.severity,
+                t.description.clone(),
+            )
+        })
+        .collect();
+    current.sort();
+    incoming.sort();
+    if current == incoming {
+        return Ok(false);
+    }
 
-            if latest_timestamp.is_none_or(|lt| point.timestamp > lt) {
-                latest_timestamp = Some(point.timestamp);
+    let now = Utc::now();
+    let txn = db.begin().await?;
+
+    sensor_thresholds::Entity::delete_many()
+        .filter(sensor_thresholds::Column::SensorId.eq(sensor_id))
+        .exec(&txn)
+        .await?;
+
+    if !thresholds.is_empty() {
+        let models = thresholds.iter().map(|t| sensor_thresholds::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            sensor_id: Set(sensor_id),
+            kind: Set(t.kind.as_str().to_string()),
+            value: Set(t.value),
+            severity: Set(t.severity),
+            description: Set(t.description.clone()),
+            updated_at: Set(Some(now.into())),
+        });
+        sensor_thresholds::Entity::insert_many(models)
+            .exec_without_returning(&txn)
+            .await?;
+    }
+
+    txn.commit().await?;
+
+    tracing::info!(sensor_id = %sensor_id, count = thresholds.len(), "Updated sensor thresholds");
+    Ok(true)
+}
+
+/// How far back gap detection looks. Older gaps are left to the daily full
+/// re-sync; this also matches the hourly aggregate refresh window.
+pub const GAP_LOOKBACK_HOURS: i64 = 24;
+
+/// A stretch without readings between two stored readings of a sensor
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct GapWindow {
+    pub sensor_id: Uuid,
+    pub location_id: i32,
+    /// Last reading before the gap
+    pub from: chrono::DateTime<Utc>,
+    /// First reading after the gap
+    pub to: chrono::DateTime<Utc>,
+}
+
+/// One targeted `locations_history` fetch covering one or more gaps
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BackfillRequest {
+    pub from: chrono::DateTime<Utc>,
+    pub to: chrono::DateTime<Utc>,
+    pub location_ids: Vec<i32>,
+}
+
+#[derive(Debug, FromQueryResult)]
+struct GapRow {
+    sensor_id: Uuid,
+    location_id: i32,
+    gap_start: chrono::DateTime<Utc>,
+    gap_end: chrono::DateTime<Utc>,
+}
+
+/// Find gaps longer than `min_gap` between consecutive readings of active
+/// sensors within the last [`GAP_LOOKBACK_HOURS`].
+///
+/// Only interior gaps are reported: missing data after a sensor's latest
+/// reading is what the next incremental sync fetches anyway.
+///
+/// # Errors
+///
+/// Returns an error if the query fails.
+pub async fn find_recent_gaps(
+    db: &DatabaseConnection,
+    min_gap: Duration,
+) -> AppResult<Vec<GapWindow>> {
+    let since = Utc::now() - Duration::hours(GAP_LOOKBACK_HOURS);
+
+    let rows = db
+        .query_all(Statement::from_sql_and_values(
+            sea_orm::DatabaseBackend::Postgres,
+            r"SELECT r.sensor_id, s.vaisala_location_id AS location_id,
+                     r.prev_time AS gap_start, r.time AS gap_end
+              FROM (
+                  SELECT sensor_id, time,
+                         LAG(time) OVER (PARTITION BY sensor_id ORDER BY time) AS prev_time
+                  FROM readings
+                  WHERE time >= $1
+              ) r
+              JOIN sensors s ON s.id = r.sensor_id
+              WHERE s.is_active = true
+                AND r.prev_time IS NOT NULL
+                AND r.time - r.prev_time > $2 * INTERVAL '1 second'
+              ORDER BY r.prev_time",
+            [since.into(), min_gap.num_seconds().into()],
+        ))
+        .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| GapRow::from_query_result(&row, ""))
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
+        .map(|r| GapWindow {
+            sensor_id: r.sensor_id,
+            location_id: r.location_id,
+            from: r.gap_start,
+            to: r.gap_end,
+        })
+        .collect())
+}
+
+/// Group gaps into as few `locations_history` requests as possible.
+///
+/// Overlapping or touching windows are merged into one request spanning both,
+/// covering the union of their locations.
+pub fn plan_backfills(gaps: &[GapWindow]) -> Vec<BackfillRequest> {
+    let mut sorted: Vec<&GapWindow> = gaps.iter().collect();
+    sorted.sort_by_key(|g| (g.from, g.to));
+
+    let mut requests: Vec<BackfillRequest> = Vec::new();
+    for gap in sorted {
+        match requests.last_mut() {
+            Some(last) if gap.from <= last.to => {
+                last.to = last.to.max(gap.to);
+                if !last.location_ids.contains(&gap.location_id) {
+                    last.location_ids.push(gap.location_id);
+                }
             }
+            _ => requests.push(BackfillRequest {
+                from: gap.from,
+                to: gap.to,
+                location_ids: vec![gap.location_id],
+            }),
         }
+    }
 
-        // Batch insert in chunks of BATCH_SIZE
-        for chunk in models.chunks(BATCH_SIZE) {
-            if let Err(e) = readings::Entity::insert_many(chunk.to_vec())
-                .on_conflict(
-                    sea_orm::sea_query::OnConflict::columns([
-                        readings::Column::SensorId,
-                        readings::Column::Time,
-                    ])
-                    .do_nothing()
-                    .to_owned(),
-                )
-                .exec(db)
-                .await
-            {
-                // "None of the records are inserted" is expected from ON CONFLICT DO NOTHING
-                // when all records in the batch are duplicates
-                let msg = e.to_string();
-                if !msg.contains("None of the records") && !msg.contains("duplicate") {
-                    tracing::warn!(
-                        error = %e,
-                        batch_size = chunk.len(),
-                        "Failed to insert reading batch"
+    requests
+}
+
+/// Fetch and store readings for the given gaps.
+///
+/// Only points strictly inside a sensor's gap windows and its sanity range are
+/// stored, and sync state is left untouched (gaps lie before `last_data_time`). Returns the
+/// number of rows inserted.
+///
+/// # Errors
+///
+/// Returns an error if a Vaisala request fails; rows stored by earlier
+/// requests are kept.
+pub async fn backfill_gaps(
+    db: &DatabaseConnection,
+    vaisala: &VaisalaClient,
+    gaps: &[GapWindow],
+    logged_overrides_realtime: bool,
+    sanity: &SanityCheck,
+) -> AppResult<u64> {
+    let sensor_settings: HashMap<Uuid, (bool, SanityRange)> = sensors::Entity::find()
+        .filter(sensors::Column::IsActive.eq(true))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|s| (s.id, (s.align_timestamps, sanity.range_for(&s))))
+        .collect();
+
+    let mut inserted = 0;
+    for request in plan_backfills(gaps) {
+        let history = vaisala
+            .get_locations_history(&request.location_ids, request.from, Some(request.to))
+            .await?;
+
+        for resource in history.data {
+            let attrs = resource.attributes;
+            let windows: Vec<&GapWindow> = gaps
+                .iter()
+                .filter(|g| g.location_id == attrs.id && g.from < request.to && g.to > request.from)
+                .collect();
+            let Some(sensor_id) = windows.first().map(|g| g.sensor_id) else {
+                continue;
+            };
+
+            let points: Vec<DataPoint> = attrs
+                .data_points
+                .into_iter()
+                .filter(|dp| {
+                    windows.iter().any(|g| {
+                        dp.timestamp > g.from.timestamp() && dp.timestamp < g.to.timestamp()
+                    })
+                })
+                .collect();
+            let (align, range) = sensor_settings
+                .get(&sensor_id)
+                .copied()
+                .unwrap_or((true, sanity.global));
+            let (points, _) = sanity::screen(sensor_id, points, range);
+            if points.is_empty() {
+                continue;
+            }
+
+            let models = reading_models(sensor_id, align, points, logged_overrides_realtime);
+
+            match store_sensor_readings(db, sensor_id, models, logged_overrides_realtime, None).await {
+                Ok(count) => {
+                    inserted += count;
+                    tracing::info!(
+                        sensor_id = %sensor_id,
+                        inserted = count,
+                        "Backfilled readings gap"
                     );
                 }
+                Err(e) => {
+                    tracing::warn!(error = %e, sensor_id = %sensor_id, "Failed to store gap backfill");
+                }
             }
         }
+    }
 
-        // Update sync state with the latest timestamp
-        if let Some(ts) = latest_timestamp
-            && let Some(latest) = chrono::DateTime::from_timestamp(ts, 0)
-        {
-            update_sync_state_success(db, *sensor_id, latest).await;
+    Ok(inserted)
+}
+
+/// Align Vaisala data points and convert them into reading rows.
+///
+/// Each row keeps the point's unrounded time in `raw_time`.
+fn reading_models(
+    sensor_id: Uuid,
+    align: bool,
+    points: Vec<DataPoint>,
+    logged_overrides_realtime: bool,
+) -> Vec<readings::ActiveModel> {
+    // (aligned time, raw time, value, logged)
+    type Point = (chrono::DateTime<Utc>, chrono::DateTime<Utc>, f64, bool);
+
+    let rounded: Vec<Point> = points
+        .into_iter()
+        .map(|point| {
+            let raw_time = chrono::DateTime::from_timestamp(point.timestamp, 0)
+                .unwrap_or_else(Utc::now);
+            let time = chrono::DateTime::from_timestamp(
+                align_timestamp(raw_time.timestamp(), align),
+                0,
+            )
+            .unwrap_or(raw_time);
+            (time, raw_time, point.value, point.logged)
+        })
+        .collect();
+
+    // Rounding can map several points onto one timestamp; collapse them first
+    // (an upsert may not touch the same row twice within one statement)
+    let ingested_at = Utc::now();
+    merge_points_by(rounded, |p| (p.0, p.3), logged_overrides_realtime)
+        .into_iter()
+        .map(|(time, raw_time, value, logged)| readings::ActiveModel {
+            sensor_id: Set(sensor_id),
+            time: Set(time.into()),
+            value: Set(value),
+            logged: Set(Some(logged)),
+            ingested_at: Set(Some(ingested_at.into())),
+            raw_time: Set(Some(raw_time.into())),
+        })
+        .collect()
+}
+
+/// Conflict clause for reading inserts on `(sensor_id, time)`.
+fn readings_on_conflict(logged_overrides_realtime: bool) -> OnConflict {
+    let mut on_conflict =
+        OnConflict::columns([readings::Column::SensorId, readings::Column::Time]);
+
+    if logged_overrides_realtime {
+        // Logged values replace realtime ones, never the reverse. Rows stored
+        // before `logged` was tracked (NULL) count as realtime
+        on_conflict
+            .update_columns([
+                readings::Column::Value,
+                readings::Column::Logged,
+                readings::Column::IngestedAt,
+                readings::Column::RawTime,
+            ])
+            .action_and_where(Expr::cust("readings.logged IS NOT TRUE AND EXCLUDED.logged"));
+    } else {
+        on_conflict.do_nothing();
+    }
+
+    on_conflict
+}
+
+/// Insert one sensor's readings and advance its sync state atomically.
+///
+/// All batches and the sync_state upsert run in a single transaction, so
+/// `last_data_time` never claims data that wasn't durably inserted. On error
+/// the transaction is rolled back and sync_state is left untouched.
+///
+/// Returns the number of rows inserted (or updated, when logged readings
+/// replace realtime ones).
+///
+/// # Errors
+///
+/// Returns an error if any insert, the sync state update, or the commit fails.
+pub async fn store_sensor_readings(
+    db: &DatabaseConnection,
+    sensor_id: Uuid,
+    models: Vec<readings::ActiveModel>,
+    logged_overrides_realtime: bool,
+    latest_time: Option<chrono::DateTime<Utc>>,
+) -> AppResult<u64> {
+    let (inserted, _) = insert_readings(
+        db,
+        sensor_id,
+        models,
+        logged_overrides_realtime,
+        latest_time,
+        None,
+    )
+    .await?;
+    Ok(inserted)
+}
+
+/// [`store_sensor_readings`], also counting the rows for new timestamps
+/// older than `backfill_before`.
+///
+/// Rows that replace a stored realtime reading are written but not counted
+/// as backfill: the timestamp was already there.
+async fn insert_readings(
+    db: &DatabaseConnection,
+    sensor_id: Uuid,
+    models: Vec<readings::ActiveModel>,
+    logged_overrides_realtime: bool,
+    latest_time: Option<chrono::DateTime<Utc>>,
+    backfill_before: Option<chrono::DateTime<Utc>>,
+) -> AppResult<(u64, u64)> {
+    let on_conflict = readings_on_conflict(logged_overrides_realtime);
+    let txn = db.begin().await?;
+
+    // Batch insert in chunks of BATCH_SIZE
+    let mut inserted = 0;
+    let mut backfilled = 0;
+    for chunk in models.chunks(BATCH_SIZE) {
+        let mut insert =
+            readings::Entity::insert_many(chunk.to_vec()).on_conflict(on_conflict.clone());
+        let Some(before) = backfill_before else {
+            inserted += insert.exec_without_returning(&txn).await?;
+            continue;
+        };
+
+        // `xmax = 0` only for rows this statement inserted, not updated
+        QueryTrait::query(&mut insert).returning(Query::returning().exprs([
+            Expr::col(readings::Column::Time).into(),
+            Expr::cust("xmax = 0"),
+        ]));
+        let rows = txn
+            .query_all(insert.build(sea_orm::DatabaseBackend::Postgres))
+            .await?;
+        inserted += rows.len() as u64;
+        for row in &rows {
+            let time: chrono::DateTime<chrono::FixedOffset> = row.try_get_by_index(0)?;
+            let created: bool = row.try_get_by_index(1)?;
+            if created && time < before {
+                backfilled += 1;
+            }
         }
+    }
 
-        tracing::info!(
-            count = sample_count,
-            sensor_id = %sensor_id,
-            location_id = attrs.id,
-            "Synced readings"
-        );
+    // Update sync state with the latest timestamp
+    if let Some(latest) = latest_time {
+        upsert_sync_state_success(&txn, sensor_id, latest).await?;
     }
 
-    Ok(())
+    txn.commit().await?;
+    Ok((inserted, backfilled))
 }
 
 /// Sync device status for all active sensors.
 ///
+/// While `events` has subscribers, statuses that differ from a sensor's
+/// previous row within `lookback` (see [`DeviceSnapshot::changed_from`]) are
+/// published to it. A sensor without a row that recent has nothing to compare
+/// against and is not published; without subscribers the previous rows are
+/// not read at all.
+///
 /// # Errors
 ///
 /// Returns an error if the database or Vaisala API operations fail.
-pub async fn sync_device_status(db: &DatabaseConnection, vaisala: &VaisalaClient) -> AppResult<()> {
+pub async fn sync_device_status(
+    db: &DatabaseConnection,
+    vaisala: &VaisalaClient,
+    events: &DeviceEventSender,
+    lookback: Duration,
+) -> AppResult<()> {
     // Get all active sensors
     let sensors: Vec<sensors::Model> = sensors::Entity::find()
         .filter(sensors::Column::IsActive.eq(true))
@@ -515,10 +1290,10 @@ pub async fn sync_device_status(db: &DatabaseConnection, vaisala: &VaisalaClient
         return Ok(());
     }
 
-    // Build location_id -> sensor_id map
-    let location_map: HashMap<i32, Uuid> = sensors
+    // Build location_id -> sensor map
+    let location_map: HashMap<i32, &sensors::Model> = sensors
         .iter()
-        .map(|s| (s.vaisala_location_id, s.id))
+        .map(|s| (s.vaisala_location_id, s))
         .collect();
 
     let location_ids: Vec<i32> = location_map.keys().copied().collect();
@@ -529,56 +1304,134 @@ pub async fn sync_device_status(db: &DatabaseConnection, vaisala: &VaisalaClient
     let data = vaisala.get_locations_data(&location_ids).await?;
 
     let now = Utc::now();
+    let previous = if events.receiver_count() > 0 {
+        latest_device_snapshots(db, now - lookback).await?
+    } else {
+        HashMap::new()
+    };
+    let mut changed = 0usize;
 
     // Insert device status for each location from JSON API data array
     for resource in data.data {
         let attrs = resource.attributes;
-        let Some(sensor_id) = location_map.get(&attrs.id) else {
+        let Some(sensor) = location_map.get(&attrs.id) else {
             continue;
         };
 
+        let snapshot = DeviceSnapshot {
+            battery_level: Some(attrs.battery_level),
+            battery_state: Some(attrs.battery_state),
+            signal_quality: Some(attrs.signal_quality),
+            device_status: Some(attrs.device_status),
+            unreachable: Some(attrs.unreachable),
+        };
+
         let status = device_status::ActiveModel {
-            sensor_id: Set(*sensor_id),
+            sensor_id: Set(sensor.id),
             time: Set(now.into()),
-            battery_level: Set(Some(attrs.battery_level)),
-            battery_state: Set(Some(attrs.battery_state)),
-            signal_quality: Set(Some(attrs.signal_quality)),
-            device_status: Set(Some(attrs.device_status)),
-            unreachable: Set(Some(attrs.unreachable)),
+            battery_level: Set(snapshot.battery_level),
+            battery_state: Set(snapshot.battery_state),
+            signal_quality: Set(snapshot.signal_quality),
+            device_status: Set(snapshot.device_status.clone()),
+            unreachable: Set(snapshot.unreachable),
         };
 
         if let Err(e) = status.insert(db).await {
             tracing::warn!(
-                sensor_id = %sensor_id,
+                sensor_id = %sensor.id,
                 error = %e,
                 "Failed to insert device status"
             );
+            continue;
+        }
+
+        if let Some(prev) = previous.get(&sensor.id)
+            && snapshot.changed_from(prev)
+        {
+            changed += 1;
+            // Fails only when nobody is subscribed
+            let _ = events.send(DeviceStatusEvent::new(
+                sensor.id,
+                sensor.station_id,
+                now,
+                snapshot,
+                prev,
+            ));
         }
     }
 
-    tracing::info!("Device status sync completed");
+    tracing::info!(changed, "Device status sync completed");
     Ok(())
 }
 
-async fn update_sync_state_success(
+/// Latest device status row per sensor
+#[derive(Debug, FromQueryResult)]
+struct LatestDeviceStatusRow {
+    sensor_id: Uuid,
+    battery_level: Option<i16>,
+    battery_state: Option<i16>,
+    signal_quality: Option<i16>,
+    device_status: Option<String>,
+    unreachable: Option<bool>,
+}
+
+/// Most recent status stored since `since` for each sensor, to detect changes
+/// against. Bounding the time keeps the scan to the latest chunks.
+async fn latest_device_snapshots(
     db: &DatabaseConnection,
+    since: chrono::DateTime<Utc>,
+) -> AppResult<HashMap<Uuid, DeviceSnapshot>> {
+    let sql = "SELECT DISTINCT ON (sensor_id) sensor_id, battery_level, battery_state,
+                signal_quality, device_status, unreachable
+         FROM device_status
+         WHERE time >= $1
+         ORDER BY sensor_id, time DESC";
+
+    Ok(db
+        .query_all(Statement::from_sql_and_values(
+            sea_orm::DatabaseBackend::Postgres,
+            sql,
+            [since.into()],
+        ))
+        .await?
+        .into_iter()
+        .map(|row| LatestDeviceStatusRow::from_query_result(&row, ""))
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
+        .map(|r| {
+            (
+                r.sensor_id,
+                DeviceSnapshot {
+                    battery_level: r.battery_level,
+                    battery_state: r.battery_state,
+                    signal_quality: r.signal_quality,
+                    device_status: r.device_status,
+                    unreachable: r.unreachable,
+                },
+            )
+        })
+        .collect())
+}
+
+async fn upsert_sync_state_success<C: ConnectionTrait>(
+    db: &C,
     sensor_id: Uuid,
     latest_time: chrono::DateTime<Utc>,
-) {
+) -> Result<(), sea_orm::DbErr> {
     let state = sync_state::ActiveModel {
         sensor_id: Set(sensor_id),
         last_data_time: Set(Some(latest_time.into())),
         last_sync_attempt: Set(Some(Utc::now().into())),
-        sync_status: Set(Some("success".to_string())),
+        sync_status: Set(Some(SyncStatus::Success)),
         error_message: Set(None),
         retry_count: Set(Some(0)),
         last_full_sync: sea_orm::ActiveValue::NotSet,
     };
 
     // Upsert sync state (note: last_full_sync is updated separately by scheduler)
-    if let Err(e) = sync_state::Entity::insert(state)
+    sync_state::Entity::insert(state)
         .on_conflict(
-            sea_orm::sea_query::OnConflict::column(sync_state::Column::SensorId)
+            OnConflict::column(sync_state::Column::SensorId)
                 .update_columns([
                     sync_state::Column::LastDataTime,
                     sync_state::Column::LastSyncAttempt,
@@ -588,15 +1441,10 @@ async fn update_sync_state_success(
                 ])
                 .to_owned(),
         )
-        .exec(db)
-        .await
-    {
-        tracing::warn!(
-            sensor_id = %sensor_id,
-            error = %e,
-            "Failed to update sync state"
-        );
-    }
+        .exec_without_returning(db)
+        .await?;
+
+    Ok(())
 }
 
 async fn update_sync_state_error(db: &DatabaseConnection, sensor_id: Uuid, error: &str) {
@@ -613,7 +1461,7 @@ async fn update_sync_state_error(db: &DatabaseConnection, sensor_id: Uuid, error
         sensor_id: Set(sensor_id),
         last_data_time: Set(None),
         last_sync_attempt: Set(Some(Utc::now().into())),
-        sync_status: Set(Some("error".to_string())),
+        sync_status: Set(Some(SyncStatus::Error)),
         error_message: Set(Some(error.to_string())),
         retry_count: Set(Some(retry_count)),
         last_full_sync: sea_orm::ActiveValue::NotSet,
@@ -621,7 +1469,7 @@ async fn update_sync_state_error(db: &DatabaseConnection, sensor_id: Uuid, error
 
     if let Err(e) = sync_state::Entity::insert(state)
         .on_conflict(
-            sea_orm::sea_query::OnConflict::column(sync_state::Column::SensorId)
+            OnConflict::column(sync_state::Column::SensorId)
                 .update_columns([
                     sync_state::Column::LastSyncAttempt,
                     sync_state::Column::SyncStatus,
@@ -701,8 +1549,8 @@ pub async fn needs_full_sync(db: &DatabaseConnection) -> bool {
 
 /// Sync active alarms from Vaisala.
 ///
-/// Fetches all active alarms and upserts them into the database.
-/// Links alarms to sensors via the alarm_locations junction table.
+/// Fetches all active alarms and stores them with [`apply_active_alarms`].
+/// Alarms are recorded even during a maintenance window.
 ///
 /// # Errors
 ///
@@ -712,7 +1560,46 @@ pub async fn sync_alarms(db: &DatabaseConnection, vaisala: &VaisalaClient) -> Ap
 
     // Fetch active alarms (include system alarms)
     let response = vaisala.get_active_alarms(None, true).await?;
+    let total_alarms = response.data.len();
+
+    let active = response.data.into_iter().map(|r| r.attributes).collect();
+    let (created, updated) = apply_active_alarms(db, active).await?;
+
+    tracing::info!(
+        created,
+        updated,
+        total = total_alarms,
+        "Alarms sync completed"
+    );
+
+    Ok(())
+}
 
+fn non_empty(s: String) -> Option<String> {
+    if s.is_empty() { None } else { Some(s) }
+}
+
+/// Store the current list of active alarms.
+///
+/// Upserts every alarm in one statement per batch, links newly created alarms
+/// to their sensors via the alarm_locations junction table, and marks alarms
+/// no longer in `active` as inactive with a single UPDATE. All changes are
+/// applied in one transaction; an alarm the database rejects (e.g. an
+/// oversized description) is logged and skipped through a savepoint instead of
+/// aborting the pass. Returns `(created, updated)`, where `updated` counts
+/// existing alarms that were rewritten.
+///
+/// Unchanged alarms are left alone, and `updated_at` only moves when something
+/// other than the duration changed (it grows on every pass while an alarm is
+/// on), so `since` polls only see real changes.
+///
+/// # Errors
+///
+/// Returns an error if any database operation fails; nothing is committed then.
+pub async fn apply_active_alarms(
+    db: &DatabaseConnection,
+    active: Vec<ActiveAlarmAttributes>,
+) -> AppResult<(usize, usize)> {
     // Build sensor lookup by vaisala_location_id (includes station_id for linking)
     let all_sensors = sensors::Entity::find().all(db).await?;
     let sensor_map: HashMap<i32, Uuid> = all_sensors
@@ -724,24 +1611,29 @@ pub async fn sync_alarms(db: &DatabaseConnection, vaisala: &VaisalaClient) -> Ap
         .map(|s| (s.vaisala_location_id, s.station_id))
         .collect();
 
-    // Build existing alarms lookup by vaisala_alarm_id
-    let existing_alarms: HashMap<i32, alarms::Model> = alarms::Entity::find()
+    let existing_ids: HashSet<i32> = alarms::Entity::find()
+        .select_only()
+        .column(alarms::Column::VaisalaAlarmId)
+        .into_tuple::<i32>()
         .all(db)
         .await?
         .into_iter()
-        .map(|a| (a.vaisala_alarm_id, a))
         .collect();
 
     let now = Utc::now();
-    let mut created = 0;
-    let mut updated = 0;
+    let active_ids: Vec<i32> = active.iter().map(|a| a.id).collect();
 
-    // Collect active IDs and total count before consuming the response
-    let active_ids: Vec<i32> = response.data.iter().map(|r| r.attributes.id).collect();
-    let total_alarms = response.data.len();
+    let mut seen = HashSet::new();
+    let mut models = Vec::with_capacity(active.len());
+    // Links of new alarms, by Vaisala alarm ID
+    let mut links: Vec<(i32, alarm_locations::ActiveModel)> = Vec::new();
+    let mut new_ids = HashSet::new();
 
-    for resource in response.data {
-        let attrs = resource.attributes;
+    for attrs in active {
+        // An upsert cannot touch the same row twice in one statement
+        if !seen.insert(attrs.id) {
+            continue;
+        }
 
         // Convert timestamps
         let when_on = chrono::DateTime::from_timestamp(attrs.when_on as i64, 0)
@@ -756,148 +1648,173 @@ pub async fn sync_alarms(db: &DatabaseConnection, vaisala: &VaisalaClient) -> Ap
             .when_condition
             .and_then(|ts| chrono::DateTime::from_timestamp(ts as i64, 0));
 
-        let ack_comments = attrs.ack_comments.map(|c| serde_json::json!(c));
+        // Derive station_id from the first location_id that maps to a sensor
+        let station_id = attrs
+            .location_ids
+            .iter()
+            .find_map(|loc_id| sensor_station_map.get(loc_id).copied());
+
+        let location_ids =
+            (!attrs.location_ids.is_empty()).then(|| serde_json::json!(attrs.location_ids));
+
+        let alarm_id = Uuid::new_v4();
+        if !existing_ids.contains(&attrs.id) {
+            new_ids.insert(attrs.id);
+
+            // Link new alarms to sensors via alarm_locations
+            links.extend(attrs.location_ids.iter().filter_map(|loc_id| {
+                sensor_map.get(loc_id).map(|sensor_id| {
+                    let link = alarm_locations::ActiveModel {
+                        alarm_id: Set(alarm_id),
+                        sensor_id: Set(*sensor_id),
+                    };
+                    (attrs.id, link)
+                })
+            }));
+        }
 
-        if let Some(existing) = existing_alarms.get(&attrs.id) {
-            // Update existing alarm
-            let mut model: alarms::ActiveModel = existing.clone().into();
-            model.severity = Set(attrs.severity);
-            model.description = Set(attrs.description.clone());
-            model.error_text = Set(if attrs.error_text.is_empty() {
-                None
-            } else {
-                Some(attrs.error_text.clone())
-            });
-            model.when_off = Set(when_off.map(Into::into));
-            model.when_ack = Set(when_ack.map(Into::into));
-            model.duration_sec = Set(Some(attrs.duration_sec));
-            model.status = Set(attrs.status);
-            model.ack_comments = Set(ack_comments);
-            model.ack_action_taken = Set(attrs.ack_action_taken.clone());
-            model.updated_at = Set(Some(now.into()));
-
-            if let Err(e) = model.update(db).await {
-                tracing::warn!(
-                    error = %e,
-                    vaisala_alarm_id = attrs.id,
-                    "Failed to update alarm"
-                );
-            } else {
-                updated += 1;
-            }
-        } else {
-            // Derive station_id from the first location_id that maps to a sensor
-            let station_id = attrs
-                .location_ids
-                .iter()
-                .find_map(|loc_id| sensor_station_map.get(loc_id).copied());
-
-            // Create new alarm
-            let alarm_id = Uuid::new_v4();
-            let alarm = alarms::ActiveModel {
-                id: Set(alarm_id),
-                vaisala_alarm_id: Set(attrs.id),
-                severity: Set(attrs.severity),
-                description: Set(attrs.description.clone()),
-                error_text: Set(if attrs.error_text.is_empty() {
-                    None
-                } else {
-                    Some(attrs.error_text.clone())
-                }),
-                alarm_type: Set(None), // Could derive from description/error_text if needed
-                when_on: Set(when_on.into()),
-                when_off: Set(when_off.map(Into::into)),
-                when_ack: Set(when_ack.map(Into::into)),
-                when_condition: Set(when_condition.map(Into::into)),
-                duration_sec: Set(Some(attrs.duration_sec)),
-                status: Set(attrs.status),
-                is_system: Set(attrs.is_system),
-                serial_number: Set(if attrs.serial_number.is_empty() {
-                    None
-                } else {
-                    Some(attrs.serial_number.clone())
-                }),
-                location_text: Set(if attrs.location.is_empty() {
-                    None
-                } else {
-                    Some(attrs.location.clone())
-                }),
-                zone_text: Set(if attrs.zone.is_empty() {
-                    None
-                } else {
-                    Some(attrs.zone.clone())
-                }),
-                station_id: Set(station_id),
-                ack_required: Set(attrs.ack_required),
-                ack_comments: Set(ack_comments),
-                ack_action_taken: Set(attrs.ack_action_taken.clone()),
-                created_at: Set(Some(now.into())),
-                updated_at: Set(Some(now.into())),
-            };
+        // Columns outside ALARM_UPDATE_COLUMNS only apply when the alarm is created
+        models.push(alarms::ActiveModel {
+            id: Set(alarm_id),
+            vaisala_alarm_id: Set(attrs.id),
+            severity: Set(attrs.severity),
+            description: Set(attrs.description),
+            error_text: Set(non_empty(attrs.error_text)),
+            alarm_type: Set(None), // Could derive from description/error_text if needed
+            when_on: Set(when_on.into()),
+            when_off: Set(when_off.map(Into::into)),
+            when_ack: Set(when_ack.map(Into::into)),
+            when_condition: Set(when_condition.map(Into::into)),
+            duration_sec: Set(Some(attrs.duration_sec)),
+            status: Set(attrs.status),
+            is_system: Set(attrs.is_system),
+            serial_number: Set(non_empty(attrs.serial_number)),
+            location_text: Set(non_empty(attrs.location)),
+            zone_text: Set(non_empty(attrs.zone)),
+            station_id: Set(station_id),
+            ack_required: Set(attrs.ack_required),
+            ack_comments: Set(attrs.ack_comments.map(|c| serde_json::json!(c))),
+            ack_action_taken: Set(attrs.ack_action_taken),
+            location_ids: Set(location_ids),
+            created_at: Set(Some(now.into())),
+            updated_at: Set(Some(now.into())),
+        });
+    }
 
-            match alarm.insert(db).await {
-                Ok(_) => {
-                    created += 1;
-
-                    // Link alarm to sensors via alarm_locations
-                    for location_id in &attrs.location_ids {
-                        if let Some(sensor_id) = sensor_map.get(location_id) {
-                            let link = alarm_locations::ActiveModel {
-                                alarm_id: Set(alarm_id),
-                                sensor_id: Set(*sensor_id),
-                            };
-                            if let Err(e) = link.insert(db).await {
-                                // Ignore duplicate key errors
-                                let msg = e.to_string();
-                                if !msg.contains("duplicate") {
-                                    tracing::warn!(
-                                        error = %e,
-                                        alarm_id = %alarm_id,
-                                        sensor_id = %sensor_id,
-                                        "Failed to link alarm to sensor"
-                                    );
-                                }
-                            }
-                        }
-                    }
-                }
-                Err(e) => {
-                    tracing::warn!(
-                        error = %e,
-                        vaisala_alarm_id = attrs.id,
-                        "Failed to create alarm"
-                    );
-                }
-            }
-        }
+    let changed = alarm_columns_differ(&ALARM_UPDATE_COLUMNS);
+    let meaningful: Vec<alarms::Column> = ALARM_UPDATE_COLUMNS
+        .into_iter()
+        .filter(|c| !matches!(c, alarms::Column::DurationSec))
+        .collect();
+    let updated_at = format!(
+        "CASE WHEN {} THEN EXCLUDED.updated_at ELSE alarms.updated_at END",
+        alarm_columns_differ(&meaningful)
+    );
+
+    let on_conflict = OnConflict::column(alarms::Column::VaisalaAlarmId)
+        .update_columns(ALARM_UPDATE_COLUMNS)
+        .value(alarms::Column::UpdatedAt, Expr::cust(updated_at))
+        .action_and_where(Expr::cust(changed))
+        .to_owned();
+
+    let txn = db.begin().await?;
+
+    let mut written = 0;
+    let mut rejected = HashSet::new();
+    for chunk in models.chunks(BATCH_SIZE) {
+        let (rows, bad) = upsert_alarms(&txn, chunk, &on_conflict).await?;
+        written += rows;
+        rejected.extend(bad);
+    }
+    let created = new_ids.difference(&rejected).count();
+    // Inserts plus rewritten conflicts; skipped conflicts affect no row
+    let updated = usize::try_from(written).unwrap_or(usize::MAX).saturating_sub(created);
+
+    let links: Vec<_> = links
+        .into_iter()
+        .filter(|(id, _)| !rejected.contains(id))
+        .map(|(_, link)| link)
+        .collect();
+    for chunk in links.chunks(BATCH_SIZE) {
+        alarm_locations::Entity::insert_many(chunk.to_vec())
+            .on_conflict(
+                OnConflict::columns([
+                    alarm_locations::Column::AlarmId,
+                    alarm_locations::Column::SensorId,
+                ])
+                .do_nothing()
+                .to_owned(),
+            )
+            .exec_without_returning(&txn)
+            .await?;
     }
 
     // Mark alarms as inactive if they're no longer in the active list
-    for (vaisala_id, existing) in &existing_alarms {
-        if existing.status && !active_ids.contains(vaisala_id) {
-            let mut model: alarms::ActiveModel = existing.clone().into();
-            model.status = Set(false);
-            model.when_off = Set(Some(now.into()));
-            model.updated_at = Set(Some(now.into()));
-
-            if let Err(e) = model.update(db).await {
-                tracing::warn!(
-                    error = %e,
-                    vaisala_alarm_id = vaisala_id,
-                    "Failed to mark alarm as inactive"
-                );
-            }
+    let deactivated = alarms::Entity::update_many()
+        .col_expr(alarms::Column::Status, Expr::value(false))
+        .col_expr(alarms::Column::WhenOff, Expr::value(now))
+        .col_expr(alarms::Column::UpdatedAt, Expr::value(now))
+        .filter(alarms::Column::Status.eq(true))
+        .filter(alarms::Column::VaisalaAlarmId.is_not_in(active_ids))
+        .exec(&txn)
+        .await?;
+
+    txn.commit().await?;
+
+    tracing::debug!(deactivated = deactivated.rows_affected, "Inactive alarms closed");
+
+    Ok((created, updated))
+}
+
+/// Upsert a batch of alarms inside a savepoint of `txn`.
+///
+/// If the batch fails, it is retried one alarm per savepoint so only the
+/// offending rows are lost. Returns the rows written and the Vaisala IDs of
+/// the alarms that could not be stored.
+async fn upsert_alarms(
+    txn: &DatabaseTransaction,
+    models: &[alarms::ActiveModel],
+    on_conflict: &OnConflict,
+) -> AppResult<(u64, Vec<i32>)> {
+    let batch = txn.begin().await?;
+    match alarms::Entity::insert_many(models.to_vec())
+        .on_conflict(on_conflict.clone())
+        .exec_without_returning(&batch)
+        .await
+    {
+        Ok(written) => {
+            batch.commit().await?;
+            return Ok((written, Vec::new()));
+        }
+        Err(e) => {
+            batch.rollback().await?;
+            tracing::warn!(error = %e, alarms = models.len(), "Alarm batch rejected, storing alarms one by one");
         }
     }
 
-    tracing::info!(
-        created,
-        updated,
-        total = total_alarms,
-        "Alarms sync completed"
-    );
+    let mut written = 0;
+    let mut rejected = Vec::new();
+    for model in models {
+        let row = txn.begin().await?;
+        match alarms::Entity::insert(model.clone())
+            .on_conflict(on_conflict.clone())
+            .exec_without_returning(&row)
+            .await
+        {
+            Ok(rows) => {
+                row.commit().await?;
+                written += rows;
+            }
+            Err(e) => {
+                row.rollback().await?;
+                let id = *model.vaisala_alarm_id.as_ref();
+                tracing::warn!(error = %e, vaisala_alarm_id = id, "Failed to store alarm");
+                rejected.push(id);
+            }
+        }
+    }
 
-    Ok(())
+    Ok((written, rejected))
 }
 
 /// Sync events from Vaisala.
@@ -965,6 +1882,13 @@ pub async fn sync_events(db: &DatabaseConnection, vaisala: &VaisalaClient) -> Ap
             let station_id = location_id_int
                 .and_then(|id| sensor_station_map_events.get(&id).copied());
 
+            let affected_location_ids = attrs
+                .affected_location_ids
+                .as_deref()
+                .map(parse_location_ids)
+                .filter(|ids| !ids.is_empty())
+                .map(|ids| serde_json::json!(ids));
+
             let extra_fields = if attrs.extra_fields.is_empty() {
                 None
             } else {
@@ -996,6 +1920,8 @@ pub async fn sync_events(db: &DatabaseConnection, vaisala: &VaisalaClient) -> Ap
                 device_id: Set(attrs.device_id),
                 channel_id: Set(attrs.channel_id),
                 host_id: Set(attrs.host_id),
+                location_id: Set(location_id_int),
+                affected_location_ids: Set(affected_location_ids),
                 extra_fields: Set(extra_fields),
             };
 
@@ -1034,57 +1960,79 @@ pub async fn sync_events(db: &DatabaseConnection, vaisala: &VaisalaClient) -> Ap
 
 /// Refresh continuous aggregates after new data is synced.
 ///
-/// Refreshes the hourly aggregate for recent data (last 24 hours).
-/// This ensures dashboards show aggregated data promptly without waiting
-/// for the scheduled refresh policy.
+/// Refreshes the hourly aggregates (and their percentiles) for recent data
+/// (last 24 hours), and the daily ones for the last 7 days. This ensures
+/// dashboards show aggregated data promptly without waiting for the scheduled
+/// refresh policy.
 ///
-/// Note: Only refreshes hourly; daily/weekly/monthly are less time-sensitive
-/// and can rely on their scheduled policies.
+/// Note: weekly/monthly are less time-sensitive and rely on their scheduled
+/// policies.
 pub async fn refresh_continuous_aggregates(db: &DatabaseConnection) {
     tracing::debug!("Refreshing continuous aggregates...");
 
-    // Refresh hourly aggregate for recent data (last 24 hours to now)
     // Using a bounded window is faster than refreshing the entire history
-    let result = db
-        .execute(Statement::from_string(
-            sea_orm::DatabaseBackend::Postgres,
-            "CALL refresh_continuous_aggregate('readings_hourly', NOW() - INTERVAL '24 hours', NOW())".to_string(),
-        ))
-        .await;
-
-    match result {
-        Ok(_) => tracing::debug!("Hourly continuous aggregate refreshed"),
-        Err(e) => tracing::warn!(error = %e, "Failed to refresh hourly aggregate"),
-    }
-
-    // Also refresh daily for last 7 days (less frequently needed but helps with dashboard)
-    let result = db
-        .execute(Statement::from_string(
-            sea_orm::DatabaseBackend::Postgres,
-            "CALL refresh_continuous_aggregate('readings_daily', NOW() - INTERVAL '7 days', NOW())".to_string(),
-        ))
-        .await;
+    let recent = [
+        ("readings_hourly", "24 hours"),
+        ("readings_hourly_percentiles", "24 hours"),
+        ("readings_daily", "7 days"),
+        ("readings_daily_percentiles", "7 days"),
+    ];
+    for (view, window) in recent {
+        let result = db
+            .execute(Statement::from_string(
+                sea_orm::DatabaseBackend::Postgres,
+                format!(
+                    "CALL refresh_continuous_aggregate('{view}', NOW() - INTERVAL '{window}', NOW())"
+                ),
+            ))
+            .await;
 
-    match result {
-        Ok(_) => tracing::debug!("Daily continuous aggregate refreshed"),
-        Err(e) => tracing::warn!(error = %e, "Failed to refresh daily aggregate"),
+        match result {
+            Ok(_) => tracing::debug!(aggregate = view, "Continuous aggregate refreshed"),
+            Err(e) => tracing::warn!(error = %e, aggregate = view, "Failed to refresh aggregate"),
+        }
     }
 }
 
 /// Refresh all continuous aggregates for the entire data range.
 ///
 /// Called after a full sync to ensure all historical data is aggregated.
-/// Uses NULL, NULL to refresh the entire materialized range.
+/// Uses NULL, NULL to refresh the entire materialized range, unless raw chunks
+/// have been dropped (retention): then the window starts at the oldest raw
+/// reading, since refreshing buckets whose readings are gone would erase them.
 pub async fn refresh_continuous_aggregates_full(db: &DatabaseConnection) {
     tracing::info!("Refreshing continuous aggregates for full history...");
 
-    let aggregates = ["readings_hourly", "readings_daily", "readings_weekly", "readings_monthly"];
+    // Hourly aggregates older than the oldest raw hour mean chunks were dropped
+    let window_start = match db
+        .query_one(Statement::from_string(
+            sea_orm::DatabaseBackend::Postgres,
+            "SELECT oldest.min_time
+             FROM (SELECT MIN(time) AS min_time FROM readings) AS oldest
+             WHERE EXISTS (
+                 SELECT 1 FROM readings_hourly
+                 WHERE bucket < time_bucket('1 hour', oldest.min_time)
+             )",
+        ))
+        .await
+    {
+        Ok(row) => row
+            .and_then(|r| r.try_get::<Option<chrono::DateTime<Utc>>>("", "min_time").ok())
+            .flatten()
+            .map_or_else(|| "NULL".to_string(), |t| format!("'{}'::timestamptz", t.to_rfc3339())),
+        Err(e) => {
+            tracing::warn!(error = %e, "Failed to check for dropped readings, skipping full refresh");
+            return;
+        }
+    };
 
-    for agg in aggregates {
+    for agg in timescale::CONTINUOUS_AGGREGATES {
+        // Only buckets entirely inside the window are refreshed, so a bucket
+        // straddling the oldest reading keeps its pre-retention aggregate
         let result = db
             .execute(Statement::from_string(
                 sea_orm::DatabaseBackend::Postgres,
-                format!("CALL refresh_continuous_aggregate('{agg}', NULL, NULL)"),
+                format!("CALL refresh_continuous_aggregate('{agg}', {window_start}, NULL)"),
             ))
             .await;
 
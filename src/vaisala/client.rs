@@ -1,63 +1,191 @@
 use chrono::{DateTime, Utc};
-use reqwest::Client;
+use reqwest::header::{self, HeaderMap, HeaderValue};
+use reqwest::{Client, Response, StatusCode};
 use std::time::Duration;
+use uuid::Uuid;
 
 use crate::config::Config;
 use crate::error::{AppError, AppResult};
+use crate::services::metrics;
 use crate::vaisala::models::{
     ActiveAlarmsResponse, EventsResponse, LocationsDataResponse, LocationsHistoryResponse,
     LocationsResponse,
 };
 
+/// Path every viewLinc REST base URL ends with
+const REST_API_SUFFIX: &str = "/rest/v1";
+
+/// Longest wait before a retry, whether from backoff or `Retry-After`
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(300);
+
+/// Statuses worth retrying: rate limiting and transient gateway failures
+fn is_retryable(status: StatusCode) -> bool {
+    matches!(
+        status,
+        StatusCode::TOO_MANY_REQUESTS
+            | StatusCode::BAD_GATEWAY
+            | StatusCode::SERVICE_UNAVAILABLE
+            | StatusCode::GATEWAY_TIMEOUT
+    )
+}
+
+/// Delay before retry number `attempt` (0-based): `base` doubled per attempt,
+/// plus up to half again as jitter (`jitter` in 0 to 1), capped at
+/// [`MAX_RETRY_DELAY`].
+pub fn backoff_delay(attempt: u32, base: Duration, jitter: f64) -> Duration {
+    let exponential = base
+        .saturating_mul(2_u32.saturating_pow(attempt))
+        .min(MAX_RETRY_DELAY);
+    (exponential + exponential.mul_f64(jitter.clamp(0.0, 1.0) / 2.0)).min(MAX_RETRY_DELAY)
+}
+
+/// Wait requested by a `Retry-After` header, in seconds or as an HTTP date.
+///
+/// A date in the past means no wait; unparsable values are ignored.
+pub fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers.get(header::RETRY_AFTER)?.to_str().ok()?.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds).min(MAX_RETRY_DELAY));
+    }
+    let at = DateTime::parse_from_rfc2822(value).ok()?;
+    let wait = (at.with_timezone(&Utc) - Utc::now()).to_std().unwrap_or_default();
+    Some(wait.min(MAX_RETRY_DELAY))
+}
+
+/// Uniform fraction in 0 to 1 for jitter, from a random UUID's bits
+fn jitter_fraction() -> f64 {
+    f64::from(Uuid::new_v4().as_u128() as u32) / f64::from(u32::MAX)
+}
+
+/// Normalize a configured Vaisala base URL so endpoint paths can be appended
+/// with a single `/`.
+///
+/// Trims whitespace and trailing slashes. A URL not ending in `/rest/v1` is
+/// kept as given (test servers and proxies may use other paths) but logged,
+/// since viewLinc itself answers 404 everywhere else.
+pub fn normalize_base_url(base_url: &str) -> String {
+    let normalized = base_url.trim().trim_end_matches('/').to_string();
+    if !normalized.ends_with(REST_API_SUFFIX) {
+        tracing::warn!(
+            base_url = %normalized,
+            "VAISALA_BASE_URL does not end with {REST_API_SUFFIX}; viewLinc requests may fail with 404"
+        );
+    }
+    normalized
+}
+
 pub struct VaisalaClient {
     http_client: Client,
     base_url: String,
     bearer_token: String,
+    max_retries: u32,
+    retry_base: Duration,
 }
 
 impl VaisalaClient {
     #[must_use]
     pub fn new(config: &Config) -> Self {
+        // Large history payloads compress well; reqwest decodes gzip bodies
+        // transparently and strips their Content-Encoding header
+        let mut default_headers = HeaderMap::new();
+        default_headers.insert(header::ACCEPT_ENCODING, HeaderValue::from_static("gzip"));
+
         let http_client = Client::builder()
             .danger_accept_invalid_certs(config.vaisala_skip_tls_verify)
+            .default_headers(default_headers)
+            .gzip(true)
             .timeout(Duration::from_secs(300)) // 5 minutes for large history requests
             .build()
             .expect("Failed to create HTTP client");
 
         Self {
             http_client,
-            base_url: config.vaisala_base_url.clone(),
+            base_url: normalize_base_url(&config.vaisala_base_url),
             bearer_token: config.vaisala_bearer_token.clone(),
+            max_retries: config.vaisala_max_retries,
+            retry_base: Duration::from_millis(config.vaisala_retry_base_ms),
         }
     }
 
-    /// Get all locations (zones and sensors) visible to the authenticated user.
+    /// GET `url`, retrying 429 and 502/503/504 answers with exponential
+    /// backoff (or as long as `Retry-After` asks) up to `max_retries` times.
     ///
     /// # Errors
     ///
-    /// Returns `AppError::VaisalaApi` if the request fails or returns an error status.
-    pub async fn get_locations(&self) -> AppResult<LocationsResponse> {
-        let url = format!("{}/locations?flatten=true", self.base_url);
+    /// Returns `AppError::VaisalaApi` if the request fails, or with the status
+    /// of the last answer if it is not a success. A final 429 is reported as
+    /// `Rate limited (429)`, which the scheduler retries on its own schedule.
+    async fn send_with_retry(&self, url: &str) -> AppResult<Response> {
+        let mut attempt = 0;
+        loop {
+            let result = self
+                .http_client
+                .get(url)
+                .bearer_auth(&self.bearer_token)
+                .send()
+                .await;
+            // Every attempt counts, so retried failures show up as errors
+            metrics::record_vaisala_request(
+                !result.as_ref().is_ok_and(|r| r.status().is_success()),
+            );
+            let response =
+                result.map_err(|e| AppError::VaisalaApi(format!("Request failed: {e}")))?;
+
+            let status = response.status();
+            if is_retryable(status) && attempt < self.max_retries {
+                let delay = retry_after(response.headers()).unwrap_or_else(|| {
+                    backoff_delay(attempt, self.retry_base, jitter_fraction())
+                });
+                tracing::warn!(
+                    status = status.as_u16(),
+                    attempt = attempt + 1,
+                    max_retries = self.max_retries,
+                    delay_ms = delay.as_millis() as u64,
+                    "Vaisala request failed, retrying"
+                );
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+                continue;
+            }
+
+            if status == StatusCode::TOO_MANY_REQUESTS {
+                return Err(AppError::VaisalaApi("Rate limited (429)".to_string()));
+            }
+
+            if !status.is_success() {
+                return Err(AppError::VaisalaApi(format!(
+                    "HTTP {}: {}",
+                    status,
+                    response.text().await.unwrap_or_default()
+                )));
+            }
+
+            return Ok(response);
+        }
+    }
 
-        let response = self
-            .http_client
-            .get(&url)
-            .bearer_auth(&self.bearer_token)
+    /// Whether the Vaisala server answers HTTP requests within `timeout`.
+    ///
+    /// Any response counts, including error statuses: this checks
+    /// reachability, not credentials.
+    pub async fn ping(&self, timeout: Duration) -> bool {
+        self.http_client
+            .get(&self.base_url)
+            .timeout(timeout)
             .send()
             .await
-            .map_err(|e| AppError::VaisalaApi(format!("Request failed: {e}")))?;
+            .is_ok()
+    }
 
-        if response.status() == reqwest::StatusCode::TOO_MANY_REQUESTS {
-            return Err(AppError::VaisalaApi("Rate limited (429)".to_string()));
-        }
+    /// Get all locations (zones and sensors) visible to the authenticated user.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AppError::VaisalaApi` if the request fails or returns an error status.
+    pub async fn get_locations(&self) -> AppResult<LocationsResponse> {
+        let url = format!("{}/locations?flatten=true", self.base_url);
 
-        if !response.status().is_success() {
-            return Err(AppError::VaisalaApi(format!(
-                "HTTP {}: {}",
-                response.status(),
-                response.text().await.unwrap_or_default()
-            )));
-        }
+        let response = self.send_with_retry(&url).await?;
 
         response
             .json()
@@ -101,24 +229,16 @@ impl VaisalaClient {
             ),
         };
 
-        let response = self
-            .http_client
-            .get(&url)
-            .bearer_auth(&self.bearer_token)
-            .send()
-            .await
-            .map_err(|e| AppError::VaisalaApi(format!("Request failed: {e}")))?;
-
-        if response.status() == reqwest::StatusCode::TOO_MANY_REQUESTS {
-            return Err(AppError::VaisalaApi("Rate limited (429)".to_string()));
-        }
+        let response = self.send_with_retry(&url).await?;
 
-        if !response.status().is_success() {
-            return Err(AppError::VaisalaApi(format!(
-                "HTTP {}: {}",
-                response.status(),
-                response.text().await.unwrap_or_default()
-            )));
+        // A Content-Encoding left on the response was not decoded by reqwest
+        let content_encoding = response
+            .headers()
+            .get(header::CONTENT_ENCODING)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
+        if let Some(encoding) = &content_encoding {
+            tracing::warn!(encoding = %encoding, "locations_history response has an undecoded Content-Encoding");
         }
 
         let text = response
@@ -126,9 +246,16 @@ impl VaisalaClient {
             .await
             .map_err(|e| AppError::VaisalaApi(format!("Failed to get response text: {e}")))?;
 
+        tracing::debug!(
+            bytes = text.len(),
+            locations = location_ids.len(),
+            "Received locations_history response"
+        );
+
         serde_json::from_str(&text).map_err(|e| {
             tracing::error!(
                 error = %e,
+                content_encoding = ?content_encoding,
                 body_preview = %text.chars().take(500).collect::<String>(),
                 "Failed to parse locations_history response"
             );
@@ -161,25 +288,7 @@ impl VaisalaClient {
             self.base_url, ids_str
         );
 
-        let response = self
-            .http_client
-            .get(&url)
-            .bearer_auth(&self.bearer_token)
-            .send()
-            .await
-            .map_err(|e| AppError::VaisalaApi(format!("Request failed: {e}")))?;
-
-        if response.status() == reqwest::StatusCode::TOO_MANY_REQUESTS {
-            return Err(AppError::VaisalaApi("Rate limited (429)".to_string()));
-        }
-
-        if !response.status().is_success() {
-            return Err(AppError::VaisalaApi(format!(
-                "HTTP {}: {}",
-                response.status(),
-                response.text().await.unwrap_or_default()
-            )));
-        }
+        let response = self.send_with_retry(&url).await?;
 
         response
             .json()
@@ -223,25 +332,7 @@ impl VaisalaClient {
             url = format!("{}?{}", url, params.join("&"));
         }
 
-        let response = self
-            .http_client
-            .get(&url)
-            .bearer_auth(&self.bearer_token)
-            .send()
-            .await
-            .map_err(|e| AppError::VaisalaApi(format!("Request failed: {e}")))?;
-
-        if response.status() == reqwest::StatusCode::TOO_MANY_REQUESTS {
-            return Err(AppError::VaisalaApi("Rate limited (429)".to_string()));
-        }
-
-        if !response.status().is_success() {
-            return Err(AppError::VaisalaApi(format!(
-                "HTTP {}: {}",
-                response.status(),
-                response.text().await.unwrap_or_default()
-            )));
-        }
+        let response = self.send_with_retry(&url).await?;
 
         let text = response
             .text()
@@ -306,25 +397,7 @@ impl VaisalaClient {
             url = format!("{}&page_size={}", url, size);
         }
 
-        let response = self
-            .http_client
-            .get(&url)
-            .bearer_auth(&self.bearer_token)
-            .send()
-            .await
-            .map_err(|e| AppError::VaisalaApi(format!("Request failed: {e}")))?;
-
-        if response.status() == reqwest::StatusCode::TOO_MANY_REQUESTS {
-            return Err(AppError::VaisalaApi("Rate limited (429)".to_string()));
-        }
-
-        if !response.status().is_success() {
-            return Err(AppError::VaisalaApi(format!(
-                "HTTP {}: {}",
-                response.status(),
-                response.text().await.unwrap_or_default()
-            )));
-        }
+        let response = self.send_with_retry(&url).await?;
 
         let text = response
             .text()
@@ -1,4 +1,4 @@
 pub mod client;
 pub mod models;
 
-pub use client::VaisalaClient;
+pub use client::{backoff_delay, normalize_base_url, retry_after, VaisalaClient};
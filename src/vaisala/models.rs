@@ -90,11 +90,145 @@ pub struct LocationHistoryAttributes {
     /// Data points as [timestamp, value, logged] tuples
     #[serde(default)]
     pub data_points: Vec<DataPoint>,
-    /// Threshold configuration (usually empty)
+    /// Threshold configuration (usually empty); see [`parse_thresholds`]
     #[serde(default)]
     pub thresholds: Vec<serde_json::Value>,
 }
 
+/// Direction of an alarm threshold
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ThresholdKind {
+    High,
+    Low,
+}
+
+impl ThresholdKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::High => "high",
+            Self::Low => "low",
+        }
+    }
+
+    /// Interpret a label such as "High", "HighHigh", "above" or "min".
+    fn from_label(label: &str) -> Option<Self> {
+        let label = label.to_ascii_lowercase();
+        if ["high", "above", "max", "upper", ">"].iter().any(|k| label.contains(k)) {
+            Some(Self::High)
+        } else if ["low", "below", "min", "lower", "<"].iter().any(|k| label.contains(k)) {
+            Some(Self::Low)
+        } else {
+            None
+        }
+    }
+}
+
+/// A sensor alarm threshold parsed from `locations_history`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Threshold {
+    pub kind: ThresholdKind,
+    pub value: f64,
+    pub severity: Option<i16>,
+    pub description: Option<String>,
+}
+
+/// Keys naming a threshold's direction, in order of preference
+const THRESHOLD_KIND_KEYS: [&str; 5] = ["type", "kind", "direction", "condition", "name"];
+/// Keys holding a threshold's limit value, in order of preference
+const THRESHOLD_VALUE_KEYS: [&str; 4] = ["value", "limit", "threshold", "setpoint"];
+/// Keys that are themselves limits in the `{"high": 25, "low": 5}` shape
+const THRESHOLD_LIMIT_KEYS: [&str; 6] = ["high", "low", "max", "min", "upper", "lower"];
+
+/// Number or numeric string
+fn json_number(value: &serde_json::Value) -> Option<f64> {
+    let n = match value {
+        serde_json::Value::Number(n) => n.as_f64()?,
+        serde_json::Value::String(s) => s.trim().parse().ok()?,
+        _ => return None,
+    };
+    n.is_finite().then_some(n)
+}
+
+/// Parse the threshold entries viewLinc attaches to a location.
+///
+/// viewLinc versions emit different shapes, all accepted here:
+/// - `{"type": "high", "value": 25.0}` (also `kind`/`direction`/`condition`/`name`
+///   with `limit`/`threshold`/`setpoint`; values may be numeric strings)
+/// - `{"high": 25.0, "low": 5.0}` (also `max`/`min`/`upper`/`lower`)
+/// - either of the above nested under `"threshold"`, or inside an array
+///
+/// Entries with `"enabled": false` and entries that cannot be interpreted are skipped.
+pub fn parse_thresholds(raw: &[serde_json::Value]) -> Vec<Threshold> {
+    let mut thresholds = Vec::new();
+    for value in raw {
+        collect_thresholds(value, &mut thresholds);
+    }
+    thresholds
+}
+
+fn collect_thresholds(value: &serde_json::Value, out: &mut Vec<Threshold>) {
+    let obj = match value {
+        serde_json::Value::Array(items) => {
+            for item in items {
+                collect_thresholds(item, out);
+            }
+            return;
+        }
+        serde_json::Value::Object(obj) => obj,
+        _ => return,
+    };
+
+    if obj.get("enabled").and_then(serde_json::Value::as_bool) == Some(false) {
+        return;
+    }
+    if let Some(inner @ serde_json::Value::Object(_)) = obj.get("threshold") {
+        collect_thresholds(inner, out);
+        return;
+    }
+
+    let severity = ["severity", "priority"]
+        .iter()
+        .find_map(|k| obj.get(*k).and_then(json_number))
+        .and_then(|n| i16::try_from(n as i64).ok());
+    let description = ["description", "label", "text"]
+        .iter()
+        .find_map(|k| obj.get(*k).and_then(serde_json::Value::as_str))
+        .filter(|s| !s.is_empty())
+        .map(str::to_string);
+
+    let kind = THRESHOLD_KIND_KEYS
+        .iter()
+        .find_map(|k| obj.get(*k).and_then(serde_json::Value::as_str))
+        .and_then(ThresholdKind::from_label);
+    let limit = THRESHOLD_VALUE_KEYS
+        .iter()
+        .find_map(|k| obj.get(*k).and_then(json_number));
+
+    if let (Some(kind), Some(value)) = (kind, limit) {
+        out.push(Threshold {
+            kind,
+            value,
+            severity,
+            description,
+        });
+        return;
+    }
+
+    for key in THRESHOLD_LIMIT_KEYS {
+        if let (Some(kind), Some(value)) = (
+            ThresholdKind::from_label(key),
+            obj.get(key).and_then(json_number),
+        ) {
+            out.push(Threshold {
+                kind,
+                value,
+                severity,
+                description: description.clone(),
+            });
+        }
+    }
+}
+
 /// A single data point: [timestamp_epoch, value, logged_bool]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(from = "RawDataPoint")]
@@ -269,6 +403,22 @@ pub struct EventAttributes {
     pub extra_fields: Vec<serde_json::Value>,
 }
 
+/// Parse a comma-separated location ID list such as `affected_location_ids`.
+///
+/// Blank entries are ignored and entries that are not integers are skipped
+/// with a warning. Duplicates are dropped, keeping first-seen order.
+pub fn parse_location_ids(raw: &str) -> Vec<i32> {
+    let mut ids = Vec::new();
+    for token in raw.split(',').map(str::trim).filter(|t| !t.is_empty()) {
+        match token.parse::<i32>() {
+            Ok(id) if !ids.contains(&id) => ids.push(id),
+            Ok(_) => {}
+            Err(_) => tracing::warn!(token, list = raw, "Skipping non-numeric location ID"),
+        }
+    }
+    ids
+}
+
 /// Location ID can be an integer or a string like "N/A"
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(untagged)]
@@ -0,0 +1,90 @@
+//! Unit tests for aggregate statistic selection (`stats`) and bucket
+//! suppression (`min_count`, `min_coverage`).
+//!
+//! Run with: cargo test --test aggregate_stats_unit_test
+
+use river_db::routes::stations::{fallback_query, view_query, AggregateStats, MinCount};
+use uuid::Uuid;
+
+#[test]
+fn stats_default_to_all_but_percentiles() {
+    let stats = AggregateStats::parse(None).unwrap();
+    assert_eq!(stats, AggregateStats::default());
+    assert_eq!(stats.as_key(), "avg,min,max,count");
+
+    let sql = view_query("readings_hourly", &[Uuid::nil()], stats);
+    assert!(!sql.contains("_percentiles"), "{sql}");
+    assert!(!fallback_query("1 hour", stats).contains("percentile_cont("));
+}
+
+#[test]
+fn stats_parse_is_case_insensitive_and_rejects_unknown() {
+    let stats = AggregateStats::parse(Some("AVG, count")).unwrap();
+    assert!(stats.avg && stats.count && !stats.min && !stats.max);
+    assert_eq!(stats.as_key(), "avg,count");
+
+    assert!(AggregateStats::parse(Some("avg,stddev")).is_err());
+    assert!(AggregateStats::parse(Some("")).is_err());
+}
+
+#[test]
+fn avg_only_queries_omit_other_columns() {
+    let stats = AggregateStats::parse(Some("avg")).unwrap();
+
+    let sql = view_query("readings_hourly", &[Uuid::nil()], stats);
+    assert!(sql.contains("SELECT v.bucket, v.sensor_id, v.avg_value\n"), "{sql}");
+    for column in ["min_value", "max_value", "min_time", "max_time", "p50_value", "count"] {
+        assert!(!sql.contains(column), "{column} in {sql}");
+    }
+
+    let sql = fallback_query("1 hour", stats);
+    assert!(sql.contains("AVG(value) AS avg_value"), "{sql}");
+    for expr in ["MIN(", "MAX(", "first(", "last(", "percentile_cont(", "COUNT("] {
+        assert!(!sql.contains(expr), "{expr} in {sql}");
+    }
+}
+
+#[test]
+fn min_brings_its_time() {
+    let stats = AggregateStats::parse(Some("min")).unwrap();
+    let sql = view_query("readings_daily", &[Uuid::nil()], stats);
+    assert!(sql.contains("v.min_value, v.min_time"), "{sql}");
+    assert!(!sql.contains("avg_value"), "{sql}");
+}
+
+#[test]
+fn percentiles_are_selected_individually() {
+    let stats = AggregateStats::parse(Some("p95")).unwrap();
+    assert!(stats.p95 && !stats.p50);
+
+    let sql = view_query("readings_hourly", &[Uuid::nil()], stats);
+    assert!(sql.contains("SELECT v.bucket, v.sensor_id, p.p95_value\n"), "{sql}");
+    assert!(sql.contains("LEFT JOIN readings_hourly_percentiles p"), "{sql}");
+
+    let sql = fallback_query("1 hour", stats);
+    assert!(sql.contains("percentile_cont(0.95)"), "{sql}");
+    assert!(!sql.contains("p50_value"), "{sql}");
+}
+
+#[test]
+fn min_count_takes_the_stricter_threshold() {
+    // An hour of 10-minute samples is 6 readings
+    let coverage = MinCount::new(None, Some(0.5)).unwrap();
+    assert_eq!(coverage.threshold(3_600, 600), 3);
+    // Rounded up: 40% of 6 needs 3 readings, not 2
+    assert_eq!(MinCount::new(None, Some(0.4)).unwrap().threshold(3_600, 600), 3);
+
+    let both = MinCount::new(Some(5), Some(0.5)).unwrap();
+    assert_eq!(both.threshold(3_600, 600), 5);
+    assert_eq!(both.threshold(86_400, 600), 72);
+
+    assert!(!MinCount::default().is_set());
+    assert_eq!(MinCount::default().threshold(3_600, 600), 0);
+}
+
+#[test]
+fn min_count_rejects_out_of_range_values() {
+    assert!(MinCount::new(Some(-1), None).is_err());
+    assert!(MinCount::new(None, Some(1.5)).is_err());
+    assert!(MinCount::new(None, Some(-0.1)).is_err());
+}
@@ -0,0 +1,362 @@
+//! End-to-end tests for the aggregates endpoint against TimescaleDB.
+//!
+//! Run with: cargo test --test aggregates_db_test
+
+mod common;
+
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use chrono::{DateTime, Duration, Utc};
+use river_db::config::parse_resolutions;
+use river_db::entity::sensors;
+use river_db::routes::build_router;
+use sea_orm::{sea_query::Expr, ColumnTrait, EntityTrait, QueryFilter};
+use serde_json::Value;
+use tower::ServiceExt;
+
+/// Three hours of 10-minute readings starting at a fixed hour
+fn window() -> (DateTime<Utc>, DateTime<Utc>) {
+    let start = DateTime::from_timestamp(1_735_689_600, 0).unwrap(); // 2025-01-01T00:00:00Z
+    (start, start + Duration::hours(3))
+}
+
+#[tokio::test]
+async fn hourly_aggregates_from_continuous_aggregate() {
+    let Some(test_db) = common::timescale().await else {
+        return;
+    };
+    let station = common::seed_station(
+        &test_db.db,
+        &[("MTurbNTU", "Turbidity"), ("MDepthmm", "Depth")],
+    )
+    .await;
+    let (start, end) = window();
+
+    // Turbidity rises 0..17; depth is constant
+    let step = Duration::minutes(10);
+    common::seed_readings(&test_db.db, station.sensor_ids[0], start, step, 18, f64::from).await;
+    common::seed_readings(&test_db.db, station.sensor_ids[1], start, step, 18, |_| 42.0).await;
+    common::refresh_aggregate(&test_db.db, "readings_hourly", start, end).await;
+
+    let router = build_router(common::app_state(&test_db));
+    let uri = format!(
+        "/api/stations/{}/aggregates/hourly?start={}&end={}",
+        station.id,
+        start.format("%Y-%m-%dT%H:%M:%SZ"),
+        (end - Duration::seconds(1)).format("%Y-%m-%dT%H:%M:%SZ"),
+    );
+    let (status, body) = common::get_json(router, &uri).await;
+
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(body["station"]["id"], station.id.to_string());
+    assert_eq!(body["times"].as_array().unwrap().len(), 3);
+    assert_eq!(body["errors"], serde_json::json!([]));
+
+    // Sensors come back ordered by name
+    let sensors = body["sensors"].as_array().unwrap();
+    assert_eq!(sensors[0]["name"], "MDepthmm");
+    assert_eq!(sensors[1]["name"], "MTurbNTU");
+
+    let turbidity = &sensors[1];
+    assert_eq!(turbidity["avg"], serde_json::json!([2.5, 8.5, 14.5]));
+    assert_eq!(turbidity["min"], serde_json::json!([0.0, 6.0, 12.0]));
+    assert_eq!(turbidity["max"], serde_json::json!([5.0, 11.0, 17.0]));
+    assert_eq!(turbidity["count"], serde_json::json!([6, 6, 6]));
+    assert_eq!(turbidity["min_time"][1], "2025-01-01T01:00:00Z");
+    assert_eq!(turbidity["max_time"][1], "2025-01-01T01:50:00Z");
+
+    assert_eq!(sensors[0]["avg"], serde_json::json!([42.0, 42.0, 42.0]));
+}
+
+#[tokio::test]
+async fn unknown_resolution_is_rejected() {
+    let Some(test_db) = common::timescale().await else {
+        return;
+    };
+    let station = common::seed_station(&test_db.db, &[("MDepthmm", "Depth")]).await;
+
+    let router = build_router(common::app_state(&test_db));
+    let uri = format!(
+        "/api/stations/{}/aggregates/minutely?window=24h",
+        station.id
+    );
+    let (status, _) = common::get_json(router, &uri).await;
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn resolution_is_case_insensitive_with_aliases() {
+    let Some(test_db) = common::timescale().await else {
+        return;
+    };
+    let station = common::seed_station(&test_db.db, &[("MDepthmm", "Depth")]).await;
+
+    let router = build_router(common::app_state(&test_db));
+    for (resolution, canonical) in [("Hourly", "hourly"), ("DAILY", "daily"), ("1h", "hourly")] {
+        let uri = format!(
+            "/api/stations/{}/aggregates/{resolution}?window=24h",
+            station.id
+        );
+        let (status, body) = common::get_json(router.clone(), &uri).await;
+
+        assert_eq!(status, StatusCode::OK, "{resolution}: {body}");
+        assert_eq!(body["resolution"], canonical);
+    }
+}
+
+#[tokio::test]
+async fn disabled_resolution_is_rejected() {
+    let Some(test_db) = common::timescale().await else {
+        return;
+    };
+    let station = common::seed_station(&test_db.db, &[("MDepthmm", "Depth")]).await;
+
+    let mut config = common::test_config(&test_db.url);
+    config.enabled_resolutions = parse_resolutions("hourly, 1d");
+    let router = build_router(common::app_state_with(test_db.db.clone(), config));
+
+    let uri = format!("/api/stations/{}/aggregates/monthly?window=30d", station.id);
+    let (status, body) = common::get_json(router.clone(), &uri).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(
+        body["error"],
+        "Resolution monthly is disabled. Enabled resolutions: hourly, daily"
+    );
+
+    let uri = format!("/api/stations/{}/aggregates/daily?window=24h", station.id);
+    let (status, body) = common::get_json(router, &uri).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+}
+
+#[tokio::test]
+async fn rounded_averages_keep_one_extra_place() {
+    let Some(test_db) = common::timescale().await else {
+        return;
+    };
+    let station = common::seed_station(&test_db.db, &[("MDepthmm", "Depth")]).await;
+    sensors::Entity::update_many()
+        .col_expr(sensors::Column::DecimalPlaces, Expr::value(1i16))
+        .filter(sensors::Column::Id.eq(station.sensor_ids[0]))
+        .exec(&test_db.db)
+        .await
+        .unwrap();
+    let (start, end) = window();
+
+    // Hourly average of 1.0, 1.1, 1.1, ... is 1.0666...
+    let value = |i: i32| if i % 3 == 0 { 1.0 } else { 1.1 };
+    let step = Duration::minutes(10);
+    common::seed_readings(&test_db.db, station.sensor_ids[0], start, step, 18, value).await;
+    common::refresh_aggregate(&test_db.db, "readings_hourly", start, end).await;
+
+    let router = build_router(common::app_state(&test_db));
+    let uri = format!(
+        "/api/v1/stations/{}/aggregates/hourly?start={}&end={}",
+        station.id,
+        start.format("%Y-%m-%dT%H:%M:%SZ"),
+        (end - Duration::seconds(1)).format("%Y-%m-%dT%H:%M:%SZ"),
+    );
+
+    let (status, body) = common::get_json(router.clone(), &format!("{uri}&round=true")).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    let sensor = &body["sensors"][0];
+    assert_eq!(sensor["avg"], serde_json::json!([1.07, 1.07, 1.07]));
+    assert_eq!(sensor["min"][0], 1.0);
+    assert_eq!(sensor["max"][0], 1.1);
+
+    let (_, body) = common::get_json(router.clone(), &uri).await;
+    assert_ne!(body["sensors"][0]["avg"][0], 1.07);
+
+    let response = router
+        .oneshot(
+            Request::get(format!("{uri}&round=true&format=csv"))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let csv = String::from_utf8(bytes.to_vec()).unwrap();
+    let row = csv.lines().nth(1).unwrap();
+    assert!(row.starts_with("2025-01-01T00:00:00+00:00,1.07,1,1.1,"), "{row}");
+}
+
+#[tokio::test]
+async fn stats_selection_returns_only_requested_arrays() {
+    let Some(test_db) = common::timescale().await else {
+        return;
+    };
+    let station = common::seed_station(&test_db.db, &[("MTurbNTU", "Turbidity")]).await;
+    let (start, end) = window();
+    let step = Duration::minutes(10);
+    common::seed_readings(&test_db.db, station.sensor_ids[0], start, step, 18, f64::from).await;
+    common::refresh_aggregate(&test_db.db, "readings_hourly", start, end).await;
+
+    let router = build_router(common::app_state(&test_db));
+    let uri = format!(
+        "/api/v1/stations/{}/aggregates/hourly?start={}&end={}&stats=avg,count",
+        station.id,
+        start.format("%Y-%m-%dT%H:%M:%SZ"),
+        (end - Duration::seconds(1)).format("%Y-%m-%dT%H:%M:%SZ"),
+    );
+    let (status, body) = common::get_json(router.clone(), &uri).await;
+
+    assert_eq!(status, StatusCode::OK, "{body}");
+    let sensor = &body["sensors"][0];
+    assert_eq!(sensor["avg"], serde_json::json!([2.5, 8.5, 14.5]));
+    assert_eq!(sensor["count"], serde_json::json!([6, 6, 6]));
+    for stat in ["min", "max", "min_time", "max_time"] {
+        assert!(sensor.get(stat).is_none(), "{stat} in {sensor}");
+    }
+
+    let (status, _) = common::get_json(router, &uri.replace("avg,count", "median")).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn min_count_nulls_partial_buckets_but_keeps_counts() {
+    let Some(test_db) = common::timescale().await else {
+        return;
+    };
+    let station = common::seed_station(&test_db.db, &[("MDepthmm", "Depth")]).await;
+    let (start, end) = window();
+
+    // A full first hour (6 readings) and only 2 readings in the second
+    let step = Duration::minutes(10);
+    common::seed_readings(&test_db.db, station.sensor_ids[0], start, step, 8, f64::from).await;
+    for view in ["readings_hourly", "readings_hourly_percentiles"] {
+        common::refresh_aggregate(&test_db.db, view, start, end).await;
+    }
+
+    let router = build_router(common::app_state(&test_db));
+    let uri = format!(
+        "/api/v1/stations/{}/aggregates/hourly?start={}&end={}",
+        station.id,
+        start.format("%Y-%m-%dT%H:%M:%SZ"),
+        (end - Duration::seconds(1)).format("%Y-%m-%dT%H:%M:%SZ"),
+    );
+
+    let (_, body) = common::get_json(router.clone(), &uri).await;
+    assert_eq!(body["sensors"][0]["avg"], serde_json::json!([2.5, 6.5]));
+
+    // 3 readings, or half of the 6 expected at the default 10-minute interval
+    let stats = "stats=avg,min,max,p95,count";
+    for filter in ["min_count=3", "min_coverage=0.5"] {
+        let (status, body) =
+            common::get_json(router.clone(), &format!("{uri}&{filter}&{stats}")).await;
+        assert_eq!(status, StatusCode::OK, "{body}");
+        let sensor = &body["sensors"][0];
+        assert_eq!(sensor["avg"], serde_json::json!([2.5, null]), "{filter}");
+        assert_eq!(sensor["min"], serde_json::json!([0.0, null]), "{filter}");
+        assert_eq!(sensor["p95"], serde_json::json!([4.75, null]), "{filter}");
+        assert_eq!(sensor["max_time"][1], Value::Null, "{filter}");
+        assert_eq!(sensor["count"], serde_json::json!([6, 2]), "{filter}");
+    }
+
+    let (status, _) = common::get_json(router, &format!("{uri}&min_coverage=2")).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn percentiles_from_view_match_raw_fallback() {
+    let Some(test_db) = common::timescale().await else {
+        return;
+    };
+    let (start, end) = window();
+    let step = Duration::minutes(10);
+    let refreshed = common::seed_station(&test_db.db, &[("MTurbNTU", "Turbidity")]).await;
+    common::seed_readings(&test_db.db, refreshed.sensor_ids[0], start, step, 18, f64::from).await;
+    for view in ["readings_hourly", "readings_hourly_percentiles"] {
+        common::refresh_aggregate(&test_db.db, view, start, end).await;
+    }
+
+    // Outside the refreshed window, so only the on-the-fly fallback sees it
+    let raw_start = start + Duration::days(1);
+    let raw = common::seed_station(&test_db.db, &[("MTurbNTU", "Turbidity")]).await;
+    common::seed_readings(&test_db.db, raw.sensor_ids[0], raw_start, step, 18, f64::from).await;
+
+    let router = build_router(common::app_state(&test_db));
+    for (station, start) in [(&refreshed, start), (&raw, raw_start)] {
+        let uri = format!(
+            "/api/v1/stations/{}/aggregates/hourly?start={}&end={}",
+            station.id,
+            start.format("%Y-%m-%dT%H:%M:%SZ"),
+            (start + Duration::hours(3) - Duration::seconds(1)).format("%Y-%m-%dT%H:%M:%SZ"),
+        );
+        // Percentiles are opt-in
+        let (status, body) = common::get_json(router.clone(), &uri).await;
+        assert_eq!(status, StatusCode::OK, "{body}");
+        assert!(body["sensors"][0].get("p50").is_none(), "{body}");
+
+        let (status, body) =
+            common::get_json(router.clone(), &format!("{uri}&stats=avg,p50,p95")).await;
+        assert_eq!(status, StatusCode::OK, "{body}");
+
+        // Six readings per hour: the median sits between the 3rd and 4th
+        let sensor = &body["sensors"][0];
+        assert_eq!(sensor["p50"], serde_json::json!([2.5, 8.5, 14.5]));
+        assert_eq!(sensor["p95"], serde_json::json!([4.75, 10.75, 16.75]));
+
+        let response = router
+            .clone()
+            .oneshot(
+                Request::get(format!("{uri}&format=csv&stats=p50,p95"))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let csv = String::from_utf8(bytes.to_vec()).unwrap();
+        let mut lines = csv.lines();
+        assert_eq!(lines.next(), Some("time,MTurbNTU_p50,MTurbNTU_p95"));
+        assert!(lines.next().unwrap().ends_with(",2.5,4.75"), "{csv}");
+    }
+}
+
+#[tokio::test]
+async fn fill_gaps_returns_every_bucket_of_the_range() {
+    let Some(test_db) = common::timescale().await else {
+        return;
+    };
+    let station = common::seed_station(
+        &test_db.db,
+        &[("MDepthmm", "Depth"), ("MTurbNTU", "Turbidity")],
+    )
+    .await;
+    let (start, _) = window();
+    let end = start + Duration::hours(6);
+
+    // Depth reports in the first and last hour only; turbidity never does
+    let step = Duration::minutes(10);
+    let depth = station.sensor_ids[0];
+    common::seed_readings(&test_db.db, depth, start, step, 6, |_| 1.0).await;
+    common::seed_readings(&test_db.db, depth, end - Duration::hours(1), step, 3, |_| 2.0).await;
+    common::refresh_aggregate(&test_db.db, "readings_hourly", start, end).await;
+
+    let router = build_router(common::app_state(&test_db));
+    let uri = format!(
+        "/api/v1/stations/{}/aggregates/hourly?start={}&end={}&time_format=epoch",
+        station.id,
+        start.format("%Y-%m-%dT%H:%M:%SZ"),
+        (end - Duration::seconds(1)).format("%Y-%m-%dT%H:%M:%SZ"),
+    );
+
+    let (_, body) = common::get_json(router.clone(), &uri).await;
+    assert_eq!(body["times"].as_array().unwrap().len(), 2);
+
+    let (status, body) = common::get_json(router, &format!("{uri}&fill_gaps=true")).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    let hours: Vec<i64> = (0..6).map(|h| start.timestamp() + h * 3_600).collect();
+    assert_eq!(body["times"], serde_json::json!(hours));
+
+    let sensors = body["sensors"].as_array().unwrap();
+    assert_eq!(sensors[0]["avg"], serde_json::json!([1.0, null, null, null, null, 2.0]));
+    assert_eq!(sensors[0]["count"], serde_json::json!([6, 0, 0, 0, 0, 3]));
+    assert_eq!(sensors[1]["avg"], serde_json::json!([null, null, null, null, null, null]));
+    assert_eq!(sensors[1]["count"], serde_json::json!([0, 0, 0, 0, 0, 0]));
+}
@@ -0,0 +1,217 @@
+//! Tests for CSV/NDJSON exports of alarms and events.
+//!
+//! Run with: cargo test --test alarm_export_db_test
+
+mod common;
+
+use axum::body::Body;
+use axum::http::{header, Request, StatusCode};
+use chrono::{DateTime, Duration, Utc};
+use futures::StreamExt;
+use river_db::entity::{alarms, events};
+use river_db::routes::build_router;
+use sea_orm::{EntityTrait, Set};
+use std::collections::HashSet;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+fn event(station_id: Uuid, time: DateTime<Utc>, num: i32, message: String) -> events::ActiveModel {
+    events::ActiveModel {
+        time: Set(time.into()),
+        vaisala_event_num: Set(num),
+        category: Set("admin".to_string()),
+        message: Set(message),
+        user_name: Set(Some("operator".to_string())),
+        entity: Set(None),
+        entity_id: Set(None),
+        sensor_id: Set(None),
+        station_id: Set(Some(station_id)),
+        device_id: Set(None),
+        channel_id: Set(None),
+        host_id: Set(None),
+        location_id: Set(None),
+        affected_location_ids: Set(None),
+        extra_fields: Set(None),
+    }
+}
+
+async fn get_text(router: axum::Router, uri: &str, accept: Option<&str>) -> (StatusCode, String, String) {
+    let mut request = Request::get(uri);
+    if let Some(accept) = accept {
+        request = request.header(header::ACCEPT, accept);
+    }
+    let response = router.oneshot(request.body(Body::empty()).unwrap()).await.unwrap();
+    let status = response.status();
+    let content_type = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .map(|v| v.to_str().unwrap().to_string())
+        .unwrap_or_default();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, content_type, String::from_utf8(bytes.to_vec()).unwrap())
+}
+
+#[tokio::test]
+async fn alarms_and_events_export_as_csv() {
+    let Some(test_db) = common::timescale().await else {
+        return;
+    };
+    let station = common::seed_station(&test_db.db, &[("MDepthmm", "Depth")]).await;
+    let start = DateTime::from_timestamp(1_735_689_600, 0).unwrap(); // 2025-01-01T00:00:00Z
+    let seed = i32::from_str_radix(&Uuid::new_v4().simple().to_string()[..6], 16).unwrap();
+
+    let alarm_id = Uuid::new_v4();
+    alarms::Entity::insert(alarms::ActiveModel {
+        id: Set(alarm_id),
+        vaisala_alarm_id: Set(seed),
+        severity: Set(2),
+        description: Set("Depth high, check \"gauge\"".to_string()),
+        error_text: Set(None),
+        alarm_type: Set(None),
+        when_on: Set(start.into()),
+        when_off: Set(Some((start + Duration::minutes(90)).into())),
+        when_ack: Set(None),
+        when_condition: Set(None),
+        duration_sec: Set(Some(5400.0)),
+        status: Set(false),
+        is_system: Set(false),
+        serial_number: Set(None),
+        location_text: Set(None),
+        zone_text: Set(None),
+        station_id: Set(Some(station.id)),
+        ack_required: Set(false),
+        ack_comments: Set(None),
+        ack_action_taken: Set(None),
+        location_ids: Set(None),
+        created_at: Set(None),
+        updated_at: Set(None),
+    })
+    .exec_without_returning(&test_db.db)
+    .await
+    .unwrap();
+
+    for i in 0..3 {
+        let time = start + Duration::minutes(i);
+        let num = seed + i32::try_from(i).unwrap();
+        events::Entity::insert(event(station.id, time, num, format!("Changed setting {i}")))
+            .exec_without_returning(&test_db.db)
+            .await
+            .unwrap();
+    }
+
+    let router = build_router(common::app_state(&test_db));
+
+    let (status, content_type, body) = get_text(
+        router.clone(),
+        &format!("/api/v1/stations/{}/alarms?format=csv", station.id),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(content_type, "text/csv");
+    let lines: Vec<&str> = body.lines().collect();
+    assert_eq!(lines[0], "id,severity,description,when_on,when_off,status,station,duration");
+    assert_eq!(
+        lines[1],
+        format!(
+            "{alarm_id},2,\"Depth high, check \"\"gauge\"\"\",2025-01-01T00:00:00+00:00,2025-01-01T01:30:00+00:00,false,{},1h 30m",
+            station.name
+        )
+    );
+    assert_eq!(lines.len(), 2);
+
+    // page_size is ignored for exports: every event in range is streamed
+    let uri = format!(
+        "/api/v1/events?start=2025-01-01T00:00:00Z&end=2025-01-01T01:00:00Z&station_id={}&page_size=1",
+        station.id
+    );
+    let (status, content_type, body) = get_text(router.clone(), &uri, Some("text/csv")).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(content_type, "text/csv");
+    let lines: Vec<&str> = body.lines().collect();
+    assert_eq!(lines[0], "time,num,category,message,user,station");
+    assert_eq!(
+        lines[1],
+        format!(
+            "2025-01-01T00:02:00+00:00,{},admin,Changed setting 2,operator,{}",
+            seed + 2,
+            station.name
+        )
+    );
+    assert_eq!(lines.len(), 4);
+
+    let (status, content_type, body) = get_text(router, &format!("{uri}&format=ndjson"), None).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(content_type, "application/x-ndjson");
+    let rows: Vec<serde_json::Value> = body.lines().map(|l| serde_json::from_str(l).unwrap()).collect();
+    assert_eq!(rows.len(), 3);
+    assert_eq!(rows[2]["message"], "Changed setting 0");
+}
+
+#[tokio::test]
+async fn events_synced_during_an_export_do_not_shift_pages() {
+    let Some(test_db) = common::timescale().await else {
+        return;
+    };
+    let station = common::seed_station(&test_db.db, &[("MDepthmm", "Depth")]).await;
+    let start = DateTime::from_timestamp(1_735_689_600, 0).unwrap(); // 2025-01-01T00:00:00Z
+    let seed = i32::from_str_radix(&Uuid::new_v4().simple().to_string()[..6], 16).unwrap();
+
+    // More than one export page; pairs of events share a time
+    const COUNT: i32 = 1500;
+    let seeded: Vec<events::ActiveModel> = (0..COUNT)
+        .map(|i| {
+            let time = start + Duration::seconds(i64::from(i / 2));
+            event(station.id, time, seed + i, format!("Event {i}"))
+        })
+        .collect();
+    events::Entity::insert_many(seeded)
+        .exec_without_returning(&test_db.db)
+        .await
+        .unwrap();
+
+    let router = build_router(common::app_state(&test_db));
+    let response = router
+        .oneshot(
+            Request::get(format!(
+                "/api/v1/events?start=2025-01-01T00:00:00Z&end=2025-01-02T00:00:00Z&station_id={}&format=ndjson",
+                station.id
+            ))
+            .body(Body::empty())
+            .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // The first page is fetched and still being sent (one NDJSON line at a
+    // time into a bounded channel) when a newer event is synced
+    let mut frames = response.into_body().into_data_stream();
+    let mut body = String::from_utf8(frames.next().await.unwrap().unwrap().to_vec()).unwrap();
+    events::Entity::insert(event(
+        station.id,
+        start + Duration::hours(1),
+        seed + COUNT,
+        "Synced during export".to_string(),
+    ))
+    .exec_without_returning(&test_db.db)
+    .await
+    .unwrap();
+    while let Some(frame) = frames.next().await {
+        body.push_str(std::str::from_utf8(&frame.unwrap()).unwrap());
+    }
+
+    let nums: Vec<i64> = body
+        .lines()
+        .map(|line| {
+            let row: serde_json::Value = serde_json::from_str(line).unwrap();
+            row["vaisala_event_num"].as_i64().unwrap()
+        })
+        .collect();
+    let unique: HashSet<i64> = nums.iter().copied().collect();
+    assert_eq!(unique.len(), nums.len(), "duplicate events in export");
+    let expected: HashSet<i64> = (0..COUNT).map(|i| i64::from(seed + i)).collect();
+    assert_eq!(unique, expected);
+}
@@ -0,0 +1,125 @@
+//! Tests for storing Vaisala's active alarms and polling them with `since`.
+//!
+//! Each sync pass closes every stored alarm missing from its list, so the
+//! tests in this file take turns through [`SYNC`] when sharing a database.
+//!
+//! Run with: cargo test --test alarm_sync_db_test
+
+mod common;
+
+use axum::http::StatusCode;
+use chrono::{SecondsFormat, Utc};
+use river_db::routes::build_router;
+use river_db::sync::worker;
+use river_db::vaisala::models::ActiveAlarmAttributes;
+use serde_json::Value;
+use tokio::sync::Mutex;
+
+static SYNC: Mutex<()> = Mutex::const_new(());
+
+fn alarm(id: i32, location_id: i32, duration_sec: f64) -> ActiveAlarmAttributes {
+    serde_json::from_value(serde_json::json!({
+        "id": id,
+        "severity": 2,
+        "description": "High turbidity",
+        "when_on": 1_700_000_000.0,
+        "duration_sec": duration_sec,
+        "status": true,
+        "location_ids": [location_id],
+    }))
+    .unwrap()
+}
+
+/// Status of each alarm of `station` changed since `since`
+async fn poll(router: &axum::Router, since: chrono::DateTime<Utc>, station: &str) -> Vec<bool> {
+    let since = since.to_rfc3339_opts(SecondsFormat::Micros, true);
+    let (status, body) = common::get_json(
+        router.clone(),
+        &format!("/api/v1/alarms/active?since={since}"),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    body.as_array()
+        .unwrap()
+        .iter()
+        .filter(|a| a["station_id"] == station)
+        .map(|a| a["status"].as_bool().unwrap())
+        .collect()
+}
+
+#[tokio::test]
+async fn since_reports_changes_and_closed_alarms_only() {
+    let Some(test_db) = common::timescale().await else {
+        return;
+    };
+    let _sync = SYNC.lock().await;
+    let station = common::seed_station(&test_db.db, &[("MTurbNTU", "Turbidity")]).await;
+    let location_id = station.location_ids[0];
+    let station_id = station.id.to_string();
+    let router = build_router(common::app_state(&test_db));
+
+    let start = Utc::now();
+    worker::apply_active_alarms(&test_db.db, vec![alarm(location_id, location_id, 60.0)])
+        .await
+        .unwrap();
+    assert_eq!(poll(&router, start, &station_id).await, vec![true]);
+
+    // Vaisala reports the same alarm, only its duration has grown
+    let polled = Utc::now();
+    let (created, _) =
+        worker::apply_active_alarms(&test_db.db, vec![alarm(location_id, location_id, 660.0)])
+            .await
+            .unwrap();
+    assert_eq!(created, 0);
+    assert!(poll(&router, polled, &station_id).await.is_empty());
+
+    // The alarm cleared: pollers see it closed
+    let polled = Utc::now();
+    worker::apply_active_alarms(&test_db.db, Vec::new())
+        .await
+        .unwrap();
+    assert_eq!(poll(&router, polled, &station_id).await, vec![false]);
+
+    // Without since, only active alarms are listed
+    let (_, body) = common::get_json(router, "/api/v1/alarms/active").await;
+    assert!(!body
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|a| a["station_id"] == station_id.as_str()));
+}
+
+#[tokio::test]
+async fn bad_alarm_is_skipped_without_losing_the_pass() {
+    let Some(test_db) = common::timescale().await else {
+        return;
+    };
+    let _sync = SYNC.lock().await;
+    let station = common::seed_station(
+        &test_db.db,
+        &[("MDepthmm", "Depth"), ("MTurbNTU", "Turbidity")],
+    )
+    .await;
+    let (good, bad) = (station.location_ids[0], station.location_ids[1]);
+    let station_id = station.id.to_string();
+
+    // Longer than the 256 characters the description column holds
+    let mut oversized = alarm(bad, bad, 60.0);
+    oversized.description = "x".repeat(300);
+    let (created, _) =
+        worker::apply_active_alarms(&test_db.db, vec![alarm(good, good, 60.0), oversized])
+            .await
+            .unwrap();
+    assert_eq!(created, 1);
+
+    let router = build_router(common::app_state(&test_db));
+    let (_, body) = common::get_json(router, "/api/v1/alarms/active").await;
+    let stored: Vec<&Value> = body
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|a| a["station_id"] == station_id.as_str())
+        .collect();
+    assert_eq!(stored.len(), 1);
+    assert_eq!(stored[0]["description"], "High turbidity");
+}
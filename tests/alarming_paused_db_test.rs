@@ -0,0 +1,81 @@
+//! Tests for refreshing the sensors' alarming paused flag from Vaisala.
+//!
+//! Run with: cargo test --test alarming_paused_db_test
+
+mod common;
+
+use std::sync::{Arc, Mutex};
+
+use axum::{extract::State, routing::get, Json, Router};
+use river_db::entity::sensors;
+use river_db::sync::worker;
+use river_db::vaisala::VaisalaClient;
+use sea_orm::EntityTrait;
+use serde_json::Value;
+use uuid::Uuid;
+
+/// `/locations` body listing the given sensor locations with their pause flag
+fn locations_body(station: &str, sensors: &[(i32, bool)]) -> Value {
+    let data: Vec<Value> = sensors
+        .iter()
+        .map(|&(node_id, pause)| {
+            serde_json::json!({
+                "type": "locations",
+                "id": node_id.to_string(),
+                "attributes": {
+                    "path": format!("viewLinc/Zone/{station}/S{node_id}"),
+                    "node_id": node_id,
+                    "leaf": true,
+                    "pause": pause,
+                },
+            })
+        })
+        .collect();
+    serde_json::json!({"jsonapi": {"version": "1.0"}, "data": data})
+}
+
+async fn paused(db: &sea_orm::DatabaseConnection, sensor_id: Uuid) -> bool {
+    sensors::Entity::find_by_id(sensor_id)
+        .one(db)
+        .await
+        .unwrap()
+        .unwrap()
+        .alarming_paused
+}
+
+#[tokio::test]
+async fn alarming_paused_follows_vaisala() {
+    let Some(test_db) = common::timescale().await else {
+        return;
+    };
+    let station =
+        common::seed_station(&test_db.db, &[("MDepthmm", "Depth"), ("MTurbNTU", "Turbidity")]).await;
+    let (depth, turbidity) = (station.location_ids[0], station.location_ids[1]);
+
+    let body = Arc::new(Mutex::new(locations_body(
+        &station.name,
+        &[(depth, true), (turbidity, false)],
+    )));
+    let router = Router::new()
+        .route(
+            "/locations",
+            get(|State(body): State<Arc<Mutex<Value>>>| async move {
+                Json(body.lock().unwrap().clone())
+            }),
+        )
+        .with_state(body.clone());
+    let mut config = common::test_config(&test_db.url);
+    config.vaisala_base_url = common::mock_vaisala(router).await;
+    let vaisala = VaisalaClient::new(&config);
+
+    let changed = worker::refresh_alarming_paused(&test_db.db, &vaisala).await.unwrap();
+    assert!(changed >= 1);
+    assert!(paused(&test_db.db, station.sensor_ids[0]).await);
+    assert!(!paused(&test_db.db, station.sensor_ids[1]).await);
+
+    // Resumed in viewLinc
+    *body.lock().unwrap() = locations_body(&station.name, &[(depth, false), (turbidity, false)]);
+    worker::refresh_alarming_paused(&test_db.db, &vaisala).await.unwrap();
+    assert!(!paused(&test_db.db, station.sensor_ids[0]).await);
+    assert!(!paused(&test_db.db, station.sensor_ids[1]).await);
+}
@@ -0,0 +1,41 @@
+//! Tests for the versioned `/api/v1` prefix and the deprecated `/api` alias.
+//!
+//! Run with: cargo test --test api_version_db_test
+
+mod common;
+
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use river_db::routes::build_router;
+use tower::ServiceExt;
+
+#[tokio::test]
+async fn versioned_and_unversioned_prefixes_both_resolve() {
+    let Some(test_db) = common::timescale().await else {
+        return;
+    };
+    common::seed_station(&test_db.db, &[("MDepthmm", "Depth")]).await;
+    let router = build_router(common::app_state(&test_db));
+
+    let mut bodies = Vec::new();
+    for (uri, deprecated) in [("/api/v1/zones", false), ("/api/zones", true)] {
+        let response = router
+            .clone()
+            .oneshot(Request::get(uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK, "{uri}");
+        assert_eq!(
+            response.headers().get("Deprecation").is_some(),
+            deprecated,
+            "{uri}"
+        );
+        bodies.push(
+            axum::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .unwrap(),
+        );
+    }
+    assert_eq!(bodies[0], bodies[1]);
+}
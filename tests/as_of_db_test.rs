@@ -0,0 +1,124 @@
+//! Tests for `as_of` snapshot queries on station readings.
+//!
+//! Run with: cargo test --test as_of_db_test
+
+mod common;
+
+use axum::http::StatusCode;
+use chrono::{DateTime, Duration, Utc};
+use river_db::entity::readings;
+use river_db::routes::build_router;
+use river_db::sync::worker;
+use sea_orm::{ConnectionTrait, DatabaseBackend, Set, Statement};
+use uuid::Uuid;
+
+async fn set_ingested_at(
+    db: &sea_orm::DatabaseConnection,
+    sensor_id: Uuid,
+    time: DateTime<Utc>,
+    ingested_at: DateTime<Utc>,
+) {
+    db.execute(Statement::from_sql_and_values(
+        DatabaseBackend::Postgres,
+        "UPDATE readings SET ingested_at = $1 WHERE sensor_id = $2 AND time = $3",
+        [ingested_at.into(), sensor_id.into(), time.into()],
+    ))
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn readings_ingested_after_as_of_are_excluded() {
+    let Some(test_db) = common::timescale().await else {
+        return;
+    };
+    let station = common::seed_station(&test_db.db, &[("MDepthmm", "Depth")]).await;
+    let sensor_id = station.sensor_ids[0];
+    let start = DateTime::from_timestamp(1_735_689_600, 0).unwrap(); // 2025-01-01T00:00:00Z
+    let step = Duration::minutes(10);
+    common::seed_readings(&test_db.db, sensor_id, start, step, 3, |i| f64::from(i)).await;
+
+    // First reading has no ingest time (pre-migration), the second was
+    // ingested promptly and the third backfilled a week later
+    set_ingested_at(&test_db.db, sensor_id, start + step, start + step).await;
+    set_ingested_at(
+        &test_db.db,
+        sensor_id,
+        start + step * 2,
+        start + Duration::days(7),
+    )
+    .await;
+
+    let router = build_router(common::app_state(&test_db));
+    let uri = format!(
+        "/api/stations/{}/readings?start=2025-01-01T00:00:00Z&end=2025-01-01T01:00:00Z&as_of=2025-01-02T00:00:00Z",
+        station.id
+    );
+    let (status, body) = common::get_json(router.clone(), &uri).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(body["sensors"][0]["values"], serde_json::json!([0.0, 1.0]));
+
+    let (status, body) = common::get_json(router, &format!("{uri}&count_only=true")).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(body["readings"], 2);
+
+    let uri = format!(
+        "/api/stations/{}/readings?start=2025-01-01T00:00:00Z&end=2025-01-01T01:00:00Z",
+        station.id
+    );
+    let (_, body) = common::get_json(build_router(common::app_state(&test_db)), &uri).await;
+    assert_eq!(
+        body["sensors"][0]["values"],
+        serde_json::json!([0.0, 1.0, 2.0])
+    );
+}
+
+#[tokio::test]
+async fn readings_replaced_after_as_of_are_hidden() {
+    let Some(test_db) = common::timescale().await else {
+        return;
+    };
+    let station = common::seed_station(&test_db.db, &[("MDepthmm", "Depth")]).await;
+    let sensor_id = station.sensor_ids[0];
+    let start = DateTime::from_timestamp(1_735_689_600, 0).unwrap(); // 2025-01-01T00:00:00Z
+    let step = Duration::minutes(10);
+    common::seed_readings(&test_db.db, sensor_id, start, step, 2, |i| f64::from(i)).await;
+    set_ingested_at(&test_db.db, sensor_id, start, start).await;
+    set_ingested_at(&test_db.db, sensor_id, start + step, start + step).await;
+    test_db
+        .db
+        .execute(Statement::from_sql_and_values(
+            DatabaseBackend::Postgres,
+            "UPDATE readings SET logged = false WHERE sensor_id = $1 AND time = $2",
+            [sensor_id.into(), (start + step).into()],
+        ))
+        .await
+        .unwrap();
+
+    // The logged value replaces the realtime one, moving its ingest time to now
+    let logged = readings::ActiveModel {
+        sensor_id: Set(sensor_id),
+        time: Set((start + step).into()),
+        value: Set(9.0),
+        logged: Set(Some(true)),
+        ingested_at: Set(Some(Utc::now().into())),
+        raw_time: Set(None),
+    };
+    worker::store_sensor_readings(&test_db.db, sensor_id, vec![logged], true, None)
+        .await
+        .unwrap();
+
+    let uri = format!(
+        "/api/stations/{}/readings?start=2025-01-01T00:00:00Z&end=2025-01-01T01:00:00Z",
+        station.id
+    );
+    let router = build_router(common::app_state(&test_db));
+    // The snapshot predates the replacement and shows neither value
+    let (status, body) =
+        common::get_json(router.clone(), &format!("{uri}&as_of=2025-01-02T00:00:00Z")).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(body["sensors"][0]["values"], serde_json::json!([0.0]));
+
+    let (_, body) = common::get_json(router, &uri).await;
+    assert_eq!(body["sensors"][0]["values"], serde_json::json!([0.0, 9.0]));
+}
@@ -0,0 +1,103 @@
+//! Tests that readings sync counts points Vaisala delivers out of order, older
+//! than a sensor's newest stored reading.
+//!
+//! Run with: cargo test --test backfill_db_test
+
+mod common;
+
+use axum::{routing::get, Json, Router};
+use river_db::sync::sanity::SanityCheck;
+use river_db::sync::worker;
+use river_db::vaisala::VaisalaClient;
+
+/// Stand-in Vaisala serving `points` for the station's only location
+async fn vaisala_with(location_id: i32, points: &[(i64, f64, bool)]) -> String {
+    let body = common::locations_history_body(location_id, points);
+    common::mock_vaisala(Router::new().route(
+        "/locations_history",
+        get(move || {
+            let body = body.clone();
+            async move { Json(body) }
+        }),
+    ))
+    .await
+}
+
+#[tokio::test]
+async fn full_sync_counts_out_of_order_points() {
+    let Some(test_db) = common::timescale().await else {
+        return;
+    };
+    let station = common::seed_station(&test_db.db, &[("MDepthmm", "Depth")]).await;
+    let location_id = station.location_ids[0];
+    let t0 = 1_735_689_600; // 2025-01-01T00:00:00Z
+
+    let mut config = common::test_config(&test_db.url);
+    let sanity = SanityCheck::from_config(&config);
+
+    config.vaisala_base_url =
+        vaisala_with(location_id, &[(t0 + 600, 2.0, true), (t0 + 1200, 3.0, true)]).await;
+    let vaisala = VaisalaClient::new(&config);
+    let pass = worker::sync_readings(&test_db.db, &vaisala, 90, false, false, &sanity)
+        .await
+        .unwrap();
+    assert_eq!(pass.points_inserted, 2);
+    assert_eq!(pass.backfill_points, 0);
+
+    // The full re-sync sees an earlier point appear alongside the stored ones
+    // and a new one after them
+    config.vaisala_base_url = vaisala_with(
+        location_id,
+        &[
+            (t0, 1.0, true),
+            (t0 + 600, 2.0, true),
+            (t0 + 1200, 3.0, true),
+            (t0 + 1800, 4.0, true),
+        ],
+    )
+    .await;
+    let vaisala = VaisalaClient::new(&config);
+    let pass = worker::sync_readings(&test_db.db, &vaisala, 90, true, false, &sanity)
+        .await
+        .unwrap();
+    assert_eq!(pass.points_inserted, 2);
+    assert_eq!(pass.backfill_points, 1);
+
+    // Nothing new the second time round
+    let pass = worker::sync_readings(&test_db.db, &vaisala, 90, true, false, &sanity)
+        .await
+        .unwrap();
+    assert_eq!(pass.points_inserted, 0);
+    assert_eq!(pass.backfill_points, 0);
+}
+
+#[tokio::test]
+async fn logged_replacements_are_not_backfill() {
+    let Some(test_db) = common::timescale().await else {
+        return;
+    };
+    let station = common::seed_station(&test_db.db, &[("MDepthmm", "Depth")]).await;
+    let location_id = station.location_ids[0];
+    let t0 = 1_735_689_600; // 2025-01-01T00:00:00Z
+
+    let mut config = common::test_config(&test_db.url);
+    let sanity = SanityCheck::from_config(&config);
+
+    config.vaisala_base_url =
+        vaisala_with(location_id, &[(t0, 1.0, false), (t0 + 600, 2.0, true)]).await;
+    let vaisala = VaisalaClient::new(&config);
+    worker::sync_readings(&test_db.db, &vaisala, 90, false, true, &sanity)
+        .await
+        .unwrap();
+
+    // The logged copy of the realtime point replaces it: written, but its
+    // timestamp was already stored
+    config.vaisala_base_url =
+        vaisala_with(location_id, &[(t0, 1.5, true), (t0 + 600, 2.0, true)]).await;
+    let vaisala = VaisalaClient::new(&config);
+    let pass = worker::sync_readings(&test_db.db, &vaisala, 90, true, true, &sanity)
+        .await
+        .unwrap();
+    assert_eq!(pass.points_inserted, 1);
+    assert_eq!(pass.backfill_points, 0);
+}
@@ -2,7 +2,10 @@
 //!
 //! Run with: cargo test --test cache_unit_test
 
+use river_db::common::{build_response_cache, CachedResponse};
 use river_db::routes::cache;
+use std::sync::Arc;
+use std::time::Duration;
 
 #[test]
 fn cache_key_builds_correctly() {
@@ -19,3 +22,47 @@ fn cache_key_builds_correctly() {
         cache::cache_key("readings", &["station", "json"])
     );
 }
+
+#[tokio::test]
+async fn unbounded_entries_expire_before_bounded() {
+    let cache = build_response_cache(
+        1024 * 1024,
+        Duration::from_secs(60),
+        Duration::from_millis(50),
+    );
+    let entry = |bounded| CachedResponse {
+        data: Arc::new(b"{}".to_vec()),
+        max_time: None,
+        bounded,
+        ttl: None,
+    };
+
+    cache.insert("bounded".to_string(), entry(true)).await;
+    cache.insert("unbounded".to_string(), entry(false)).await;
+    tokio::time::sleep(Duration::from_millis(150)).await;
+
+    assert!(cache.get("bounded").await.is_some());
+    assert!(cache.get("unbounded").await.is_none());
+}
+
+#[tokio::test]
+async fn ttl_override_takes_precedence() {
+    let cache = build_response_cache(
+        1024 * 1024,
+        Duration::from_secs(60),
+        Duration::from_secs(60),
+    );
+    let entry = |ttl| CachedResponse {
+        data: Arc::new(b"{}".to_vec()),
+        max_time: None,
+        bounded: true,
+        ttl: Some(ttl),
+    };
+
+    cache.insert("short".to_string(), entry(Duration::from_millis(50))).await;
+    cache.insert("long".to_string(), entry(Duration::from_secs(60))).await;
+    tokio::time::sleep(Duration::from_millis(150)).await;
+
+    assert!(cache.get("long").await.is_some());
+    assert!(cache.get("short").await.is_none());
+}
@@ -0,0 +1,153 @@
+//! Tests for the sensor catalog endpoint and when the sync drops its cache.
+//!
+//! Run with: cargo test --test catalog_db_test
+
+mod common;
+
+use axum::body::Body;
+use axum::http::Request;
+use axum::{routing::get, Json, Router};
+use river_db::routes::build_router;
+use river_db::sync::scheduler;
+use sea_orm::{ConnectionTrait, DatabaseBackend, Statement};
+use serde_json::{json, Value};
+use tower::ServiceExt;
+use uuid::Uuid;
+
+#[tokio::test]
+async fn catalog_groups_units_ranges_and_precision_by_type() {
+    let Some(test_db) = common::timescale().await else {
+        return;
+    };
+    // A type unique to this test, so sensors seeded by others don't count
+    let sensor_type = format!("Type-{}", Uuid::new_v4().simple());
+    let station = common::seed_station(
+        &test_db.db,
+        &[
+            ("A", sensor_type.as_str()),
+            ("B", &sensor_type),
+            ("C", &sensor_type),
+        ],
+    )
+    .await;
+
+    let metadata = [
+        ("mm", Some(0.0), Some(1000.0), 1_i16),
+        ("mm", Some(-5.0), Some(800.0), 2),
+        ("m", None, None, 3),
+    ];
+    for (sensor, (units, min, max, decimals)) in station.sensor_ids.iter().zip(metadata) {
+        test_db
+            .db
+            .execute(Statement::from_sql_and_values(
+                DatabaseBackend::Postgres,
+                "UPDATE sensors
+                 SET display_units = $2, units_min = $3, units_max = $4, decimal_places = $5
+                 WHERE id = $1",
+                [
+                    (*sensor).into(),
+                    units.into(),
+                    min.into(),
+                    max.into(),
+                    decimals.into(),
+                ],
+            ))
+            .await
+            .unwrap();
+    }
+
+    let router = build_router(common::app_state(&test_db));
+    let response = router
+        .oneshot(Request::get("/api/v1/catalog").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    assert!(response.status().is_success());
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let catalog: Value = serde_json::from_slice(&bytes).unwrap();
+
+    let entry = catalog
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|e| e["type"] == sensor_type.as_str())
+        .expect("type listed");
+    assert_eq!(
+        *entry,
+        json!({
+            "type": sensor_type,
+            "sensor_count": 3,
+            "units": [
+                {
+                    "units": "m",
+                    "sensor_count": 1,
+                    "units_min": null,
+                    "units_max": null,
+                    "decimal_places": 3,
+                },
+                {
+                    "units": "mm",
+                    "sensor_count": 2,
+                    "units_min": -5.0,
+                    "units_max": 1000.0,
+                    "decimal_places": 2,
+                },
+            ],
+        })
+    );
+}
+
+/// `X-Cache` header of a catalog request
+async fn catalog_cache(router: &axum::Router) -> String {
+    let response = router
+        .clone()
+        .oneshot(Request::get("/api/v1/catalog").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    assert!(response.status().is_success());
+    response.headers()["x-cache"].to_str().unwrap().to_string()
+}
+
+#[tokio::test]
+async fn catalog_refreshed_after_alarming_is_paused() {
+    let Some(test_db) = common::timescale().await else {
+        return;
+    };
+    let station = common::seed_station(&test_db.db, &[("MDepthmm", "Depth")]).await;
+    let location_id = station.location_ids[0];
+    let locations = json!({
+        "jsonapi": {"version": "1.0"},
+        "data": [{
+            "type": "locations",
+            "id": location_id.to_string(),
+            "attributes": {
+                "path": format!("viewLinc/Zone/{}/MDepthmm", station.name),
+                "node_id": location_id,
+                "leaf": true,
+                "pause": true,
+            },
+        }],
+    });
+    let mut config = common::test_config(&test_db.url);
+    config.vaisala_base_url = common::mock_vaisala(Router::new().route(
+        "/locations",
+        get(move || {
+            let locations = locations.clone();
+            async move { Json(locations) }
+        }),
+    ))
+    .await;
+    let state = common::app_state_with(test_db.db.clone(), config);
+    let router = build_router(state.clone());
+
+    catalog_cache(&router).await;
+    assert_eq!(catalog_cache(&router).await, "HIT");
+
+    scheduler::refresh_alarming_paused(&state).await;
+    assert_eq!(catalog_cache(&router).await, "MISS");
+
+    // Nothing changed on the next pass, so the cache stays
+    scheduler::refresh_alarming_paused(&state).await;
+    assert_eq!(catalog_cache(&router).await, "HIT");
+}
@@ -0,0 +1,369 @@
+//! Shared TimescaleDB harness for database-backed integration tests.
+//!
+//! [`timescale`] connects to `TEST_DATABASE_URL` when set. Otherwise it starts
+//! a throwaway `timescale/timescaledb` container (requires Docker) that lives
+//! as long as the returned [`TestDb`]. If neither is available the caller
+//! should skip the test.
+//!
+//! Migrations run on every connection, so the schema (hypertables, continuous
+//! aggregates) is the production one. Seed helpers create uniquely named rows,
+//! so tests can share one database.
+
+#![allow(dead_code)]
+
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use chrono::{DateTime, Duration, Utc};
+use futures::StreamExt;
+use river_db::common::AppState;
+use river_db::config::{Config, Deployment, ALL_RESOLUTIONS};
+use river_db::entity::{readings, sensors, stations};
+use river_db::vaisala::VaisalaClient;
+use sea_orm::{ConnectionTrait, Database, DatabaseConnection, EntityTrait, Set};
+use sea_orm_migration::MigratorTrait;
+use serde_json::Value;
+use testcontainers::core::{IntoContainerPort, WaitFor};
+use testcontainers::runners::AsyncRunner;
+use testcontainers::{ContainerAsync, GenericImage, ImageExt};
+use tower::ServiceExt;
+use uuid::Uuid;
+
+/// TimescaleDB image used when no `TEST_DATABASE_URL` is given
+const TIMESCALE_IMAGE: (&str, &str) = ("timescale/timescaledb", "latest-pg16");
+
+/// Postgres restarts once after initdb, so connections are retried
+const CONNECT_ATTEMPTS: u32 = 30;
+
+/// A migrated TimescaleDB database for one test
+pub struct TestDb {
+    pub db: DatabaseConnection,
+    pub url: String,
+    // Dropping the handle stops and removes the container
+    _container: Option<ContainerAsync<GenericImage>>,
+}
+
+/// Connect to a migrated TimescaleDB, or `None` (with a note on stderr) if
+/// neither `TEST_DATABASE_URL` nor Docker is available.
+pub async fn timescale() -> Option<TestDb> {
+    let (url, container) = match std::env::var("TEST_DATABASE_URL") {
+        Ok(url) => (url, None),
+        Err(_) => match start_container().await {
+            Ok((url, container)) => (url, Some(container)),
+            Err(e) => {
+                eprintln!("TEST_DATABASE_URL not set and TimescaleDB container unavailable ({e}), skipping");
+                return None;
+            }
+        },
+    };
+
+    let db = connect_with_retry(&url).await;
+    migration::Migrator::up(&db, None).await.expect("run migrations");
+
+    Some(TestDb {
+        db,
+        url,
+        _container: container,
+    })
+}
+
+async fn start_container() -> Result<(String, ContainerAsync<GenericImage>), String> {
+    let (name, tag) = TIMESCALE_IMAGE;
+    let container = GenericImage::new(name, tag)
+        .with_exposed_port(5432.tcp())
+        .with_wait_for(WaitFor::message_on_stderr(
+            "database system is ready to accept connections",
+        ))
+        .with_env_var("POSTGRES_PASSWORD", "postgres")
+        .with_env_var("POSTGRES_DB", "river_test")
+        .start()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let host = container.get_host().await.map_err(|e| e.to_string())?;
+    let port = container
+        .get_host_port_ipv4(5432.tcp())
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok((
+        format!("postgresql://postgres:postgres@{host}:{port}/river_test"),
+        container,
+    ))
+}
+
+async fn connect_with_retry(url: &str) -> DatabaseConnection {
+    let mut attempt = 0;
+    loop {
+        attempt += 1;
+        match Database::connect(url).await {
+            Ok(db) if db.ping().await.is_ok() => return db,
+            Ok(_) | Err(_) if attempt < CONNECT_ATTEMPTS => {
+                tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+            }
+            Ok(_) => panic!("test database not answering at {url}"),
+            Err(e) => panic!("connect to test database: {e}"),
+        }
+    }
+}
+
+/// Configuration for tests: rate limiting off, no Vaisala access needed.
+pub fn test_config(database_url: &str) -> Config {
+    Config {
+        database_url: database_url.to_string(),
+        database_replica_url: None,
+        refresh_aggregates_on_start: false,
+        vaisala_base_url: "http://127.0.0.1:9".to_string(),
+        vaisala_bearer_token: "test".to_string(),
+        vaisala_skip_tls_verify: false,
+        vaisala_max_history_days: 90,
+        vaisala_max_retries: 0,
+        vaisala_retry_base_ms: 0,
+        sync_readings_interval_seconds: 3600,
+        sync_device_status_interval_seconds: 3600,
+        sync_alarms_interval_seconds: 3600,
+        sync_events_interval_seconds: 3600,
+        sync_retry_max: 0,
+        sync_retry_delay_seconds: 0,
+        sync_logged_overrides_realtime: false,
+        sync_stale_after_seconds: 10800,
+        sync_gap_threshold_seconds: 0,
+        sync_runs_retention_days: 30,
+        sync_sanity_min: -1e30,
+        sync_sanity_max: 1e30,
+        sync_sanity_use_units_range: false,
+        sync_sensor_type_allowlist: Vec::new(),
+        sync_sensor_type_denylist: Vec::new(),
+        default_sample_interval_sec: 600,
+        api_host: "127.0.0.1".to_string(),
+        api_port: 0,
+        api_default_page_size: 100,
+        api_max_page_size: 1000,
+        cors_max_age_seconds: 0,
+        admin_token: None,
+        compression_level: None,
+        enabled_resolutions: ALL_RESOLUTIONS.iter().map(ToString::to_string).collect(),
+        json_stream_threshold_bytes: 33_554_432,
+        readings_point_budget: 5_000_000,
+        max_sensors_per_request: 50,
+        // Seeded data lies in the past; tests opt in to the default window
+        default_readings_window: None,
+        shutdown_drain_seconds: 0,
+        shutdown_timeout_seconds: 30,
+        disable_rate_limiting: true,
+        rate_limit_metadata_per_second: 1,
+        rate_limit_metadata_burst: 100,
+        rate_limit_data_per_second: 1,
+        rate_limit_data_burst: 100,
+        bulk_concurrent_limit: 5,
+        bulk_bypass_points: 10_000,
+        per_client_concurrent_limit: 0,
+        cache_ttl_seconds: 300,
+        cache_unbounded_ttl_seconds: 30,
+        cache_max_bytes: 16 * 1024 * 1024,
+        cache_latest_ttl_seconds: 10,
+        cache_aggregates_ttl_seconds: 3600,
+        deployment: Deployment::Local,
+    }
+}
+
+/// Application state backed by the test database.
+pub fn app_state(test_db: &TestDb) -> AppState {
+    app_state_with(test_db.db.clone(), test_config(&test_db.url))
+}
+
+/// Application state on `db` (possibly `DatabaseConnection::Disconnected`)
+/// with a customized `config`.
+pub fn app_state_with(db: DatabaseConnection, config: Config) -> AppState {
+    let vaisala = VaisalaClient::new(&config);
+    AppState::new(db, config, vaisala)
+}
+
+/// GET `uri`, returning the status and the JSON body (`Null` if the body is
+/// not JSON, e.g. a plain-text query rejection).
+pub async fn get_json(router: axum::Router, uri: &str) -> (StatusCode, Value) {
+    let response = router
+        .oneshot(Request::get(uri).body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    let status = response.status();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, serde_json::from_slice(&bytes).unwrap_or(Value::Null))
+}
+
+/// GET `uri`, asserting success, and return the JSON body.
+pub async fn get_ok_json(router: &axum::Router, uri: &str) -> Value {
+    let (status, body) = get_json(router.clone(), uri).await;
+    assert!(status.is_success(), "{uri}: {status} {body}");
+    body
+}
+
+/// JSON payload of the first Server-Sent Event on `response`, waiting up to
+/// five seconds for it.
+pub async fn first_sse_data(response: axum::response::Response) -> Value {
+    let mut frames = response.into_body().into_data_stream();
+    let mut received = String::new();
+    loop {
+        let frame = tokio::time::timeout(std::time::Duration::from_secs(5), frames.next())
+            .await
+            .expect("no event within 5s")
+            .expect("stream ended before an event")
+            .unwrap();
+        received.push_str(std::str::from_utf8(&frame).unwrap());
+        // An event ends with a blank line
+        if let Some((event, _)) = received.split_once("\n\n") {
+            let data = event
+                .lines()
+                .find_map(|line| line.strip_prefix("data:"))
+                .expect("event without data");
+            return serde_json::from_str(data.trim()).unwrap();
+        }
+    }
+}
+
+/// A seeded station and its sensors (in the order given)
+pub struct SeededStation {
+    pub id: Uuid,
+    pub name: String,
+    pub sensor_ids: Vec<Uuid>,
+    /// Vaisala location ID of each sensor
+    pub location_ids: Vec<i32>,
+}
+
+/// Insert a uniquely named station with one active sensor per `(name, type)`.
+pub async fn seed_station(db: &DatabaseConnection, sensors: &[(&str, &str)]) -> SeededStation {
+    let suffix = Uuid::new_v4().simple().to_string();
+    let node_id = i32::from_str_radix(&suffix[..6], 16).unwrap() * 16;
+
+    let id = Uuid::new_v4();
+    let name = format!("test-{}", &suffix[..12]);
+    stations::Entity::insert(stations::ActiveModel {
+        id: Set(id),
+        zone_id: Set(None),
+        name: Set(name.clone()),
+        vaisala_node_id: Set(node_id),
+        vaisala_path: Set(None),
+        latitude: Set(None),
+        longitude: Set(None),
+        altitude_m: Set(None),
+        created_at: Set(None),
+        discovered_at: Set(None),
+    })
+    .exec_without_returning(db)
+    .await
+    .unwrap();
+
+    let mut sensor_ids = Vec::with_capacity(sensors.len());
+    let mut location_ids = Vec::with_capacity(sensors.len());
+    for (i, (sensor_name, sensor_type)) in sensors.iter().enumerate() {
+        let sensor_id = Uuid::new_v4();
+        let location_id = node_id + 1 + i32::try_from(i).unwrap();
+        sensors::Entity::insert(sensors::ActiveModel {
+            id: Set(sensor_id),
+            station_id: Set(id),
+            vaisala_location_id: Set(location_id),
+            name: Set((*sensor_name).to_string()),
+            sensor_type: Set((*sensor_type).to_string()),
+            display_units: Set(None),
+            units_name: Set(None),
+            units_min: Set(None),
+            units_max: Set(None),
+            decimal_places: Set(None),
+            device_serial_number: Set(None),
+            probe_serial_number: Set(None),
+            channel_id: Set(None),
+            sample_interval_sec: Set(None),
+            is_active: Set(Some(true)),
+            align_timestamps: Set(true),
+            alarming_paused: Set(false),
+            created_at: Set(None),
+            updated_at: Set(None),
+            discovered_at: Set(None),
+        })
+        .exec_without_returning(db)
+        .await
+        .unwrap();
+        sensor_ids.push(sensor_id);
+        location_ids.push(location_id);
+    }
+
+    SeededStation {
+        id,
+        name,
+        sensor_ids,
+        location_ids,
+    }
+}
+
+/// Insert `count` readings every `step` from `start`, valued by `value(i)`.
+pub async fn seed_readings(
+    db: &DatabaseConnection,
+    sensor_id: Uuid,
+    start: DateTime<Utc>,
+    step: Duration,
+    count: i32,
+    value: impl Fn(i32) -> f64,
+) {
+    let models: Vec<readings::ActiveModel> = (0..count)
+        .map(|i| readings::ActiveModel {
+            sensor_id: Set(sensor_id),
+            time: Set((start + step * i).into()),
+            value: Set(value(i)),
+            logged: Set(Some(true)),
+            ingested_at: Set(None),
+            raw_time: Set(None),
+        })
+        .collect();
+
+    readings::Entity::insert_many(models)
+        .exec_without_returning(db)
+        .await
+        .unwrap();
+}
+
+/// Serve `router` as a stand-in Vaisala API, returning its base URL.
+///
+/// The server runs until the test's runtime shuts down.
+pub async fn mock_vaisala(router: axum::Router) -> String {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, router).await.unwrap() });
+    format!("http://{addr}")
+}
+
+/// A `/locations_history` body with one location and `[timestamp, value, logged]` points.
+pub fn locations_history_body(location_id: i32, points: &[(i64, f64, bool)]) -> serde_json::Value {
+    serde_json::json!({
+        "jsonapi": {"version": "1.0"},
+        "data": [{
+            "type": "locations_history",
+            "id": location_id.to_string(),
+            "attributes": {
+                "id": location_id,
+                "name": "mock",
+                "zone": "mock",
+                "data_points": points
+                    .iter()
+                    .map(|(t, v, logged)| serde_json::json!([t, v, logged]))
+                    .collect::<Vec<_>>(),
+            },
+        }],
+    })
+}
+
+/// Materialize a continuous aggregate over `[start, end)`.
+pub async fn refresh_aggregate(
+    db: &DatabaseConnection,
+    view: &str,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) {
+    db.execute_unprepared(&format!(
+        "CALL refresh_continuous_aggregate('{view}', '{}', '{}')",
+        start.to_rfc3339(),
+        end.to_rfc3339()
+    ))
+    .await
+    .unwrap();
+}
@@ -0,0 +1,53 @@
+//! Unit tests for response compression negotiation.
+//!
+//! Run with: cargo test --test compression_unit_test
+
+mod common;
+
+use axum::{
+    body::Body,
+    http::{header, Request, StatusCode},
+};
+use river_db::routes::build_router;
+use sea_orm::DatabaseConnection;
+use tower::ServiceExt;
+
+/// Router without a database; the dashboard page needs none
+fn app(compression_level: Option<i32>) -> axum::Router {
+    let mut config = common::test_config("postgresql://unused");
+    config.compression_level = compression_level;
+    build_router(common::app_state_with(DatabaseConnection::Disconnected, config))
+}
+
+async fn content_encoding(compression_level: Option<i32>, accept: &str) -> Option<String> {
+    let response = app(compression_level)
+        .oneshot(
+            Request::get("/")
+                .header(header::ACCEPT_ENCODING, accept)
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    response
+        .headers()
+        .get(header::CONTENT_ENCODING)
+        .map(|v| v.to_str().unwrap().to_string())
+}
+
+#[tokio::test]
+async fn brotli_when_advertised() {
+    assert_eq!(content_encoding(None, "br").await.as_deref(), Some("br"));
+    assert_eq!(content_encoding(Some(11), "gzip;q=0.5, br").await.as_deref(), Some("br"));
+}
+
+#[tokio::test]
+async fn gzip_when_brotli_not_advertised() {
+    assert_eq!(content_encoding(Some(9), "gzip").await.as_deref(), Some("gzip"));
+}
+
+#[tokio::test]
+async fn uncompressed_without_accept_encoding() {
+    assert_eq!(content_encoding(None, "identity").await, None);
+}
@@ -0,0 +1,175 @@
+//! Unit tests for per-client and bulk concurrency limiting and in-flight counting.
+//!
+//! Run with: cargo test --test concurrency_unit_test
+
+use axum::{
+    body::{Body, HttpBody},
+    http::{Request, StatusCode},
+    middleware,
+    routing::get,
+    Router,
+};
+use river_db::common::BulkLimiter;
+use river_db::error::AppError;
+use river_db::services::{concurrency, InFlightRequests, PerClientConcurrency};
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+use tower::ServiceExt;
+
+const LIMIT: usize = 2;
+
+/// Router whose handler blocks until `release` hands out a permit
+fn app(release: Arc<Semaphore>) -> Router {
+    Router::new()
+        .route(
+            "/data",
+            get(move || {
+                let release = release.clone();
+                async move {
+                    let _ = release.acquire().await;
+                    "ok"
+                }
+            }),
+        )
+        .layer(middleware::from_fn_with_state(
+            PerClientConcurrency::new(LIMIT),
+            concurrency::limit_per_client,
+        ))
+}
+
+fn request(ip: &str) -> Request<Body> {
+    Request::builder()
+        .uri("/data")
+        .header("x-forwarded-for", ip)
+        .body(Body::empty())
+        .unwrap()
+}
+
+#[tokio::test]
+async fn request_beyond_limit_is_rejected() {
+    let release = Arc::new(Semaphore::new(0));
+    let app = app(release.clone());
+
+    // Fill the client's slots with requests that stay in flight
+    let pending: Vec<_> = (0..LIMIT)
+        .map(|_| tokio::spawn(app.clone().oneshot(request("10.0.0.1"))))
+        .collect();
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+
+    let rejected = app.clone().oneshot(request("10.0.0.1")).await.unwrap();
+    assert_eq!(rejected.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+    // Other clients are unaffected
+    release.add_permits(1);
+    let other = app.clone().oneshot(request("10.0.0.2")).await.unwrap();
+    assert_eq!(other.status(), StatusCode::OK);
+
+    release.add_permits(LIMIT);
+    for handle in pending {
+        let response = handle.await.unwrap().unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+}
+
+#[test]
+fn slot_is_released_when_permit_drops() {
+    let limiter = PerClientConcurrency::new(1);
+    let ip = "10.0.0.1".parse().unwrap();
+
+    let permit = limiter.try_acquire(ip);
+    assert!(permit.is_some());
+    assert!(limiter.try_acquire(ip).is_none());
+
+    drop(permit);
+    assert!(limiter.try_acquire(ip).is_some());
+}
+
+#[test]
+fn bulk_permits_are_shared_across_endpoints() {
+    // Each handler sees its own clone of the state, as with axum's State extractor
+    let limiter = BulkLimiter::new(2);
+    let readings = limiter.clone();
+    let aggregates = limiter.clone();
+
+    let csv = readings.acquire("csv").unwrap();
+    let ndjson = aggregates.acquire("ndjson").unwrap();
+    assert!(csv.is_some() && ndjson.is_some());
+
+    // Pool exhausted by one readings + one aggregates request
+    assert!(matches!(
+        readings.acquire("csv"),
+        Err(AppError::ServiceUnavailable(_))
+    ));
+    assert!(matches!(
+        aggregates.acquire("ndjson"),
+        Err(AppError::ServiceUnavailable(_))
+    ));
+
+    // JSON never takes a permit
+    assert!(aggregates.acquire("json").unwrap().is_none());
+
+    drop(csv);
+    assert!(aggregates.acquire("csv").unwrap().is_some());
+}
+
+#[test]
+fn small_bulk_requests_bypass_exhausted_pool() {
+    let limiter = BulkLimiter::new(1).with_bypass_points(1_000);
+
+    // A large export holds the only permit
+    let large = limiter.acquire_sized("csv", 5_000_000).unwrap();
+    assert!(large.is_some());
+    assert!(matches!(
+        limiter.acquire_sized("csv", 1_001),
+        Err(AppError::ServiceUnavailable(_))
+    ));
+
+    // One sensor for an hour still goes through, without taking a permit
+    assert!(limiter.acquire_sized("csv", 6).unwrap().is_none());
+    assert!(limiter.acquire_sized("ndjson", 1_000).unwrap().is_none());
+
+    // Bypass disabled: every bulk request needs a permit
+    let strict = BulkLimiter::new(1);
+    let _held = strict.acquire_sized("csv", 6).unwrap();
+    assert!(strict.acquire_sized("csv", 6).is_err());
+}
+
+#[tokio::test]
+async fn in_flight_counts_requests_until_body_is_consumed() {
+    let in_flight = InFlightRequests::new();
+    let app = Router::new()
+        .route("/data", get(|| async { "ok" }))
+        .layer(middleware::from_fn_with_state(
+            in_flight.clone(),
+            concurrency::track_in_flight,
+        ));
+
+    let response = app.oneshot(request("10.0.0.1")).await.unwrap();
+    // Headers are out, but the body has not been sent yet
+    assert_eq!(in_flight.count(), 1);
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert_eq!(&body[..], b"ok");
+    assert_eq!(in_flight.count(), 0);
+}
+
+#[tokio::test]
+async fn tracked_bodies_keep_their_size_hint() {
+    let app = Router::new()
+        .route("/data", get(|| async { "ok" }))
+        .layer(middleware::from_fn_with_state(
+            InFlightRequests::new(),
+            concurrency::track_in_flight,
+        ))
+        .layer(middleware::from_fn_with_state(
+            PerClientConcurrency::new(LIMIT),
+            concurrency::limit_per_client,
+        ));
+
+    let response = app.oneshot(request("10.0.0.1")).await.unwrap();
+    // Lets hyper send Content-Length rather than a chunked body
+    assert_eq!(response.body().size_hint().exact(), Some(2));
+    assert!(!response.body().is_end_stream());
+}
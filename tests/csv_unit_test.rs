@@ -0,0 +1,130 @@
+//! Unit tests for CSV export helpers, including RFC 4180 quoting.
+//!
+//! Run with: cargo test --test csv_unit_test
+
+use chrono::{DateTime, Utc};
+use river_db::routes::stations::{build_csv_response, csv_header_meta, SensorData};
+use uuid::Uuid;
+
+fn sensor(id: &str, name: &str) -> SensorData {
+    SensorData {
+        id: id.parse::<Uuid>().unwrap(),
+        name: name.to_string(),
+        sensor_type: "Depth".to_string(),
+        units: Some("mm".to_string()),
+        is_active: true,
+        values: vec![],
+        raw_times: None,
+        thresholds: None,
+        decimal_places: None,
+    }
+}
+
+#[test]
+fn header_meta_maps_columns_to_sensor_ids() {
+    let sensors = [
+        sensor("00000000-0000-0000-0000-000000000001", "MDepthmm"),
+        sensor("00000000-0000-0000-0000-000000000002", "MTurbNTU"),
+    ];
+
+    assert_eq!(
+        csv_header_meta(&sensors),
+        [
+            "# columns: time",
+            "00000000-0000-0000-0000-000000000001=MDepthmm",
+            "00000000-0000-0000-0000-000000000002=MTurbNTU",
+        ]
+    );
+}
+
+#[test]
+fn header_meta_without_sensors_lists_time_only() {
+    assert_eq!(csv_header_meta(&[]), ["# columns: time"]);
+}
+
+async fn csv_bytes(times: &[DateTime<Utc>], sensors: &[SensorData]) -> Vec<u8> {
+    let response = build_csv_response(times, sensors, true).unwrap();
+    axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap()
+        .to_vec()
+}
+
+#[tokio::test]
+async fn csv_output_is_identical_regardless_of_sensor_order() {
+    let times = [
+        DateTime::from_timestamp(1_735_689_600, 0).unwrap(),
+        DateTime::from_timestamp(1_735_690_200, 0).unwrap(),
+    ];
+    let with_values = |mut s: SensorData, values: [f64; 2]| {
+        s.values = values.into_iter().map(Some).collect();
+        s
+    };
+    let turb = with_values(sensor("00000000-0000-0000-0000-000000000001", "MTurbNTU"), [1.0, 2.0]);
+    let depth_b = with_values(sensor("00000000-0000-0000-0000-000000000003", "MDepthmm"), [3.0, 4.0]);
+    let depth_a = with_values(sensor("00000000-0000-0000-0000-000000000002", "MDepthmm"), [5.0, 6.0]);
+
+    let first = csv_bytes(&times, &[turb.clone(), depth_b.clone(), depth_a.clone()]).await;
+    let second = csv_bytes(&times, &[depth_a, turb, depth_b]).await;
+    assert_eq!(first, second);
+
+    // Columns by name, then UUID for equal names
+    assert_eq!(
+        String::from_utf8(first).unwrap(),
+        "# columns: time,00000000-0000-0000-0000-000000000002=MDepthmm,\
+         00000000-0000-0000-0000-000000000003=MDepthmm,\
+         00000000-0000-0000-0000-000000000001=MTurbNTU\n\
+         time,MDepthmm,MDepthmm,MTurbNTU\n\
+         2025-01-01T00:00:00+00:00,5,3,1\n\
+         2025-01-01T00:10:00+00:00,6,4,2\n"
+    );
+}
+
+#[tokio::test]
+async fn csv_quotes_names_with_commas_and_quotes() {
+    let times = [DateTime::from_timestamp(1_735_689_600, 0).unwrap()];
+    let mut tricky = sensor("00000000-0000-0000-0000-000000000001", "a,b\"c");
+    tricky.values = vec![Some(1.5)];
+    let mut plain = sensor("00000000-0000-0000-0000-000000000002", "b");
+    plain.values = vec![None];
+
+    let bytes = csv_bytes(&times, &[tricky, plain]).await;
+    // The metadata line quotes names like the header row does
+    let (meta, bytes) = bytes.split_at(bytes.iter().position(|b| *b == b'\n').unwrap() + 1);
+    assert_eq!(
+        meta,
+        b"# columns: time,\"00000000-0000-0000-0000-000000000001=a,b\"\"c\",\
+          00000000-0000-0000-0000-000000000002=b\n"
+    );
+    assert!(bytes.starts_with(b"time,\"a,b\"\"c\",b\n"));
+
+    let mut reader = csv::Reader::from_reader(bytes);
+    let header: Vec<String> = reader.headers().unwrap().iter().map(str::to_string).collect();
+    assert_eq!(header, ["time", "a,b\"c", "b"]);
+    let rows: Vec<csv::StringRecord> = reader.records().map(Result::unwrap).collect();
+    assert_eq!(rows.len(), 1);
+    assert_eq!(&rows[0][0], "2025-01-01T00:00:00+00:00");
+    assert_eq!(&rows[0][1], "1.5");
+    assert_eq!(&rows[0][2], "");
+}
+
+#[tokio::test]
+async fn csv_rows_span_several_chunks_intact() {
+    let times: Vec<DateTime<Utc>> = (0..1000)
+        .map(|i| DateTime::from_timestamp(1_735_689_600 + i * 600, 0).unwrap())
+        .collect();
+    let mut depth = sensor("00000000-0000-0000-0000-000000000001", "MDepthmm");
+    depth.values = (0..1000).map(|i| Some(f64::from(i))).collect();
+
+    let response = build_csv_response(&times, &[depth], false).unwrap();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let mut reader = csv::Reader::from_reader(bytes.as_ref());
+    let values: Vec<String> = reader
+        .records()
+        .map(|row| row.unwrap()[1].to_string())
+        .collect();
+    assert_eq!(values.len(), 1000);
+    assert_eq!(values[999], "999");
+}
@@ -0,0 +1,48 @@
+//! Tests for the default window applied to unbounded station readings.
+//!
+//! Run with: cargo test --test default_window_db_test
+
+mod common;
+
+use chrono::{DateTime, Duration, Utc};
+use river_db::routes::build_router;
+use serde_json::Value;
+
+fn time_at(value: &Value) -> DateTime<Utc> {
+    value.as_str().unwrap().parse().unwrap()
+}
+
+#[tokio::test]
+async fn unbounded_requests_get_the_default_window() {
+    let Some(test_db) = common::timescale().await else {
+        return;
+    };
+    let station = common::seed_station(&test_db.db, &[("MDepthmm", "Depth")]).await;
+    let sensor_id = station.sensor_ids[0];
+    let old = DateTime::from_timestamp(1_735_689_600, 0).unwrap(); // 2025-01-01T00:00:00Z
+    common::seed_readings(&test_db.db, sensor_id, old, Duration::minutes(10), 2, f64::from).await;
+    let recent = DateTime::from_timestamp(Utc::now().timestamp() / 600 * 600 - 3600, 0).unwrap();
+    common::seed_readings(&test_db.db, sensor_id, recent, Duration::minutes(10), 3, f64::from).await;
+
+    let mut config = common::test_config(&test_db.url);
+    config.default_readings_window = Some(Duration::days(7));
+    let router = build_router(common::app_state_with(test_db.db.clone(), config));
+    let uri = format!("/api/v1/stations/{}/readings", station.id);
+
+    let body = common::get_ok_json(&router, &uri).await;
+    assert_eq!(body["times"].as_array().unwrap().len(), 3);
+    let (start, end) = (time_at(&body["effective_start"]), time_at(&body["effective_end"]));
+    assert_eq!(end - start, Duration::days(7));
+    assert!((Utc::now() - end).num_seconds().abs() < 120);
+
+    // An explicit bound opts out of the default
+    let body = common::get_ok_json(&router, &format!("{uri}?start=2024-12-31T00:00:00Z")).await;
+    assert_eq!(body["times"].as_array().unwrap().len(), 5);
+    assert!(body["effective_end"].is_null());
+
+    // Disabled: all history
+    let router = build_router(common::app_state(&test_db));
+    let body = common::get_ok_json(&router, &uri).await;
+    assert_eq!(body["times"].as_array().unwrap().len(), 5);
+    assert!(body["effective_start"].is_null());
+}
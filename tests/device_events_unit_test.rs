@@ -0,0 +1,163 @@
+//! Unit tests for device status change detection, label decoding and the
+//! event stream.
+//!
+//! Run with: cargo test --test device_events_unit_test
+
+mod common;
+
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use chrono::Utc;
+use river_db::routes::build_router;
+use river_db::services::device_events::{self, DeviceSnapshot, DeviceStatusEvent};
+use sea_orm::DatabaseConnection;
+use std::time::Duration;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+fn snapshot(battery_level: i16, device_status: &str, unreachable: bool) -> DeviceSnapshot {
+    DeviceSnapshot {
+        battery_level: Some(battery_level),
+        battery_state: Some(0),
+        signal_quality: Some(80),
+        device_status: Some(device_status.to_string()),
+        unreachable: Some(unreachable),
+    }
+}
+
+/// Open the device status stream as client `ip`
+async fn open_stream(router: axum::Router, ip: &str) -> axum::response::Response {
+    router
+        .oneshot(
+            Request::get("/api/v1/device-status/stream")
+                .header("x-forwarded-for", ip)
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap()
+}
+
+#[test]
+fn battery_label_follows_level_thresholds() {
+    assert_eq!(snapshot(90, "", false).battery_label(), "ok");
+    assert_eq!(snapshot(25, "", false).battery_label(), "low");
+    assert_eq!(snapshot(10, "", false).battery_label(), "critical");
+    assert_eq!(snapshot(-1, "", false).battery_label(), "unknown");
+    let none = DeviceSnapshot {
+        battery_level: None,
+        ..snapshot(0, "", false)
+    };
+    assert_eq!(none.battery_label(), "unknown");
+}
+
+#[test]
+fn status_label_prefers_unreachable() {
+    assert_eq!(snapshot(90, "", false).status_label(), "ok");
+    assert_eq!(snapshot(90, "OK", false).status_label(), "ok");
+    assert_eq!(snapshot(90, "Sensor failure", false).status_label(), "fault");
+    assert_eq!(snapshot(90, "Sensor failure", true).status_label(), "unreachable");
+}
+
+#[test]
+fn level_and_signal_drift_is_not_a_change() {
+    let previous = snapshot(80, "", false);
+    let mut current = snapshot(78, "", false);
+    current.signal_quality = Some(40);
+    assert!(!current.changed_from(&previous));
+
+    assert!(snapshot(20, "", false).changed_from(&previous));
+    assert!(snapshot(80, "", true).changed_from(&previous));
+    assert!(snapshot(80, "Sensor failure", false).changed_from(&previous));
+}
+
+#[tokio::test]
+async fn events_reach_subscribers_with_both_labels() {
+    let tx = device_events::channel();
+    let mut rx = tx.subscribe();
+
+    let event = DeviceStatusEvent::new(
+        Uuid::new_v4(),
+        Uuid::new_v4(),
+        Utc::now(),
+        snapshot(8, "", false),
+        &snapshot(30, "", false),
+    );
+    tx.send(event).unwrap();
+
+    let received = rx.recv().await.unwrap();
+    assert_eq!(received.battery_label, "critical");
+    assert_eq!(received.previous_battery_label, "ok");
+    assert_eq!(received.status_label, "ok");
+}
+
+#[tokio::test]
+async fn stream_ends_on_shutdown() {
+    // Without a station filter the stream never queries the database
+    let config = common::test_config("postgresql://unused");
+    let state = common::app_state_with(DatabaseConnection::Disconnected, config);
+    let response = build_router(state.clone())
+        .oneshot(
+            Request::get("/api/v1/device-status/stream")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    state.shutdown.cancel();
+    tokio::time::timeout(
+        Duration::from_secs(5),
+        axum::body::to_bytes(response.into_body(), usize::MAX),
+    )
+    .await
+    .expect("stream still open after shutdown")
+    .unwrap();
+}
+
+#[tokio::test]
+async fn stream_sends_published_events() {
+    // Without a station filter the stream never queries the database
+    let config = common::test_config("postgresql://unused");
+    let state = common::app_state_with(DatabaseConnection::Disconnected, config);
+    let response = open_stream(build_router(state.clone()), "10.0.0.1").await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // The handler subscribes before responding, so nothing is missed
+    let sensor_id = Uuid::new_v4();
+    state
+        .device_events
+        .send(DeviceStatusEvent::new(
+            sensor_id,
+            Uuid::new_v4(),
+            Utc::now(),
+            snapshot(90, "", true),
+            &snapshot(90, "", false),
+        ))
+        .unwrap();
+
+    let event = common::first_sse_data(response).await;
+    assert_eq!(event["sensor_id"], sensor_id.to_string());
+    assert_eq!(event["status_label"], "unreachable");
+    assert_eq!(event["previous_status_label"], "ok");
+}
+
+#[tokio::test]
+async fn streams_count_against_the_per_client_limit() {
+    let mut config = common::test_config("postgresql://unused");
+    config.disable_rate_limiting = false;
+    config.per_client_concurrent_limit = 1;
+    let state = common::app_state_with(DatabaseConnection::Disconnected, config);
+    let router = build_router(state);
+
+    let open = open_stream(router.clone(), "10.0.0.1").await;
+    assert_eq!(open.status(), StatusCode::OK);
+    let rejected = open_stream(router.clone(), "10.0.0.1").await;
+    assert_eq!(rejected.status(), StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(open_stream(router.clone(), "10.0.0.2").await.status(), StatusCode::OK);
+
+    // Closing the stream frees the slot
+    drop(open);
+    assert_eq!(open_stream(router, "10.0.0.1").await.status(), StatusCode::OK);
+}
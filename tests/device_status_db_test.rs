@@ -0,0 +1,142 @@
+//! Tests for the latest device status of a station's sensors.
+//!
+//! Run with: cargo test --test device_status_db_test
+
+mod common;
+
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use axum::{routing::get, Json, Router};
+use chrono::{Duration, Utc};
+use river_db::routes::build_router;
+use river_db::sync::worker;
+use sea_orm::{ConnectionTrait, DatabaseBackend, Statement};
+use serde_json::json;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+async fn insert_status(
+    db: &sea_orm::DatabaseConnection,
+    sensor_id: Uuid,
+    time: &str,
+    battery_level: i16,
+    unreachable: bool,
+) {
+    db.execute(Statement::from_sql_and_values(
+        DatabaseBackend::Postgres,
+        "INSERT INTO device_status
+             (sensor_id, time, battery_level, battery_state, signal_quality, device_status, unreachable)
+         VALUES ($1, $2::timestamptz, $3, 0, 80, 'OK', $4)",
+        [sensor_id.into(), time.into(), battery_level.into(), unreachable.into()],
+    ))
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn returns_latest_status_per_sensor() {
+    let Some(test_db) = common::timescale().await else {
+        return;
+    };
+    let station = common::seed_station(
+        &test_db.db,
+        &[
+            ("MTurbNTU", "Turbidity"),
+            ("MDepthmm", "Depth"),
+            ("MBattV", "Battery"),
+        ],
+    )
+    .await;
+    let (turbidity, depth) = (station.sensor_ids[0], station.sensor_ids[1]);
+
+    insert_status(&test_db.db, turbidity, "2025-01-01T00:00:00Z", 90, false).await;
+    insert_status(&test_db.db, turbidity, "2025-01-01T00:30:00Z", 20, false).await;
+    insert_status(&test_db.db, depth, "2025-01-01T00:00:00Z", 80, true).await;
+
+    let router = build_router(common::app_state(&test_db));
+    let (status, body) = common::get_json(
+        router.clone(),
+        &format!("/api/v1/stations/{}/devices/status", station.name),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(body["station"]["id"], station.id.to_string());
+
+    // The battery sensor never reported a status; the rest are by name
+    let sensors = body["sensors"].as_array().unwrap();
+    assert_eq!(sensors.len(), 2);
+    assert_eq!(sensors[0]["name"], "MDepthmm");
+    assert_eq!(sensors[0]["status_label"], "unreachable");
+    assert_eq!(sensors[1]["name"], "MTurbNTU");
+    assert_eq!(sensors[1]["time"], "2025-01-01T00:30:00Z");
+    assert_eq!(sensors[1]["battery_level"], 20);
+    assert_eq!(sensors[1]["battery_label"], "low");
+    assert_eq!(sensors[1]["status_label"], "ok");
+
+    let (status, _) = common::get_json(
+        router,
+        &format!("/api/v1/stations/{}/devices/status", Uuid::new_v4()),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn sync_publishes_changes_to_the_stream() {
+    let Some(test_db) = common::timescale().await else {
+        return;
+    };
+    let station = common::seed_station(&test_db.db, &[("MTurbNTU", "Turbidity")]).await;
+    let (sensor_id, location_id) = (station.sensor_ids[0], station.location_ids[0]);
+    let previous = (Utc::now() - Duration::minutes(1)).to_rfc3339();
+    insert_status(&test_db.db, sensor_id, &previous, 90, false).await;
+
+    // The battery dropped to a critical level since the previous sync
+    let router = Router::new().route(
+        "/locations_data",
+        get(move || async move {
+            Json(json!({
+                "jsonapi": {"version": "1.0"},
+                "data": [{
+                    "type": "locations_data",
+                    "id": location_id.to_string(),
+                    "attributes": {
+                        "id": location_id,
+                        "battery_level": 5,
+                        "signal_quality": 80,
+                        "device_status": "OK",
+                    },
+                }],
+            }))
+        }),
+    );
+    let mut config = common::test_config(&test_db.url);
+    config.vaisala_base_url = common::mock_vaisala(router).await;
+    let state = common::app_state_with(test_db.db.clone(), config);
+
+    let response = build_router(state.clone())
+        .oneshot(
+            Request::get(format!("/api/v1/device-status/stream?station_id={}", station.id))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    worker::sync_device_status(
+        &test_db.db,
+        &state.vaisala_client,
+        &state.device_events,
+        Duration::hours(1),
+    )
+    .await
+    .unwrap();
+
+    let event = common::first_sse_data(response).await;
+    assert_eq!(event["sensor_id"], sensor_id.to_string());
+    assert_eq!(event["station_id"], station.id.to_string());
+    assert_eq!(event["battery_level"], 5);
+    assert_eq!(event["battery_label"], "critical");
+    assert_eq!(event["previous_battery_label"], "ok");
+}
@@ -0,0 +1,67 @@
+//! Tests for the storage diagnostics admin endpoint against TimescaleDB.
+//!
+//! Run with: cargo test --test diagnostics_db_test
+
+mod common;
+
+use axum::body::Body;
+use axum::http::{header, Request, StatusCode};
+use chrono::{DateTime, Duration};
+use river_db::routes::build_router;
+use serde_json::Value;
+use tower::ServiceExt;
+
+#[tokio::test]
+async fn diagnostics_report_hypertables() {
+    let Some(test_db) = common::timescale().await else {
+        return;
+    };
+    let station = common::seed_station(&test_db.db, &[("MDepthmm", "Depth")]).await;
+    let start = DateTime::from_timestamp(1_735_689_600, 0).unwrap(); // 2025-01-01T00:00:00Z
+    common::seed_readings(
+        &test_db.db,
+        station.sensor_ids[0],
+        start,
+        Duration::minutes(10),
+        6,
+        f64::from,
+    )
+    .await;
+
+    let mut config = common::test_config(&test_db.url);
+    config.admin_token = Some("secret".to_string());
+    let router = build_router(common::app_state_with(test_db.db.clone(), config));
+
+    let unauthorized = router
+        .clone()
+        .oneshot(Request::get("/api/v1/admin/diagnostics").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(unauthorized.status(), StatusCode::UNAUTHORIZED);
+
+    let response = router
+        .oneshot(
+            Request::get("/api/v1/admin/diagnostics")
+                .header(header::AUTHORIZATION, "Bearer secret")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let body: Value = serde_json::from_slice(&bytes).unwrap();
+
+    assert!(body["database_bytes"].as_i64().unwrap() > 0);
+    assert_eq!(body["timescaledb_available"], true);
+    let readings = body["hypertables"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|h| h["name"] == "readings")
+        .expect("readings hypertable listed");
+    assert!(readings["chunk_count"].as_i64().unwrap() >= 1, "{readings}");
+    assert!(readings["total_bytes"].as_i64().unwrap() > 0, "{readings}");
+}
@@ -0,0 +1,41 @@
+//! Tests for the opt-in `{data, meta}` envelope on list endpoints.
+//!
+//! Run with: cargo test --test envelope_db_test
+
+mod common;
+
+use river_db::routes::build_router;
+
+#[tokio::test]
+async fn list_endpoints_wrap_only_when_asked() {
+    let Some(test_db) = common::timescale().await else {
+        return;
+    };
+    let station =
+        common::seed_station(&test_db.db, &[("MDepthmm", "Depth"), ("MTurbNTU", "Turbidity")]).await;
+    let router = build_router(common::app_state(&test_db));
+    let sensors_uri = format!("/api/v1/stations/{}/sensors", station.id);
+
+    let bare = common::get_ok_json(&router, &sensors_uri).await;
+    assert_eq!(bare.as_array().unwrap().len(), 2);
+
+    let wrapped = common::get_ok_json(&router, &format!("{sensors_uri}?envelope=true")).await;
+    assert_eq!(wrapped["data"], bare);
+    assert_eq!(wrapped["meta"]["count"], 2);
+    assert!(wrapped["meta"]["generated_at"].is_string());
+
+    for uri in ["/api/v1/zones", "/api/v1/stations"] {
+        let bare = common::get_ok_json(&router, uri).await;
+        let wrapped = common::get_ok_json(&router, &format!("{uri}?envelope=true")).await;
+        assert!(bare.is_array(), "{uri}");
+        assert_eq!(
+            wrapped["meta"]["count"].as_u64().unwrap(),
+            wrapped["data"].as_array().unwrap().len() as u64,
+            "{uri}"
+        );
+    }
+
+    // Explicit false keeps the bare array
+    let explicit = common::get_ok_json(&router, &format!("{sensors_uri}?envelope=false")).await;
+    assert_eq!(explicit, bare);
+}
@@ -0,0 +1,39 @@
+//! Unit tests for JSON error bodies.
+//!
+//! Run with: cargo test --test error_unit_test
+
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use river_db::error::AppError;
+use serde_json::Value;
+
+async fn body_of(error: AppError) -> (StatusCode, Value) {
+    let response = error.into_response();
+    let status = response.status();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, serde_json::from_slice(&bytes).unwrap())
+}
+
+#[tokio::test]
+async fn not_found_names_the_missing_resource() {
+    let (status, body) = body_of(AppError::not_found("zone", "BREATHE")).await;
+
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(
+        body,
+        serde_json::json!({"error": "Zone not found", "resource": "zone", "id": "BREATHE"})
+    );
+
+    let (_, body) = body_of(AppError::not_found("sync_pass", "last")).await;
+    assert_eq!(body["error"], "Sync pass not found");
+}
+
+#[tokio::test]
+async fn other_errors_have_only_a_message() {
+    let (status, body) = body_of(AppError::BadRequest("bad".to_string())).await;
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body, serde_json::json!({"error": "bad"}));
+}
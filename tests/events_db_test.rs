@@ -0,0 +1,158 @@
+//! Tests for event listing filters.
+//!
+//! Run with: cargo test --test events_db_test
+
+mod common;
+
+use axum::http::StatusCode;
+use chrono::{DateTime, Duration};
+use river_db::entity::events;
+use river_db::routes::build_router;
+use sea_orm::{EntityTrait, Set};
+use serde_json::Value;
+use uuid::Uuid;
+
+#[tokio::test]
+async fn events_filtered_by_several_categories() {
+    let Some(test_db) = common::timescale().await else {
+        return;
+    };
+    let station = common::seed_station(&test_db.db, &[("MDepthmm", "Depth")]).await;
+    let start = DateTime::from_timestamp(1_735_689_600, 0).unwrap(); // 2025-01-01T00:00:00Z
+    let seed = i32::from_str_radix(&Uuid::new_v4().simple().to_string()[..6], 16).unwrap();
+
+    for (i, category) in ["alarm", "admin", "system", "alarm"].into_iter().enumerate() {
+        let i = i32::try_from(i).unwrap();
+        events::Entity::insert(events::ActiveModel {
+            time: Set((start + Duration::minutes(i64::from(i))).into()),
+            vaisala_event_num: Set(seed + i),
+            category: Set(category.to_string()),
+            message: Set(format!("Event {i}")),
+            user_name: Set(None),
+            entity: Set(None),
+            entity_id: Set(None),
+            sensor_id: Set(None),
+            station_id: Set(Some(station.id)),
+            device_id: Set(None),
+            channel_id: Set(None),
+            host_id: Set(None),
+            location_id: Set(None),
+            affected_location_ids: Set(None),
+            extra_fields: Set(None),
+        })
+        .exec_without_returning(&test_db.db)
+        .await
+        .unwrap();
+    }
+
+    let router = build_router(common::app_state(&test_db));
+    let base = format!(
+        "/api/v1/events?start=2025-01-01T00:00:00Z&end=2025-01-01T01:00:00Z&station_id={}",
+        station.id
+    );
+    let categories = |body: &Value| -> Vec<String> {
+        body["events"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|e| e["category"].as_str().unwrap().to_string())
+            .collect()
+    };
+
+    let (status, body) =
+        common::get_json(router.clone(), &format!("{base}&category=alarm,admin")).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(body["total"], 3);
+    assert_eq!(categories(&body), ["alarm", "admin", "alarm"]);
+
+    // Single values keep working
+    let (status, body) = common::get_json(router.clone(), &format!("{base}&category=system")).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(body["total"], 1);
+    assert_eq!(categories(&body), ["system"]);
+
+    let (status, body) = common::get_json(router, &format!("{base}&category=alarm,bogus")).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST, "{body}");
+    assert!(body["error"].as_str().unwrap().contains("bogus"));
+}
+
+/// An event about `location_id` and `affected` locations, not linked to a sensor
+fn location_event(
+    time: DateTime<chrono::Utc>,
+    num: i32,
+    location_id: Option<i32>,
+    affected: Option<Value>,
+) -> events::ActiveModel {
+    events::ActiveModel {
+        time: Set(time.into()),
+        vaisala_event_num: Set(num),
+        category: Set("alarm".to_string()),
+        message: Set(format!("Event {num}")),
+        user_name: Set(None),
+        entity: Set(None),
+        entity_id: Set(None),
+        sensor_id: Set(None),
+        station_id: Set(None),
+        device_id: Set(None),
+        channel_id: Set(None),
+        host_id: Set(None),
+        location_id: Set(location_id),
+        affected_location_ids: Set(affected),
+        extra_fields: Set(None),
+    }
+}
+
+#[tokio::test]
+async fn sensor_events_include_affected_locations() {
+    let Some(test_db) = common::timescale().await else {
+        return;
+    };
+    let station =
+        common::seed_station(&test_db.db, &[("MDepthmm", "Depth"), ("MTurbNTU", "Turbidity")]).await;
+    let (depth, turbidity) = (station.location_ids[0], station.location_ids[1]);
+    let start = DateTime::from_timestamp(1_735_689_600, 0).unwrap(); // 2025-01-01T00:00:00Z
+    let seed = i32::from_str_radix(&Uuid::new_v4().simple().to_string()[..6], 16).unwrap();
+
+    for (i, (location_id, affected)) in [
+        (Some(depth), None),
+        (None, Some(serde_json::json!([turbidity, depth]))),
+        (Some(turbidity), Some(serde_json::json!([turbidity]))),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        let i = i32::try_from(i).unwrap();
+        let time = start + Duration::minutes(i64::from(i));
+        events::Entity::insert(location_event(time, seed + i, location_id, affected))
+            .exec_without_returning(&test_db.db)
+            .await
+            .unwrap();
+    }
+
+    let router = build_router(common::app_state(&test_db));
+    let uri = |sensor_id: Uuid| {
+        format!(
+            "/api/v1/sensors/{sensor_id}/events?start=2025-01-01T00:00:00Z&end=2025-01-01T01:00:00Z"
+        )
+    };
+    let nums = |body: &Value| -> Vec<i64> {
+        body["events"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|e| e["vaisala_event_num"].as_i64().unwrap() - i64::from(seed))
+            .collect()
+    };
+
+    let (status, body) = common::get_json(router.clone(), &uri(station.sensor_ids[0])).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(body["total"], 2);
+    assert_eq!(nums(&body), [1, 0]);
+
+    let (status, body) = common::get_json(router.clone(), &uri(station.sensor_ids[1])).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(nums(&body), [2, 1]);
+
+    let (status, _) = common::get_json(router, &uri(Uuid::new_v4())).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
@@ -0,0 +1,44 @@
+//! Unit tests for response format negotiation.
+//!
+//! Run with: cargo test --test format_unit_test
+
+use axum::http::{header, HeaderMap, HeaderValue};
+use river_db::common::format::{format_from_accept, negotiate_format};
+
+fn accept(value: &'static str) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    headers.insert(header::ACCEPT, HeaderValue::from_static(value));
+    headers
+}
+
+#[test]
+fn query_parameter_takes_precedence_over_accept() {
+    assert_eq!(negotiate_format(Some("CSV"), &accept("application/json")), "csv");
+    // An explicit format=json beats an Accept header asking for CSV
+    assert_eq!(negotiate_format(Some("json"), &accept("text/csv")), "json");
+}
+
+#[test]
+fn accept_header_used_without_query_parameter() {
+    assert_eq!(negotiate_format(None, &accept("text/csv")), "csv");
+    assert_eq!(negotiate_format(None, &accept("application/x-ndjson")), "ndjson");
+    assert_eq!(negotiate_format(None, &accept("application/json")), "json");
+    assert_eq!(negotiate_format(None, &HeaderMap::new()), "json");
+    assert_eq!(negotiate_format(None, &accept("text/html")), "json");
+}
+
+#[test]
+fn accept_q_values_pick_the_preferred_format() {
+    assert_eq!(
+        format_from_accept("text/csv;q=0.9, application/json;q=1.0"),
+        Some("json")
+    );
+    assert_eq!(
+        format_from_accept("application/json;q=0.5, text/csv"),
+        Some("csv")
+    );
+    // Ties go to the first listed; q=0 excludes a type
+    assert_eq!(format_from_accept("text/csv, application/x-ndjson"), Some("csv"));
+    assert_eq!(format_from_accept("text/csv;q=0, */*;q=0.1"), Some("json"));
+    assert_eq!(format_from_accept("text/html, image/png"), None);
+}
@@ -0,0 +1,116 @@
+//! Tests for gzip-compressed CSV/NDJSON exports (`compress=gzip`).
+//!
+//! Run with: cargo test --test gzip_export_db_test
+
+mod common;
+
+use async_compression::tokio::bufread::GzipDecoder;
+use axum::body::Body;
+use axum::http::{header, HeaderMap, Request, StatusCode};
+use chrono::{DateTime, Duration};
+use river_db::routes::build_router;
+use tokio::io::AsyncReadExt;
+use tower::ServiceExt;
+
+/// Status, headers and raw body bytes of a GET, advertising gzip support so
+/// the response compression layer would also apply if it could
+async fn get_bytes(router: axum::Router, uri: &str) -> (StatusCode, HeaderMap, Vec<u8>) {
+    let response = router
+        .oneshot(
+            Request::get(uri)
+                .header(header::ACCEPT_ENCODING, "gzip")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let status = response.status();
+    let headers = response.headers().clone();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, headers, bytes.to_vec())
+}
+
+async fn gunzip(bytes: &[u8]) -> Vec<u8> {
+    let mut decoded = Vec::new();
+    GzipDecoder::new(bytes)
+        .read_to_end(&mut decoded)
+        .await
+        .unwrap();
+    decoded
+}
+
+/// Compare the gzipped export at `uri` with the same export uncompressed.
+async fn assert_gzip_matches(router: &axum::Router, uri: &str, filename: &str) {
+    // Without compress, the layer gzips based on Accept-Encoding alone
+    let (status, headers, plain) = get_bytes(router.clone(), uri).await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(headers.get(header::CONTENT_DISPOSITION).is_none());
+    let plain = match headers.get(header::CONTENT_ENCODING) {
+        Some(encoding) if encoding == "gzip" => gunzip(&plain).await,
+        _ => plain,
+    };
+    assert!(!plain.is_empty());
+
+    let (status, headers, compressed) =
+        get_bytes(router.clone(), &format!("{uri}&compress=gzip")).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(headers[header::CONTENT_ENCODING], "gzip");
+    assert_eq!(
+        headers[header::CONTENT_DISPOSITION],
+        format!("attachment; filename=\"{filename}\"").as_str()
+    );
+    // Compressed once: a single decode yields the plain export
+    assert_eq!(gunzip(&compressed).await, plain);
+}
+
+#[tokio::test]
+async fn gzip_exports_match_uncompressed_output() {
+    let Some(test_db) = common::timescale().await else {
+        return;
+    };
+    let station = common::seed_station(
+        &test_db.db,
+        &[("MTurbNTU", "Turbidity"), ("MDepthmm", "Depth")],
+    )
+    .await;
+    let start = DateTime::from_timestamp(1_735_689_600, 0).unwrap(); // 2025-01-01T00:00:00Z
+    let end = start + Duration::hours(3);
+    for sensor in &station.sensor_ids {
+        common::seed_readings(
+            &test_db.db,
+            *sensor,
+            start,
+            Duration::minutes(10),
+            18,
+            f64::from,
+        )
+        .await;
+    }
+    common::refresh_aggregate(&test_db.db, "readings_hourly", start, end).await;
+
+    let router = build_router(common::app_state(&test_db));
+    let range = "start=2025-01-01T00:00:00Z&end=2025-01-01T02:59:59Z";
+    let readings = format!("/api/v1/stations/{}/readings?{range}", station.id);
+    let aggregates = format!("/api/v1/stations/{}/aggregates/hourly?{range}", station.id);
+
+    for format in ["csv", "ndjson"] {
+        assert_gzip_matches(
+            &router,
+            &format!("{readings}&format={format}"),
+            &format!("{}-readings.{format}.gz", station.name),
+        )
+        .await;
+        assert_gzip_matches(
+            &router,
+            &format!("{aggregates}&format={format}"),
+            &format!("{}-hourly.{format}.gz", station.name),
+        )
+        .await;
+    }
+
+    // JSON is compressed by Accept-Encoding only
+    let (status, _, _) = get_bytes(router, &format!("{readings}&compress=gzip")).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
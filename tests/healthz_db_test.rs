@@ -0,0 +1,88 @@
+//! Tests for the `/healthz` body.
+//!
+//! The healthy case uses `TEST_DATABASE_URL` or a Docker TimescaleDB
+//! container and is skipped if neither is available. See `tests/common/mod.rs`.
+//!
+//! Run with: cargo test --test healthz_db_test
+
+mod common;
+
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use axum::{routing::get, Router};
+use river_db::common::AppState;
+use river_db::routes::build_router;
+use sea_orm::DatabaseConnection;
+use serde_json::Value;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use tower::ServiceExt;
+
+async fn get_health(state: AppState) -> (StatusCode, Value) {
+    let response = build_router(state)
+        .oneshot(Request::get("/healthz").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    let status = response.status();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, serde_json::from_slice(&bytes).unwrap())
+}
+
+#[tokio::test]
+async fn ok_when_dependencies_reachable() {
+    let Some(test_db) = common::timescale().await else {
+        return;
+    };
+    let base_url =
+        common::mock_vaisala(Router::new().route("/", get(|| async { "viewLinc" }))).await;
+
+    let mut config = common::test_config(&test_db.url);
+    config.vaisala_base_url = base_url;
+    let (status, body) = get_health(common::app_state_with(test_db.db.clone(), config)).await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["status"], "ok");
+    assert_eq!(body["db"], true);
+    assert_eq!(body["vaisala"], true);
+    assert!(body["uptime_seconds"].is_u64());
+    assert_eq!(body["version"], env!("CARGO_PKG_VERSION"));
+}
+
+#[tokio::test]
+async fn degraded_when_dependencies_unreachable() {
+    // Disconnected database and a Vaisala URL nothing listens on
+    let config = common::test_config("postgresql://unused");
+    let (status, body) =
+        get_health(common::app_state_with(DatabaseConnection::Disconnected, config)).await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["status"], "degraded");
+    assert_eq!(body["db"], false);
+    assert_eq!(body["vaisala"], false);
+}
+
+#[tokio::test]
+async fn vaisala_check_is_reused_between_probes() {
+    let hits = Arc::new(AtomicUsize::new(0));
+    let counter = hits.clone();
+    let base_url = common::mock_vaisala(Router::new().route(
+        "/",
+        get(move || {
+            counter.fetch_add(1, Ordering::SeqCst);
+            async { "viewLinc" }
+        }),
+    ))
+    .await;
+
+    let mut config = common::test_config("postgresql://unused");
+    config.vaisala_base_url = base_url;
+    let state = common::app_state_with(DatabaseConnection::Disconnected, config);
+
+    for _ in 0..3 {
+        let (_, body) = get_health(state.clone()).await;
+        assert_eq!(body["vaisala"], true);
+    }
+    assert_eq!(hits.load(Ordering::SeqCst), 1);
+}
@@ -0,0 +1,96 @@
+//! End-to-end tests for the hierarchy endpoint against TimescaleDB.
+//!
+//! Run with: cargo test --test hierarchy_db_test
+
+mod common;
+
+use axum::http::StatusCode;
+use river_db::entity::zones;
+use river_db::routes::build_router;
+use sea_orm::{ConnectionTrait, DatabaseBackend, DatabaseConnection, EntityTrait, Set, Statement};
+use uuid::Uuid;
+
+/// Insert a uniquely named zone and move `station_id` into it.
+async fn seed_zone(db: &DatabaseConnection, station_id: Uuid) -> Uuid {
+    let id = Uuid::new_v4();
+    zones::Entity::insert(zones::ActiveModel {
+        id: Set(id),
+        name: Set(format!("zone-{}", &id.simple().to_string()[..12])),
+        vaisala_path: Set(None),
+        description: Set(None),
+        created_at: Set(None),
+        discovered_at: Set(None),
+    })
+    .exec_without_returning(db)
+    .await
+    .unwrap();
+
+    db.execute(Statement::from_sql_and_values(
+        DatabaseBackend::Postgres,
+        "UPDATE stations SET zone_id = $1 WHERE id = $2",
+        [id.into(), station_id.into()],
+    ))
+    .await
+    .unwrap();
+    id
+}
+
+#[tokio::test]
+async fn zone_scoped_tree_nests_stations_and_sensors() {
+    let Some(test_db) = common::timescale().await else {
+        return;
+    };
+    let station = common::seed_station(
+        &test_db.db,
+        &[("MDepthmm", "Depth"), ("MTurbNTU", "Turbidity")],
+    )
+    .await;
+    let zone_id = seed_zone(&test_db.db, station.id).await;
+    test_db
+        .db
+        .execute(Statement::from_sql_and_values(
+            DatabaseBackend::Postgres,
+            "UPDATE sensors SET is_active = false WHERE id = $1",
+            [station.sensor_ids[1].into()],
+        ))
+        .await
+        .unwrap();
+
+    let router = build_router(common::app_state(&test_db));
+
+    let (status, body) =
+        common::get_json(router.clone(), &format!("/api/hierarchy?zone_id={zone_id}")).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    let zones = body["zones"].as_array().unwrap();
+    assert_eq!(zones.len(), 1);
+    assert_eq!(zones[0]["id"], zone_id.to_string());
+    assert_eq!(zones[0]["stations"][0]["id"], station.id.to_string());
+    let sensors = zones[0]["stations"][0]["sensors"].as_array().unwrap();
+    assert_eq!(sensors.len(), 1);
+    assert_eq!(sensors[0]["name"], "MDepthmm");
+    assert_eq!(body["unassigned_stations"], serde_json::json!([]));
+
+    let (status, body) = common::get_json(
+        router,
+        &format!("/api/hierarchy?zone_id={zone_id}&include_inactive=true"),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    let sensors = body["zones"][0]["stations"][0]["sensors"].as_array().unwrap();
+    assert_eq!(sensors.len(), 2);
+}
+
+#[tokio::test]
+async fn stations_without_zone_are_unassigned() {
+    let Some(test_db) = common::timescale().await else {
+        return;
+    };
+    let station = common::seed_station(&test_db.db, &[("MDepthmm", "Depth")]).await;
+
+    let router = build_router(common::app_state(&test_db));
+    let (status, body) = common::get_json(router, "/api/hierarchy").await;
+
+    assert_eq!(status, StatusCode::OK, "{body}");
+    let unassigned = body["unassigned_stations"].as_array().unwrap();
+    assert!(unassigned.iter().any(|s| s["id"] == station.id.to_string()));
+}
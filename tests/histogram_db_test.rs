@@ -0,0 +1,67 @@
+//! Tests for the sensor value histogram endpoint.
+//!
+//! Run with: cargo test --test histogram_db_test
+
+mod common;
+
+use axum::http::StatusCode;
+use chrono::{DateTime, Duration};
+use river_db::routes::build_router;
+use serde_json::{json, Value};
+
+fn assert_close(actual: &Value, expected: &[f64]) {
+    let actual: Vec<f64> = actual.as_array().unwrap().iter().map(|v| v.as_f64().unwrap()).collect();
+    assert_eq!(actual.len(), expected.len(), "{actual:?}");
+    for (a, e) in actual.iter().zip(expected) {
+        assert!((a - e).abs() < 1e-9, "{actual:?} != {expected:?}");
+    }
+}
+
+#[tokio::test]
+async fn histogram_counts_values_per_bin() {
+    let Some(test_db) = common::timescale().await else {
+        return;
+    };
+    let station = common::seed_station(&test_db.db, &[("MDepthmm", "Depth")]).await;
+    let sensor_id = station.sensor_ids[0];
+    let start = DateTime::from_timestamp(1_735_689_600, 0).unwrap(); // 2025-01-01T00:00:00Z
+    // Values 0.0 through 9.0
+    common::seed_readings(&test_db.db, sensor_id, start, Duration::minutes(10), 10, f64::from).await;
+
+    let router = build_router(common::app_state(&test_db));
+    let base = format!(
+        "/api/v1/sensors/{sensor_id}/histogram?start=2025-01-01T00:00:00Z&end=2025-01-02T00:00:00Z"
+    );
+
+    // Data bounds: the maximum lands in the last bin
+    let (status, body) = common::get_json(router.clone(), &format!("{base}&bins=5")).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(body["min"], json!(0.0));
+    assert_eq!(body["max"], json!(9.0));
+    assert_close(&body["edges"], &[0.0, 1.8, 3.6, 5.4, 7.2, 9.0]);
+    assert_eq!(body["counts"], json!([2, 2, 2, 2, 2]));
+    assert_eq!((body["below"].as_i64(), body["above"].as_i64()), (Some(0), Some(0)));
+
+    // Explicit bounds: values outside are reported separately
+    let (status, body) =
+        common::get_json(router.clone(), &format!("{base}&bins=2&min=2&max=6")).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(body["edges"], json!([2.0, 4.0, 6.0]));
+    assert_eq!(body["counts"], json!([2, 3]));
+    assert_eq!((body["below"].as_i64(), body["above"].as_i64()), (Some(2), Some(3)));
+
+    // No data in range
+    let uri = format!(
+        "/api/v1/sensors/{sensor_id}/histogram?start=2024-01-01T00:00:00Z&end=2024-01-02T00:00:00Z"
+    );
+    let (status, body) = common::get_json(router.clone(), &uri).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(body["counts"], json!([]));
+    assert_eq!(body["edges"], json!([]));
+    assert!(body["min"].is_null());
+
+    for invalid in ["bins=0", "bins=1001", "min=5&max=5"] {
+        let (status, body) = common::get_json(router.clone(), &format!("{base}&{invalid}")).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{invalid}: {body}");
+    }
+}
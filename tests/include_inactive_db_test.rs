@@ -0,0 +1,66 @@
+//! Tests for `include_inactive` on the station data endpoints.
+//!
+//! Run with: cargo test --test include_inactive_db_test
+
+mod common;
+
+use axum::http::StatusCode;
+use chrono::{DateTime, Duration};
+use river_db::entity::sensors;
+use river_db::routes::build_router;
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
+use sea_orm::sea_query::Expr;
+use serde_json::Value;
+
+/// `(name, is_active)` of each sensor column in a response body
+fn columns(body: &Value) -> Vec<(String, bool)> {
+    body["sensors"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|s| (s["name"].as_str().unwrap().to_string(), s["is_active"].as_bool().unwrap()))
+        .collect()
+}
+
+#[tokio::test]
+async fn inactive_sensor_data_only_with_flag() {
+    let Some(test_db) = common::timescale().await else {
+        return;
+    };
+    let station = common::seed_station(
+        &test_db.db,
+        &[("MDepthmm", "Depth"), ("MTurbNTU", "Turbidity")],
+    ).await;
+    let start = DateTime::from_timestamp(1_735_689_600, 0).unwrap(); // 2025-01-01T00:00:00Z
+    let end = start + Duration::hours(3);
+    for sensor_id in &station.sensor_ids {
+        common::seed_readings(&test_db.db, *sensor_id, start, Duration::minutes(10), 18, |_| 1.0).await;
+    }
+    common::refresh_aggregate(&test_db.db, "readings_hourly", start, end).await;
+
+    // Retire the turbidity sensor
+    sensors::Entity::update_many()
+        .col_expr(sensors::Column::IsActive, Expr::value(false))
+        .filter(sensors::Column::Id.eq(station.sensor_ids[1]))
+        .exec(&test_db.db)
+        .await
+        .unwrap();
+
+    let router = build_router(common::app_state(&test_db));
+    let range = "start=2025-01-01T00:00:00Z&end=2025-01-01T03:00:00Z";
+    for path in ["readings", "aggregates/hourly"] {
+        let uri = format!("/api/v1/stations/{}/{path}?{range}", station.id);
+        let (status, body) = common::get_json(router.clone(), &uri).await;
+        assert_eq!(status, StatusCode::OK, "{path}: {body}");
+        assert_eq!(columns(&body), vec![("MDepthmm".to_string(), true)], "{path}");
+
+        let (status, body) =
+            common::get_json(router.clone(), &format!("{uri}&include_inactive=true")).await;
+        assert_eq!(status, StatusCode::OK, "{path}: {body}");
+        assert_eq!(
+            columns(&body),
+            vec![("MDepthmm".to_string(), true), ("MTurbNTU".to_string(), false)],
+            "{path}"
+        );
+    }
+}
@@ -0,0 +1,117 @@
+//! Unit tests for streamed JSON readings responses.
+//!
+//! Run with: cargo test --test json_stream_unit_test
+
+use chrono::{DateTime, Duration, Utc};
+use river_db::common::time::{TimeArray, TimeFormat};
+use river_db::routes::stations::{
+    estimated_json_bytes, json_chunks, ReadingsResponse, SensorColumns, SensorData, StationRef,
+    ZoneRef,
+};
+use uuid::Uuid;
+
+fn response(rows: usize, format: TimeFormat) -> ReadingsResponse {
+    let start = DateTime::from_timestamp(1_735_689_600, 0).unwrap();
+    let times: Vec<DateTime<Utc>> = (0..rows)
+        .map(|i| start + Duration::minutes(10 * i64::try_from(i).unwrap()))
+        .collect();
+    let sensor = |name: &str, scale: f64| SensorData {
+        id: Uuid::new_v4(),
+        name: name.to_string(),
+        sensor_type: "Depth".to_string(),
+        units: None,
+        is_active: true,
+        values: (0..rows)
+            .map(|i| (i % 7 != 0).then(|| f64::from(u32::try_from(i).unwrap()) * scale))
+            .collect(),
+        raw_times: None,
+        thresholds: None,
+        decimal_places: None,
+    };
+
+    ReadingsResponse {
+        zone: Some(ZoneRef {
+            id: Uuid::new_v4(),
+            name: "zone".to_string(),
+        }),
+        station: StationRef {
+            id: Uuid::new_v4(),
+            name: "station".to_string(),
+        },
+        start: times.first().copied(),
+        end: times.last().copied(),
+        effective_start: None,
+        effective_end: None,
+        resolution_hint: Some("raw".to_string()),
+        sensors_meta: None,
+        times: TimeArray::new(times, format),
+        sensors: SensorColumns::new(vec![sensor("MDepthmm", 0.5), sensor("MTurbNTU", 1.25)], false),
+    }
+}
+
+fn streamed(response: &ReadingsResponse) -> String {
+    json_chunks(response)
+        .collect::<serde_json::Result<Vec<_>>>()
+        .unwrap()
+        .concat()
+}
+
+#[test]
+fn chunks_match_buffered_serialization() {
+    // More rows than one times chunk
+    for format in [TimeFormat::Iso, TimeFormat::Epoch] {
+        let response = response(10_000, format);
+        assert_eq!(streamed(&response), serde_json::to_string(&response).unwrap());
+    }
+}
+
+#[test]
+fn empty_response_streams_valid_json() {
+    let mut response = response(0, TimeFormat::Iso);
+    response.sensors.clear();
+    assert_eq!(streamed(&response), serde_json::to_string(&response).unwrap());
+}
+
+#[test]
+fn compact_meta_moves_metadata_out_of_sensors() {
+    let full = response(100, TimeFormat::Iso);
+    let mut compact = response(100, TimeFormat::Iso);
+    compact.sensors.compact = true;
+    compact.sensors_meta = compact.sensors.meta();
+
+    let json = serde_json::to_string(&compact).unwrap();
+    assert_eq!(streamed(&compact), json);
+
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    let id = compact.sensors[0].id.to_string();
+    assert_eq!(value["sensors_meta"][&id]["name"], "MDepthmm");
+    assert_eq!(value["sensors_meta"][&id]["type"], "Depth");
+    let entry = value["sensors"][0].as_object().unwrap();
+    assert_eq!(entry.keys().collect::<Vec<_>>(), ["id", "values"]);
+
+    // The default shape has no `sensors_meta` at all
+    let full_json = serde_json::to_string(&full).unwrap();
+    assert!(!full_json.contains("sensors_meta"));
+
+    // Entries shrink to IDs and values; the metadata is sent once per sensor
+    // in `sensors_meta`, so the total only grows by the repeated ID keys
+    let sizes = |response: &ReadingsResponse| {
+        let value = serde_json::to_value(response).unwrap();
+        let sensors = serde_json::to_vec(&value["sensors"]).unwrap().len();
+        (sensors, serde_json::to_vec(&value).unwrap().len())
+    };
+    let (full_sensors, full_total) = sizes(&full);
+    let (compact_sensors, compact_total) = sizes(&compact);
+    assert!(compact_sensors < full_sensors, "{compact_sensors} vs {full_sensors}");
+    assert!(
+        compact_total < full_total + 64 * full.sensors.len(),
+        "{compact_total} vs {full_total}"
+    );
+}
+
+#[test]
+fn estimate_grows_with_rows_and_sensors() {
+    assert_eq!(estimated_json_bytes(0, 10), 0);
+    assert!(estimated_json_bytes(1000, 2) < estimated_json_bytes(1000, 3));
+    assert_eq!(estimated_json_bytes(usize::MAX, 3), usize::MAX);
+}
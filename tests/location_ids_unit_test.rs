@@ -0,0 +1,18 @@
+//! Unit tests for parsing comma-separated Vaisala location ID lists.
+//!
+//! Run with: cargo test --test location_ids_unit_test
+
+use river_db::vaisala::models::parse_location_ids;
+
+#[test]
+fn non_numeric_tokens_are_skipped() {
+    assert_eq!(parse_location_ids("101,102,abc"), vec![101, 102]);
+}
+
+#[test]
+fn blanks_whitespace_and_duplicates_are_tolerated() {
+    assert_eq!(parse_location_ids(""), Vec::<i32>::new());
+    assert_eq!(parse_location_ids(" , ,"), Vec::<i32>::new());
+    assert_eq!(parse_location_ids(" 7 ,, 3,7 "), vec![7, 3]);
+    assert_eq!(parse_location_ids("N/A"), Vec::<i32>::new());
+}
@@ -0,0 +1,88 @@
+//! Tests that with `logged_overrides_realtime` a logged reading replaces a
+//! stored realtime one at the same timestamp, whichever arrives first.
+//!
+//! Run with: cargo test --test logged_override_db_test
+
+mod common;
+
+use chrono::{DateTime, Duration, Utc};
+use river_db::entity::readings;
+use river_db::sync::worker;
+use sea_orm::{
+    ColumnTrait, ConnectionTrait, DatabaseBackend, EntityTrait, QueryFilter, Set, Statement,
+};
+use uuid::Uuid;
+
+fn reading(
+    sensor_id: Uuid,
+    time: DateTime<Utc>,
+    value: f64,
+    logged: bool,
+) -> readings::ActiveModel {
+    readings::ActiveModel {
+        sensor_id: Set(sensor_id),
+        time: Set(time.into()),
+        value: Set(value),
+        logged: Set(Some(logged)),
+        ingested_at: Set(Some(Utc::now().into())),
+        raw_time: Set(Some(time.into())),
+    }
+}
+
+/// Store one reading the way a sync pass does
+async fn store(db: &sea_orm::DatabaseConnection, model: readings::ActiveModel) -> u64 {
+    let sensor_id = *model.sensor_id.as_ref();
+    worker::store_sensor_readings(db, sensor_id, vec![model], true, None)
+        .await
+        .unwrap()
+}
+
+/// (value, logged) stored for `sensor_id` at `time`
+async fn stored(
+    db: &sea_orm::DatabaseConnection,
+    sensor_id: Uuid,
+    time: DateTime<Utc>,
+) -> (f64, Option<bool>) {
+    let row = readings::Entity::find()
+        .filter(readings::Column::SensorId.eq(sensor_id))
+        .filter(readings::Column::Time.eq(time))
+        .one(db)
+        .await
+        .unwrap()
+        .expect("reading stored");
+    (row.value, row.logged)
+}
+
+#[tokio::test]
+async fn logged_wins_in_either_order() {
+    let Some(test_db) = common::timescale().await else {
+        return;
+    };
+    let db = &test_db.db;
+    let sensor = common::seed_station(db, &[("MDepthmm", "Depth")])
+        .await
+        .sensor_ids[0];
+    let t0 = DateTime::from_timestamp(1_735_689_600, 0).unwrap(); // 2025-01-01T00:00:00Z
+    let (first, second, legacy) = (t0, t0 + Duration::minutes(10), t0 + Duration::minutes(20));
+
+    // Realtime first, then logged: replaced
+    assert_eq!(store(db, reading(sensor, first, 1.0, false)).await, 1);
+    assert_eq!(store(db, reading(sensor, first, 1.5, true)).await, 1);
+    assert_eq!(stored(db, sensor, first).await, (1.5, Some(true)));
+
+    // Logged first, then realtime: kept
+    assert_eq!(store(db, reading(sensor, second, 2.5, true)).await, 1);
+    assert_eq!(store(db, reading(sensor, second, 2.0, false)).await, 0);
+    assert_eq!(stored(db, sensor, second).await, (2.5, Some(true)));
+
+    // A row stored before `logged` was tracked counts as realtime
+    db.execute(Statement::from_sql_and_values(
+        DatabaseBackend::Postgres,
+        "INSERT INTO readings (sensor_id, time, value, logged) VALUES ($1, $2, 3.0, NULL)",
+        [sensor.into(), legacy.into()],
+    ))
+    .await
+    .unwrap();
+    assert_eq!(store(db, reading(sensor, legacy, 3.5, true)).await, 1);
+    assert_eq!(stored(db, sensor, legacy).await, (3.5, Some(true)));
+}
@@ -0,0 +1,74 @@
+//! Unit tests for maintenance windows and the admin routes guarding them.
+//!
+//! Run with: cargo test --test maintenance_unit_test
+
+mod common;
+
+use axum::{
+    body::Body,
+    http::{header, Request, StatusCode},
+};
+use chrono::{DateTime, Duration, Utc};
+use river_db::entity::maintenance_windows;
+use river_db::routes::build_router;
+use river_db::services::maintenance;
+use sea_orm::DatabaseConnection;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+fn window(start: DateTime<Utc>, end: DateTime<Utc>) -> maintenance_windows::Model {
+    maintenance_windows::Model {
+        id: Uuid::new_v4(),
+        starts_at: start.into(),
+        ends_at: end.into(),
+        reason: Some("pump replacement".to_string()),
+        created_at: None,
+    }
+}
+
+#[test]
+fn window_covers_from_start_until_end() {
+    let start = DateTime::from_timestamp(1_735_689_600, 0).unwrap();
+    let end = start + Duration::hours(2);
+    let w = window(start, end);
+
+    assert!(!maintenance::covers(&w, start - Duration::seconds(1)));
+    assert!(maintenance::covers(&w, start));
+    assert!(maintenance::covers(&w, start + Duration::hours(1)));
+    // The end is exclusive: notifications resume exactly at `end`
+    assert!(!maintenance::covers(&w, end));
+    assert!(!maintenance::covers(&w, end + Duration::minutes(5)));
+}
+
+/// Router without a database; auth is checked before any query
+fn app(admin_token: Option<&str>) -> axum::Router {
+    let mut config = common::test_config("postgresql://unused");
+    config.admin_token = admin_token.map(str::to_string);
+    build_router(common::app_state_with(DatabaseConnection::Disconnected, config))
+}
+
+async fn get_status(router: axum::Router, authorization: Option<&str>) -> StatusCode {
+    let mut request = Request::get("/api/admin/maintenance");
+    if let Some(value) = authorization {
+        request = request.header(header::AUTHORIZATION, value);
+    }
+    router
+        .oneshot(request.body(Body::empty()).unwrap())
+        .await
+        .unwrap()
+        .status()
+}
+
+#[tokio::test]
+async fn admin_routes_require_token() {
+    assert_eq!(get_status(app(Some("secret")), None).await, StatusCode::UNAUTHORIZED);
+    assert_eq!(
+        get_status(app(Some("secret")), Some("Bearer wrong")).await,
+        StatusCode::UNAUTHORIZED
+    );
+}
+
+#[tokio::test]
+async fn admin_routes_absent_without_token() {
+    assert_eq!(get_status(app(None), Some("Bearer secret")).await, StatusCode::NOT_FOUND);
+}
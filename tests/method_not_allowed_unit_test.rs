@@ -0,0 +1,55 @@
+//! Unit tests for 405 responses on known routes.
+//!
+//! Run with: cargo test --test method_not_allowed_unit_test
+
+mod common;
+
+use axum::{
+    body::Body,
+    http::{header, Request, StatusCode},
+};
+use river_db::routes::build_router;
+use sea_orm::DatabaseConnection;
+use serde_json::Value;
+use tower::ServiceExt;
+
+/// Router without a database; a 405 never reaches a handler
+fn app() -> axum::Router {
+    let config = common::test_config("postgresql://unused");
+    build_router(common::app_state_with(DatabaseConnection::Disconnected, config))
+}
+
+#[tokio::test]
+async fn unsupported_method_returns_json_envelope_and_allow_header() {
+    let response = app()
+        .oneshot(
+            Request::delete("/api/stations")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+
+    let allow = response.headers().get(header::ALLOW).unwrap().to_str().unwrap();
+    let methods: Vec<&str> = allow.split(',').map(str::trim).collect();
+    assert!(methods.contains(&"GET"), "{allow}");
+    assert!(!methods.contains(&"DELETE"), "{allow}");
+
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let body: Value = serde_json::from_slice(&bytes).unwrap();
+    assert_eq!(body["error"], "Method DELETE not allowed for this route");
+}
+
+#[tokio::test]
+async fn unknown_route_is_still_not_found() {
+    let response = app()
+        .oneshot(Request::delete("/api/nope").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
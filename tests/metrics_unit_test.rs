@@ -0,0 +1,70 @@
+//! Unit tests for the Prometheus `/metrics` endpoint.
+//!
+//! Run with: cargo test --test metrics_unit_test
+
+mod common;
+
+use axum::body::Body;
+use axum::http::{header, HeaderMap, Request, StatusCode};
+use river_db::routes::build_router;
+use sea_orm::DatabaseConnection;
+use tower::ServiceExt;
+
+/// Router without a database; the routes hit here never query it
+fn app() -> axum::Router {
+    let config = common::test_config("postgresql://unused");
+    build_router(common::app_state_with(DatabaseConnection::Disconnected, config))
+}
+
+async fn get(router: axum::Router, uri: &str) -> (StatusCode, HeaderMap, String) {
+    let response = router
+        .oneshot(Request::get(uri).body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    let status = response.status();
+    let headers = response.headers().clone();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, headers, String::from_utf8(bytes.to_vec()).unwrap())
+}
+
+/// Value of the sample starting with `series` in a scrape, 0 if absent
+fn sample(scrape: &str, series: &str) -> f64 {
+    scrape
+        .lines()
+        .find_map(|line| line.strip_prefix(series)?.trim().parse().ok())
+        .unwrap_or(0.0)
+}
+
+#[tokio::test]
+async fn request_counter_increments_per_route() {
+    let router = app();
+    let series = r#"http_requests_total{method="GET",route="/api/v1/sync/last-pass",status="404"}"#;
+
+    let (_, _, before) = get(router.clone(), "/metrics").await;
+    // No sync pass has run, so this answers 404 without touching the database
+    for _ in 0..2 {
+        let (status, _, _) = get(router.clone(), "/api/v1/sync/last-pass").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    let (status, headers, after) = get(router, "/metrics").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(headers[header::CONTENT_TYPE], "text/plain; version=0.0.4");
+
+    assert_eq!(sample(&after, series) - sample(&before, series), 2.0, "{after}");
+    let buckets = r#"http_request_duration_seconds_bucket{method="GET",route="/api/v1/sync/last-pass""#;
+    assert!(after.contains(buckets), "{after}");
+}
+
+#[tokio::test]
+async fn unknown_paths_share_one_label() {
+    let router = app();
+    let (status, _, _) = get(router.clone(), "/no/such/path/1234").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let (_, _, scrape) = get(router, "/metrics").await;
+    assert!(!scrape.contains("/no/such/path"), "{scrape}");
+    assert!(scrape.contains(r#"route="unmatched""#), "{scrape}");
+}
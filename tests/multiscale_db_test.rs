@@ -0,0 +1,111 @@
+//! End-to-end tests for the multiscale readings endpoint against TimescaleDB.
+//!
+//! Run with: cargo test --test multiscale_db_test
+
+mod common;
+
+use axum::http::StatusCode;
+use chrono::{DateTime, Duration, Utc};
+use river_db::config::parse_resolutions;
+use river_db::routes::build_router;
+
+fn ts(t: DateTime<Utc>) -> String {
+    t.format("%Y-%m-%dT%H:%M:%SZ").to_string()
+}
+
+#[tokio::test]
+async fn daily_overview_with_raw_detail() {
+    let Some(test_db) = common::timescale().await else {
+        return;
+    };
+    let station = common::seed_station(&test_db.db, &[("MDepthmm", "Depth")]).await;
+    let start = DateTime::from_timestamp(1_735_689_600, 0).unwrap(); // 2025-01-01T00:00:00Z
+    let end = start + Duration::days(3);
+
+    // Hourly readings valued by day: 1.0, 2.0, 3.0
+    common::seed_readings(
+        &test_db.db,
+        station.sensor_ids[0],
+        start,
+        Duration::hours(1),
+        72,
+        |i| f64::from(i / 24 + 1),
+    )
+    .await;
+    common::refresh_aggregate(&test_db.db, "readings_daily", start, end).await;
+
+    let detail_start = start + Duration::days(1);
+    let detail_end = detail_start + Duration::hours(2);
+    let router = build_router(common::app_state(&test_db));
+    let uri = format!(
+        "/api/stations/{}/readings/multiscale?start={}&end={}&detail_start={}&detail_end={}",
+        station.id,
+        ts(start),
+        ts(end - Duration::seconds(1)),
+        ts(detail_start),
+        ts(detail_end),
+    );
+    let (status, body) = common::get_json(router, &uri).await;
+
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(body["overview"]["resolution"], "daily");
+    assert_eq!(body["overview"]["times"].as_array().unwrap().len(), 3);
+    assert_eq!(
+        body["overview"]["sensors"][0]["avg"],
+        serde_json::json!([1.0, 2.0, 3.0])
+    );
+
+    assert_eq!(
+        body["detail"]["times"],
+        serde_json::json!([
+            "2025-01-02T00:00:00Z",
+            "2025-01-02T01:00:00Z",
+            "2025-01-02T02:00:00Z"
+        ])
+    );
+    assert_eq!(
+        body["detail"]["sensors"][0]["values"],
+        serde_json::json!([2.0, 2.0, 2.0])
+    );
+}
+
+#[tokio::test]
+async fn detail_outside_range_is_rejected() {
+    let Some(test_db) = common::timescale().await else {
+        return;
+    };
+    let station = common::seed_station(&test_db.db, &[("MDepthmm", "Depth")]).await;
+
+    let router = build_router(common::app_state(&test_db));
+    let uri = format!(
+        "/api/stations/{}/readings/multiscale?start=2025-01-01T00:00:00Z&end=2025-01-10T00:00:00Z&detail_start=2025-01-09T00:00:00Z&detail_end=2025-01-11T00:00:00Z",
+        station.id
+    );
+    let (status, body) = common::get_json(router, &uri).await;
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"], "detail window must lie within start and end");
+}
+
+#[tokio::test]
+async fn disabled_daily_resolution_is_rejected() {
+    let Some(test_db) = common::timescale().await else {
+        return;
+    };
+    let station = common::seed_station(&test_db.db, &[("MDepthmm", "Depth")]).await;
+
+    let mut config = common::test_config(&test_db.url);
+    config.enabled_resolutions = parse_resolutions("hourly");
+    let router = build_router(common::app_state_with(test_db.db.clone(), config));
+    let uri = format!(
+        "/api/stations/{}/readings/multiscale?start=2025-01-01T00:00:00Z&end=2025-01-10T00:00:00Z&detail_start=2025-01-09T00:00:00Z&detail_end=2025-01-09T02:00:00Z",
+        station.id
+    );
+    let (status, body) = common::get_json(router, &uri).await;
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(
+        body["error"],
+        "Resolution daily is disabled. Enabled resolutions: hourly"
+    );
+}
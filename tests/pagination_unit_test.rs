@@ -0,0 +1,70 @@
+//! Unit tests for keyset and offset pagination.
+//!
+//! Run with: cargo test --test pagination_unit_test
+
+use chrono::{DateTime, Utc};
+use river_db::common::pagination;
+use river_db::error::AppError;
+
+/// Simulates `WHERE time > $after ORDER BY time LIMIT $limit + 1`
+fn fetch(data: &[DateTime<Utc>], after: Option<DateTime<Utc>>, limit: usize) -> Vec<DateTime<Utc>> {
+    data.iter()
+        .copied()
+        .filter(|t| after.is_none_or(|a| *t > a))
+        .take(limit + 1)
+        .collect()
+}
+
+#[test]
+fn keyset_walks_three_pages() {
+    let data: Vec<DateTime<Utc>> = (0..7)
+        .map(|i| DateTime::from_timestamp(i * 600, 0).unwrap())
+        .collect();
+
+    let mut pages = Vec::new();
+    let mut cursor = None;
+    loop {
+        let (rows, next) = pagination::keyset_page(fetch(&data, cursor, 3), 3, |t| *t);
+        pages.push(rows);
+        match next {
+            Some(c) => cursor = Some(c),
+            None => break,
+        }
+    }
+
+    assert_eq!(pages.len(), 3);
+    assert_eq!(pages[0], data[0..3]);
+    assert_eq!(pages[1], data[3..6]);
+    assert_eq!(pages[2], data[6..7]);
+}
+
+#[test]
+fn exact_page_has_no_cursor() {
+    let data: Vec<DateTime<Utc>> = (0..3)
+        .map(|i| DateTime::from_timestamp(i * 600, 0).unwrap())
+        .collect();
+
+    let (rows, next) = pagination::keyset_page(fetch(&data, None, 3), 3, |t| *t);
+    assert_eq!(rows.len(), 3);
+    assert!(next.is_none());
+}
+
+#[test]
+fn page_offset_is_computed_without_overflow() {
+    assert_eq!(pagination::page_offset(1, 100).unwrap(), 0);
+    assert_eq!(pagination::page_offset(3, 100).unwrap(), 200);
+    assert_eq!(
+        pagination::page_offset(pagination::MAX_PAGE, 1000).unwrap(),
+        (pagination::MAX_PAGE as u64 - 1) * 1000
+    );
+}
+
+#[test]
+fn page_offset_rejects_out_of_range_pages() {
+    for page in [i32::MAX, 0, -1, i32::MIN] {
+        assert!(matches!(
+            pagination::page_offset(page, 1000),
+            Err(AppError::BadRequest(_))
+        ));
+    }
+}
@@ -0,0 +1,39 @@
+//! Tests for the raw readings point budget.
+//!
+//! Run with: cargo test --test point_budget_db_test
+use tower::ServiceExt;
+
+mod common;
+
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use river_db::routes::build_router;
+
+#[tokio::test]
+async fn requests_over_budget_are_rejected() {
+    let Some(test_db) = common::timescale().await else {
+        return;
+    };
+    let station = common::seed_station(
+        &test_db.db,
+        &[("MDepthmm", "Depth"), ("MTurbNTU", "Turbidity")],
+    ).await;
+
+    // Two sensors at the 10-minute default: 288 points per day
+    let mut config = common::test_config(&test_db.url);
+    config.readings_point_budget = 300;
+    let router = build_router(common::app_state_with(test_db.db.clone(), config));
+
+    for (range, expected) in [
+        ("start=2025-01-01T00:00:00Z&end=2025-01-02T00:00:00Z", StatusCode::OK),
+        ("start=2025-01-01T00:00:00Z&end=2025-01-03T00:00:00Z", StatusCode::BAD_REQUEST),
+    ] {
+        let uri = format!("/api/v1/stations/{}/readings?{range}", station.id);
+        let response = router
+            .clone()
+            .oneshot(Request::get(&uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), expected, "{range}");
+    }
+}
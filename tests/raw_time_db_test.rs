@@ -0,0 +1,92 @@
+//! Tests that synced readings keep their unrounded sample time and that
+//! `with_raw_time=true` returns it.
+//!
+//! Run with: cargo test --test raw_time_db_test
+
+mod common;
+
+use axum::{routing::get, Json, Router};
+use chrono::{DateTime, Utc};
+use river_db::routes::build_router;
+use river_db::sync::sanity::SanityCheck;
+use river_db::sync::worker;
+use river_db::vaisala::VaisalaClient;
+use sea_orm::{ConnectionTrait, DatabaseBackend, Statement};
+
+#[tokio::test]
+async fn sync_records_raw_time_and_readings_return_it() {
+    let Some(test_db) = common::timescale().await else {
+        return;
+    };
+    let station = common::seed_station(&test_db.db, &[("MDepthmm", "Depth")]).await;
+    let sensor = station.sensor_ids[0];
+
+    // Both samples are off the 10-minute grid the sensor is aligned to
+    let t0 = 1_735_689_600; // 2025-01-01T00:00:00Z
+    let body = common::locations_history_body(
+        station.location_ids[0],
+        &[(t0 + 123, 1.0, true), (t0 + 590, 2.0, true)],
+    );
+    let base_url = common::mock_vaisala(Router::new().route(
+        "/locations_history",
+        get(move || {
+            let body = body.clone();
+            async move { Json(body) }
+        }),
+    ))
+    .await;
+
+    let mut config = common::test_config(&test_db.url);
+    config.vaisala_base_url = base_url;
+    let vaisala = VaisalaClient::new(&config);
+    let sanity = SanityCheck::from_config(&config);
+    worker::sync_readings(&test_db.db, &vaisala, 90, false, false, &sanity)
+        .await
+        .unwrap();
+
+    let rows = test_db
+        .db
+        .query_all(Statement::from_sql_and_values(
+            DatabaseBackend::Postgres,
+            "SELECT time, raw_time FROM readings WHERE sensor_id = $1 ORDER BY time",
+            [sensor.into()],
+        ))
+        .await
+        .unwrap();
+    let times: Vec<(i64, i64)> = rows
+        .iter()
+        .map(|row| {
+            let time: DateTime<Utc> = row.try_get("", "time").unwrap();
+            let raw: DateTime<Utc> = row.try_get("", "raw_time").unwrap();
+            (time.timestamp(), raw.timestamp())
+        })
+        .collect();
+    assert_eq!(times, vec![(t0, t0 + 123), (t0 + 600, t0 + 590)]);
+
+    let router = build_router(common::app_state_with(test_db.db.clone(), config));
+    let range = "start=2025-01-01T00:00:00Z&end=2025-01-01T01:00:00Z";
+
+    let long = common::get_ok_json(
+        &router,
+        &format!("/api/v1/sensors/{sensor}/readings?{range}&with_raw_time=true"),
+    )
+    .await;
+    assert_eq!(long["readings"][0]["time"], "2025-01-01T00:00:00Z");
+    assert_eq!(long["readings"][0]["raw_time"], "2025-01-01T00:02:03Z");
+
+    let wide = common::get_ok_json(
+        &router,
+        &format!(
+            "/api/v1/stations/{}/readings?{range}&with_raw_time=true&time_format=epoch",
+            station.id
+        ),
+    )
+    .await;
+    assert_eq!(wide["times"], serde_json::json!([t0, t0 + 600]));
+    assert_eq!(wide["sensors"][0]["raw_times"], serde_json::json!([t0 + 123, t0 + 590]));
+
+    // Off by default
+    let plain =
+        common::get_ok_json(&router, &format!("/api/v1/sensors/{sensor}/readings?{range}")).await;
+    assert!(plain["readings"][0].get("raw_time").is_none());
+}
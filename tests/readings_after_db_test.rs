@@ -0,0 +1,56 @@
+//! Tests for incremental polling of station readings with `after`.
+//!
+//! Run with: cargo test --test readings_after_db_test
+
+mod common;
+
+use axum::http::StatusCode;
+use chrono::{DateTime, Duration};
+use river_db::routes::build_router;
+use serde_json::json;
+
+#[tokio::test]
+async fn after_returns_only_newer_readings() {
+    let Some(test_db) = common::timescale().await else {
+        return;
+    };
+    let station = common::seed_station(
+        &test_db.db,
+        &[("MDepthmm", "Depth"), ("MTurbNTU", "Turbidity")],
+    )
+    .await;
+    let t0 = 1_735_689_600; // 2025-01-01T00:00:00Z
+    let start = DateTime::from_timestamp(t0, 0).unwrap();
+    for sensor in &station.sensor_ids {
+        common::seed_readings(&test_db.db, *sensor, start, Duration::minutes(10), 6, f64::from)
+            .await;
+    }
+
+    let router = build_router(common::app_state(&test_db));
+    let base = format!("/api/v1/stations/{}/readings?time_format=epoch", station.id);
+
+    // The cursor is exclusive: the reading at 00:20 was already seen
+    let (status, body) =
+        common::get_json(router.clone(), &format!("{base}&after={}", t0 + 1200)).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(body["times"], json!([t0 + 1800, t0 + 2400, t0 + 3000]));
+    for sensor in body["sensors"].as_array().unwrap() {
+        assert_eq!(sensor["values"], json!([3.0, 4.0, 5.0]));
+    }
+
+    // Same delta with an ISO cursor, combined with an end bound
+    let uri = format!("{base}&after=2025-01-01T00:20:00Z&end=2025-01-01T00:40:00Z");
+    let (_, body) = common::get_json(router.clone(), &uri).await;
+    assert_eq!(body["times"], json!([t0 + 1800, t0 + 2400]));
+
+    // Nothing newer than the last reading
+    let (status, body) =
+        common::get_json(router.clone(), &format!("{base}&after={}", t0 + 3000)).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(body["times"], json!([]));
+
+    // A cursor after the end bound is a reversed range
+    let uri = format!("{base}&after={}&end=2025-01-01T00:10:00Z", t0 + 1200);
+    let (status, _) = common::get_json(router, &uri).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
@@ -0,0 +1,55 @@
+//! Tests for the startup refresh of empty continuous aggregates.
+//!
+//! Run with: cargo test --test refresh_aggregates_db_test
+
+mod common;
+
+use chrono::{DateTime, Duration};
+use river_db::services::timescale;
+use sea_orm::{ConnectionTrait, DatabaseBackend, DatabaseConnection, Statement};
+use uuid::Uuid;
+
+/// Rows in `view`, optionally only those of one sensor
+async fn count_rows(db: &DatabaseConnection, view: &str, sensor_id: Option<Uuid>) -> i64 {
+    let filter = sensor_id.map_or(String::new(), |id| format!(" WHERE sensor_id = '{id}'"));
+    db.query_one(Statement::from_string(
+        DatabaseBackend::Postgres,
+        format!("SELECT COUNT(*) AS n FROM {view}{filter}"),
+    ))
+    .await
+    .unwrap()
+    .unwrap()
+    .try_get("", "n")
+    .unwrap()
+}
+
+#[tokio::test]
+async fn empty_aggregates_are_filled_from_readings() {
+    let Some(test_db) = common::timescale().await else {
+        return;
+    };
+    // Only meaningful on a database whose aggregates were never refreshed
+    for view in ["readings_hourly", "readings_daily"] {
+        if count_rows(&test_db.db, view, None).await > 0 {
+            return;
+        }
+    }
+
+    let station = common::seed_station(&test_db.db, &[("MDepthmm", "Depth")]).await;
+    let start = DateTime::from_timestamp(1_735_689_600, 0).unwrap(); // 2025-01-01T00:00:00Z
+    common::seed_readings(
+        &test_db.db,
+        station.sensor_ids[0],
+        start,
+        Duration::hours(1),
+        48,
+        |_| 1.0,
+    )
+    .await;
+
+    timescale::refresh_empty_aggregates(test_db.db.clone()).await;
+
+    let sensor_id = Some(station.sensor_ids[0]);
+    assert_eq!(count_rows(&test_db.db, "readings_daily", sensor_id).await, 2);
+    assert_eq!(count_rows(&test_db.db, "readings_hourly", sensor_id).await, 48);
+}
@@ -0,0 +1,149 @@
+//! Tests for linking alarms and events synced before their sensor was known.
+//!
+//! Run with: cargo test --test relink_db_test
+
+mod common;
+
+use axum::body::Body;
+use axum::http::{header, Request, StatusCode};
+use chrono::DateTime;
+use river_db::entity::{alarm_locations, alarms, events};
+use river_db::routes::build_router;
+use river_db::sync::relink;
+use sea_orm::{ColumnTrait, EntityTrait, PaginatorTrait, QueryFilter, Set};
+use serde_json::{json, Value};
+use tower::ServiceExt;
+use uuid::Uuid;
+
+#[tokio::test]
+async fn orphaned_alarms_and_events_are_relinked() {
+    let Some(test_db) = common::timescale().await else {
+        return;
+    };
+    let db = &test_db.db;
+    let station = common::seed_station(db, &[("MDepthmm", "Depth")]).await;
+    let (sensor_id, location_id) = (station.sensor_ids[0], station.location_ids[0]);
+    let time = DateTime::from_timestamp(1_735_689_600, 0).unwrap(); // 2025-01-01T00:00:00Z
+    let seed = i32::from_str_radix(&Uuid::new_v4().simple().to_string()[..6], 16).unwrap();
+
+    // As stored by a sync that ran before the sensor was discovered; the
+    // second location is still unknown
+    let alarm_id = Uuid::new_v4();
+    let alarm = alarms::ActiveModel {
+        id: Set(alarm_id),
+        vaisala_alarm_id: Set(seed),
+        severity: Set(2),
+        description: Set("Depth high".to_string()),
+        error_text: Set(None),
+        alarm_type: Set(None),
+        when_on: Set(time.into()),
+        when_off: Set(None),
+        when_ack: Set(None),
+        when_condition: Set(None),
+        duration_sec: Set(None),
+        status: Set(false),
+        is_system: Set(false),
+        serial_number: Set(None),
+        location_text: Set(None),
+        zone_text: Set(None),
+        station_id: Set(None),
+        ack_required: Set(false),
+        ack_comments: Set(None),
+        ack_action_taken: Set(None),
+        location_ids: Set(Some(json!([location_id, -seed]))),
+        created_at: Set(None),
+        updated_at: Set(None),
+    };
+    // Synced before location IDs were stored
+    let legacy_alarm = alarms::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        vaisala_alarm_id: Set(seed + 1),
+        location_ids: Set(None),
+        ..alarm.clone()
+    };
+    alarms::Entity::insert_many([alarm, legacy_alarm])
+        .exec_without_returning(db)
+        .await
+        .unwrap();
+    let event = events::ActiveModel {
+        time: Set(time.into()),
+        vaisala_event_num: Set(seed),
+        category: Set("alarm".to_string()),
+        message: Set("Depth high".to_string()),
+        user_name: Set(None),
+        entity: Set(None),
+        entity_id: Set(None),
+        sensor_id: Set(None),
+        station_id: Set(None),
+        device_id: Set(None),
+        channel_id: Set(None),
+        host_id: Set(None),
+        location_id: Set(Some(location_id)),
+        affected_location_ids: Set(None),
+        extra_fields: Set(None),
+    };
+    let legacy_event = events::ActiveModel {
+        vaisala_event_num: Set(seed + 1),
+        location_id: Set(None),
+        ..event.clone()
+    };
+    events::Entity::insert_many([event, legacy_event])
+        .exec_without_returning(db)
+        .await
+        .unwrap();
+
+    let mut config = common::test_config(&test_db.url);
+    config.admin_token = Some("secret".to_string());
+    let router = build_router(common::app_state_with(db.clone(), config));
+    let response = router
+        .oneshot(
+            Request::post("/api/v1/admin/relink")
+                .header(header::AUTHORIZATION, "Bearer secret")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let counts: Value = serde_json::from_slice(&bytes).unwrap();
+    assert!(counts["alarms"].as_u64().unwrap() >= 1, "{counts}");
+    assert!(counts["alarm_locations"].as_u64().unwrap() >= 1, "{counts}");
+    assert!(counts["events"].as_u64().unwrap() >= 1, "{counts}");
+    // The legacy rows are reported rather than silently skipped
+    assert!(counts["alarms_without_locations"].as_u64().unwrap() >= 1, "{counts}");
+    assert!(counts["events_without_location"].as_u64().unwrap() >= 1, "{counts}");
+
+    let alarm = alarms::Entity::find_by_id(alarm_id).one(db).await.unwrap().unwrap();
+    assert_eq!(alarm.station_id, Some(station.id));
+    let links = || {
+        alarm_locations::Entity::find()
+            .filter(alarm_locations::Column::AlarmId.eq(alarm_id))
+            .all(db)
+    };
+    let linked = links().await.unwrap();
+    assert_eq!(linked.len(), 1);
+    assert_eq!(linked[0].sensor_id, sensor_id);
+
+    let event = events::Entity::find()
+        .filter(events::Column::VaisalaEventNum.eq(seed))
+        .one(db)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(event.sensor_id, Some(sensor_id));
+    assert_eq!(event.station_id, Some(station.id));
+
+    // Repeating finds nothing new for these records
+    relink::relink_orphans(db).await.unwrap();
+    assert_eq!(links().await.unwrap().len(), 1);
+    let unlinked = events::Entity::find()
+        .filter(events::Column::VaisalaEventNum.eq(seed))
+        .filter(events::Column::SensorId.is_null())
+        .count(db)
+        .await
+        .unwrap();
+    assert_eq!(unlinked, 0);
+}
@@ -0,0 +1,37 @@
+//! Database-backed tests for read replica routing.
+//!
+//! Run with: cargo test --test replica_db_test
+
+mod common;
+
+use axum::extract::{Query, State};
+use river_db::common::envelope::EnvelopeQuery;
+use river_db::routes::zones;
+use sea_orm::DatabaseConnection;
+
+#[tokio::test]
+async fn handlers_read_from_replica_when_configured() {
+    let Some(test_db) = common::timescale().await else {
+        return;
+    };
+    let config = common::test_config(&test_db.url);
+
+    // The primary is unusable, so any successful query must have used the replica
+    let state = common::app_state_with(DatabaseConnection::Disconnected, config)
+        .with_read_replica(test_db.db.clone());
+
+    assert!(
+        zones::list_zones(State(state.clone()), Query(EnvelopeQuery::default()))
+            .await
+            .is_ok()
+    );
+
+    // Without a replica, reads go to the (disconnected) primary and fail
+    let primary_only =
+        common::app_state_with(DatabaseConnection::Disconnected, (*state.config).clone());
+    assert!(
+        zones::list_zones(State(primary_only), Query(EnvelopeQuery::default()))
+            .await
+            .is_err()
+    );
+}
@@ -0,0 +1,46 @@
+//! Database-backed tests for route resolution helpers.
+//!
+//! Run with: cargo test --test resolve_db_test
+
+mod common;
+
+use river_db::error::AppError;
+use river_db::routes::resolve_station_sensor;
+use sea_orm::DatabaseConnection;
+use uuid::Uuid;
+
+/// Seed a station with one sensor, returning (station ID, sensor ID).
+async fn seed_station(db: &DatabaseConnection) -> (Uuid, Uuid) {
+    let station = common::seed_station(db, &[("MDepthmm", "Depth")]).await;
+    (station.id, station.sensor_ids[0])
+}
+
+#[tokio::test]
+async fn station_sensor_resolves_by_uuid_and_name() {
+    let Some(test_db) = common::timescale().await else {
+        return;
+    };
+    let db = test_db.db;
+    let (station_id, sensor_id) = seed_station(&db).await;
+
+    let by_id = resolve_station_sensor(&db, station_id, &sensor_id.to_string())
+        .await
+        .unwrap();
+    assert_eq!(by_id.id, sensor_id);
+
+    let by_name = resolve_station_sensor(&db, station_id, "mdepthMM").await.unwrap();
+    assert_eq!(by_name.id, sensor_id);
+}
+
+#[tokio::test]
+async fn sensor_from_another_station_is_not_found() {
+    let Some(test_db) = common::timescale().await else {
+        return;
+    };
+    let db = test_db.db;
+    let (station_a, _) = seed_station(&db).await;
+    let (_, sensor_b) = seed_station(&db).await;
+
+    let result = resolve_station_sensor(&db, station_a, &sensor_b.to_string()).await;
+    assert!(matches!(result, Err(AppError::NotFound { resource: "sensor", .. })));
+}
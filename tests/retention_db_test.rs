@@ -0,0 +1,78 @@
+//! Tests for the readings retention admin endpoints against TimescaleDB.
+//!
+//! Run with: cargo test --test retention_db_test
+
+mod common;
+
+use axum::body::Body;
+use axum::http::{header, Method, Request, StatusCode};
+use river_db::routes::build_router;
+use serde_json::{json, Value};
+use tower::ServiceExt;
+
+async fn send(router: axum::Router, method: Method, uri: &str, body: Option<Value>) -> (StatusCode, Value) {
+    let request = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header(header::AUTHORIZATION, "Bearer secret")
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(body.map_or_else(Body::empty, |b| Body::from(b.to_string())))
+        .unwrap();
+    let response = router.oneshot(request).await.unwrap();
+    let status = response.status();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let body = if bytes.is_empty() {
+        Value::Null
+    } else {
+        serde_json::from_slice(&bytes).unwrap()
+    };
+    (status, body)
+}
+
+#[tokio::test]
+async fn retention_policy_round_trip() {
+    let Some(test_db) = common::timescale().await else {
+        return;
+    };
+    let mut config = common::test_config(&test_db.url);
+    config.admin_token = Some("secret".to_string());
+    let router = build_router(common::app_state_with(test_db.db.clone(), config));
+    let uri = "/api/v1/admin/retention";
+
+    // Far beyond any seeded data, so other tests sharing the database are unaffected
+    let (status, body) = send(
+        router.clone(),
+        Method::POST,
+        uri,
+        Some(json!({"older_than": "100 years", "confirm": true})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert!(body["job_id"].is_i64());
+    let job_id = body["job_id"].clone();
+
+    let (status, body) = send(router.clone(), Method::GET, uri, None).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(body["job_id"], job_id);
+    assert!(body["drop_after"].as_str().unwrap().contains("100"));
+
+    let (status, body) = send(
+        router.clone(),
+        Method::POST,
+        "/api/v1/admin/retention/drop",
+        Some(json!({"older_than": "100 years", "confirm": true})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(body["dropped_chunks"], json!([]));
+
+    let (status, _) = send(router.clone(), Method::DELETE, uri, None).await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    let (status, body) = send(router.clone(), Method::GET, uri, None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(body.is_null());
+    let (status, _) = send(router, Method::DELETE, uri, None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
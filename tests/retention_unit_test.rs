@@ -0,0 +1,92 @@
+//! Unit tests for retention interval validation and the confirmation guard.
+//!
+//! Run with: cargo test --test retention_unit_test
+
+mod common;
+
+use axum::{
+    body::Body,
+    http::{header, Request, StatusCode},
+};
+use river_db::routes::build_router;
+use river_db::services::retention::{self, RetentionInterval};
+use sea_orm::DatabaseConnection;
+use serde_json::{json, Value};
+use tower::ServiceExt;
+
+#[test]
+fn intervals_are_parsed_strictly() {
+    for (input, expected, days) in [
+        ("400 days", "400 days", 400),
+        ("1 year", "1 year", 365),
+        ("2 YEARS", "2 years", 730),
+        ("18 months", "18 months", 540),
+        ("  30   weeks ", "30 weeks", 210),
+    ] {
+        let interval = RetentionInterval::parse(input).unwrap();
+        assert_eq!(interval.to_string(), expected, "{input:?}");
+        assert_eq!(interval.days(), days, "{input:?}");
+    }
+
+    for input in [
+        "",
+        "400",
+        "days",
+        "0 days",
+        "-5 days",
+        "+5 days",
+        "1.5 years",
+        "5 fortnights",
+        "400 days'; DROP TABLE readings; --",
+        "1 year 2 months",
+    ] {
+        assert!(RetentionInterval::parse(input).is_err(), "{input:?}");
+    }
+}
+
+#[test]
+fn short_retention_is_rejected() {
+    assert!(retention::validate("124 days", 90).is_err());
+    assert!(retention::validate("125 days", 90).is_ok());
+    // The full re-sync window also bounds the minimum
+    assert!(retention::validate("200 days", 365).is_err());
+    assert!(retention::validate("2 years", 365).is_ok());
+}
+
+/// Router without a database; validation runs before any query
+fn app() -> axum::Router {
+    let mut config = common::test_config("postgresql://unused");
+    config.admin_token = Some("secret".to_string());
+    build_router(common::app_state_with(DatabaseConnection::Disconnected, config))
+}
+
+async fn post_json(uri: &str, body: Value) -> (StatusCode, Value) {
+    let response = app()
+        .oneshot(
+            Request::post(uri)
+                .header(header::AUTHORIZATION, "Bearer secret")
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(body.to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let status = response.status();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, serde_json::from_slice(&bytes).unwrap())
+}
+
+#[tokio::test]
+async fn destructive_requests_need_confirmation() {
+    for uri in ["/api/v1/admin/retention", "/api/v1/admin/retention/drop"] {
+        let (status, body) = post_json(uri, json!({"older_than": "2 years"})).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{uri}");
+        assert!(body["error"].as_str().unwrap().contains("confirm"), "{uri}: {body}");
+
+        let (status, body) = post_json(uri, json!({"older_than": "30 days", "confirm": true})).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{uri}");
+        assert!(body["error"].as_str().unwrap().contains("too short"), "{uri}: {body}");
+    }
+}
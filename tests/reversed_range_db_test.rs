@@ -0,0 +1,45 @@
+//! Tests that every time-ranged endpoint rejects an end before its start.
+//!
+//! Run with: cargo test --test reversed_range_db_test
+
+mod common;
+
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use river_db::routes::build_router;
+use serde_json::Value;
+use tower::ServiceExt;
+
+#[tokio::test]
+async fn reversed_ranges_are_rejected() {
+    let Some(test_db) = common::timescale().await else {
+        return;
+    };
+    let station = common::seed_station(&test_db.db, &[("MDepthmm", "Depth")]).await;
+    let sensor = station.sensor_ids[0];
+    let router = build_router(common::app_state(&test_db));
+
+    let range = "start=2025-01-02T00:00:00Z&end=2025-01-01T00:00:00Z";
+    let uris = [
+        format!("/api/v1/stations/{}/readings?{range}", station.id),
+        format!("/api/v1/stations/{}/aggregates/hourly?{range}", station.id),
+        format!("/api/v1/sensors/{sensor}/readings?{range}"),
+        format!("/api/v1/alarms?{range}"),
+        format!("/api/v1/events?{range}"),
+        format!("/api/v1/sensors/{sensor}/events?{range}"),
+    ];
+
+    for uri in uris {
+        let response = router
+            .clone()
+            .oneshot(Request::get(&uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST, "{uri}");
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: Value = serde_json::from_slice(&bytes).unwrap();
+        assert!(body.to_string().contains("end time must be after start time"), "{uri}: {body}");
+    }
+}
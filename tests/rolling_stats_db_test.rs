@@ -0,0 +1,74 @@
+//! End-to-end tests for the rolling stats endpoint against TimescaleDB.
+//!
+//! Run with: cargo test --test rolling_stats_db_test
+
+mod common;
+
+use axum::http::StatusCode;
+use chrono::{DateTime, Duration};
+use river_db::routes::build_router;
+use serde_json::Value;
+
+#[tokio::test]
+async fn deltas_against_earlier_readings() {
+    let Some(test_db) = common::timescale().await else {
+        return;
+    };
+    let station = common::seed_station(&test_db.db, &[("MDepthmm", "Depth")]).await;
+    let start = DateTime::from_timestamp(1_735_689_600, 0).unwrap(); // 2025-01-01T00:00:00Z
+
+    // Two days of hourly readings rising by 0.5 per hour: 25 hours back is
+    // covered, 7 days back is not
+    common::seed_readings(
+        &test_db.db,
+        station.sensor_ids[0],
+        start,
+        Duration::hours(1),
+        49,
+        |i| 88.0 + f64::from(i) * 0.5,
+    )
+    .await;
+
+    let router = build_router(common::app_state(&test_db));
+    let uri = format!("/api/sensors/{}/stats/rolling", station.sensor_ids[0]);
+    let (status, body) = common::get_json(router, &uri).await;
+
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(body["latest"]["value"], 112.0);
+    assert_eq!(body["latest"]["time"], "2025-01-03T00:00:00Z");
+
+    let changes = body["changes"].as_array().unwrap();
+    assert_eq!(changes[0]["period"], "1h");
+    assert_eq!(changes[0]["reference_value"], 111.5);
+    assert_eq!(changes[0]["delta"], 0.5);
+
+    assert_eq!(changes[1]["period"], "24h");
+    assert_eq!(changes[1]["reference_time"], "2025-01-02T00:00:00Z");
+    assert_eq!(changes[1]["delta"], 12.0);
+    let percent = changes[1]["percent_change"].as_f64().unwrap();
+    assert!((percent - 12.0).abs() < 1e-9, "{percent}");
+
+    assert_eq!(changes[2]["period"], "7d");
+    assert_eq!(changes[2]["reference_value"], Value::Null);
+    assert_eq!(changes[2]["delta"], Value::Null);
+}
+
+#[tokio::test]
+async fn sensor_without_data_has_null_stats() {
+    let Some(test_db) = common::timescale().await else {
+        return;
+    };
+    let station = common::seed_station(&test_db.db, &[("MDepthmm", "Depth")]).await;
+
+    let router = build_router(common::app_state(&test_db));
+    let uri = format!("/api/sensors/{}/stats/rolling", station.sensor_ids[0]);
+    let (status, body) = common::get_json(router, &uri).await;
+
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(body["latest"], Value::Null);
+    assert!(body["changes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .all(|c| c["delta"].is_null()));
+}
@@ -0,0 +1,85 @@
+//! Unit tests for rounding JSON values to sensor decimal places.
+//!
+//! Run with: cargo test --test round_unit_test
+
+use river_db::common::round::{average_decimal_places, format_decimal, round_to, round_values};
+
+#[test]
+fn zero_decimal_places_rounds_to_integers() {
+    assert_eq!(round_to(2.5, Some(0)), 3.0);
+    assert_eq!(round_to(2.4, Some(0)), 2.0);
+    assert_eq!(round_to(-2.5, Some(0)), -3.0);
+    assert_eq!(round_to(-2.4, Some(0)), -2.0);
+}
+
+#[test]
+fn two_decimal_places() {
+    assert_eq!(round_to(12.34567, Some(2)), 12.35);
+    assert_eq!(round_to(0.1 + 0.2, Some(2)), 0.3);
+    assert_eq!(round_to(-5.6789, Some(2)), -5.68);
+    assert_eq!(round_to(12.0, Some(2)), 12.0);
+}
+
+#[test]
+fn null_decimal_places_leaves_values_exact() {
+    assert_eq!(round_to(12.34567, None), 12.34567);
+
+    let mut values = vec![Some(0.1 + 0.2), None, Some(-1.23456)];
+    round_values(&mut values, None);
+    assert_eq!(values, vec![Some(0.1 + 0.2), None, Some(-1.23456)]);
+}
+
+#[test]
+fn negative_values_rounding_to_zero_are_positive_zero() {
+    let rounded = round_to(-0.004, Some(2));
+    assert_eq!(rounded, 0.0);
+    assert!(rounded.is_sign_positive());
+    assert_eq!(serde_json::to_string(&rounded).unwrap(), "0.0");
+}
+
+#[test]
+fn negative_decimal_places_treated_as_zero() {
+    assert_eq!(round_to(17.6, Some(-1)), 18.0);
+}
+
+#[test]
+fn round_values_skips_missing_entries() {
+    let mut values = vec![Some(1.006), None, Some(-4.444)];
+    round_values(&mut values, Some(2));
+    assert_eq!(values, vec![Some(1.01), None, Some(-4.44)]);
+}
+
+#[test]
+fn extreme_magnitudes_are_formatted_without_exponent() {
+    for value in [1e-7, -3.2e-12, 5e-324, 1e21, -7.5e300, f64::MAX] {
+        let formatted = format_decimal(value, None);
+        assert!(!formatted.contains(['e', 'E']), "{formatted}");
+        assert_eq!(formatted.parse::<f64>().unwrap(), value);
+    }
+    assert_eq!(format_decimal(1e-7, None), "0.0000001");
+    assert_eq!(format_decimal(1e21, None), "1000000000000000000000");
+}
+
+#[test]
+fn decimal_places_fix_the_number_of_places() {
+    assert_eq!(format_decimal(12.34567, Some(2)), "12.35");
+    assert_eq!(format_decimal(12.0, Some(2)), "12.00");
+    assert_eq!(format_decimal(1e-7, Some(3)), "0.000");
+    assert_eq!(format_decimal(-1e-7, Some(3)), "0.000");
+    assert_eq!(format_decimal(2.5e20, Some(1)), "250000000000000000000.0");
+    assert_eq!(format_decimal(7.25, Some(-1)), "7");
+}
+
+#[test]
+fn non_finite_values_are_empty() {
+    assert_eq!(format_decimal(f64::NAN, None), "");
+    assert_eq!(format_decimal(f64::INFINITY, Some(2)), "");
+}
+
+#[test]
+fn averages_get_one_extra_place() {
+    assert_eq!(average_decimal_places(Some(1)), Some(2));
+    assert_eq!(average_decimal_places(Some(0)), Some(1));
+    assert_eq!(average_decimal_places(Some(-2)), Some(1));
+    assert_eq!(average_decimal_places(None), None);
+}
@@ -0,0 +1,58 @@
+//! Unit tests for the effective sensor sample interval and the readings
+//! size estimate built on it.
+//!
+//! Run with: cargo test --test sample_interval_unit_test
+
+use chrono::{DateTime, Duration};
+use river_db::entity::sensors;
+use river_db::routes::stations::estimated_points;
+use uuid::Uuid;
+
+fn sensor(sample_interval_sec: Option<i32>) -> sensors::Model {
+    sensors::Model {
+        id: Uuid::nil(),
+        station_id: Uuid::nil(),
+        vaisala_location_id: 1,
+        name: "MDepthmm".to_string(),
+        sensor_type: "Depth".to_string(),
+        display_units: None,
+        units_name: None,
+        units_min: None,
+        units_max: None,
+        decimal_places: None,
+        device_serial_number: None,
+        probe_serial_number: None,
+        channel_id: None,
+        sample_interval_sec,
+        is_active: Some(true),
+        align_timestamps: true,
+        alarming_paused: false,
+        created_at: None,
+        updated_at: None,
+        discovered_at: None,
+    }
+}
+
+#[test]
+fn reported_interval_is_used() {
+    assert_eq!(sensor(Some(300)).effective_interval(600), 300);
+}
+
+#[test]
+fn missing_interval_falls_back_to_configured_default() {
+    assert_eq!(sensor(None).effective_interval(600), 600);
+    assert_eq!(sensor(None).effective_interval(900), 900);
+    // Non-positive values are treated as unknown
+    assert_eq!(sensor(Some(0)).effective_interval(900), 900);
+}
+
+#[test]
+fn point_estimate_sums_range_over_each_interval() {
+    let start = DateTime::from_timestamp(1_735_689_600, 0).unwrap(); // 2025-01-01T00:00:00Z
+    let end = start + Duration::days(1);
+    let sensors = [sensor(Some(300)), sensor(None)];
+
+    // 288 five-minute points plus 144 at the 10-minute default
+    assert_eq!(estimated_points(&sensors, start, end, 600), 432);
+    assert_eq!(estimated_points(&sensors, end, start, 600), 0);
+}
@@ -0,0 +1,71 @@
+//! End-to-end test of the readings sanity range: a mocked Vaisala API feeds a
+//! sentinel value through `sync_readings` into TimescaleDB.
+//!
+//! Run with: cargo test --test sanity_db_test
+
+mod common;
+
+use axum::{routing::get, Json, Router};
+use chrono::{DateTime, Duration};
+use river_db::sync::sanity::SanityCheck;
+use river_db::sync::worker;
+use river_db::vaisala::VaisalaClient;
+use sea_orm::{ConnectionTrait, DatabaseBackend, Statement};
+
+#[tokio::test]
+async fn sentinel_value_excluded_from_hourly_average() {
+    let Some(test_db) = common::timescale().await else {
+        return;
+    };
+    let station = common::seed_station(&test_db.db, &[("MDepthmm", "Depth")]).await;
+
+    // Six 10-minute samples in one hour, two of them viewLinc sentinels: one
+    // logged, one realtime
+    let start = DateTime::from_timestamp(1_735_689_600, 0).unwrap(); // 2025-01-01T00:00:00Z
+    let t0 = start.timestamp();
+    let points = [
+        (t0, 10.0, true),
+        (t0 + 600, 12.0, true),
+        (t0 + 1200, 1e38, true),
+        (t0 + 1800, 14.0, true),
+        (t0 + 2400, -1e38, false),
+        (t0 + 3000, 18.0, true),
+    ];
+    let body = common::locations_history_body(station.location_ids[0], &points);
+    let base_url = common::mock_vaisala(Router::new().route(
+        "/locations_history",
+        get(move || {
+            let body = body.clone();
+            async move { Json(body) }
+        }),
+    ))
+    .await;
+
+    let mut config = common::test_config(&test_db.url);
+    config.vaisala_base_url = base_url;
+    let vaisala = VaisalaClient::new(&config);
+    let sanity = SanityCheck::from_config(&config);
+
+    let pass = worker::sync_readings(&test_db.db, &vaisala, 90, false, false, &sanity)
+        .await
+        .unwrap();
+    assert_eq!(pass.points_inserted, 4);
+
+    common::refresh_aggregate(&test_db.db, "readings_hourly", start, start + Duration::hours(1))
+        .await;
+
+    let row = test_db
+        .db
+        .query_one(Statement::from_sql_and_values(
+            DatabaseBackend::Postgres,
+            "SELECT avg_value, max_value, count FROM readings_hourly WHERE sensor_id = $1",
+            [station.sensor_ids[0].into()],
+        ))
+        .await
+        .unwrap()
+        .expect("hourly bucket");
+
+    assert_eq!(row.try_get::<f64>("", "avg_value").unwrap(), 13.5);
+    assert_eq!(row.try_get::<f64>("", "max_value").unwrap(), 18.0);
+    assert_eq!(row.try_get::<i64>("", "count").unwrap(), 4);
+}
@@ -0,0 +1,86 @@
+//! Unit tests for the readings sanity range.
+//!
+//! Run with: cargo test --test sanity_unit_test
+
+use river_db::entity::sensors;
+use river_db::sync::sanity::{screen, SanityCheck, SanityRange};
+use river_db::vaisala::models::DataPoint;
+use uuid::Uuid;
+
+const GLOBAL: SanityRange = SanityRange { min: -1e30, max: 1e30 };
+
+fn point(timestamp: i64, value: f64) -> DataPoint {
+    DataPoint {
+        timestamp,
+        value,
+        logged: true,
+    }
+}
+
+fn sensor(units_min: Option<f64>, units_max: Option<f64>) -> sensors::Model {
+    sensors::Model {
+        id: Uuid::new_v4(),
+        station_id: Uuid::new_v4(),
+        vaisala_location_id: 1,
+        name: "MDepthmm".to_string(),
+        sensor_type: "Depth".to_string(),
+        display_units: Some("mm".to_string()),
+        units_name: None,
+        units_min,
+        units_max,
+        decimal_places: None,
+        device_serial_number: None,
+        probe_serial_number: None,
+        channel_id: None,
+        sample_interval_sec: None,
+        is_active: Some(true),
+        align_timestamps: true,
+        alarming_paused: false,
+        created_at: None,
+        updated_at: None,
+        discovered_at: None,
+    }
+}
+
+#[test]
+fn drop_removes_sentinels() {
+    let points = vec![point(0, 1.5), point(600, 1e38), point(1200, -2.0)];
+    let (kept, anomalies) = screen(Uuid::new_v4(), points, GLOBAL);
+
+    assert_eq!(anomalies, 1);
+    let values: Vec<f64> = kept.iter().map(|p| p.value).collect();
+    assert_eq!(values, [1.5, -2.0]);
+}
+
+#[test]
+fn non_finite_values_are_out_of_range() {
+    assert!(!GLOBAL.contains(f64::NAN));
+    assert!(!GLOBAL.contains(f64::INFINITY));
+    assert!(GLOBAL.contains(0.0));
+    assert!(GLOBAL.contains(1e30));
+}
+
+#[test]
+fn units_range_narrows_global_range_when_enabled() {
+    let check = SanityCheck {
+        global: GLOBAL,
+        use_units_range: true,
+    };
+
+    assert_eq!(
+        check.range_for(&sensor(Some(0.0), Some(5000.0))),
+        SanityRange { min: 0.0, max: 5000.0 }
+    );
+    assert_eq!(
+        check.range_for(&sensor(None, Some(100.0))),
+        SanityRange { min: -1e30, max: 100.0 }
+    );
+    // An inverted units range is ignored
+    assert_eq!(check.range_for(&sensor(Some(10.0), Some(1.0))), GLOBAL);
+
+    let global_only = SanityCheck {
+        use_units_range: false,
+        ..check
+    };
+    assert_eq!(global_only.range_for(&sensor(Some(0.0), Some(5000.0))), GLOBAL);
+}
@@ -0,0 +1,82 @@
+//! Tests for the opt-in active alarm fields on station sensor listings.
+//!
+//! Run with: cargo test --test sensor_alarm_status_db_test
+
+mod common;
+
+use chrono::DateTime;
+use river_db::entity::{alarm_locations, alarms};
+use river_db::routes::build_router;
+use sea_orm::{DatabaseConnection, EntityTrait, Set};
+use uuid::Uuid;
+
+async fn seed_alarm(db: &DatabaseConnection, sensor_id: Uuid, severity: i16, active: bool) {
+    let id = Uuid::new_v4();
+    let seed = i32::from_str_radix(&id.simple().to_string()[..6], 16).unwrap();
+    let time = DateTime::from_timestamp(1_735_689_600, 0).unwrap(); // 2025-01-01T00:00:00Z
+    alarms::Entity::insert(alarms::ActiveModel {
+        id: Set(id),
+        vaisala_alarm_id: Set(seed),
+        severity: Set(severity),
+        description: Set("Depth high".to_string()),
+        error_text: Set(None),
+        alarm_type: Set(None),
+        when_on: Set(time.into()),
+        when_off: Set(None),
+        when_ack: Set(None),
+        when_condition: Set(None),
+        duration_sec: Set(None),
+        status: Set(active),
+        is_system: Set(false),
+        serial_number: Set(None),
+        location_text: Set(None),
+        zone_text: Set(None),
+        station_id: Set(None),
+        ack_required: Set(false),
+        ack_comments: Set(None),
+        ack_action_taken: Set(None),
+        location_ids: Set(None),
+        created_at: Set(None),
+        updated_at: Set(None),
+    })
+    .exec_without_returning(db)
+    .await
+    .unwrap();
+    alarm_locations::Entity::insert(alarm_locations::ActiveModel {
+        alarm_id: Set(id),
+        sensor_id: Set(sensor_id),
+    })
+    .exec_without_returning(db)
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn active_alarm_fields_only_with_flag() {
+    let Some(test_db) = common::timescale().await else {
+        return;
+    };
+    let db = &test_db.db;
+    let station =
+        common::seed_station(db, &[("ADepthmm", "Depth"), ("BTurbNTU", "Turbidity")]).await;
+    let (alarming, cleared) = (station.sensor_ids[0], station.sensor_ids[1]);
+    seed_alarm(db, alarming, 1, true).await;
+    seed_alarm(db, alarming, 3, true).await;
+    seed_alarm(db, cleared, 4, false).await;
+    let router = build_router(common::app_state(&test_db));
+    let uri = format!("/api/v1/stations/{}/sensors", station.id);
+
+    let plain = common::get_ok_json(&router, &uri).await;
+    assert!(plain[0]["has_active_alarm"].is_null());
+    assert!(plain[0]["max_active_severity"].is_null());
+
+    let sensors = common::get_ok_json(&router, &format!("{uri}?with_alarms=true")).await;
+    assert_eq!(sensors[0]["name"], "ADepthmm");
+    assert_eq!(sensors[0]["has_active_alarm"], true);
+    assert_eq!(sensors[0]["max_active_severity"], 3);
+    assert_eq!(sensors[1]["has_active_alarm"], false);
+    assert!(sensors[1]["max_active_severity"].is_null());
+
+    let grouped = common::get_ok_json(&router, &format!("{uri}/grouped?with_alarms=true")).await;
+    assert_eq!(grouped["Depth"]["sensors"][0]["max_active_severity"], 3);
+}
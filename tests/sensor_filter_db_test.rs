@@ -0,0 +1,106 @@
+//! Tests that location discovery skips sensor types rejected by
+//! `SYNC_SENSOR_TYPE_ALLOWLIST`/`SYNC_SENSOR_TYPE_DENYLIST`.
+//!
+//! Run with: cargo test --test sensor_filter_db_test
+
+mod common;
+
+use axum::{extract::RawQuery, routing::get, Json, Router};
+use river_db::entity::{sensors, stations};
+use river_db::sync::sensor_filter::SensorTypeFilter;
+use river_db::sync::worker;
+use river_db::vaisala::VaisalaClient;
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+fn location(path: &str, node_id: i32, leaf: bool) -> Value {
+    json!({
+        "type": "locations",
+        "id": node_id.to_string(),
+        "attributes": {
+            "path": path,
+            "text": path.rsplit('/').next().unwrap(),
+            "node_id": node_id,
+            "leaf": leaf,
+        },
+    })
+}
+
+fn location_data(path: &str, location_id: i32) -> Value {
+    json!({
+        "type": "locations_data",
+        "id": location_id.to_string(),
+        "attributes": {
+            "id": location_id,
+            "location_name": path.rsplit('/').next().unwrap(),
+            "location_path": path,
+        },
+    })
+}
+
+#[tokio::test]
+async fn denied_sensor_types_are_not_created() {
+    let Some(test_db) = common::timescale().await else {
+        return;
+    };
+    let suffix = Uuid::new_v4().simple().to_string();
+    let node_id = i32::from_str_radix(&suffix[..6], 16).unwrap() * 16;
+    let station_path = format!("viewLinc/Z-{}/S-{}", &suffix[..8], &suffix[..8]);
+    let sensor_paths = [
+        (format!("{station_path}/MDepthmm"), node_id + 2),
+        (format!("{station_path}/MBattV"), node_id + 3),
+        (format!("{station_path}/MTurbNTU"), node_id + 4),
+    ];
+
+    let mut locations = vec![
+        location(station_path.rsplit_once('/').unwrap().0, node_id, false),
+        location(&station_path, node_id + 1, false),
+    ];
+    locations.extend(sensor_paths.iter().map(|(path, id)| location(path, *id, true)));
+    let locations = json!({"jsonapi": {"version": "1.0"}, "data": locations});
+
+    let details: Vec<(String, i32)> = sensor_paths.to_vec();
+    let router = Router::new()
+        .route("/locations", get(move || async move { Json(locations) }))
+        .route(
+            "/locations_data",
+            get(move |RawQuery(query): RawQuery| async move {
+                // Only the requested IDs, so a filtered sensor can't slip through
+                let requested = query.unwrap_or_default();
+                let data: Vec<Value> = details
+                    .iter()
+                    .filter(|(_, id)| requested.contains(&id.to_string()))
+                    .map(|(path, id)| location_data(path, *id))
+                    .collect();
+                Json(json!({"jsonapi": {"version": "1.0"}, "data": data}))
+            }),
+        );
+    let mut config = common::test_config(&test_db.url);
+    config.vaisala_base_url = common::mock_vaisala(router).await;
+    // Battery is denied even though it is also allowed
+    config.sync_sensor_type_allowlist = vec!["depth".to_string(), "Battery".to_string()];
+    config.sync_sensor_type_denylist = vec!["battery".to_string()];
+    let vaisala = VaisalaClient::new(&config);
+
+    worker::sync_locations(&test_db.db, &vaisala, &SensorTypeFilter::from_config(&config))
+        .await
+        .unwrap();
+
+    let station = stations::Entity::find()
+        .filter(stations::Column::VaisalaNodeId.eq(node_id + 1))
+        .one(&test_db.db)
+        .await
+        .unwrap()
+        .expect("station created");
+    let mut created: Vec<String> = sensors::Entity::find()
+        .filter(sensors::Column::StationId.eq(station.id))
+        .all(&test_db.db)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|s| s.sensor_type)
+        .collect();
+    created.sort();
+    assert_eq!(created, vec!["Depth".to_string()]);
+}
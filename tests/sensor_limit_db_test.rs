@@ -0,0 +1,43 @@
+//! Tests for the per-request sensor limit on station data endpoints.
+//!
+//! Run with: cargo test --test sensor_limit_db_test
+
+mod common;
+
+use axum::http::StatusCode;
+use chrono::{DateTime, Duration};
+use river_db::routes::build_router;
+
+#[tokio::test]
+async fn too_many_sensors_rejected_unless_filtered() {
+    let Some(test_db) = common::timescale().await else {
+        return;
+    };
+    let station = common::seed_station(
+        &test_db.db,
+        &[("MDepthmm", "Depth"), ("MTurbNTU", "Turbidity"), ("MTempC", "Temperature")],
+    )
+    .await;
+    let start = DateTime::from_timestamp(1_735_689_600, 0).unwrap(); // 2025-01-01T00:00:00Z
+    for sensor_id in &station.sensor_ids {
+        common::seed_readings(&test_db.db, *sensor_id, start, Duration::minutes(10), 12, |_| 1.0).await;
+    }
+    common::refresh_aggregate(&test_db.db, "readings_hourly", start, start + Duration::hours(2)).await;
+
+    let mut config = common::test_config(&test_db.url);
+    config.max_sensors_per_request = 2;
+    let router = build_router(common::app_state_with(test_db.db.clone(), config));
+
+    let range = "start=2025-01-01T00:00:00Z&end=2025-01-02T00:00:00Z";
+    for path in ["readings", "aggregates/hourly"] {
+        let uri = format!("/api/v1/stations/{}/{path}?{range}", station.id);
+        let (status, body) = common::get_json(router.clone(), &uri).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{path}: {body}");
+        assert!(body["error"].as_str().unwrap().contains("matches 3 sensors"), "{path}: {body}");
+
+        let (status, body) =
+            common::get_json(router.clone(), &format!("{uri}&sensor_types=Depth,Turbidity")).await;
+        assert_eq!(status, StatusCode::OK, "{path}: {body}");
+        assert_eq!(body["sensors"].as_array().unwrap().len(), 2, "{path}");
+    }
+}
@@ -0,0 +1,182 @@
+//! Tests that location discovery deactivates sensors removed from viewLinc
+//! and reactivates them when they return.
+//!
+//! Run with: cargo test --test sensor_removal_db_test
+
+mod common;
+
+use axum::{extract::RawQuery, routing::get, Json, Router};
+use river_db::entity::{sensors, stations};
+use river_db::sync::sensor_filter::SensorTypeFilter;
+use river_db::sync::worker;
+use river_db::vaisala::VaisalaClient;
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, QueryOrder};
+use serde_json::{json, Value};
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+fn location(path: &str, node_id: i32, leaf: bool, deleted: bool) -> Value {
+    json!({
+        "type": "locations",
+        "id": node_id.to_string(),
+        "attributes": {
+            "path": path,
+            "text": path.rsplit('/').next().unwrap(),
+            "node_id": node_id,
+            "leaf": leaf,
+            "deleted": deleted,
+        },
+    })
+}
+
+fn location_data(path: &str, location_id: i32) -> Value {
+    json!({
+        "type": "locations_data",
+        "id": location_id.to_string(),
+        "attributes": {
+            "id": location_id,
+            "location_name": path.rsplit('/').next().unwrap(),
+            "location_path": path,
+        },
+    })
+}
+
+/// (name, is_active) of the station's sensors, by name
+async fn activity(
+    db: &sea_orm::DatabaseConnection,
+    station_node_id: i32,
+) -> Vec<(String, Option<bool>)> {
+    let station = stations::Entity::find()
+        .filter(stations::Column::VaisalaNodeId.eq(station_node_id))
+        .one(db)
+        .await
+        .unwrap()
+        .expect("station created");
+    sensors::Entity::find()
+        .filter(sensors::Column::StationId.eq(station.id))
+        .order_by_asc(sensors::Column::Name)
+        .all(db)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|s| (s.name, s.is_active))
+        .collect()
+}
+
+#[tokio::test]
+async fn removed_sensors_become_inactive_until_they_return() {
+    let Some(test_db) = common::timescale().await else {
+        return;
+    };
+    let suffix = Uuid::new_v4().simple().to_string();
+    let node_id = i32::from_str_radix(&suffix[..6], 16).unwrap() * 16;
+    let station_path = format!("viewLinc/Z-{}/S-{}", &suffix[..8], &suffix[..8]);
+    let sensor_paths = vec![
+        (format!("{station_path}/MDepthmm"), node_id + 2),
+        (format!("{station_path}/MTurbNTU"), node_id + 3),
+        (format!("{station_path}/MTempC"), node_id + 4),
+    ];
+
+    // (listed, deleted) per sensor, switched between the two passes
+    let listing = Arc::new(Mutex::new(vec![(true, false); 3]));
+    let locations = {
+        let listing = listing.clone();
+        let sensor_paths = sensor_paths.clone();
+        move || {
+            let mut data = vec![
+                location(
+                    station_path.rsplit_once('/').unwrap().0,
+                    node_id,
+                    false,
+                    false,
+                ),
+                location(&station_path, node_id + 1, false, false),
+            ];
+            let listing = listing.lock().unwrap().clone();
+            for ((path, id), (listed, deleted)) in sensor_paths.iter().zip(listing) {
+                if listed {
+                    data.push(location(path, *id, true, deleted));
+                }
+            }
+            Json(json!({"jsonapi": {"version": "1.0"}, "data": data}))
+        }
+    };
+    let details = sensor_paths.clone();
+    let router = Router::new()
+        .route("/locations", get(move || async move { locations() }))
+        .route(
+            "/locations_data",
+            get(move |RawQuery(query): RawQuery| async move {
+                let requested = query.unwrap_or_default();
+                let data: Vec<Value> = details
+                    .iter()
+                    .filter(|(_, id)| requested.contains(&id.to_string()))
+                    .map(|(path, id)| location_data(path, *id))
+                    .collect();
+                Json(json!({"jsonapi": {"version": "1.0"}, "data": data}))
+            }),
+        );
+    let mut config = common::test_config(&test_db.url);
+    config.vaisala_base_url = common::mock_vaisala(router).await;
+    let vaisala = VaisalaClient::new(&config);
+    let filter = SensorTypeFilter::default();
+
+    worker::sync_locations(&test_db.db, &vaisala, &filter)
+        .await
+        .unwrap();
+    let ours: HashSet<Uuid> = sensors::Entity::find()
+        .filter(sensors::Column::VaisalaLocationId.is_in(sensor_paths.iter().map(|(_, id)| *id)))
+        .all(&test_db.db)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|s| s.id)
+        .collect();
+    // Other tests' sensors sharing the database are deactivated too
+    let changed = |pass: &worker::LocationsPass| {
+        pass.activity_changed
+            .iter()
+            .filter(|id| ours.contains(id))
+            .count()
+    };
+
+    // Turbidity vanishes from /locations; temperature is flagged deleted
+    *listing.lock().unwrap() = vec![(true, false), (false, false), (true, true)];
+    let pass = worker::sync_locations(&test_db.db, &vaisala, &filter)
+        .await
+        .unwrap();
+    assert_eq!(changed(&pass), 2);
+
+    assert_eq!(
+        activity(&test_db.db, node_id + 1).await,
+        vec![
+            ("MDepthmm".to_string(), Some(true)),
+            ("MTempC".to_string(), Some(false)),
+            ("MTurbNTU".to_string(), Some(false)),
+        ]
+    );
+
+    // Both come back: the existing rows are reactivated, not duplicated
+    *listing.lock().unwrap() = vec![(true, false); 3];
+    let pass = worker::sync_locations(&test_db.db, &vaisala, &filter)
+        .await
+        .unwrap();
+    assert_eq!(pass.sensors_created, 0);
+    assert_eq!(changed(&pass), 2);
+
+    assert_eq!(
+        activity(&test_db.db, node_id + 1).await,
+        vec![
+            ("MDepthmm".to_string(), Some(true)),
+            ("MTempC".to_string(), Some(true)),
+            ("MTurbNTU".to_string(), Some(true)),
+        ]
+    );
+
+    // A pass without changes touches nothing
+    let pass = worker::sync_locations(&test_db.db, &vaisala, &filter)
+        .await
+        .unwrap();
+    assert_eq!(changed(&pass), 0);
+}
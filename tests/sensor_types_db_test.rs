@@ -0,0 +1,43 @@
+//! Tests for sensor types mapped by the `sensor_type_patterns` table.
+//!
+//! Run with: cargo test --test sensor_types_db_test
+
+mod common;
+
+use river_db::sync::sensor_types::SensorTypePatterns;
+use sea_orm::{ConnectionTrait, DatabaseBackend, Statement};
+
+#[tokio::test]
+async fn db_configured_patterns_map_sensor_names() {
+    let Some(test_db) = common::timescale().await else {
+        return;
+    };
+
+    let execute = |sql: &'static str| {
+        test_db
+            .db
+            .execute(Statement::from_string(DatabaseBackend::Postgres, sql))
+    };
+    // Left over from an earlier run that failed before cleaning up
+    execute("DELETE FROM sensor_type_patterns WHERE keyword = 'pH'").await.unwrap();
+
+    // Seeded by the migration
+    let patterns = SensorTypePatterns::load(&test_db.db).await.unwrap();
+    assert_eq!(patterns.derive("MDepthmm"), "Depth");
+    assert_eq!(patterns.derive("MCondTdegC"), "Cond_Temperature");
+    assert_eq!(patterns.derive("MpHunit"), "MpHunit");
+
+    execute(
+        "INSERT INTO sensor_type_patterns (keyword, sensor_type, priority)
+         VALUES ('pH', 'pH', 90)",
+    )
+    .await
+    .unwrap();
+
+    let patterns = SensorTypePatterns::load(&test_db.db).await.unwrap();
+    // Remove the row before asserting, so other tests sharing the database
+    // keep the seeded patterns
+    execute("DELETE FROM sensor_type_patterns WHERE keyword = 'pH'").await.unwrap();
+    assert_eq!(patterns.derive("MpHunit"), "pH");
+    assert_eq!(patterns.derive("MDepthmm"), "Depth");
+}
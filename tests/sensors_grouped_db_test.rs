@@ -0,0 +1,60 @@
+//! Tests for the station sensors grouped-by-type endpoint.
+//!
+//! Run with: cargo test --test sensors_grouped_db_test
+
+mod common;
+
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use river_db::entity::sensors;
+use river_db::routes::build_router;
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
+use sea_orm::sea_query::Expr;
+use serde_json::Value;
+use tower::ServiceExt;
+
+#[tokio::test]
+async fn sensors_grouped_by_type_with_counts_and_units() {
+    let Some(test_db) = common::timescale().await else {
+        return;
+    };
+    let station = common::seed_station(
+        &test_db.db,
+        &[("MTurbNTU", "Turbidity"), ("MDepthmm", "Depth"), ("ADepthmm", "Depth")],
+    )
+    .await;
+    sensors::Entity::update_many()
+        .col_expr(sensors::Column::DisplayUnits, Expr::value("mm"))
+        .filter(sensors::Column::StationId.eq(station.id))
+        .filter(sensors::Column::SensorType.eq("Depth"))
+        .exec(&test_db.db)
+        .await
+        .unwrap();
+    let router = build_router(common::app_state(&test_db));
+
+    let response = router
+        .oneshot(
+            Request::get(format!("/api/v1/stations/{}/sensors/grouped", station.name))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let body: Value = serde_json::from_slice(&bytes).unwrap();
+
+    let types: Vec<&String> = body.as_object().unwrap().keys().collect();
+    assert_eq!(types, ["Depth", "Turbidity"]);
+
+    let depth = &body["Depth"];
+    assert_eq!(depth["count"], 2);
+    assert_eq!(depth["units"], serde_json::json!(["mm"]));
+    assert_eq!(depth["sensors"][0]["name"], "ADepthmm");
+    assert_eq!(depth["sensors"][1]["name"], "MDepthmm");
+
+    assert_eq!(body["Turbidity"]["count"], 1);
+    assert_eq!(body["Turbidity"]["units"], serde_json::json!([]));
+}
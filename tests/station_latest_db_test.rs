@@ -0,0 +1,70 @@
+//! Tests for the station latest-readings endpoint.
+//!
+//! Run with: cargo test --test station_latest_db_test
+
+mod common;
+
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use chrono::{DateTime, Duration};
+use river_db::routes::build_router;
+use serde_json::{json, Value};
+use tower::ServiceExt;
+
+/// Fetch `uri`, returning the status, `X-Cache` header and JSON body
+async fn get_json(router: axum::Router, uri: &str) -> (StatusCode, String, Value) {
+    let response = router
+        .oneshot(Request::get(uri).body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    let status = response.status();
+    let x_cache = response
+        .headers()
+        .get("X-Cache")
+        .map(|v| v.to_str().unwrap().to_string())
+        .unwrap_or_default();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, x_cache, serde_json::from_slice(&bytes).unwrap())
+}
+
+#[tokio::test]
+async fn latest_value_per_sensor() {
+    let Some(test_db) = common::timescale().await else {
+        return;
+    };
+    let station = common::seed_station(
+        &test_db.db,
+        &[("MDepthmm", "Depth"), ("MTurbNTU", "Turbidity"), ("MTempC", "Temperature")],
+    )
+    .await;
+    let start = DateTime::from_timestamp(1_735_689_600, 0).unwrap(); // 2025-01-01T00:00:00Z
+    let step = Duration::minutes(10);
+    common::seed_readings(&test_db.db, station.sensor_ids[0], start, step, 6, f64::from).await;
+    common::seed_readings(&test_db.db, station.sensor_ids[1], start, step, 3, |i| f64::from(i) * 10.0).await;
+
+    let router = build_router(common::app_state(&test_db));
+    let uri = format!("/api/v1/stations/{}/latest", station.id);
+
+    let (status, x_cache, body) = get_json(router.clone(), &uri).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(x_cache, "MISS");
+    assert_eq!(body["time"], "2025-01-01T00:50:00Z");
+    let sensors = body["sensors"].as_array().unwrap();
+    let by_name = |name: &str| sensors.iter().find(|s| s["name"] == name).unwrap().clone();
+    assert_eq!(by_name("MDepthmm")["value"], json!(5.0));
+    assert_eq!(by_name("MDepthmm")["time"], "2025-01-01T00:50:00Z");
+    assert_eq!(by_name("MTurbNTU")["value"], json!(20.0));
+    assert_eq!(by_name("MTurbNTU")["type"], "Turbidity");
+    assert!(by_name("MTempC")["value"].is_null());
+
+    let (_, x_cache, _) = get_json(router.clone(), &uri).await;
+    assert_eq!(x_cache, "HIT");
+
+    // A newer reading replaces the cached snapshot
+    common::seed_readings(&test_db.db, station.sensor_ids[2], start + Duration::hours(1), step, 1, |_| 12.5).await;
+    let (_, x_cache, body) = get_json(router, &uri).await;
+    assert_eq!(x_cache, "MISS");
+    assert_eq!(body["time"], "2025-01-01T01:00:00Z");
+}
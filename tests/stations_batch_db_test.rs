@@ -0,0 +1,131 @@
+//! Tests for the station batch detail endpoint, including gzip-encoded
+//! request bodies.
+//!
+//! Run with: cargo test --test stations_batch_db_test
+
+mod common;
+
+use async_compression::tokio::bufread::GzipEncoder;
+use axum::body::Body;
+use axum::http::{header, Request, StatusCode};
+use chrono::{DateTime, Duration};
+use river_db::routes::build_router;
+use sea_orm::DatabaseConnection;
+use serde_json::Value;
+use tokio::io::AsyncReadExt;
+use tower::ServiceExt;
+
+/// POST `body` as JSON, gzip-encoded when `gzip` is set; returns the status
+/// and raw response body.
+async fn post(router: axum::Router, uri: &str, body: Vec<u8>, gzip: bool) -> (StatusCode, Vec<u8>) {
+    let mut request = Request::post(uri).header(header::CONTENT_TYPE, "application/json");
+    let body = if gzip {
+        request = request.header(header::CONTENT_ENCODING, "gzip");
+        let mut encoded = Vec::new();
+        GzipEncoder::new(body.as_slice())
+            .read_to_end(&mut encoded)
+            .await
+            .unwrap();
+        encoded
+    } else {
+        body
+    };
+    let response = router
+        .oneshot(request.body(Body::from(body)).unwrap())
+        .await
+        .unwrap();
+    let status = response.status();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, bytes.to_vec())
+}
+
+async fn post_json(router: axum::Router, uri: &str, body: &Value) -> (StatusCode, Value) {
+    let (status, bytes) = post(router, uri, body.to_string().into_bytes(), false).await;
+    (status, serde_json::from_slice(&bytes).unwrap())
+}
+
+#[tokio::test]
+async fn batch_returns_details_in_request_order() {
+    let Some(test_db) = common::timescale().await else {
+        return;
+    };
+    let first = common::seed_station(&test_db.db, &[("MDepthmm", "Depth"), ("MTurbNTU", "Turbidity")]).await;
+    let second = common::seed_station(&test_db.db, &[("MBattV", "Battery")]).await;
+    let start = DateTime::from_timestamp(1_735_689_600, 0).unwrap(); // 2025-01-01T00:00:00Z
+    common::seed_readings(&test_db.db, first.sensor_ids[0], start, Duration::minutes(10), 3, |_| 1.0).await;
+
+    let router = build_router(common::app_state(&test_db));
+    let request = serde_json::json!({
+        "station_ids": [second.name.to_uppercase(), "no-such-station", first.id.to_string()],
+    });
+    let (status, body) = post_json(router, "/api/v1/stations/batch", &request).await;
+
+    assert_eq!(status, StatusCode::OK, "{body}");
+    let results = body.as_array().unwrap();
+    assert_eq!(results.len(), 3);
+
+    assert_eq!(results[0]["found"], true);
+    assert_eq!(results[0]["station"]["id"], second.id.to_string());
+    assert_eq!(results[0]["station"]["reading_count"], 0);
+
+    assert_eq!(results[1]["id"], "no-such-station");
+    assert_eq!(results[1]["found"], false);
+    assert!(results[1]["station"].is_null());
+
+    let detail = &results[2]["station"];
+    assert_eq!(detail["sensors"].as_array().unwrap().len(), 2);
+    assert_eq!(detail["reading_count"], 3);
+    assert_eq!(detail["data_start"], "2025-01-01T00:00:00Z");
+    assert_eq!(detail["data_end"], "2025-01-01T00:20:00Z");
+}
+
+#[tokio::test]
+async fn oversized_batch_is_rejected() {
+    let Some(test_db) = common::timescale().await else {
+        return;
+    };
+    let router = build_router(common::app_state(&test_db));
+    let ids: Vec<String> = (0..=river_db::routes::stations::MAX_BATCH_STATIONS)
+        .map(|i| format!("station-{i}"))
+        .collect();
+    let (status, _) = post_json(router, "/api/v1/stations/batch", &serde_json::json!({"station_ids": ids})).await;
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn gzipped_batch_matches_plain_request() {
+    let Some(test_db) = common::timescale().await else {
+        return;
+    };
+    let station = common::seed_station(&test_db.db, &[("MDepthmm", "Depth")]).await;
+    let router = build_router(common::app_state(&test_db));
+    let request = serde_json::json!({"station_ids": [station.name, "no-such-station"]})
+        .to_string()
+        .into_bytes();
+
+    let (plain_status, plain) = post(router.clone(), "/api/v1/stations/batch", request.clone(), false).await;
+    let (gzip_status, gzipped) = post(router, "/api/v1/stations/batch", request, true).await;
+
+    assert_eq!(plain_status, StatusCode::OK);
+    assert_eq!(gzip_status, StatusCode::OK);
+    let plain: Value = serde_json::from_slice(&plain).unwrap();
+    assert_eq!(plain[0]["station"]["id"], station.id.to_string());
+    assert_eq!(serde_json::from_slice::<Value>(&gzipped).unwrap(), plain);
+}
+
+#[tokio::test]
+async fn body_limit_applies_to_decompressed_size() {
+    // Rejected while the body is read, before the handler queries the database
+    let config = common::test_config("postgresql://unused");
+    let router = build_router(common::app_state_with(DatabaseConnection::Disconnected, config));
+    let request = serde_json::json!({"station_ids": ["a".repeat(2 * 1024 * 1024)]})
+        .to_string()
+        .into_bytes();
+
+    let (status, _) = post(router, "/api/v1/stations/batch", request, true).await;
+
+    assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+}
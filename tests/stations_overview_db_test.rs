@@ -0,0 +1,63 @@
+//! Tests for the fleet-wide station overview.
+//!
+//! Run with: cargo test --test stations_overview_db_test
+
+mod common;
+
+use chrono::{Duration, Utc};
+use river_db::entity::sync_state::{self, SyncStatus};
+use river_db::routes::build_router;
+use sea_orm::{EntityTrait, Set};
+use serde_json::Value;
+
+#[tokio::test]
+async fn overview_reports_freshness_and_counts() {
+    let Some(test_db) = common::timescale().await else {
+        return;
+    };
+    let db = &test_db.db;
+    let fresh =
+        common::seed_station(db, &[("MDepthmm", "Depth"), ("MTurbNTU", "Turbidity")]).await;
+    let empty = common::seed_station(db, &[("MDepthmm", "Depth")]).await;
+
+    // One synced sensor a minute ago; the other was never synced
+    let last_data_time = Utc::now() - Duration::seconds(60);
+    sync_state::Entity::insert(sync_state::ActiveModel {
+        sensor_id: Set(fresh.sensor_ids[0]),
+        last_data_time: Set(Some(last_data_time.into())),
+        last_sync_attempt: Set(Some(Utc::now().into())),
+        sync_status: Set(Some(SyncStatus::Success)),
+        error_message: Set(None),
+        retry_count: Set(Some(0)),
+        last_full_sync: Set(None),
+    })
+    .exec_without_returning(db)
+    .await
+    .unwrap();
+    let router = build_router(common::app_state(&test_db));
+
+    let overview = common::get_ok_json(&router, "/api/v1/stations/overview").await;
+    let find = |overview: &Value, name: &str| {
+        overview
+            .as_array()
+            .unwrap()
+            .iter()
+            .position(|s| s["name"] == name)
+            .unwrap()
+    };
+    let row = &overview[find(&overview, &fresh.name)];
+    assert_eq!(row["sensor_count"], 2);
+    assert_eq!(row["stale_sensor_count"], 1);
+    assert_eq!(row["active_alarm_count"], 0);
+    assert!(row["data_end"].is_string());
+    assert!(row["lag_seconds"].as_i64().unwrap() >= 60);
+
+    let row = &overview[find(&overview, &empty.name)];
+    assert!(row["data_end"].is_null());
+    assert_eq!(row["stale_sensor_count"], 1);
+
+    // Stations without data sort before those with recent data
+    let by_staleness =
+        common::get_ok_json(&router, "/api/v1/stations/overview?order=staleness").await;
+    assert!(find(&by_staleness, &empty.name) < find(&by_staleness, &fresh.name));
+}
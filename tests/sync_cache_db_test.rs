@@ -0,0 +1,95 @@
+//! Tests that a readings sync invalidates the cached responses of the
+//! stations it wrote to.
+//!
+//! Run with: cargo test --test sync_cache_db_test
+
+mod common;
+
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use axum::{routing::get, Json, Router};
+use chrono::{DateTime, Duration};
+use river_db::common::AppState;
+use river_db::routes::build_router;
+use river_db::services::cache;
+use river_db::sync::sanity::SanityCheck;
+use river_db::sync::worker;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+/// Fetch `uri`, returning the status and `X-Cache` header
+async fn get_cache_status(router: Router, uri: &str) -> (StatusCode, String) {
+    let response = router
+        .oneshot(Request::get(uri).body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    let x_cache = response
+        .headers()
+        .get("X-Cache")
+        .map(|v| v.to_str().unwrap().to_string())
+        .unwrap_or_default();
+    (response.status(), x_cache)
+}
+
+/// Cached keys of a station's readings responses
+fn readings_keys(state: &AppState, station_id: Uuid) -> Vec<String> {
+    let prefix = format!("readings:{station_id}:");
+    state
+        .response_cache
+        .iter()
+        .map(|(key, _)| key.to_string())
+        .filter(|key| key.starts_with(&prefix))
+        .collect()
+}
+
+#[tokio::test]
+async fn sync_invalidates_unbounded_readings_cache() {
+    let Some(test_db) = common::timescale().await else {
+        return;
+    };
+    let synced = common::seed_station(&test_db.db, &[("MDepthmm", "Depth")]).await;
+    let untouched = common::seed_station(&test_db.db, &[("MDepthmm", "Depth")]).await;
+    let start = DateTime::from_timestamp(1_735_689_600, 0).unwrap(); // 2025-01-01T00:00:00Z
+    for station in [&synced, &untouched] {
+        common::seed_readings(&test_db.db, station.sensor_ids[0], start, Duration::minutes(10), 6, |_| 1.0).await;
+    }
+
+    let mut config = common::test_config(&test_db.url);
+    let t1 = start.timestamp() + 3600;
+    let body = common::locations_history_body(synced.location_ids[0], &[(t1, 2.0, true), (t1 + 600, 3.0, true)]);
+    config.vaisala_base_url = common::mock_vaisala(Router::new().route(
+        "/locations_history",
+        get(move || {
+            let body = body.clone();
+            async move { Json(body) }
+        }),
+    ))
+    .await;
+    let sanity = SanityCheck::from_config(&config);
+    let state = common::app_state_with(test_db.db.clone(), config);
+    let router = build_router(state.clone());
+
+    // Unbounded queries (no end), cached on first request
+    for station in [&synced, &untouched] {
+        let uri = format!("/api/v1/stations/{}/readings?start=2025-01-01T00:00:00Z", station.id);
+        assert_eq!(get_cache_status(router.clone(), &uri).await, (StatusCode::OK, "MISS".to_string()));
+        assert_eq!(get_cache_status(router.clone(), &uri).await, (StatusCode::OK, "HIT".to_string()));
+    }
+    let synced_keys = readings_keys(&state, synced.id);
+    let untouched_keys = readings_keys(&state, untouched.id);
+    assert_eq!(synced_keys.len(), 1);
+    assert_eq!(untouched_keys.len(), 1);
+
+    let pass = worker::sync_readings(&test_db.db, &state.vaisala_client, 3650, false, false, &sanity)
+        .await
+        .unwrap();
+    assert!(pass.updated_sensors.contains(&synced.sensor_ids[0]));
+    cache::invalidate_sensors(&state, &pass.updated_sensors).await;
+
+    // Gone without a freshness check; other stations keep their entries
+    assert!(!state.response_cache.contains_key(&synced_keys[0]));
+    assert!(state.response_cache.contains_key(&untouched_keys[0]));
+
+    let uri = format!("/api/v1/stations/{}/readings?start=2025-01-01T00:00:00Z", synced.id);
+    assert_eq!(get_cache_status(router, &uri).await, (StatusCode::OK, "MISS".to_string()));
+}
@@ -0,0 +1,160 @@
+//! Database-backed tests for the sync worker.
+//!
+//! Run with: cargo test --test sync_db_test
+
+mod common;
+
+use chrono::{DateTime, Utc};
+use river_db::entity::{alarm_locations, alarms, readings, sync_state};
+use river_db::sync::worker;
+use river_db::vaisala::models::ActiveAlarmAttributes;
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, PaginatorTrait, QueryFilter, Set};
+use uuid::Uuid;
+
+/// Seed a station with one depth sensor, returning its ID and Vaisala location ID.
+async fn seed_sensor(db: &DatabaseConnection) -> (Uuid, i32) {
+    let station = common::seed_station(db, &[("MDepthmm", "Depth")]).await;
+    (station.sensor_ids[0], station.location_ids[0])
+}
+
+fn reading(sensor_id: Uuid, time: DateTime<Utc>) -> readings::ActiveModel {
+    readings::ActiveModel {
+        sensor_id: Set(sensor_id),
+        time: Set(time.into()),
+        value: Set(1.0),
+        logged: Set(Some(true)),
+        ingested_at: Set(None),
+        raw_time: Set(None),
+    }
+}
+
+async fn reading_count(db: &DatabaseConnection, sensor_id: Uuid) -> u64 {
+    readings::Entity::find()
+        .filter(readings::Column::SensorId.eq(sensor_id))
+        .count(db)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn sync_state_matches_committed_readings() {
+    let Some(test_db) = common::timescale().await else {
+        return;
+    };
+    let db = test_db.db;
+    let (sensor_id, _) = seed_sensor(&db).await;
+
+    let times: Vec<DateTime<Utc>> = (1..=3)
+        .map(|i| DateTime::from_timestamp(1_700_000_000 + i * 600, 0).unwrap())
+        .collect();
+    let models = times.iter().map(|t| reading(sensor_id, *t)).collect();
+
+    let inserted = worker::store_sensor_readings(&db, sensor_id, models, false, times.last().copied())
+        .await
+        .unwrap();
+    assert_eq!(inserted, 3);
+    assert_eq!(reading_count(&db, sensor_id).await, 3);
+
+    let state = sync_state::Entity::find_by_id(sensor_id)
+        .one(&db)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        state.last_data_time.map(|t| t.with_timezone(&Utc)),
+        times.last().copied()
+    );
+}
+
+#[tokio::test]
+async fn failed_batch_rolls_back_readings_and_sync_state() {
+    let Some(test_db) = common::timescale().await else {
+        return;
+    };
+    let db = test_db.db;
+    let (sensor_id, _) = seed_sensor(&db).await;
+
+    // First batch is valid; the second references an unknown sensor and fails the FK
+    let mut models: Vec<readings::ActiveModel> = (0..1000)
+        .map(|i| reading(sensor_id, DateTime::from_timestamp(1_700_000_000 + i * 600, 0).unwrap()))
+        .collect();
+    models.push(reading(Uuid::new_v4(), Utc::now()));
+
+    let result = worker::store_sensor_readings(&db, sensor_id, models, false, Some(Utc::now())).await;
+    assert!(result.is_err());
+
+    assert_eq!(reading_count(&db, sensor_id).await, 0);
+    assert!(
+        sync_state::Entity::find_by_id(sensor_id)
+            .one(&db)
+            .await
+            .unwrap()
+            .is_none()
+    );
+}
+
+fn alarm(id: i32, severity: i16, location_id: i32) -> ActiveAlarmAttributes {
+    serde_json::from_value(serde_json::json!({
+        "id": id,
+        "severity": severity,
+        "description": "Low battery",
+        "when_on": 1_700_000_000.0,
+        "status": true,
+        "location_ids": [location_id],
+    }))
+    .unwrap()
+}
+
+async fn find_alarm(db: &DatabaseConnection, vaisala_alarm_id: i32) -> alarms::Model {
+    alarms::Entity::find()
+        .filter(alarms::Column::VaisalaAlarmId.eq(vaisala_alarm_id))
+        .one(db)
+        .await
+        .unwrap()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn alarm_sync_upserts_links_and_deactivates() {
+    let Some(test_db) = common::timescale().await else {
+        return;
+    };
+    let db = test_db.db;
+    let (sensor_id, location_id) = seed_sensor(&db).await;
+    let kept_id = location_id;
+    let dropped_id = location_id.wrapping_add(1);
+
+    let (created, updated) = worker::apply_active_alarms(
+        &db,
+        vec![alarm(kept_id, 1, location_id), alarm(dropped_id, 1, location_id)],
+    )
+    .await
+    .unwrap();
+    assert_eq!((created, updated), (2, 0));
+
+    let kept = find_alarm(&db, kept_id).await;
+    assert!(kept.status);
+    let links = alarm_locations::Entity::find()
+        .filter(alarm_locations::Column::AlarmId.eq(kept.id))
+        .all(&db)
+        .await
+        .unwrap();
+    assert_eq!(links.len(), 1);
+    assert_eq!(links[0].sensor_id, sensor_id);
+
+    // Second pass: one alarm changed, the other no longer active
+    let (created, updated) = worker::apply_active_alarms(&db, vec![alarm(kept_id, 3, location_id)])
+        .await
+        .unwrap();
+    assert_eq!((created, updated), (0, 1));
+
+    let kept_after = find_alarm(&db, kept_id).await;
+    assert_eq!(kept_after.id, kept.id);
+    assert_eq!(kept_after.severity, 3);
+    assert!(kept_after.status);
+    assert!(kept_after.when_off.is_none());
+
+    let dropped = find_alarm(&db, dropped_id).await;
+    assert!(!dropped.status);
+    assert!(dropped.when_off.is_some());
+}
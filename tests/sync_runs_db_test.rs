@@ -0,0 +1,59 @@
+//! Tests for the sync run history and `/api/sync/runs`.
+//!
+//! Run with: cargo test --test sync_runs_db_test
+
+mod common;
+
+use axum::http::StatusCode;
+use chrono::{Duration, Utc};
+use river_db::entity::sync_runs;
+use river_db::routes::build_router;
+use river_db::sync::history;
+use sea_orm::{ColumnTrait, EntityTrait, PaginatorTrait, QueryFilter};
+
+#[tokio::test]
+async fn runs_are_recorded_listed_and_pruned() {
+    let Some(test_db) = common::timescale().await else {
+        return;
+    };
+    let now = Utc::now();
+
+    // Older than the retention and pruned by the next record
+    history::record_run(&test_db.db, "events", now - Duration::days(40), Ok(None), 0).await;
+    history::record_run(&test_db.db, "readings", now - Duration::seconds(2), Ok(Some(120)), 30).await;
+    history::record_run(
+        &test_db.db,
+        "alarms",
+        now - Duration::seconds(1),
+        Err("Rate limited".to_string()),
+        30,
+    )
+    .await;
+
+    let expired = sync_runs::Entity::find()
+        .filter(sync_runs::Column::StartedAt.lt(now - Duration::days(30)))
+        .count(&test_db.db)
+        .await
+        .unwrap();
+    assert_eq!(expired, 0);
+
+    let router = build_router(common::app_state(&test_db));
+    let (status, body) =
+        common::get_json(router.clone(), "/api/sync/runs?task=alarms&limit=1").await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    let runs = body.as_array().unwrap();
+    assert_eq!(runs.len(), 1);
+    assert_eq!(runs[0]["task"], "alarms");
+    assert_eq!(runs[0]["status"], "error");
+    assert_eq!(runs[0]["error"], "Rate limited");
+    assert!(runs[0]["records_affected"].is_null());
+
+    let (status, body) =
+        common::get_json(router.clone(), "/api/sync/runs?task=readings&limit=1").await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(body[0]["status"], "success");
+    assert_eq!(body[0]["records_affected"], 120);
+
+    let (status, _) = common::get_json(router, "/api/sync/runs?task=weather").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
@@ -0,0 +1,118 @@
+//! Tests for the per-sensor sync status endpoint.
+//!
+//! Run with: cargo test --test sync_status_db_test
+
+mod common;
+
+use axum::http::StatusCode;
+use river_db::routes::build_router;
+use sea_orm::{ConnectionTrait, DatabaseBackend, Statement};
+use serde_json::Value;
+use uuid::Uuid;
+
+async fn insert_sync_state(
+    db: &sea_orm::DatabaseConnection,
+    sensor_id: Uuid,
+    data_age_seconds: i64,
+    status: &str,
+    error: Option<&str>,
+) {
+    db.execute(Statement::from_sql_and_values(
+        DatabaseBackend::Postgres,
+        "INSERT INTO sync_state
+             (sensor_id, last_data_time, last_sync_attempt, sync_status, error_message, retry_count)
+         VALUES ($1, NOW() - $2 * INTERVAL '1 second', NOW(), $3, $4, 0)",
+        [
+            sensor_id.into(),
+            data_age_seconds.into(),
+            status.into(),
+            error.map(str::to_string).into(),
+        ],
+    ))
+    .await
+    .unwrap();
+}
+
+/// Names of this station's sensors in the response, in order
+fn sensor_names(body: &Value, station: &str) -> Vec<String> {
+    body.as_array()
+        .unwrap()
+        .iter()
+        .filter(|s| s["station"]["name"] == station)
+        .map(|s| s["sensor"]["name"].as_str().unwrap().to_string())
+        .collect()
+}
+
+#[tokio::test]
+async fn reports_sync_state_with_filters() {
+    let Some(test_db) = common::timescale().await else {
+        return;
+    };
+    let station = common::seed_station(
+        &test_db.db,
+        &[
+            ("MDepthmm", "Depth"),
+            ("MTurbNTU", "Turbidity"),
+            ("MTempC", "Temperature"),
+            ("MCondUS", "Conductivity"),
+        ],
+    )
+    .await;
+    let (depth, turbidity, removed) = (
+        station.sensor_ids[0],
+        station.sensor_ids[1],
+        station.sensor_ids[3],
+    );
+    insert_sync_state(&test_db.db, depth, 60, "success", None).await;
+    insert_sync_state(&test_db.db, turbidity, 7_200, "error", Some("HTTP 500")).await;
+    insert_sync_state(&test_db.db, removed, 60, "success", None).await;
+
+    // Inactive sensors are no longer synced, so they are not listed
+    test_db
+        .db
+        .execute(Statement::from_sql_and_values(
+            DatabaseBackend::Postgres,
+            "UPDATE sensors SET is_active = false WHERE id = $1",
+            [removed.into()],
+        ))
+        .await
+        .unwrap();
+
+    let router = build_router(common::app_state(&test_db));
+    let (status, body) = common::get_json(router.clone(), "/api/v1/sync/status").await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(
+        sensor_names(&body, &station.name),
+        vec!["MDepthmm", "MTempC", "MTurbNTU"]
+    );
+    let turbidity = body
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|s| s["sensor"]["id"] == turbidity.to_string())
+        .unwrap();
+    assert_eq!(turbidity["sync_status"], "error");
+    assert_eq!(turbidity["error_message"], "HTTP 500");
+    let staleness = turbidity["staleness_seconds"].as_i64().unwrap();
+    assert!((7_200..7_260).contains(&staleness), "{staleness}");
+
+    let (_, body) = common::get_json(router.clone(), "/api/v1/sync/status?status=error").await;
+    assert_eq!(sensor_names(&body, &station.name), vec!["MTurbNTU"]);
+
+    // The never-synced temperature sensor has no data, so it counts as stale
+    let (_, body) = common::get_json(
+        router.clone(),
+        "/api/v1/sync/status?stale_over_seconds=3600",
+    )
+    .await;
+    assert_eq!(
+        sensor_names(&body, &station.name),
+        vec!["MTempC", "MTurbNTU"]
+    );
+
+    for query in ["status=broken", "stale_over_seconds=-1"] {
+        let (status, _) =
+            common::get_json(router.clone(), &format!("/api/v1/sync/status?{query}")).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{query}");
+    }
+}
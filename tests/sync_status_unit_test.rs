@@ -0,0 +1,32 @@
+//! Unit tests for the typed sync status.
+//!
+//! Run with: cargo test --test sync_status_unit_test
+
+use river_db::entity::sync_state::SyncStatus;
+use sea_orm::{ActiveEnum, Iterable};
+
+#[test]
+fn stored_and_serialized_as_lowercase() {
+    for (status, text) in [
+        (SyncStatus::Pending, "pending"),
+        (SyncStatus::Success, "success"),
+        (SyncStatus::Error, "error"),
+    ] {
+        assert_eq!(status.to_value(), text);
+        assert_eq!(serde_json::to_value(status).unwrap(), text);
+        assert_eq!(SyncStatus::try_from_value(&text.to_string()).unwrap(), status);
+    }
+}
+
+#[test]
+fn unknown_values_are_rejected() {
+    assert!(SyncStatus::try_from_value(&"sucess".to_string()).is_err());
+    assert!(serde_json::from_str::<SyncStatus>("\"Error\"").is_err());
+}
+
+#[test]
+fn variants_match_database_check_constraint() {
+    // Keep in sync with chk_sync_state_status in the migrations
+    let values: Vec<String> = SyncStatus::iter().map(|s| s.to_value()).collect();
+    assert_eq!(values, ["pending", "success", "error"]);
+}
@@ -0,0 +1,81 @@
+//! Unit tests for sync helpers.
+//!
+//! Run with: cargo test --test sync_unit_test
+
+use chrono::{DateTime, Utc};
+use river_db::sync::worker;
+
+fn at(secs: i64) -> DateTime<Utc> {
+    DateTime::from_timestamp(secs, 0).unwrap()
+}
+
+#[test]
+fn logged_point_replaces_realtime_in_either_order() {
+    // Realtime first, then logged: logged wins
+    let merged = worker::merge_points(vec![(at(600), 1.0, false), (at(600), 2.0, true)], true);
+    assert_eq!(merged, vec![(at(600), 2.0, true)]);
+
+    // Logged first, then realtime: logged is kept
+    let merged = worker::merge_points(vec![(at(600), 2.0, true), (at(600), 1.0, false)], true);
+    assert_eq!(merged, vec![(at(600), 2.0, true)]);
+}
+
+#[test]
+fn first_point_wins_when_override_disabled() {
+    let merged = worker::merge_points(
+        vec![(at(600), 1.0, false), (at(600), 2.0, true), (at(1200), 3.0, true)],
+        false,
+    );
+    assert_eq!(merged, vec![(at(600), 1.0, false), (at(1200), 3.0, true)]);
+}
+
+#[test]
+fn aligned_timestamps_round_to_ten_minutes() {
+    // 1_700_000_400 is on the 600s grid
+    assert_eq!(worker::align_timestamp(1_700_000_400, true), 1_700_000_400);
+    assert_eq!(worker::align_timestamp(1_700_000_399, true), 1_700_000_400);
+    assert_eq!(worker::align_timestamp(1_700_000_699, true), 1_700_000_400);
+    assert_eq!(worker::align_timestamp(1_700_000_700, true), 1_700_001_000);
+}
+
+#[test]
+fn unaligned_timestamps_are_kept_raw() {
+    assert_eq!(worker::align_timestamp(1_700_000_400, false), 1_700_000_400);
+    assert_eq!(worker::align_timestamp(1_700_000_699, false), 1_700_000_699);
+}
+
+fn gap(location_id: i32, from: i64, to: i64) -> worker::GapWindow {
+    worker::GapWindow {
+        sensor_id: uuid::Uuid::from_u128(location_id as u128),
+        location_id,
+        from: at(from),
+        to: at(to),
+    }
+}
+
+#[test]
+fn overlapping_gaps_share_one_backfill_request() {
+    let requests = worker::plan_backfills(&[
+        gap(2, 3_000, 6_000),
+        gap(1, 600, 3_600),
+        gap(1, 10_000, 12_000),
+        gap(3, 6_000, 7_200),
+    ]);
+
+    assert_eq!(
+        requests,
+        vec![
+            worker::BackfillRequest {
+                from: at(600),
+                to: at(7_200),
+                location_ids: vec![1, 2, 3],
+            },
+            worker::BackfillRequest {
+                from: at(10_000),
+                to: at(12_000),
+                location_ids: vec![1],
+            },
+        ]
+    );
+    assert!(worker::plan_backfills(&[]).is_empty());
+}
@@ -0,0 +1,93 @@
+//! Tests that `include_thresholds=true` attaches each sensor's limit lines to
+//! station readings and aggregates, and that they are omitted by default.
+//!
+//! Run with: cargo test --test thresholds_overlay_db_test
+
+mod common;
+
+use axum::Router;
+use chrono::{DateTime, Duration};
+use river_db::routes::build_router;
+use sea_orm::{ConnectionTrait, DatabaseBackend, Statement};
+use serde_json::json;
+use uuid::Uuid;
+
+#[tokio::test]
+async fn thresholds_attached_only_when_requested() {
+    let Some(test_db) = common::timescale().await else {
+        return;
+    };
+    let station = common::seed_station(
+        &test_db.db,
+        &[("MTurbNTU", "Turbidity"), ("MDepthmm", "Depth"), ("MTempC", "Temperature")],
+    )
+    .await;
+    let (with_thresholds, with_range, without) =
+        (station.sensor_ids[0], station.sensor_ids[1], station.sensor_ids[2]);
+
+    // Two high thresholds (the lower one is reported) and one low
+    for (kind, value) in [("high", 15.0), ("high", 10.0), ("low", 2.0)] {
+        test_db
+            .db
+            .execute(Statement::from_sql_and_values(
+                DatabaseBackend::Postgres,
+                "INSERT INTO sensor_thresholds (id, sensor_id, kind, value) VALUES ($1, $2, $3, $4)",
+                [Uuid::new_v4().into(), with_thresholds.into(), kind.into(), value.into()],
+            ))
+            .await
+            .unwrap();
+    }
+    test_db
+        .db
+        .execute(Statement::from_sql_and_values(
+            DatabaseBackend::Postgres,
+            "UPDATE sensors SET units_min = 0, units_max = 5000 WHERE id = $1",
+            [with_range.into()],
+        ))
+        .await
+        .unwrap();
+
+    let start = DateTime::from_timestamp(1_735_689_600, 0).unwrap(); // 2025-01-01T00:00:00Z
+    let end = start + Duration::hours(2);
+    for sensor in &station.sensor_ids {
+        common::seed_readings(&test_db.db, *sensor, start, Duration::minutes(10), 12, f64::from)
+            .await;
+    }
+    common::refresh_aggregate(&test_db.db, "readings_hourly", start, end).await;
+
+    let router = build_router(common::app_state(&test_db));
+    let range = "start=2025-01-01T00:00:00Z&end=2025-01-01T02:00:00Z";
+    let readings = format!("/api/v1/stations/{}/readings?{range}", station.id);
+    let aggregates = format!("/api/v1/stations/{}/aggregates/hourly?{range}", station.id);
+
+    for uri in [&readings, &aggregates] {
+        let plain = common::get_ok_json(&router, uri).await;
+        for sensor in plain["sensors"].as_array().unwrap() {
+            assert!(sensor.get("thresholds").is_none(), "{uri}: {sensor}");
+        }
+
+        let overlaid =
+            common::get_ok_json(&router, &format!("{uri}&include_thresholds=true")).await;
+        let limits = |id: Uuid| {
+            overlaid["sensors"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .find(|s| s["id"] == id.to_string())
+                .unwrap()
+                .get("thresholds")
+                .cloned()
+        };
+        assert_eq!(
+            limits(with_thresholds),
+            Some(json!({ "high": 10.0, "low": 2.0, "source": "thresholds" })),
+            "{uri}"
+        );
+        assert_eq!(
+            limits(with_range),
+            Some(json!({ "high": 5000.0, "low": 0.0, "source": "units_range" })),
+            "{uri}"
+        );
+        assert_eq!(limits(without), None, "{uri}");
+    }
+}
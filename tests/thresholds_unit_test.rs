@@ -0,0 +1,58 @@
+//! Unit tests for parsing viewLinc threshold configuration.
+//!
+//! Run with: cargo test --test thresholds_unit_test
+
+use river_db::vaisala::models::{parse_thresholds, Threshold, ThresholdKind};
+use serde_json::json;
+
+fn limits(thresholds: &[Threshold]) -> Vec<(ThresholdKind, f64)> {
+    thresholds.iter().map(|t| (t.kind, t.value)).collect()
+}
+
+#[test]
+fn parses_typed_entries() {
+    let parsed = parse_thresholds(&[
+        json!({"type": "High", "value": 25.5, "severity": 2, "description": "Too warm"}),
+        json!({"direction": "below", "limit": "4"}),
+    ]);
+
+    assert_eq!(
+        limits(&parsed),
+        vec![(ThresholdKind::High, 25.5), (ThresholdKind::Low, 4.0)]
+    );
+    assert_eq!(parsed[0].severity, Some(2));
+    assert_eq!(parsed[0].description.as_deref(), Some("Too warm"));
+    assert_eq!(parsed[1].severity, None);
+}
+
+#[test]
+fn parses_limit_maps_and_nested_entries() {
+    let parsed = parse_thresholds(&[
+        json!({"high": 30, "low": 2}),
+        json!({"threshold": {"kind": "max", "setpoint": 12.0}}),
+        json!([{"condition": "lower", "threshold": 1.5}]),
+    ]);
+
+    assert_eq!(
+        limits(&parsed),
+        vec![
+            (ThresholdKind::High, 30.0),
+            (ThresholdKind::Low, 2.0),
+            (ThresholdKind::High, 12.0),
+            (ThresholdKind::Low, 1.5),
+        ]
+    );
+}
+
+#[test]
+fn skips_disabled_and_unrecognized_entries() {
+    let parsed = parse_thresholds(&[
+        json!({"type": "high", "value": 10, "enabled": false}),
+        json!({"type": "rate", "value": 3}),
+        json!({"type": "low", "value": "N/A"}),
+        json!("high"),
+        json!(null),
+    ]);
+
+    assert!(parsed.is_empty());
+}
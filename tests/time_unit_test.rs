@@ -0,0 +1,147 @@
+//! Unit tests for relative time and timestamp parsing and timestamp serialization.
+//!
+//! Run with: cargo test --test time_unit_test
+
+use chrono::{DateTime, Duration, Utc};
+use river_db::common::time::{self, TimeArray, TimeFormat};
+use river_db::config::parse_default_window;
+
+#[test]
+fn relative_duration_parses_units() {
+    assert_eq!(time::parse_relative_duration("30m").unwrap(), Duration::minutes(30));
+    assert_eq!(time::parse_relative_duration("24h").unwrap(), Duration::hours(24));
+    assert_eq!(time::parse_relative_duration("7d").unwrap(), Duration::days(7));
+    assert_eq!(time::parse_relative_duration("2w").unwrap(), Duration::weeks(2));
+
+    // Invalid inputs are rejected
+    assert!(time::parse_relative_duration("").is_err());
+    assert!(time::parse_relative_duration("h").is_err());
+    assert!(time::parse_relative_duration("0d").is_err());
+    assert!(time::parse_relative_duration("-1d").is_err());
+    assert!(time::parse_relative_duration("7y").is_err());
+}
+
+#[test]
+fn window_ends_on_minute_boundary() {
+    let (start, end) = time::resolve_window("24h").unwrap();
+    assert_eq!(end - start, Duration::hours(24));
+    assert_eq!(end.timestamp() % 60, 0);
+}
+
+#[test]
+fn default_readings_window_parses_or_falls_back() {
+    assert_eq!(parse_default_window("24h"), Some(Duration::hours(24)));
+    assert_eq!(parse_default_window(" 2w "), Some(Duration::weeks(2)));
+    assert_eq!(parse_default_window("forever"), Some(Duration::days(7)));
+    assert_eq!(parse_default_window(""), None);
+}
+
+#[test]
+fn time_array_serializes_per_format() {
+    let t = DateTime::<Utc>::from_timestamp(1_735_689_600, 0).unwrap();
+
+    let iso = TimeArray::new(vec![t], TimeFormat::Iso);
+    assert_eq!(serde_json::to_string(&iso).unwrap(), r#"["2025-01-01T00:00:00Z"]"#);
+    // Matches the default chrono serialization used before `time_format` existed
+    assert_eq!(serde_json::to_string(&iso).unwrap(), serde_json::to_string(&vec![t]).unwrap());
+
+    let epoch = TimeArray::new(vec![Some(t), None], TimeFormat::Epoch);
+    assert_eq!(serde_json::to_string(&epoch).unwrap(), "[1735689600,null]");
+
+    let format: TimeFormat = serde_json::from_str(r#""epoch""#).unwrap();
+    assert_eq!(format, TimeFormat::Epoch);
+}
+
+#[test]
+fn resolution_hint_follows_dashboard_thresholds() {
+    assert_eq!(time::resolution_hint(Duration::hours(1)), "raw");
+    assert_eq!(time::resolution_hint(Duration::days(14)), "raw");
+    assert_eq!(time::resolution_hint(Duration::days(15)), "hourly");
+    assert_eq!(time::resolution_hint(Duration::days(120)), "hourly");
+    assert_eq!(time::resolution_hint(Duration::days(200)), "daily");
+    assert_eq!(time::resolution_hint(Duration::days(800)), "weekly");
+}
+
+#[test]
+fn resolution_names_normalize() {
+    assert_eq!(time::normalize_resolution("Hourly"), Some("hourly"));
+    assert_eq!(time::normalize_resolution("DAILY"), Some("daily"));
+    assert_eq!(time::normalize_resolution("1h"), Some("hourly"));
+    assert_eq!(time::normalize_resolution("1D"), Some("daily"));
+    assert_eq!(time::normalize_resolution("1w"), Some("weekly"));
+    assert_eq!(time::normalize_resolution("monthly"), Some("monthly"));
+
+    assert_eq!(time::normalize_resolution("minutely"), None);
+    assert_eq!(time::normalize_resolution("2h"), None);
+}
+
+#[test]
+fn timestamps_parse_from_rfc3339_or_epoch() {
+    let expected = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+    assert_eq!(time::parse_timestamp("2023-11-14T22:13:20Z"), Some(expected));
+    assert_eq!(time::parse_timestamp("2023-11-14T23:13:20+01:00"), Some(expected));
+    assert_eq!(time::parse_timestamp("1700000000"), Some(expected));
+    assert_eq!(
+        time::parse_timestamp("1700000000.5"),
+        Some(expected + Duration::milliseconds(500))
+    );
+
+    assert_eq!(time::parse_timestamp("yesterday"), None);
+    assert_eq!(time::parse_timestamp("NaN"), None);
+    assert_eq!(time::parse_timestamp(""), None);
+}
+
+#[test]
+fn query_start_and_end_accept_epoch_seconds() {
+    use axum::extract::Query;
+    use river_db::routes::stations::StationReadingsQuery;
+
+    let parse = |query: &str| {
+        let uri: axum::http::Uri = format!("/readings?{query}").parse().unwrap();
+        Query::<StationReadingsQuery>::try_from_uri(&uri).map(|q| (q.0.start, q.0.end))
+    };
+
+    let epoch = parse("start=1700000000&end=1700003600").unwrap();
+    let iso = parse("start=2023-11-14T22:13:20Z&end=2023-11-14T23:13:20Z").unwrap();
+    assert_eq!(epoch, iso);
+    assert_eq!(epoch.0, DateTime::from_timestamp(1_700_000_000, 0));
+
+    // Mixed forms and omitted bounds
+    let mixed = parse("start=1700000000").unwrap();
+    assert_eq!(mixed, (DateTime::from_timestamp(1_700_000_000, 0), None));
+    assert!(parse("start=soon").is_err());
+
+    // JSON numbers deserialize too
+    let json: StationReadingsQuery = serde_json::from_str(r#"{"start": 1700000000}"#).unwrap();
+    assert_eq!(json.start, epoch.0);
+}
+
+#[test]
+fn histogram_range_accepts_epoch_seconds() {
+    use axum::extract::Query;
+    use river_db::routes::sensors::HistogramQuery;
+
+    let parse = |query: &str| {
+        let uri: axum::http::Uri = format!("/histogram?{query}").parse().unwrap();
+        Query::<HistogramQuery>::try_from_uri(&uri).map(|q| (q.0.start, q.0.end))
+    };
+
+    let epoch = parse("start=1700000000&end=1700003600").unwrap();
+    let iso = parse("start=2023-11-14T22:13:20Z&end=2023-11-14T23:13:20Z").unwrap();
+    assert_eq!(epoch, iso);
+    assert!(parse("start=1700000000&end=later").is_err());
+}
+
+#[test]
+fn range_must_end_after_start() {
+    let start = DateTime::<Utc>::from_timestamp(1_735_689_600, 0).unwrap();
+    let end = start + Duration::hours(1);
+
+    assert!(time::validate_range(start, end).is_ok());
+    assert!(time::validate_range(end, start).is_err());
+    assert!(time::validate_range(start, start).is_err());
+
+    // Open-ended ranges have nothing to check
+    assert!(time::validate_range(Some(end), None).is_ok());
+    assert!(time::validate_range(None, Some(start)).is_ok());
+}
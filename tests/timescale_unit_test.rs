@@ -0,0 +1,64 @@
+//! Unit tests for TimescaleDB error detection.
+//!
+//! Run with: cargo test --test timescale_unit_test
+
+use river_db::services::timescale;
+use sea_orm::sqlx::error::{DatabaseError, ErrorKind};
+use sea_orm::{DbErr, RuntimeErr};
+use std::borrow::Cow;
+
+/// Minimal database error carrying only a SQLSTATE code
+#[derive(Debug)]
+struct PgError(&'static str);
+
+impl std::fmt::Display for PgError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "SQLSTATE {}", self.0)
+    }
+}
+
+impl std::error::Error for PgError {}
+
+impl DatabaseError for PgError {
+    fn message(&self) -> &str {
+        self.0
+    }
+
+    fn code(&self) -> Option<Cow<'_, str>> {
+        Some(Cow::Borrowed(self.0))
+    }
+
+    fn as_error(&self) -> &(dyn std::error::Error + Send + Sync + 'static) {
+        self
+    }
+
+    fn as_error_mut(&mut self) -> &mut (dyn std::error::Error + Send + Sync + 'static) {
+        self
+    }
+
+    fn into_error(self: Box<Self>) -> Box<dyn std::error::Error + Send + Sync + 'static> {
+        self
+    }
+
+    fn kind(&self) -> ErrorKind {
+        ErrorKind::Other
+    }
+}
+
+fn query_err(code: &'static str) -> DbErr {
+    DbErr::Query(RuntimeErr::SqlxError(sea_orm::sqlx::Error::Database(
+        Box::new(PgError(code)),
+    )))
+}
+
+#[test]
+fn missing_view_or_function_is_detected() {
+    assert!(timescale::is_missing_object(&query_err("42P01")));
+    assert!(timescale::is_missing_object(&query_err("42883")));
+}
+
+#[test]
+fn other_errors_are_not_missing_objects() {
+    assert!(!timescale::is_missing_object(&query_err("23505")));
+    assert!(!timescale::is_missing_object(&DbErr::Custom("boom".to_string())));
+}
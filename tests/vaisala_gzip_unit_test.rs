@@ -0,0 +1,55 @@
+//! Tests that the Vaisala client requests and decodes gzip responses.
+//!
+//! Run with: cargo test --test vaisala_gzip_unit_test
+
+mod common;
+
+use std::sync::{Arc, Mutex};
+
+use axum::http::{header, HeaderMap};
+use axum::{routing::get, Json, Router};
+use chrono::DateTime;
+use river_db::vaisala::VaisalaClient;
+use tower_http::compression::CompressionLayer;
+
+#[tokio::test]
+async fn gzipped_history_is_parsed() {
+    let t0 = 1_735_689_600;
+    let points: Vec<(i64, f64, bool)> = (0..500).map(|i| (t0 + i * 600, 1.5, true)).collect();
+    let body = common::locations_history_body(1271, &points);
+
+    let accept_encoding = Arc::new(Mutex::new(None));
+    let seen = accept_encoding.clone();
+    let base_url = common::mock_vaisala(
+        Router::new()
+            .route(
+                "/locations_history",
+                get(move |headers: HeaderMap| {
+                    let body = body.clone();
+                    let seen = seen.clone();
+                    async move {
+                        *seen.lock().unwrap() = headers
+                            .get(header::ACCEPT_ENCODING)
+                            .map(|v| v.to_str().unwrap().to_string());
+                        Json(body)
+                    }
+                }),
+            )
+            // Compresses only when the request advertises gzip
+            .layer(CompressionLayer::new()),
+    )
+    .await;
+
+    let mut config = common::test_config("postgresql://unused");
+    config.vaisala_base_url = base_url;
+    let vaisala = VaisalaClient::new(&config);
+
+    let history = vaisala
+        .get_locations_history(&[1271], DateTime::from_timestamp(t0, 0).unwrap(), None)
+        .await
+        .unwrap();
+
+    assert_eq!(accept_encoding.lock().unwrap().as_deref(), Some("gzip"));
+    assert_eq!(history.data.len(), 1);
+    assert_eq!(history.data[0].attributes.data_points.len(), 500);
+}
@@ -0,0 +1,96 @@
+//! Tests for the Vaisala client's retries on 429 and 502/503/504.
+//!
+//! Run with: cargo test --test vaisala_retry_unit_test
+
+mod common;
+
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::http::{header, HeaderMap, HeaderValue, StatusCode};
+use axum::response::IntoResponse;
+use axum::{routing::get, Json, Router};
+use river_db::vaisala::{backoff_delay, retry_after, VaisalaClient};
+use serde_json::json;
+
+#[test]
+fn backoff_doubles_with_bounded_jitter() {
+    let base = Duration::from_millis(100);
+    assert_eq!(backoff_delay(0, base, 0.0), Duration::from_millis(100));
+    assert_eq!(backoff_delay(3, base, 0.0), Duration::from_millis(800));
+    assert_eq!(backoff_delay(3, base, 1.0), Duration::from_millis(1200));
+    // Capped rather than overflowing
+    assert_eq!(backoff_delay(40, base, 1.0), Duration::from_secs(300));
+}
+
+#[test]
+fn retry_after_accepts_seconds_and_dates() {
+    let headers = |value: &'static str| {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::RETRY_AFTER, HeaderValue::from_static(value));
+        headers
+    };
+    assert_eq!(retry_after(&headers("2")), Some(Duration::from_secs(2)));
+    assert_eq!(
+        retry_after(&headers("Wed, 21 Oct 2015 07:28:00 GMT")),
+        Some(Duration::ZERO)
+    );
+    assert_eq!(retry_after(&headers("soon")), None);
+    assert_eq!(retry_after(&HeaderMap::new()), None);
+}
+
+/// Client against a `/locations` mock answering `failures` times with
+/// `status` before succeeding, and the shared request counter
+async fn client(
+    status: StatusCode,
+    failures: u32,
+    max_retries: u32,
+) -> (VaisalaClient, Arc<AtomicU32>) {
+    let calls = Arc::new(AtomicU32::new(0));
+    let counter = calls.clone();
+    let base_url = common::mock_vaisala(Router::new().route(
+        "/locations",
+        get(move || {
+            let counter = counter.clone();
+            async move {
+                if counter.fetch_add(1, Ordering::SeqCst) < failures {
+                    return (status, [(header::RETRY_AFTER, "0")]).into_response();
+                }
+                Json(json!({"jsonapi": {"version": "1.0"}, "data": []})).into_response()
+            }
+        }),
+    ))
+    .await;
+
+    let mut config = common::test_config("postgresql://unused");
+    config.vaisala_base_url = base_url;
+    config.vaisala_max_retries = max_retries;
+    config.vaisala_retry_base_ms = 1;
+    (VaisalaClient::new(&config), calls)
+}
+
+#[tokio::test]
+async fn transient_failures_are_retried() {
+    for status in [StatusCode::BAD_GATEWAY, StatusCode::SERVICE_UNAVAILABLE] {
+        let (vaisala, calls) = client(status, 2, 3).await;
+        let locations = vaisala.get_locations().await.unwrap();
+        assert!(locations.data.is_empty());
+        assert_eq!(calls.load(Ordering::SeqCst), 3, "{status}");
+    }
+}
+
+#[tokio::test]
+async fn persistent_rate_limiting_keeps_the_scheduler_error() {
+    let (vaisala, calls) = client(StatusCode::TOO_MANY_REQUESTS, u32::MAX, 2).await;
+    let err = vaisala.get_locations().await.unwrap_err();
+    assert!(err.to_string().contains("Rate limited (429)"), "{err}");
+    assert_eq!(calls.load(Ordering::SeqCst), 3);
+}
+
+#[tokio::test]
+async fn other_errors_are_not_retried() {
+    let (vaisala, calls) = client(StatusCode::INTERNAL_SERVER_ERROR, 1, 3).await;
+    assert!(vaisala.get_locations().await.is_err());
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+}
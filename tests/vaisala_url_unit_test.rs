@@ -0,0 +1,67 @@
+//! Tests for Vaisala base URL normalization.
+//!
+//! Run with: cargo test --test vaisala_url_unit_test
+
+mod common;
+
+use std::sync::{Arc, Mutex};
+
+use axum::http::Uri;
+use axum::{routing::get, Json, Router};
+use chrono::DateTime;
+use river_db::vaisala::{normalize_base_url, VaisalaClient};
+
+#[test]
+fn trailing_slashes_and_whitespace_are_stripped() {
+    let expected = "https://viewlinc.local/rest/v1";
+    for raw in [
+        "https://viewlinc.local/rest/v1",
+        "https://viewlinc.local/rest/v1/",
+        "https://viewlinc.local/rest/v1//",
+        " https://viewlinc.local/rest/v1/ ",
+    ] {
+        assert_eq!(normalize_base_url(raw), expected, "{raw:?}");
+    }
+}
+
+#[test]
+fn other_paths_are_kept() {
+    assert_eq!(normalize_base_url("http://127.0.0.1:8080/"), "http://127.0.0.1:8080");
+}
+
+#[tokio::test]
+async fn request_urls_match_with_and_without_trailing_slash() {
+    let t0 = 1_735_689_600;
+    let body = common::locations_history_body(1271, &[(t0, 1.5, true)]);
+
+    let requested = Arc::new(Mutex::new(Vec::new()));
+    let seen = requested.clone();
+    let base_url = common::mock_vaisala(Router::new().route(
+        "/rest/v1/locations_history",
+        get(move |uri: Uri| {
+            let body = body.clone();
+            let seen = seen.clone();
+            async move {
+                seen.lock().unwrap().push(uri.to_string());
+                Json(body)
+            }
+        }),
+    ))
+    .await;
+
+    for suffix in ["/rest/v1", "/rest/v1/"] {
+        let mut config = common::test_config("postgresql://unused");
+        config.vaisala_base_url = format!("{base_url}{suffix}");
+        let vaisala = VaisalaClient::new(&config);
+        let history = vaisala
+            .get_locations_history(&[1271], DateTime::from_timestamp(t0, 0).unwrap(), None)
+            .await
+            .unwrap();
+        assert_eq!(history.data.len(), 1, "{suffix}");
+    }
+
+    let requested = requested.lock().unwrap();
+    assert_eq!(requested.len(), 2);
+    assert_eq!(requested[0], requested[1]);
+    assert!(requested[0].starts_with("/rest/v1/locations_history?"), "{}", requested[0]);
+}
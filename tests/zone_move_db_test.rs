@@ -0,0 +1,100 @@
+//! Tests for stations moving between zones during location discovery.
+//!
+//! Run with: cargo test --test zone_move_db_test
+
+mod common;
+
+use std::sync::{Arc, Mutex};
+
+use axum::{extract::State, routing::get, Json, Router};
+use river_db::entity::{stations, zones};
+use river_db::sync::sensor_filter::SensorTypeFilter;
+use river_db::sync::worker;
+use river_db::vaisala::VaisalaClient;
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
+use serde_json::Value;
+use uuid::Uuid;
+
+/// A `/locations` entry for a zone or station (not a leaf)
+fn location(path: &str, node_id: i32) -> Value {
+    serde_json::json!({
+        "type": "locations",
+        "id": node_id.to_string(),
+        "attributes": {
+            "path": path,
+            "text": path.rsplit('/').next().unwrap(),
+            "node_id": node_id,
+            "leaf": false,
+        },
+    })
+}
+
+fn locations_body(entries: Vec<Value>) -> Value {
+    serde_json::json!({"jsonapi": {"version": "1.0"}, "data": entries})
+}
+
+async fn zone_id(db: &sea_orm::DatabaseConnection, name: &str) -> Uuid {
+    zones::Entity::find()
+        .filter(zones::Column::Name.eq(name))
+        .one(db)
+        .await
+        .unwrap()
+        .expect("zone created")
+        .id
+}
+
+#[tokio::test]
+async fn station_follows_zone_move_across_discoveries() {
+    let Some(test_db) = common::timescale().await else {
+        return;
+    };
+    let suffix = Uuid::new_v4().simple().to_string();
+    let node_id = i32::from_str_radix(&suffix[..7], 16).unwrap();
+    let (zone_a, zone_b) = (format!("A-{}", &suffix[..8]), format!("B-{}", &suffix[..8]));
+    let name = format!("S-{}", &suffix[..8]);
+
+    let body = Arc::new(Mutex::new(locations_body(vec![
+        location(&format!("viewLinc/{zone_a}"), node_id + 1),
+        location(&format!("viewLinc/{zone_a}/{name}"), node_id),
+    ])));
+    let router = Router::new()
+        .route(
+            "/locations",
+            get(|State(body): State<Arc<Mutex<Value>>>| async move {
+                Json(body.lock().unwrap().clone())
+            }),
+        )
+        .with_state(body.clone());
+    let mut config = common::test_config(&test_db.url);
+    config.vaisala_base_url = common::mock_vaisala(router).await;
+    let vaisala = VaisalaClient::new(&config);
+
+    worker::sync_locations(&test_db.db, &vaisala, &SensorTypeFilter::default())
+        .await
+        .unwrap();
+    let station = stations::Entity::find()
+        .filter(stations::Column::VaisalaNodeId.eq(node_id))
+        .one(&test_db.db)
+        .await
+        .unwrap()
+        .expect("station created");
+    assert_eq!(station.zone_id, Some(zone_id(&test_db.db, &zone_a).await));
+
+    // Moved to a zone that is new in this pass and listed after the station
+    *body.lock().unwrap() = locations_body(vec![
+        location(&format!("viewLinc/{zone_b}/{name}"), node_id),
+        location(&format!("viewLinc/{zone_a}"), node_id + 1),
+        location(&format!("viewLinc/{zone_b}"), node_id + 2),
+    ]);
+    worker::sync_locations(&test_db.db, &vaisala, &SensorTypeFilter::default())
+        .await
+        .unwrap();
+
+    let moved = stations::Entity::find_by_id(station.id)
+        .one(&test_db.db)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(moved.zone_id, Some(zone_id(&test_db.db, &zone_b).await));
+    assert_eq!(moved.vaisala_path, Some(format!("viewLinc/{zone_b}/{name}")));
+}